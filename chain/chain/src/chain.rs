@@ -50,8 +50,9 @@ use near_primitives::sharding::{
 use near_primitives::state_part::PartId;
 use near_primitives::static_clock::StaticClock;
 use near_primitives::syncing::{
-    get_num_state_parts, ReceiptProofResponse, RootProof, ShardStateSyncResponseHeader,
-    ShardStateSyncResponseHeaderV1, ShardStateSyncResponseHeaderV2, StateHeaderKey, StatePartKey,
+    get_num_state_parts, EpochSyncFinalizationResponse, EpochSyncResponse, ReceiptProofResponse,
+    RootProof, ShardStateSyncResponseHeader, ShardStateSyncResponseHeaderV1,
+    ShardStateSyncResponseHeaderV2, StateHeaderKey, StatePartKey,
 };
 use near_primitives::transaction::{
     ExecutionOutcomeWithId, ExecutionOutcomeWithIdAndProof, SignedTransaction,
@@ -67,7 +68,8 @@ use near_primitives::version::PROTOCOL_VERSION;
 use near_primitives::views::{
     BlockStatusView, DroppedReason, ExecutionOutcomeWithIdView, ExecutionStatusView,
     FinalExecutionOutcomeView, FinalExecutionOutcomeWithReceiptView, FinalExecutionStatus,
-    LightClientBlockView, SignedTransactionView,
+    LightClientBlockView, ReceiptEnumView, ReceiptTraceView, ReceiptView, SignedTransactionView,
+    TraceExecutionView, TxTraceView,
 };
 use near_store::flat::{store_helper, FlatStateDelta, FlatStorageCreationStatus, FlatStorageError};
 use near_store::StorageError;
@@ -114,9 +116,19 @@ const NUM_PARENTS_TO_CHECK_FINALITY: usize = 20;
 #[cfg(not(feature = "sandbox"))]
 const ACCEPTABLE_TIME_DIFFERENCE: i64 = 12 * 10;
 
+/// Maximum number of headers between the last final block of an epoch and the
+/// first block of the next epoch served in epoch sync finalization.
+const MAX_EPOCH_SYNC_FINALIZATION_HEADERS: usize = 100;
+
 /// Over this block height delta in advance if we are not chunk producer - route tx to upcoming validators.
 pub const TX_ROUTING_HEIGHT_HORIZON: BlockHeightDelta = 4;
 
+/// Maximum depth of the receipt tree returned by `Chain::get_transaction_trace`.
+const MAX_TRACE_DEPTH: usize = 64;
+
+/// Maximum number of receipts in the tree returned by `Chain::get_transaction_trace`.
+const MAX_TRACE_RECEIPTS: usize = 10_000;
+
 /// Private constant for 1 NEAR (copy from near/config.rs) used for reporting.
 const NEAR_BASE: Balance = 1_000_000_000_000_000_000_000_000;
 
@@ -132,6 +144,13 @@ enum ApplyChunksMode {
     NotCaughtUp,
 }
 
+/// Limits of the receipt tree built by `Chain::get_transaction_trace_with_limits`.
+struct TraceLimits {
+    max_depth: usize,
+    /// Number of receipts which can still be added to the tree.
+    receipts_left: usize,
+}
+
 /// Orphan is a block whose previous block is not accepted (in store) yet.
 /// Therefore, they are not ready to be processed yet.
 /// We save these blocks in an in-memory orphan pool to be processed later
@@ -539,6 +558,8 @@ impl Chain {
         let (store, state_roots) = runtime_adapter.genesis_state();
         let mut store =
             ChainStore::new(store, chain_genesis.height, chain_config.save_trie_changes);
+        store.set_account_activity_index(chain_config.account_activity_index);
+        store.set_event_index(chain_config.event_index);
         let genesis_chunks = genesis_chunks(
             state_roots.clone(),
             runtime_adapter.num_shards(&EpochId::default())?,
@@ -742,6 +763,116 @@ impl Chain {
         create_light_client_block_view(&final_block_header, chain_store, Some(next_block_producers))
     }
 
+    /// Returns the light client block of the last final block of `epoch_id`,
+    /// or `UpToDate` if that epoch hasn't finished yet as far as this node knows.
+    pub fn get_epoch_sync_response(&self, epoch_id: &EpochId) -> Result<EpochSyncResponse, Error> {
+        match self.store.get_epoch_light_client_block(&epoch_id.0) {
+            Ok(light_client_block_view) => Ok(EpochSyncResponse::Advance {
+                light_client_block_view: LightClientBlockView::clone(&light_client_block_view),
+            }),
+            Err(Error::DBNotFoundErr(_)) => Ok(EpochSyncResponse::UpToDate),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Returns the data necessary to finish epoch sync in the epoch that follows
+    /// the one of `final_block_hash`, which must be the last final block of its
+    /// epoch as recorded in its light client block.
+    pub fn get_epoch_sync_finalization_response(
+        &self,
+        final_block_hash: &CryptoHash,
+    ) -> Result<EpochSyncFinalizationResponse, Error> {
+        let final_block_header = self.get_block_header(final_block_hash)?;
+        let epoch_id = final_block_header.epoch_id().clone();
+        let mut prev_epoch_headers = vec![final_block_header];
+        let cur_epoch_header = loop {
+            if prev_epoch_headers.len() > MAX_EPOCH_SYNC_FINALIZATION_HEADERS {
+                return Err(Error::Other(format!(
+                    "Block {} is too far from the end of its epoch",
+                    final_block_hash
+                )));
+            }
+            let next_block_hash =
+                self.store.get_next_block_hash(prev_epoch_headers.last().unwrap().hash())?;
+            let header = self.get_block_header(&next_block_hash)?;
+            if header.epoch_id() != &epoch_id {
+                break header;
+            }
+            prev_epoch_headers.push(header);
+        };
+        let header_sync_init_header = prev_epoch_headers.last().unwrap().clone();
+        let header_sync_init_header_tree = PartialMerkleTree::clone(
+            &self.store.get_block_merkle_tree(header_sync_init_header.hash())?,
+        );
+        let (
+            prev_epoch_first_block_info,
+            prev_epoch_prev_last_block_info,
+            prev_epoch_last_block_info,
+            prev_epoch_info,
+            cur_epoch_info,
+            next_epoch_info,
+        ) = self.runtime_adapter.get_epoch_sync_data(
+            header_sync_init_header.hash(),
+            cur_epoch_header.epoch_id(),
+            cur_epoch_header.next_epoch_id(),
+        )?;
+        Ok(EpochSyncFinalizationResponse {
+            cur_epoch_header,
+            prev_epoch_headers,
+            header_sync_init_header,
+            header_sync_init_header_tree,
+            prev_epoch_first_block_info: prev_epoch_first_block_info.as_ref().clone(),
+            prev_epoch_prev_last_block_info: prev_epoch_prev_last_block_info.as_ref().clone(),
+            prev_epoch_last_block_info: prev_epoch_last_block_info.as_ref().clone(),
+            prev_epoch_info: prev_epoch_info.as_ref().clone(),
+            cur_epoch_info: cur_epoch_info.as_ref().clone(),
+            next_epoch_info: next_epoch_info.as_ref().clone(),
+        })
+    }
+
+    /// Finishes epoch sync with data validated by `validate_epoch_sync_finalization`.
+    /// Initializes the epoch manager and makes the last block of the previous
+    /// epoch both the header head and the final head, so that header sync
+    /// continues from there.
+    pub fn init_after_epoch_sync(
+        &mut self,
+        response: EpochSyncFinalizationResponse,
+    ) -> Result<(), Error> {
+        let EpochSyncFinalizationResponse {
+            cur_epoch_header,
+            header_sync_init_header,
+            header_sync_init_header_tree,
+            prev_epoch_first_block_info,
+            prev_epoch_prev_last_block_info,
+            prev_epoch_last_block_info,
+            prev_epoch_info,
+            cur_epoch_info,
+            next_epoch_info,
+            ..
+        } = response;
+        let prev_epoch_id = header_sync_init_header.epoch_id().clone();
+        self.runtime_adapter.epoch_sync_init_epoch_manager(
+            prev_epoch_first_block_info,
+            prev_epoch_prev_last_block_info,
+            prev_epoch_last_block_info,
+            &prev_epoch_id,
+            prev_epoch_info,
+            cur_epoch_header.epoch_id(),
+            cur_epoch_info,
+            cur_epoch_header.next_epoch_id(),
+            next_epoch_info,
+        )?;
+
+        let tip = Tip::from_header(&header_sync_init_header);
+        let mut chain_store_update = self.store.store_update();
+        chain_store_update
+            .save_epoch_sync_header(header_sync_init_header, header_sync_init_header_tree)?;
+        chain_store_update.force_save_header_head(&tip)?;
+        chain_store_update.save_final_head(&tip)?;
+        chain_store_update.commit()?;
+        Ok(())
+    }
+
     pub fn save_block(&mut self, block: MaybeValidated<Block>) -> Result<(), Error> {
         if self.store.get_block(block.hash()).is_ok() {
             return Ok(());
@@ -877,6 +1008,14 @@ impl Chain {
         if gc_stop_height > head.height {
             return Err(Error::GCError("gc_stop_height cannot be larger than head.height".into()));
         }
+        // Keep the blocks sandbox snapshots were taken at, so that the snapshots can be restored.
+        #[cfg(feature = "sandbox")]
+        let gc_stop_height = self
+            .store
+            .get_sandbox_snapshots()?
+            .iter()
+            .map(|snapshot| snapshot.height)
+            .fold(gc_stop_height, BlockHeight::min);
         let prev_epoch_id = self.get_block_header(&head.prev_block_hash)?.epoch_id().clone();
         let epoch_change = prev_epoch_id != head.epoch_id;
         let mut fork_tail = self.store.fork_tail()?;
@@ -3197,6 +3336,7 @@ impl Chain {
             }
         }
 
+        let state_root = shard_state_header.chunk_prev_state_root();
         let mut height = shard_state_header.chunk_height_included();
         let mut chain_update = self.chain_update();
         chain_update.set_state_finalize(shard_id, sync_hash, shard_state_header)?;
@@ -3216,6 +3356,16 @@ impl Chain {
             }
         }
 
+        // The node is about to start applying chunks of this shard, so warm up the compiled
+        // contract cache with the contracts of the synced state.
+        let sync_header = self.get_block_header(&sync_hash)?;
+        self.runtime_adapter.precompile_contracts_in_background(
+            shard_id,
+            &state_root,
+            sync_header.epoch_id(),
+            sync_header.next_epoch_id(),
+        )?;
+
         Ok(())
     }
 
@@ -3522,6 +3672,216 @@ impl Chain {
         Ok(FinalExecutionOutcomeWithReceiptView { final_outcome, receipts })
     }
 
+    /// Returns the causal tree of the receipts of the transaction, as far as they were executed
+    /// on the canonical chain. The tree is cut off at `MAX_TRACE_DEPTH` levels and
+    /// `MAX_TRACE_RECEIPTS` receipts.
+    pub fn get_transaction_trace(
+        &self,
+        transaction_hash: &CryptoHash,
+    ) -> Result<TxTraceView, Error> {
+        self.get_transaction_trace_with_limits(
+            transaction_hash,
+            MAX_TRACE_DEPTH,
+            MAX_TRACE_RECEIPTS,
+        )
+    }
+
+    /// Same as `get_transaction_trace`, but the tree is cut off at `max_depth` levels and
+    /// `max_receipts` receipts. Receipts whose children were left out are marked as truncated.
+    pub fn get_transaction_trace_with_limits(
+        &self,
+        transaction_hash: &CryptoHash,
+        max_depth: usize,
+        max_receipts: usize,
+    ) -> Result<TxTraceView, Error> {
+        let transaction = self.store.get_transaction(transaction_hash)?.ok_or_else(|| {
+            Error::DBNotFoundErr(format!("Transaction {} is not found", transaction_hash))
+        })?;
+        let mut outcome_shard_ids = HashMap::new();
+        let outcome = self.get_execution_outcome(transaction_hash)?;
+        let execution = self.get_trace_execution(&outcome, &mut outcome_shard_ids)?;
+        let mut limits = TraceLimits { max_depth, receipts_left: max_receipts };
+        let mut receipts = outcome
+            .outcome_with_id
+            .outcome
+            .receipt_ids
+            .iter()
+            .map(|receipt_id| {
+                self.get_receipt_trace(receipt_id, 0, &mut limits, &mut outcome_shard_ids)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Dependencies can only be resolved once the whole tree is known, as callbacks are
+        // usually siblings of the receipts they depend on.
+        let mut data_producers = HashMap::new();
+        let mut executions = HashMap::new();
+        for receipt in &receipts {
+            self.collect_trace_executions(receipt, &mut data_producers, &mut executions)?;
+        }
+        // The receipt of a transaction is executed in the same chunk if the transaction is sent
+        // to the signer itself and is sent to the next chunk of the receiver shard otherwise.
+        let is_local_receipt =
+            transaction.transaction.signer_id == transaction.transaction.receiver_id;
+        let ready_ordinal = self.store.get_block_merkle_tree(&execution.block_hash)?.size()
+            + !is_local_receipt as NumBlocks;
+        for receipt in receipts.iter_mut() {
+            Self::fill_trace_dependencies(receipt, ready_ordinal, &data_producers, &executions);
+        }
+        Ok(TxTraceView {
+            transaction: SignedTransaction::clone(&transaction).into(),
+            execution,
+            receipts,
+        })
+    }
+
+    fn get_receipt_trace(
+        &self,
+        receipt_id: &CryptoHash,
+        depth: usize,
+        limits: &mut TraceLimits,
+        outcome_shard_ids: &mut HashMap<CryptoHash, HashMap<CryptoHash, ShardId>>,
+    ) -> Result<ReceiptTraceView, Error> {
+        limits.receipts_left = limits.receipts_left.saturating_sub(1);
+        let receipt = self.store.get_receipt(receipt_id)?;
+        let is_refund =
+            receipt.as_ref().map_or(false, |receipt| receipt.predecessor_id.is_system());
+        let outcome = match self.get_execution_outcome(receipt_id) {
+            Ok(outcome) => Some(outcome),
+            Err(Error::DBNotFoundErr(_)) => None,
+            Err(err) => return Err(err),
+        };
+        let mut children = vec![];
+        let mut execution = None;
+        let mut truncated = false;
+        if let Some(outcome) = outcome {
+            for child_id in &outcome.outcome_with_id.outcome.receipt_ids {
+                if depth + 1 >= limits.max_depth || limits.receipts_left == 0 {
+                    truncated = true;
+                    break;
+                }
+                children.push(self.get_receipt_trace(
+                    child_id,
+                    depth + 1,
+                    limits,
+                    outcome_shard_ids,
+                )?);
+            }
+            execution = Some(self.get_trace_execution(&outcome, outcome_shard_ids)?);
+        }
+        Ok(ReceiptTraceView {
+            receipt_id: *receipt_id,
+            receipt: receipt.map(|receipt| Receipt::clone(&receipt).into()),
+            is_refund,
+            data_dependencies: vec![],
+            execution,
+            delay_blocks: None,
+            children,
+            truncated,
+        })
+    }
+
+    /// Returns the block and the shard in which the outcome was produced. Shards are looked up
+    /// in `DBCol::OutcomeIds`, which is cached per block in `outcome_shard_ids`.
+    fn get_trace_execution(
+        &self,
+        outcome: &ExecutionOutcomeWithIdAndProof,
+        outcome_shard_ids: &mut HashMap<CryptoHash, HashMap<CryptoHash, ShardId>>,
+    ) -> Result<TraceExecutionView, Error> {
+        let header = self.get_block_header(&outcome.block_hash)?;
+        let shard_ids = match outcome_shard_ids.entry(outcome.block_hash) {
+            std::collections::hash_map::Entry::Occupied(entry) => entry.into_mut(),
+            std::collections::hash_map::Entry::Vacant(entry) => {
+                let mut shard_ids = HashMap::new();
+                for shard_id in 0..self.runtime_adapter.num_shards(header.epoch_id())? {
+                    for id in self
+                        .store
+                        .get_outcomes_by_block_hash_and_shard_id(&outcome.block_hash, shard_id)?
+                    {
+                        shard_ids.insert(id, shard_id);
+                    }
+                }
+                entry.insert(shard_ids)
+            }
+        };
+        let id = outcome.outcome_with_id.id;
+        let shard_id = *shard_ids.get(&id).ok_or_else(|| {
+            Error::DBNotFoundErr(format!("OUTCOME IDS: {} in block {}", id, outcome.block_hash))
+        })?;
+        Ok(TraceExecutionView {
+            block_hash: outcome.block_hash,
+            block_height: header.height(),
+            shard_id,
+            outcome: outcome.outcome_with_id.outcome.clone().into(),
+        })
+    }
+
+    /// Collects which receipt produces each data id and the ordinals of the blocks in which
+    /// receipts were executed. Ordinals are used rather than heights so that skipped heights are
+    /// not counted as delays.
+    fn collect_trace_executions(
+        &self,
+        receipt: &ReceiptTraceView,
+        data_producers: &mut HashMap<CryptoHash, CryptoHash>,
+        executions: &mut HashMap<CryptoHash, NumBlocks>,
+    ) -> Result<(), Error> {
+        if let Some(ReceiptView {
+            receipt: ReceiptEnumView::Action { output_data_receivers, .. },
+            ..
+        }) = &receipt.receipt
+        {
+            // Children are visited after their parent, so if the parent forwarded its output
+            // data to a child by returning a promise, the child is recorded as the producer.
+            for data_receiver in output_data_receivers {
+                data_producers.insert(data_receiver.data_id, receipt.receipt_id);
+            }
+        }
+        if let Some(execution) = &receipt.execution {
+            let ordinal = self.store.get_block_merkle_tree(&execution.block_hash)?.size();
+            executions.insert(receipt.receipt_id, ordinal);
+        }
+        for child in &receipt.children {
+            self.collect_trace_executions(child, data_producers, executions)?;
+        }
+        Ok(())
+    }
+
+    /// Fills `data_dependencies` and `delay_blocks` of the receipt and its children.
+    /// `ready_ordinal` is the ordinal of the first block in which the parent allows the receipt
+    /// to be executed.
+    fn fill_trace_dependencies(
+        receipt: &mut ReceiptTraceView,
+        ready_ordinal: NumBlocks,
+        data_producers: &HashMap<CryptoHash, CryptoHash>,
+        executions: &HashMap<CryptoHash, NumBlocks>,
+    ) {
+        if let Some(ReceiptView {
+            receipt: ReceiptEnumView::Action { input_data_ids, .. }, ..
+        }) = &receipt.receipt
+        {
+            receipt.data_dependencies = input_data_ids
+                .iter()
+                .filter_map(|data_id| data_producers.get(data_id))
+                .copied()
+                .collect();
+        }
+        let ordinal = match executions.get(&receipt.receipt_id) {
+            Some(&ordinal) => ordinal,
+            None => return,
+        };
+        // Receipts and data produced by a receipt are outgoing even within a shard, so they can
+        // be executed in the next block at the earliest.
+        let ready_ordinal = receipt
+            .data_dependencies
+            .iter()
+            .filter_map(|id| executions.get(id))
+            .map(|producer_ordinal| producer_ordinal + 1)
+            .fold(ready_ordinal, NumBlocks::max);
+        receipt.delay_blocks = Some(ordinal.saturating_sub(ready_ordinal));
+        for child in receipt.children.iter_mut() {
+            Self::fill_trace_dependencies(child, ordinal + 1, data_producers, executions);
+        }
+    }
+
     /// Find a validator to forward transactions to
     pub fn find_chunk_producer_for_forwarding(
         &self,
@@ -4630,6 +4990,70 @@ impl Chain {
     pub fn patch_state_in_progress(&self) -> bool {
         !self.pending_state_patch.is_empty()
     }
+
+    /// Saves the head as a snapshot which `sandbox_reset_head` can roll the chain back to, and
+    /// stops garbage collection below it. Snapshots are stored in the database, so they survive
+    /// restarts. Returns the id of the snapshot.
+    #[cfg(feature = "sandbox")]
+    pub fn sandbox_save_snapshot(
+        &mut self,
+        accrued_fastforward_delta: BlockHeightDelta,
+    ) -> Result<u64, Error> {
+        let head = self.head()?;
+        let mut snapshots = self.store.get_sandbox_snapshots()?;
+        snapshots.push(crate::types::SandboxSnapshot {
+            block_hash: head.last_block_hash,
+            height: head.height,
+            accrued_fastforward_delta,
+        });
+        self.mut_store().save_sandbox_snapshots(&snapshots)?;
+        Ok((snapshots.len() - 1) as u64)
+    }
+
+    #[cfg(feature = "sandbox")]
+    pub fn sandbox_get_snapshot(
+        &self,
+        snapshot_id: u64,
+    ) -> Result<crate::types::SandboxSnapshot, Error> {
+        let snapshots = self.store.get_sandbox_snapshots()?;
+        snapshots
+            .get(snapshot_id as usize)
+            .cloned()
+            .ok_or_else(|| Error::Other(format!("Sandbox snapshot {} doesn't exist", snapshot_id)))
+    }
+
+    /// Rolls the head back to the given block, which becomes the parent of the next produced
+    /// block. Blocks after it are left on a fork. Trie nodes are never overwritten, so the state
+    /// as of the block stays available for as long as the block isn't garbage collected, see
+    /// `sandbox_save_snapshot`.
+    #[cfg(feature = "sandbox")]
+    pub fn sandbox_reset_head(&mut self, block_hash: &CryptoHash) -> Result<(), Error> {
+        let header = self.get_block_header(block_hash)?;
+        // Blocks at the tail height are already garbage collected, except for the genesis.
+        let tail = self.tail()?;
+        if header.height() < tail || (header.height() == tail && tail != self.genesis().height()) {
+            return Err(Error::Other(format!(
+                "Block {} at height {} is below the garbage collection tail {}",
+                block_hash,
+                header.height(),
+                tail
+            )));
+        }
+        let block = self.get_block(block_hash)?;
+        let last_final_block = block.header().last_final_block();
+        let final_head = if last_final_block == &CryptoHash::default() {
+            Tip::from_header(self.genesis.header())
+        } else {
+            Tip::from_header(&self.get_block_header(last_final_block)?)
+        };
+        let mut chain_store_update = self.mut_store().store_update();
+        // Also resets the header head and removes heights of the blocks after it from the index.
+        chain_store_update.save_head(&Tip::from_header(block.header()))?;
+        // Otherwise the next block on top of the head would not be finalizable.
+        chain_store_update.save_final_head(&final_head)?;
+        chain_store_update.commit()?;
+        Ok(())
+    }
 }
 
 /// Chain update helper, contains information that is needed to process block
@@ -494,6 +494,11 @@ impl Doomslug {
         ret
     }
 
+    /// Replaces the signer used for approvals, e.g. after the validator key was rotated.
+    pub fn set_signer(&mut self, signer: Option<Arc<dyn ValidatorSigner>>) {
+        self.signer = signer;
+    }
+
     fn create_approval(&self, target_height: BlockHeight) -> Option<Approval> {
         self.signer.as_ref().map(|signer| {
             Approval::new(self.tip.block_hash, self.tip.height, target_height, &**signer)
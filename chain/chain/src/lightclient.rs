@@ -1,9 +1,14 @@
 use near_chain_primitives::Error;
-use near_primitives::block::BlockHeader;
+use near_crypto::Signature;
+use near_primitives::block::{Approval, ApprovalInner, BlockHeader};
 use near_primitives::hash::{hash, CryptoHash};
-use near_primitives::types::EpochId;
+use near_primitives::merkle::combine_hash;
+use near_primitives::syncing::EpochSyncFinalizationResponse;
+use near_primitives::types::{Balance, EpochId};
 use near_primitives::views::validator_stake_view::ValidatorStakeView;
-use near_primitives::views::{BlockHeaderInnerLiteView, LightClientBlockView};
+use near_primitives::views::{
+    BlockHeaderInnerLiteView, LightClientBlockLiteView, LightClientBlockView,
+};
 
 use crate::{ChainStoreAccess, RuntimeWithEpochManagerAdapter};
 
@@ -70,3 +75,161 @@ pub fn create_light_client_block_view(
         approvals_after_next,
     })
 }
+
+/// Validates a `LightClientBlock` against the last block known to the light
+/// client, following the light client specification.
+///
+/// # Arguments
+///  * `head` - the last block known to the light client
+///  * `block_view` - the new light client block
+///  * `epoch_block_producers` - the ordered list of block producers of the epoch
+///                   of `block_view`
+///
+/// Returns the hash of the block `block_view` corresponds to.
+pub fn validate_light_client_block(
+    head: &LightClientBlockLiteView,
+    block_view: &LightClientBlockView,
+    epoch_block_producers: &[ValidatorStakeView],
+) -> Result<CryptoHash, Error> {
+    let current_block_hash = LightClientBlockLiteView {
+        prev_block_hash: block_view.prev_block_hash,
+        inner_rest_hash: block_view.inner_rest_hash,
+        inner_lite: block_view.inner_lite.clone(),
+    }
+    .hash();
+    let next_block_hash = combine_hash(&block_view.next_block_inner_hash, &current_block_hash);
+
+    let epoch_id = &block_view.inner_lite.epoch_id;
+    if epoch_id != &head.inner_lite.epoch_id && epoch_id != &head.inner_lite.next_epoch_id {
+        return Err(Error::InvalidEpochHash);
+    }
+    if block_view.inner_lite.height <= head.inner_lite.height {
+        return Err(Error::InvalidBlockHeight(block_view.inner_lite.height));
+    }
+    if epoch_id == &head.inner_lite.next_epoch_id && block_view.next_bps.is_none() {
+        return Err(Error::InvalidNextBPHash);
+    }
+
+    let approval_message = Approval::get_data_for_sig(
+        &ApprovalInner::Endorsement(next_block_hash),
+        block_view.inner_lite.height + 2,
+    );
+    verify_approvals(&block_view.approvals_after_next, &approval_message, epoch_block_producers)?;
+
+    validate_next_block_producers(block_view)?;
+    Ok(current_block_hash)
+}
+
+/// Checks that the next block producers of a light client block, if present,
+/// match the `next_bp_hash` the block commits to.
+pub fn validate_next_block_producers(block_view: &LightClientBlockView) -> Result<(), Error> {
+    if let Some(next_bps) = &block_view.next_bps {
+        // Before `BlockHeaderV3` the hash was computed over unversioned stakes.
+        let next_bps_v1 = next_bps
+            .iter()
+            .map(|bp| bp.clone().into_validator_stake().into_v1())
+            .collect::<Vec<_>>();
+        let next_bp_hash = block_view.inner_lite.next_bp_hash;
+        if CryptoHash::hash_borsh(next_bps) != next_bp_hash
+            && CryptoHash::hash_borsh(&next_bps_v1) != next_bp_hash
+        {
+            return Err(Error::InvalidNextBPHash);
+        }
+    }
+    Ok(())
+}
+
+/// Validates the data a node needs to finish epoch sync and continue with
+/// header sync.
+///
+/// # Arguments
+///  * `final_block_hash` - hash of the last final block of the previous epoch,
+///                   as verified from its light client block
+///  * `epoch_block_producers` - the ordered list of block producers of the
+///                   epoch following the one of `final_block_hash`
+///  * `response` - data received from a peer
+///
+/// The headers between the final block and the first block of the new epoch
+/// are linked by their hashes, and the first block of the new epoch has to be
+/// approved by more than 2/3 of the stake of its block producers.  That block
+/// commits to the rest of the data with its block merkle root and epoch sync
+/// data hash.
+pub fn validate_epoch_sync_finalization(
+    final_block_hash: &CryptoHash,
+    epoch_block_producers: &[ValidatorStakeView],
+    response: &EpochSyncFinalizationResponse,
+) -> Result<(), Error> {
+    let invalid = |msg: &str| Error::InvalidEpochSyncData(msg.to_string());
+    let headers = &response.prev_epoch_headers;
+    match headers.first() {
+        Some(first) if first.hash() == final_block_hash => {}
+        _ => return Err(invalid("headers don't start with the final block")),
+    }
+    for (prev, header) in headers.iter().zip(headers.iter().skip(1)) {
+        if header.prev_hash() != prev.hash() || header.epoch_id() != prev.epoch_id() {
+            return Err(invalid("headers of the previous epoch are not linked"));
+        }
+    }
+    let last_header = headers.last().unwrap();
+    if response.header_sync_init_header.hash() != last_header.hash() {
+        return Err(invalid("header sync doesn't start from the last block of the epoch"));
+    }
+
+    let header = &response.cur_epoch_header;
+    if header.prev_hash() != last_header.hash() || header.epoch_id() != last_header.next_epoch_id()
+    {
+        return Err(invalid("first block of the epoch doesn't follow the previous epoch"));
+    }
+    let approval_message = Approval::get_data_for_sig(
+        &ApprovalInner::new(last_header.hash(), last_header.height(), header.height()),
+        header.height(),
+    );
+    verify_approvals(header.approvals(), &approval_message, epoch_block_producers)?;
+
+    let mut block_merkle_tree = response.header_sync_init_header_tree.clone();
+    block_merkle_tree.insert(*last_header.hash());
+    if &block_merkle_tree.root() != header.block_merkle_root() {
+        return Err(Error::InvalidBlockMerkleRoot);
+    }
+
+    if response.prev_epoch_last_block_info.hash() != last_header.hash() {
+        return Err(invalid("block info doesn't match the last block of the epoch"));
+    }
+    let epoch_sync_data_hash = CryptoHash::hash_borsh(&(
+        &response.prev_epoch_first_block_info,
+        &response.prev_epoch_prev_last_block_info,
+        &response.prev_epoch_last_block_info,
+        &response.prev_epoch_info,
+        &response.cur_epoch_info,
+        &response.next_epoch_info,
+    ));
+    if header.epoch_sync_data_hash() != Some(epoch_sync_data_hash) {
+        return Err(invalid("epoch sync data hash mismatch"));
+    }
+    Ok(())
+}
+
+/// Checks that `approvals` are valid signatures of `approval_message` by the
+/// respective `block_producers`, and that they have more than 2/3 of the stake.
+fn verify_approvals(
+    approvals: &[Option<Signature>],
+    approval_message: &[u8],
+    block_producers: &[ValidatorStakeView],
+) -> Result<(), Error> {
+    let mut total_stake: Balance = 0;
+    let mut approved_stake: Balance = 0;
+    for (maybe_signature, block_producer) in approvals.iter().zip(block_producers.iter()) {
+        let block_producer = block_producer.clone().into_validator_stake();
+        total_stake += block_producer.stake();
+        if let Some(signature) = maybe_signature {
+            if !signature.verify(approval_message, block_producer.public_key()) {
+                return Err(Error::InvalidApprovals);
+            }
+            approved_stake += block_producer.stake();
+        }
+    }
+    if approved_stake * 3 <= total_stake * 2 {
+        return Err(Error::NotEnoughApprovals);
+    }
+    Ok(())
+}
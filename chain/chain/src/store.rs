@@ -28,15 +28,18 @@ use near_primitives::transaction::{
 use near_primitives::trie_key::{trie_key_parsers, TrieKey};
 use near_primitives::types::chunk_extra::ChunkExtra;
 use near_primitives::types::{
-    BlockExtra, BlockHeight, BlockHeightDelta, EpochId, NumBlocks, ShardId, StateChanges,
-    StateChangesExt, StateChangesForSplitStates, StateChangesKinds, StateChangesKindsExt,
-    StateChangesRequest,
+    AccountId, BlockExtra, BlockHeight, BlockHeightDelta, EpochId, NumBlocks, ShardId,
+    StateChanges, StateChangesExt, StateChangesForSplitStates, StateChangesKinds,
+    StateChangesKindsExt, StateChangesRequest,
 };
 use near_primitives::utils::{
     get_block_shard_id, get_outcome_id_block_hash, get_outcome_id_block_hash_rev, index_to_bytes,
     to_timestamp,
 };
-use near_primitives::views::LightClientBlockView;
+use near_primitives::views::{
+    AccountActivityPageView, AccountActivityRole, AccountActivityView, ContractEventView,
+    ContractEventsPageView, EventView, LightClientBlockView,
+};
 use near_store::{
     DBCol, KeyForStateChanges, ShardTries, Store, StoreUpdate, WrappedTrieChanges, CHUNK_TAIL_KEY,
     FINAL_HEAD_KEY, FORK_TAIL_KEY, HEADER_HEAD_KEY, HEAD_KEY, LARGEST_TARGET_HEIGHT_KEY,
@@ -61,6 +64,9 @@ const CACHE_SIZE: usize = 1;
 #[cfg(feature = "no_cache")]
 const CHUNK_CACHE_SIZE: usize = 1;
 
+/// Maximum number of event index entries read by a single `ChainStore::get_events` call.
+const MAX_EVENTS_SCANNED: usize = 10_000;
+
 #[derive(Clone)]
 pub enum GCMode {
     Fork(ShardTries),
@@ -366,6 +372,10 @@ pub struct ChainStore {
     /// - archive is true, cold_store is configured and migration to split_storage is finished - node
     /// working in split storage mode needs trie changes in order to do garbage collection on hot.
     save_trie_changes: bool,
+    /// Whether to populate `DBCol::AccountActivity` when saving outcomes.
+    account_activity_index: bool,
+    /// Whether to populate `DBCol::Events` when saving outcomes.
+    event_index: bool,
 }
 
 fn option_to_not_found<T, F>(res: io::Result<Option<T>>, field_name: F) -> Result<T, Error>
@@ -379,6 +389,76 @@ where
     }
 }
 
+/// Prefix of all `DBCol::AccountActivity` and `DBCol::EventsByContract` rows of the account.
+/// Account ids never contain zero bytes, so the prefix of one account is never a prefix of
+/// another one.
+fn get_account_key_prefix(account_id: &AccountId) -> Vec<u8> {
+    let mut prefix = Vec::with_capacity(account_id.len() + 1);
+    prefix.extend_from_slice(account_id.as_bytes());
+    prefix.push(0);
+    prefix
+}
+
+/// Heights are inverted so that the most recent activity of an account comes first.
+fn get_account_activity_key(
+    account_id: &AccountId,
+    height: BlockHeight,
+    id: &CryptoHash,
+    role: AccountActivityRole,
+) -> Result<Vec<u8>, Error> {
+    let mut key = get_account_key_prefix(account_id);
+    key.extend_from_slice(&(u64::MAX - height).to_be_bytes());
+    key.extend_from_slice(id.as_ref());
+    key.extend(role.try_to_vec()?);
+    Ok(key)
+}
+
+/// Parses the part of a `DBCol::AccountActivity` row following the account prefix.
+fn parse_account_activity_key(
+    key: &[u8],
+) -> Result<(BlockHeight, CryptoHash, AccountActivityRole), Error> {
+    if key.len() < 40 {
+        return Err(Error::Other(format!("wrong account activity key length: {key:?}")));
+    }
+    let height = u64::MAX - u64::from_be_bytes(key[..8].try_into().unwrap());
+    let id = CryptoHash::try_from(&key[8..40])
+        .map_err(|err| Error::Other(format!("wrong account activity key: {err}")))?;
+    let role = AccountActivityRole::try_from_slice(&key[40..])?;
+    Ok((height, id, role))
+}
+
+/// Event as stored in `DBCol::Events`.
+#[derive(BorshSerialize, BorshDeserialize)]
+struct StoredEvent {
+    contract_id: AccountId,
+    log: String,
+}
+
+fn get_events_key(
+    height: BlockHeight,
+    block_hash: &CryptoHash,
+    id: &CryptoHash,
+    log_index: u32,
+) -> Vec<u8> {
+    [&height.to_be_bytes()[..], block_hash.as_ref(), id.as_ref(), &log_index.to_be_bytes()].concat()
+}
+
+fn parse_events_key(key: &[u8]) -> Result<(BlockHeight, CryptoHash, CryptoHash), Error> {
+    if key.len() != 76 {
+        return Err(Error::Other(format!("wrong events key length: {key:?}")));
+    }
+    let height = u64::from_be_bytes(key[..8].try_into().unwrap());
+    let block_hash = CryptoHash::try_from(&key[8..40])
+        .map_err(|err| Error::Other(format!("wrong events key: {err}")))?;
+    let id = CryptoHash::try_from(&key[40..72])
+        .map_err(|err| Error::Other(format!("wrong events key: {err}")))?;
+    Ok((height, block_hash, id))
+}
+
+fn get_account_activity_by_height_key(height: BlockHeight, block_hash: &CryptoHash) -> Vec<u8> {
+    [&height.to_be_bytes()[..], block_hash.as_ref()].concat()
+}
+
 impl ChainStore {
     pub fn new(store: Store, genesis_height: BlockHeight, save_trie_changes: bool) -> ChainStore {
         ChainStore {
@@ -408,9 +488,19 @@ impl ChainStore {
             block_ordinal_to_hash: CellLruCache::new(CACHE_SIZE),
             processed_block_heights: CellLruCache::new(CACHE_SIZE),
             save_trie_changes,
+            account_activity_index: false,
+            event_index: false,
         }
     }
 
+    pub fn set_account_activity_index(&mut self, account_activity_index: bool) {
+        self.account_activity_index = account_activity_index;
+    }
+
+    pub fn set_event_index(&mut self, event_index: bool) {
+        self.event_index = event_index;
+    }
+
     pub fn new_read_only_chunks_store(&self) -> ReadOnlyChunksStore {
         ReadOnlyChunksStore::new(self.store.clone())
     }
@@ -571,6 +661,192 @@ impl ChainStore {
 }
 
 impl ChainStore {
+    /// Returns up to `limit` transactions and receipts touching the account on the canonical
+    /// chain, newest first, starting from `from_height` (inclusive) or the latest indexed block.
+    /// Entries of one block are never split between pages, so a page may hold more than `limit`
+    /// entries.
+    pub fn get_account_activity(
+        &self,
+        account_id: &AccountId,
+        from_height: Option<BlockHeight>,
+        limit: usize,
+    ) -> Result<AccountActivityPageView, Error> {
+        let prefix = get_account_key_prefix(account_id);
+        let mut lower_bound = prefix.clone();
+        lower_bound.extend_from_slice(&(u64::MAX - from_height.unwrap_or(u64::MAX)).to_be_bytes());
+        let mut upper_bound = account_id.as_bytes().to_vec();
+        upper_bound.push(1);
+        let mut activity: Vec<AccountActivityView> = vec![];
+        let mut next_block_height = None;
+        for item in self.store.iter_range(
+            DBCol::AccountActivity,
+            Some(lower_bound.as_slice()),
+            Some(upper_bound.as_slice()),
+        ) {
+            let (key, value) = item?;
+            let (block_height, id, role) = parse_account_activity_key(&key[prefix.len()..])?;
+            if activity.len() >= limit
+                && activity.last().map_or(true, |last| last.block_height != block_height)
+            {
+                next_block_height = Some(block_height);
+                break;
+            }
+            let block_hash = CryptoHash::try_from_slice(&value)?;
+            // Entries of blocks which ended up on a fork are kept until the retention removes
+            // them, skip them here.
+            match self.get_block_hash_by_height(block_height) {
+                Ok(hash) if hash == block_hash => {}
+                Ok(_) | Err(Error::DBNotFoundErr(_)) => continue,
+                Err(err) => return Err(err),
+            }
+            activity.push(AccountActivityView { block_height, block_hash, id, role });
+        }
+        Ok(AccountActivityPageView { activity, next_block_height })
+    }
+
+    /// Removes the account activity index entries of up to `limit` blocks below `height`.
+    pub fn prune_account_activity(
+        &mut self,
+        height: BlockHeight,
+        limit: usize,
+    ) -> Result<(), Error> {
+        let upper_bound = height.to_be_bytes();
+        let mut store_update = self.store.store_update();
+        for item in self
+            .store
+            .iter_range(DBCol::AccountActivityByHeight, None, Some(&upper_bound[..]))
+            .take(limit)
+        {
+            let (key, value) = item?;
+            for activity_key in Vec::<Vec<u8>>::try_from_slice(&value)? {
+                store_update.delete(DBCol::AccountActivity, &activity_key);
+            }
+            store_update.delete(DBCol::AccountActivityByHeight, &key);
+        }
+        store_update.commit()?;
+        Ok(())
+    }
+
+    /// Returns up to `limit` events emitted on the canonical chain between `from_height` and
+    /// `to_height` (both inclusive), oldest first, optionally filtered by the contract, the
+    /// standard and the name of the event. As for the account activity, events of one block are
+    /// never split between pages.
+    ///
+    /// Only the contract is indexed, so at most `MAX_EVENTS_SCANNED` index entries are read per
+    /// call; the page ends early at a block boundary if the other filters skip too many of them.
+    pub fn get_events(
+        &self,
+        contract_id: Option<&AccountId>,
+        standard: Option<&str>,
+        event: Option<&str>,
+        from_height: BlockHeight,
+        to_height: BlockHeight,
+        limit: usize,
+    ) -> Result<ContractEventsPageView, Error> {
+        self.get_events_impl(
+            contract_id,
+            standard,
+            event,
+            from_height,
+            to_height,
+            limit,
+            MAX_EVENTS_SCANNED,
+        )
+    }
+
+    fn get_events_impl(
+        &self,
+        contract_id: Option<&AccountId>,
+        standard: Option<&str>,
+        event: Option<&str>,
+        from_height: BlockHeight,
+        to_height: BlockHeight,
+        limit: usize,
+        max_scanned: usize,
+    ) -> Result<ContractEventsPageView, Error> {
+        let (col, prefix) = match contract_id {
+            Some(contract_id) => (DBCol::EventsByContract, get_account_key_prefix(contract_id)),
+            None => (DBCol::Events, vec![]),
+        };
+        let lower_bound = [prefix.as_slice(), &from_height.to_be_bytes()].concat();
+        let upper_bound = [prefix.as_slice(), &to_height.saturating_add(1).to_be_bytes()].concat();
+        let mut events: Vec<ContractEventView> = vec![];
+        let mut next_block_height = None;
+        let mut scanned_block_height = None;
+        let mut num_scanned = 0;
+        for item in
+            self.store.iter_range(col, Some(lower_bound.as_slice()), Some(upper_bound.as_slice()))
+        {
+            let (key, value) = item?;
+            let events_key = &key[prefix.len()..];
+            let (block_height, block_hash, receipt_id) = parse_events_key(events_key)?;
+            if scanned_block_height != Some(block_height) {
+                if events.len() >= limit || num_scanned >= max_scanned {
+                    next_block_height = Some(block_height);
+                    break;
+                }
+                scanned_block_height = Some(block_height);
+            }
+            num_scanned += 1;
+            match self.get_block_hash_by_height(block_height) {
+                Ok(hash) if hash == block_hash => {}
+                Ok(_) | Err(Error::DBNotFoundErr(_)) => continue,
+                Err(err) => return Err(err),
+            }
+            let stored_event = if contract_id.is_some() {
+                match self.store.get_ser::<StoredEvent>(DBCol::Events, events_key)? {
+                    Some(stored_event) => stored_event,
+                    None => continue,
+                }
+            } else {
+                StoredEvent::try_from_slice(&value)?
+            };
+            let event_view = match EventView::from_log(&stored_event.log) {
+                Some(event_view) => event_view,
+                None => continue,
+            };
+            if standard.map_or(false, |standard| standard != event_view.standard)
+                || event.map_or(false, |event| event != event_view.event)
+            {
+                continue;
+            }
+            events.push(ContractEventView {
+                block_height,
+                block_hash,
+                receipt_id,
+                contract_id: stored_event.contract_id,
+                event: event_view,
+            });
+        }
+        Ok(ContractEventsPageView { events, next_block_height })
+    }
+
+    /// Removes the events emitted in up to `limit` heights below `height` from the event index.
+    pub fn prune_events(&mut self, height: BlockHeight, limit: usize) -> Result<(), Error> {
+        let upper_bound = height.to_be_bytes();
+        let mut store_update = self.store.store_update();
+        let mut num_heights = 0;
+        let mut last_height = None;
+        for item in self.store.iter_range(DBCol::Events, None, Some(&upper_bound[..])) {
+            let (key, value) = item?;
+            let (block_height, _, _) = parse_events_key(&key)?;
+            if last_height != Some(block_height) {
+                if num_heights == limit {
+                    break;
+                }
+                num_heights += 1;
+                last_height = Some(block_height);
+            }
+            let stored_event = StoredEvent::try_from_slice(&value)?;
+            let contract_key =
+                [get_account_key_prefix(&stored_event.contract_id).as_slice(), &key].concat();
+            store_update.delete(DBCol::EventsByContract, &contract_key);
+            store_update.delete(DBCol::Events, &key);
+        }
+        store_update.commit()?;
+        Ok(())
+    }
+
     /// Returns outcomes on all forks generated by applying transaction or
     /// receipt with the given id.
     pub fn get_outcomes_by_id(
@@ -689,6 +965,25 @@ impl ChainStore {
         store_update.commit().map_err(|err| err.into())
     }
 
+    /// Sandbox snapshots, indexed by snapshot id.
+    #[cfg(feature = "sandbox")]
+    pub fn get_sandbox_snapshots(&self) -> Result<Vec<crate::types::SandboxSnapshot>, Error> {
+        Ok(self
+            .store
+            .get_ser(DBCol::BlockMisc, near_store::SANDBOX_SNAPSHOTS_KEY)?
+            .unwrap_or_default())
+    }
+
+    #[cfg(feature = "sandbox")]
+    pub fn save_sandbox_snapshots(
+        &mut self,
+        snapshots: &[crate::types::SandboxSnapshot],
+    ) -> Result<(), Error> {
+        let mut store_update = self.store.store_update();
+        store_update.set_ser(DBCol::BlockMisc, near_store::SANDBOX_SNAPSHOTS_KEY, snapshots)?;
+        store_update.commit().map_err(|err| err.into())
+    }
+
     /// Retrieve the kinds of state changes occurred in a given block.
     ///
     /// We store different types of data, so we prefer to only expose minimal information about the
@@ -1763,6 +2058,23 @@ impl<'a> ChainStoreUpdate<'a> {
         Ok(())
     }
 
+    /// Used only in Epoch Sync finalization to make `header` the first block
+    /// of the canonical chain known to the node, from which header sync continues.
+    /// Validity of Header and its block merkle tree is checked by Epoch Sync methods
+    pub fn save_epoch_sync_header(
+        &mut self,
+        header: BlockHeader,
+        block_merkle_tree: PartialMerkleTree,
+    ) -> Result<(), Error> {
+        let header_hash = *header.hash();
+        self.chain_store_cache_update
+            .block_ordinal_to_hash
+            .insert(block_merkle_tree.size(), header_hash);
+        self.chain_store_cache_update.height_to_hashes.insert(header.height(), Some(header_hash));
+        self.save_block_merkle_tree(header_hash, block_merkle_tree);
+        self.save_block_header_no_update_tree(header)
+    }
+
     pub fn save_block_header(&mut self, header: BlockHeader) -> Result<(), Error> {
         self.update_and_save_block_merkle_tree(&header)?;
         self.chain_store_cache_update.headers.insert(*header.hash(), header);
@@ -2413,7 +2725,11 @@ impl<'a> ChainStoreUpdate<'a> {
             | DBCol::_TransactionRefCount
             | DBCol::_TransactionResult
             | DBCol::StateChangesForSplitStates
-            | DBCol::CachedContractCode => {
+            | DBCol::CachedContractCode
+            | DBCol::AccountActivity
+            | DBCol::AccountActivityByHeight
+            | DBCol::Events
+            | DBCol::EventsByContract => {
                 unreachable!();
             }
             #[cfg(feature = "protocol_feature_flat_state")]
@@ -2555,6 +2871,86 @@ impl<'a> ChainStoreUpdate<'a> {
         Ok(chain_store_update)
     }
 
+    /// Adds the accounts touched by the saved outcomes to the account activity index.
+    fn index_account_activity(&self, store_update: &mut StoreUpdate) -> Result<(), Error> {
+        let mut keys_per_block: HashMap<CryptoHash, (BlockHeight, Vec<Vec<u8>>)> = HashMap::new();
+        for ((outcome_id, block_hash), outcome_with_proof) in
+            self.chain_store_cache_update.outcomes.iter()
+        {
+            let height = self.get_block_header(block_hash)?.height();
+            let mut accounts = vec![];
+            if let Some(transaction) = self.get_transaction(outcome_id)? {
+                let transaction = &transaction.transaction;
+                accounts
+                    .push((transaction.signer_id.clone(), AccountActivityRole::TransactionSigner));
+                accounts.push((
+                    transaction.receiver_id.clone(),
+                    AccountActivityRole::TransactionReceiver,
+                ));
+            } else if let Some(receipt) = self.get_receipt(outcome_id)? {
+                if !receipt.predecessor_id.is_system() {
+                    accounts.push((
+                        receipt.predecessor_id.clone(),
+                        AccountActivityRole::ReceiptPredecessor,
+                    ));
+                }
+                accounts.push((receipt.receiver_id.clone(), AccountActivityRole::ReceiptReceiver));
+            } else {
+                // Receipts which are created and executed within the same chunk are not stored,
+                // only their receiver is known from the outcome.
+                accounts.push((
+                    outcome_with_proof.outcome.executor_id.clone(),
+                    AccountActivityRole::ReceiptReceiver,
+                ));
+            }
+            let (_, keys) = keys_per_block.entry(*block_hash).or_insert_with(|| (height, vec![]));
+            for (account_id, role) in accounts {
+                let key = get_account_activity_key(&account_id, height, outcome_id, role)?;
+                store_update.set_ser(DBCol::AccountActivity, &key, block_hash)?;
+                keys.push(key);
+            }
+        }
+        for (block_hash, (height, keys)) in keys_per_block {
+            let key = get_account_activity_by_height_key(height, &block_hash);
+            let mut all_keys: Vec<Vec<u8>> =
+                self.store().get_ser(DBCol::AccountActivityByHeight, &key)?.unwrap_or_default();
+            all_keys.extend(keys);
+            store_update.set_ser(DBCol::AccountActivityByHeight, &key, &all_keys)?;
+        }
+        Ok(())
+    }
+
+    /// Adds the events emitted in the saved outcomes to the event index.
+    fn index_events(&self, store_update: &mut StoreUpdate) -> Result<(), Error> {
+        for ((outcome_id, block_hash), outcome_with_proof) in
+            self.chain_store_cache_update.outcomes.iter()
+        {
+            let outcome = &outcome_with_proof.outcome;
+            let log_indices: Vec<usize> = (0..outcome.logs.len())
+                .filter(|&index| EventView::from_log(&outcome.logs[index]).is_some())
+                .collect();
+            if log_indices.is_empty() {
+                continue;
+            }
+            let height = self.get_block_header(block_hash)?.height();
+            for index in log_indices {
+                let key = get_events_key(height, block_hash, outcome_id, index as u32);
+                let contract_key =
+                    [get_account_key_prefix(&outcome.executor_id).as_slice(), &key].concat();
+                store_update.set_ser(
+                    DBCol::Events,
+                    &key,
+                    &StoredEvent {
+                        contract_id: outcome.executor_id.clone(),
+                        log: outcome.logs[index].clone(),
+                    },
+                )?;
+                store_update.set(DBCol::EventsByContract, &contract_key, &[]);
+            }
+        }
+        Ok(())
+    }
+
     fn finalize(&mut self) -> Result<StoreUpdate, Error> {
         let mut store_update = self.store().store_update();
         Self::write_col_misc(&mut store_update, HEAD_KEY, &mut self.head)?;
@@ -2721,6 +3117,12 @@ impl<'a> ChainStoreUpdate<'a> {
                 &outcome_with_proof,
             )?;
         }
+        if self.chain_store.account_activity_index {
+            self.index_account_activity(&mut store_update)?;
+        }
+        if self.chain_store.event_index {
+            self.index_events(&mut store_update)?;
+        }
         for ((block_hash, shard_id), ids) in self.chain_store_cache_update.outcome_ids.iter() {
             store_update.set_ser(
                 DBCol::OutcomeIds,
@@ -2981,12 +3383,14 @@ mod tests {
     use near_primitives::block::{Block, Tip};
     use near_primitives::epoch_manager::block_info::BlockInfo;
     use near_primitives::errors::InvalidTxError;
-    use near_primitives::hash::hash;
+    use near_primitives::hash::{hash, CryptoHash};
     use near_primitives::test_utils::create_test_signer;
     use near_primitives::test_utils::TestBlockBuilder;
-    use near_primitives::types::{BlockHeight, EpochId, NumBlocks};
+    use near_primitives::transaction::{ExecutionOutcome, ExecutionOutcomeWithId};
+    use near_primitives::types::{AccountId, BlockHeight, EpochId, NumBlocks};
     use near_primitives::utils::index_to_bytes;
     use near_primitives::validator_signer::InMemoryValidatorSigner;
+    use near_primitives::views::AccountActivityRole;
     use near_store::test_utils::create_test_store;
     use near_store::DBCol;
 
@@ -3490,4 +3894,152 @@ mod tests {
             assert_eq!(store_update.chunk_tail().unwrap(), 0);
         }
     }
+
+    /// Activity is indexed per block, paginated newest first, skips blocks which are not on the
+    /// canonical chain and is removed by pruning.
+    #[test]
+    fn test_account_activity_index() {
+        let mut chain = get_chain();
+        chain.mut_store().set_account_activity_index(true);
+        let genesis = chain.get_block_by_height(0).unwrap();
+        let signer = Arc::new(create_test_signer("test1"));
+        let alice: AccountId = "alice".parse().unwrap();
+        let outcome = |id: CryptoHash| ExecutionOutcomeWithId {
+            id,
+            outcome: ExecutionOutcome { executor_id: alice.clone(), ..Default::default() },
+        };
+        let mut prev_block = genesis.clone();
+        for height in 1..=3 {
+            let block = TestBlockBuilder::new(&prev_block, signer.clone()).height(height).build();
+            let mut store_update = chain.mut_store().store_update();
+            store_update.save_block_header(block.header().clone()).unwrap();
+            store_update.update_height_if_not_challenged(height, *block.hash()).unwrap();
+            store_update.save_outcomes_with_proofs(
+                block.hash(),
+                0,
+                vec![outcome(hash(&[height as u8]))],
+                vec![vec![]],
+            );
+            store_update.commit().unwrap();
+            prev_block = block;
+        }
+        let fork_block = TestBlockBuilder::new(&genesis, signer).height(2).build();
+        let mut store_update = chain.mut_store().store_update();
+        store_update.save_block_header(fork_block.header().clone()).unwrap();
+        store_update.save_outcomes_with_proofs(
+            fork_block.hash(),
+            0,
+            vec![outcome(hash(&[42]))],
+            vec![vec![]],
+        );
+        store_update.commit().unwrap();
+
+        let store = chain.mut_store();
+        let page = store.get_account_activity(&alice, None, 2).unwrap();
+        let ids: Vec<_> = page.activity.iter().map(|activity| activity.id).collect();
+        assert_eq!(ids, vec![hash(&[3]), hash(&[2])]);
+        assert!(page
+            .activity
+            .iter()
+            .all(|activity| activity.role == AccountActivityRole::ReceiptReceiver));
+        assert_eq!(page.next_block_height, Some(1));
+        let page = store.get_account_activity(&alice, page.next_block_height, 2).unwrap();
+        let ids: Vec<_> = page.activity.iter().map(|activity| activity.id).collect();
+        assert_eq!(ids, vec![hash(&[1])]);
+        assert_eq!(page.next_block_height, None);
+
+        store.prune_account_activity(3, 10).unwrap();
+        let page = store.get_account_activity(&alice, None, 10).unwrap();
+        let ids: Vec<_> = page.activity.iter().map(|activity| activity.id).collect();
+        assert_eq!(ids, vec![hash(&[3])]);
+        assert_eq!(store.store().iter(DBCol::AccountActivity).count(), 1);
+    }
+
+    /// Events are parsed from the logs, can be filtered by contract and standard, skip blocks
+    /// which are not on the canonical chain and are removed by pruning.
+    #[test]
+    fn test_event_index() {
+        let mut chain = get_chain();
+        chain.mut_store().set_event_index(true);
+        let genesis = chain.get_block_by_height(0).unwrap();
+        let signer = Arc::new(create_test_signer("test1"));
+        let nft: AccountId = "nft".parse().unwrap();
+        let ft: AccountId = "ft".parse().unwrap();
+        let outcome = |id: CryptoHash, executor_id: &AccountId, standard: &str| {
+            ExecutionOutcomeWithId {
+                id,
+                outcome: ExecutionOutcome {
+                    executor_id: executor_id.clone(),
+                    logs: vec![
+                        "not an event".to_string(),
+                        format!(
+                            r#"EVENT_JSON:{{"standard":"{standard}","version":"1.0.0","event":"mint"}}"#
+                        ),
+                    ],
+                    ..Default::default()
+                },
+            }
+        };
+        let mut prev_block = genesis.clone();
+        for height in 1..=3 {
+            let block = TestBlockBuilder::new(&prev_block, signer.clone()).height(height).build();
+            let mut store_update = chain.mut_store().store_update();
+            store_update.save_block_header(block.header().clone()).unwrap();
+            store_update.update_height_if_not_challenged(height, *block.hash()).unwrap();
+            store_update.save_outcomes_with_proofs(
+                block.hash(),
+                0,
+                vec![
+                    outcome(hash(&[height as u8]), &nft, "nep171"),
+                    outcome(hash(&[height as u8, 1]), &ft, "nep141"),
+                ],
+                vec![vec![], vec![]],
+            );
+            store_update.commit().unwrap();
+            prev_block = block;
+        }
+        let fork_block = TestBlockBuilder::new(&genesis, signer).height(2).build();
+        let mut store_update = chain.mut_store().store_update();
+        store_update.save_block_header(fork_block.header().clone()).unwrap();
+        store_update.save_outcomes_with_proofs(
+            fork_block.hash(),
+            0,
+            vec![outcome(hash(&[42]), &nft, "nep171")],
+            vec![vec![]],
+        );
+        store_update.commit().unwrap();
+
+        let store = chain.mut_store();
+        let page = store.get_events(Some(&nft), None, None, 1, 3, 2).unwrap();
+        let ids: Vec<_> = page.events.iter().map(|event| event.receipt_id).collect();
+        assert_eq!(ids, vec![hash(&[1]), hash(&[2])]);
+        assert!(page.events.iter().all(|event| event.contract_id == nft
+            && event.event.standard == "nep171"
+            && event.event.event == "mint"));
+        assert_eq!(page.next_block_height, Some(3));
+        let page = store.get_events(Some(&nft), None, None, 3, 3, 2).unwrap();
+        assert_eq!(page.events.len(), 1);
+        assert_eq!(page.next_block_height, None);
+
+        let page = store.get_events(None, Some("nep141"), Some("mint"), 2, 3, 10).unwrap();
+        let ids: Vec<_> = page.events.iter().map(|event| event.receipt_id).collect();
+        assert_eq!(ids, vec![hash(&[2, 1]), hash(&[3, 1])]);
+        let page = store.get_events(None, Some("nep141"), Some("burn"), 1, 3, 10).unwrap();
+        assert!(page.events.is_empty());
+        assert_eq!(page.next_block_height, None);
+        // Scanning stops at the first block boundary after the limit of entries, even if no
+        // event matched.
+        let page = store.get_events_impl(None, Some("nep141"), Some("burn"), 1, 3, 10, 2).unwrap();
+        assert!(page.events.is_empty());
+        assert_eq!(page.next_block_height, Some(2));
+        let page = store.get_events_impl(None, Some("nep141"), None, 1, 3, 10, 1).unwrap();
+        assert_eq!(page.events.len(), 1);
+        assert_eq!(page.next_block_height, Some(2));
+
+        store.prune_events(3, 10).unwrap();
+        let page = store.get_events(None, None, None, 0, 3, 10).unwrap();
+        assert_eq!(page.events.len(), 2);
+        assert_eq!(store.store().iter(DBCol::Events).count(), 2);
+        assert_eq!(store.store().iter(DBCol::EventsByContract).count(), 2);
+    }
 }
@@ -19,17 +19,25 @@ use near_primitives::syncing::{ShardStateSyncResponseHeader, StateHeaderKey, Sta
 use near_primitives::transaction::ExecutionOutcomeWithProof;
 use near_primitives::types::chunk_extra::ChunkExtra;
 use near_primitives::types::{AccountId, BlockHeight, EpochId};
-use near_primitives::utils::{get_block_shard_id_rev, get_outcome_id_block_hash_rev};
+use near_primitives::utils::{
+    get_block_shard_id_rev, get_outcome_id_block_hash_rev, index_to_bytes,
+};
 use near_store::db::refcount;
-use near_store::{DBCol, Store, TrieChanges};
+use near_store::{DBCol, Store, TrieChanges, TAIL_KEY};
 use validate::StoreValidatorError;
 
 use crate::RuntimeWithEpochManagerAdapter;
 use near_primitives::shard_layout::get_block_shard_uid_rev;
 use near_primitives::static_clock::StaticClock;
 
+mod repair;
 mod validate;
 
+/// Columns which [`StoreValidator::repair`] can rebuild from the data they
+/// index.
+pub const REPAIRABLE_COLUMNS: [DBCol; 3] =
+    [DBCol::HeaderHashesByHeight, DBCol::ChunkHashesByHeight, DBCol::BlockRefCount];
+
 pub struct StoreValidatorCache {
     head: BlockHeight,
     header_head: BlockHeight,
@@ -73,6 +81,7 @@ pub struct StoreValidator {
     store: Store,
     inner: StoreValidatorCache,
     timeout: Option<u64>,
+    height_delay: Option<Duration>,
     start_time: Instant,
     pub is_archival: bool,
 
@@ -95,6 +104,7 @@ impl StoreValidator {
             store: store,
             inner: StoreValidatorCache::new(),
             timeout: None,
+            height_delay: None,
             start_time: StaticClock::instant(),
             is_archival,
             errors: vec![],
@@ -104,6 +114,11 @@ impl StoreValidator {
     pub fn set_timeout(&mut self, timeout: u64) {
         self.timeout = Some(timeout)
     }
+    /// Makes [`Self::validate_incremental`] sleep for `delay` after each
+    /// height, which throttles its reads from the database.
+    pub fn set_height_delay(&mut self, delay: Duration) {
+        self.height_delay = Some(delay)
+    }
     pub fn is_failed(&self) -> bool {
         self.tests == 0 || self.errors.len() > 0
     }
@@ -116,6 +131,57 @@ impl StoreValidator {
     fn process_error<K: std::fmt::Debug>(&mut self, err: StoreValidatorError, key: K, col: DBCol) {
         self.errors.push(ErrorMessage { key: format!("{key:?}"), col: col.to_string(), err })
     }
+    fn check_block_header(&mut self, block_hash: &CryptoHash, header: &BlockHeader) {
+        let col = DBCol::BlockHeader;
+        // Block Header Hash is valid
+        self.check(&validate::block_header_hash_validity, block_hash, header, col);
+        // Block Header Height is valid
+        self.check(&validate::block_header_height_validity, block_hash, header, col);
+        // Block Header can be indexed by Height
+        self.check(&validate::header_hash_indexed_by_height, block_hash, header, col);
+    }
+
+    fn check_block(&mut self, block_hash: &CryptoHash, block: &Block) {
+        let col = DBCol::Block;
+        // Block Hash is valid
+        self.check(&validate::block_hash_validity, block_hash, block, col);
+        // Block Height is valid
+        self.check(&validate::block_height_validity, block_hash, block, col);
+        // Block can be indexed by its Height
+        self.check(&validate::block_indexed_by_height, block_hash, block, col);
+        // Block Header for current Block exists
+        self.check(&validate::block_header_exists, block_hash, block, col);
+        // Chunks for current Block exist
+        self.check(&validate::block_chunks_exist, block_hash, block, col);
+        // Chunks for current Block have Height Created not higher than Block Height
+        self.check(&validate::block_chunks_height_validity, block_hash, block, col);
+        // BlockInfo for current Block exists
+        self.check(&validate::block_info_exists, block_hash, block, col);
+        // EpochInfo for current Epoch id of Block exists
+        self.check(&validate::block_epoch_exists, block_hash, block, col);
+    }
+
+    fn check_canonical_block(&mut self, height: &BlockHeight, hash: &CryptoHash) {
+        let col = DBCol::BlockHeight;
+        // Block on the Canonical Chain is stored properly
+        self.check(&validate::canonical_header_validity, height, hash, col);
+        // If prev Block exists, it's also on the Canonical Chain and
+        // there are no Blocks in range (prev_height, height) on the Canonical Chain
+        self.check(&validate::canonical_prev_block_validity, height, hash, col);
+    }
+
+    fn check_chunk(&mut self, chunk_hash: &ChunkHash, shard_chunk: &ShardChunk) {
+        let col = DBCol::Chunks;
+        // Chunk Hash is valid
+        self.check(&validate::chunk_hash_validity, chunk_hash, shard_chunk, col);
+        // Chunk Height Created is not lower than Chunk Tail
+        self.check(&validate::chunk_tail_validity, chunk_hash, shard_chunk, col);
+        // ShardChunk can be indexed by Height
+        self.check(&validate::chunk_indexed_by_height_created, chunk_hash, shard_chunk, col);
+        // Check that all Txs in Chunk exist
+        self.check(&validate::chunk_tx_exists, chunk_hash, shard_chunk, col);
+    }
+
     fn validate_col(&mut self, col: DBCol) -> Result<(), StoreValidatorError> {
         for item in self.store.clone().iter_raw_bytes(col) {
             let (key, value) = item?;
@@ -125,60 +191,24 @@ impl StoreValidator {
                 DBCol::BlockHeader => {
                     let block_hash = CryptoHash::try_from(key_ref)?;
                     let header = BlockHeader::try_from_slice(value_ref)?;
-                    // Block Header Hash is valid
-                    self.check(&validate::block_header_hash_validity, &block_hash, &header, col);
-                    // Block Header Height is valid
-                    self.check(&validate::block_header_height_validity, &block_hash, &header, col);
-                    // Block Header can be indexed by Height
-                    self.check(&validate::header_hash_indexed_by_height, &block_hash, &header, col);
+                    self.check_block_header(&block_hash, &header);
                 }
                 DBCol::Block => {
                     let block_hash = CryptoHash::try_from(key_ref)?;
                     let block = Block::try_from_slice(value_ref)?;
-                    // Block Hash is valid
-                    self.check(&validate::block_hash_validity, &block_hash, &block, col);
-                    // Block Height is valid
-                    self.check(&validate::block_height_validity, &block_hash, &block, col);
-                    // Block can be indexed by its Height
-                    self.check(&validate::block_indexed_by_height, &block_hash, &block, col);
-                    // Block Header for current Block exists
-                    self.check(&validate::block_header_exists, &block_hash, &block, col);
-                    // Chunks for current Block exist
-                    self.check(&validate::block_chunks_exist, &block_hash, &block, col);
-                    // Chunks for current Block have Height Created not higher than Block Height
-                    self.check(&validate::block_chunks_height_validity, &block_hash, &block, col);
-                    // BlockInfo for current Block exists
-                    self.check(&validate::block_info_exists, &block_hash, &block, col);
-                    // EpochInfo for current Epoch id of Block exists
-                    self.check(&validate::block_epoch_exists, &block_hash, &block, col);
+                    self.check_block(&block_hash, &block);
                     // Increase Block Refcount
                     self.check(&validate::block_increment_refcount, &block_hash, &block, col);
                 }
                 DBCol::BlockHeight => {
                     let height = BlockHeight::try_from_slice(key_ref)?;
                     let hash = CryptoHash::try_from(value_ref)?;
-                    // Block on the Canonical Chain is stored properly
-                    self.check(&validate::canonical_header_validity, &height, &hash, col);
-                    // If prev Block exists, it's also on the Canonical Chain and
-                    // there are no Blocks in range (prev_height, height) on the Canonical Chain
-                    self.check(&validate::canonical_prev_block_validity, &height, &hash, col);
+                    self.check_canonical_block(&height, &hash);
                 }
                 DBCol::Chunks => {
                     let chunk_hash = ChunkHash::try_from_slice(key_ref)?;
                     let shard_chunk = ShardChunk::try_from_slice(value_ref)?;
-                    // Chunk Hash is valid
-                    self.check(&validate::chunk_hash_validity, &chunk_hash, &shard_chunk, col);
-                    // Chunk Height Created is not lower than Chunk Tail
-                    self.check(&validate::chunk_tail_validity, &chunk_hash, &shard_chunk, col);
-                    // ShardChunk can be indexed by Height
-                    self.check(
-                        &validate::chunk_indexed_by_height_created,
-                        &chunk_hash,
-                        &shard_chunk,
-                        col,
-                    );
-                    // Check that all Txs in Chunk exist
-                    self.check(&validate::chunk_tx_exists, &chunk_hash, &shard_chunk, col);
+                    self.check_chunk(&chunk_hash, &shard_chunk);
                 }
                 DBCol::ChunkExtra => {
                     let (block_hash, shard_uid) = get_block_shard_uid_rev(key_ref)?;
@@ -361,6 +391,127 @@ impl StoreValidator {
         }
     }
 
+    /// Validates the data indexed by heights from `from_height` up to the
+    /// head and returns the height to continue from on the next call.
+    ///
+    /// Unlike [`Self::validate`] this looks only at blocks, headers and
+    /// chunks reachable from the height indices rather than iterating over
+    /// whole columns, and skips the final checks which need to see all the
+    /// data.  This makes it cheap enough to run periodically on a live node,
+    /// passing the returned height back in to validate only what was written
+    /// since.  Errors found at heights which got garbage collected while they
+    /// were being validated are dropped.  The results of the previous call
+    /// are cleared.
+    pub fn validate_incremental(&mut self, from_height: BlockHeight) -> BlockHeight {
+        self.start_time = StaticClock::instant();
+        self.inner = StoreValidatorCache::new();
+        self.errors.clear();
+        self.tests = 0;
+
+        if let Err(e) = validate::head_tail_validity(self) {
+            self.process_error(e, "HEAD / HEADER_HEAD / TAIL / CHUNK_TAIL", DBCol::BlockMisc);
+            return from_height;
+        }
+        let start = std::cmp::max(from_height, self.inner.tail);
+        for height in start..=self.inner.head {
+            let num_errors = self.errors.len();
+            if let Err(e) = self.validate_height(height) {
+                self.process_error(e, height, DBCol::BlockHeight)
+            }
+            if self.is_garbage_collected(height) {
+                self.errors.truncate(num_errors);
+            }
+            if let Some(delay) = self.height_delay {
+                std::thread::sleep(delay);
+            }
+            if let Some(timeout) = self.timeout {
+                if self.start_time.elapsed() > Duration::from_millis(timeout) {
+                    return height + 1;
+                }
+            }
+        }
+        self.inner.head + 1
+    }
+
+    fn validate_height(&mut self, height: BlockHeight) -> Result<(), StoreValidatorError> {
+        let key = index_to_bytes(height);
+        let header_hashes =
+            self.store.get_ser::<HashSet<CryptoHash>>(DBCol::HeaderHashesByHeight, &key)?;
+        if let Some(header_hashes) = header_hashes {
+            // Headers which can be indexed by Height exists
+            self.check(
+                &validate::header_hash_of_height_exists,
+                &height,
+                &header_hashes,
+                DBCol::HeaderHashesByHeight,
+            );
+            for hash in header_hashes.iter() {
+                let header =
+                    self.store.get_ser::<BlockHeader>(DBCol::BlockHeader, hash.as_ref())?;
+                if let Some(header) = header {
+                    self.check_block_header(hash, &header);
+                }
+                if let Some(block) = self.store.get_ser::<Block>(DBCol::Block, hash.as_ref())? {
+                    self.check_block(hash, &block);
+                }
+                let block_info =
+                    self.store.get_ser::<BlockInfo>(DBCol::BlockInfo, hash.as_ref())?;
+                if let Some(block_info) = block_info {
+                    // Block which can be indexed by BlockInfo exists
+                    self.check(
+                        &validate::block_info_block_header_exists,
+                        hash,
+                        &block_info,
+                        DBCol::BlockInfo,
+                    );
+                }
+            }
+        }
+        if let Some(hash) = self.store.get_ser::<CryptoHash>(DBCol::BlockHeight, &key)? {
+            self.check_canonical_block(&height, &hash);
+        }
+        let chunk_hashes =
+            self.store.get_ser::<HashSet<ChunkHash>>(DBCol::ChunkHashesByHeight, &key)?;
+        if let Some(chunk_hashes) = chunk_hashes {
+            // ShardChunk which can be indexed by Height exists
+            self.check(
+                &validate::chunk_of_height_exists,
+                &height,
+                &chunk_hashes,
+                DBCol::ChunkHashesByHeight,
+            );
+            for chunk_hash in chunk_hashes.iter() {
+                let shard_chunk =
+                    self.store.get_ser::<ShardChunk>(DBCol::Chunks, chunk_hash.as_ref())?;
+                if let Some(shard_chunk) = shard_chunk {
+                    self.check_chunk(chunk_hash, &shard_chunk);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the tail has moved past `height` since the validation started.
+    fn is_garbage_collected(&self, height: BlockHeight) -> bool {
+        match self.store.get_ser::<BlockHeight>(DBCol::BlockMisc, TAIL_KEY) {
+            Ok(Some(tail)) => tail > height,
+            _ => false,
+        }
+    }
+
+    /// Rebuilds the columns which only index data stored in other columns,
+    /// see [`REPAIRABLE_COLUMNS`], and returns the number of rows which were
+    /// written or deleted in each of them.
+    ///
+    /// The node must not be running while the columns are rebuilt.
+    pub fn repair(&self) -> Result<Vec<(DBCol, u64)>, StoreValidatorError> {
+        Ok(vec![
+            (DBCol::HeaderHashesByHeight, repair::header_hashes_by_height(self)?),
+            (DBCol::ChunkHashesByHeight, repair::chunk_hashes_by_height(self)?),
+            (DBCol::BlockRefCount, repair::block_refcount(self)?),
+        ])
+    }
+
     fn check<K: std::fmt::Debug + ?Sized, V: ?Sized>(
         &mut self,
         f: &dyn Fn(&mut StoreValidator, &K, &V) -> Result<(), StoreValidatorError>,
@@ -377,9 +528,10 @@ impl StoreValidator {
 
 #[cfg(test)]
 mod tests {
+    use near_primitives::test_utils::TestBlockBuilder;
     use near_store::test_utils::create_test_store;
 
-    use crate::test_utils::KeyValueRuntime;
+    use crate::test_utils::{setup, KeyValueRuntime};
     use crate::types::ChainConfig;
     use crate::{Chain, ChainGenesis, ChainStoreAccess, DoomslugThresholdMode};
 
@@ -469,4 +621,78 @@ mod tests {
             _ => assert!(false),
         }
     }
+
+    fn init_with_blocks(num_blocks: u64) -> (Chain, StoreValidator) {
+        let (mut chain, runtime_adapter, signer) = setup();
+        for _ in 0..num_blocks {
+            let prev_hash = *chain.head_header().unwrap().hash();
+            let prev = chain.get_block(&prev_hash).unwrap();
+            let block = TestBlockBuilder::new(&prev, signer.clone()).build();
+            chain.process_block_test(&None, block).unwrap();
+        }
+        let mut genesis = GenesisConfig::default();
+        genesis.genesis_height = 0;
+        let store = chain.store().store().clone();
+        (chain, StoreValidator::new(None, genesis, runtime_adapter, store, false))
+    }
+
+    fn header_hashes_by_height_errors(sv: &StoreValidator) -> usize {
+        let col = DBCol::HeaderHashesByHeight.to_string();
+        sv.errors.iter().filter(|error| error.col == col).count()
+    }
+
+    #[test]
+    fn test_validate_incremental() {
+        let (chain, mut sv) = init_with_blocks(4);
+        let mut store_update = chain.store().store().store_update();
+        let bogus_hashes = HashSet::from([CryptoHash::default()]);
+        store_update
+            .set_ser(DBCol::HeaderHashesByHeight, &index_to_bytes(2), &bogus_hashes)
+            .unwrap();
+        store_update.commit().unwrap();
+
+        assert_eq!(sv.validate_incremental(0), 5);
+        assert!(sv.tests_done() > 0);
+        assert_eq!(header_hashes_by_height_errors(&sv), 1);
+
+        // Heights which were validated before are skipped.
+        assert_eq!(sv.validate_incremental(3), 5);
+        assert_eq!(header_hashes_by_height_errors(&sv), 0);
+        assert_eq!(sv.validate_incremental(5), 5);
+        assert_eq!(sv.tests_done(), 0);
+    }
+
+    #[test]
+    fn test_repair() {
+        let (chain, sv) = init_with_blocks(4);
+        // Columns written by the chain are rebuilt exactly.
+        assert!(sv.repair().unwrap().iter().all(|&(_, num_repaired)| num_repaired == 0));
+
+        let store = chain.store().store();
+        let genesis_hash = *chain.genesis().hash();
+        let head_hash = chain.head().unwrap().last_block_hash;
+        let mut store_update = store.store_update();
+        store_update.delete(DBCol::HeaderHashesByHeight, &index_to_bytes(3));
+        store_update.delete(DBCol::ChunkHashesByHeight, &index_to_bytes(0));
+        store_update.set_ser(DBCol::BlockRefCount, genesis_hash.as_ref(), &5u64).unwrap();
+        store_update.set_ser(DBCol::BlockRefCount, head_hash.as_ref(), &1u64).unwrap();
+        store_update.commit().unwrap();
+
+        assert_eq!(
+            sv.repair().unwrap(),
+            vec![
+                (DBCol::HeaderHashesByHeight, 1),
+                (DBCol::ChunkHashesByHeight, 1),
+                (DBCol::BlockRefCount, 2)
+            ]
+        );
+        assert!(sv.repair().unwrap().iter().all(|&(_, num_repaired)| num_repaired == 0));
+        let header_hashes = chain.store().get_all_header_hashes_by_height(3).unwrap();
+        assert_eq!(header_hashes.len(), 1);
+        assert_eq!(
+            store.get_ser::<u64>(DBCol::BlockRefCount, genesis_hash.as_ref()).unwrap(),
+            Some(1)
+        );
+        assert_eq!(store.get_ser::<u64>(DBCol::BlockRefCount, head_hash.as_ref()).unwrap(), None);
+    }
 }
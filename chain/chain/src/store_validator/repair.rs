@@ -0,0 +1,120 @@
+//! Rebuilding of the columns which only index data stored in other columns.
+//!
+//! Each column is computed from scratch from its source of truth and then
+//! compared with what's stored, so that only the rows which differ are
+//! written.
+use crate::StoreValidator;
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_primitives::block::{Block, BlockHeader};
+use near_primitives::hash::CryptoHash;
+use near_primitives::sharding::{ChunkHash, ShardChunk};
+use near_primitives::types::BlockHeight;
+use near_primitives::utils::index_to_bytes;
+use near_store::{DBCol, Store, CHUNK_TAIL_KEY};
+use std::collections::{HashMap, HashSet};
+
+use super::validate::StoreValidatorError;
+
+/// `DBCol::HeaderHashesByHeight` from `DBCol::BlockHeader`.
+///
+/// Headers are never garbage collected, so heights below the chunk tail
+/// are left unindexed the same way garbage collection leaves them.
+pub(crate) fn header_hashes_by_height(sv: &StoreValidator) -> Result<u64, StoreValidatorError> {
+    let chunk_tail = chunk_tail(sv)?;
+    let mut expected = HashMap::<Vec<u8>, HashSet<CryptoHash>>::new();
+    for item in sv.store.iter_raw_bytes(DBCol::BlockHeader) {
+        let (key, value) = item?;
+        let header = BlockHeader::try_from_slice(&value)?;
+        if header.height() >= chunk_tail {
+            let hash = CryptoHash::try_from(key.as_ref())?;
+            expected.entry(index_to_bytes(header.height()).to_vec()).or_default().insert(hash);
+        }
+    }
+    sync_column(&sv.store, DBCol::HeaderHashesByHeight, expected, |_| true)
+}
+
+/// `DBCol::ChunkHashesByHeight` from `DBCol::Chunks`, indexed by the height
+/// the chunks were created at.
+pub(crate) fn chunk_hashes_by_height(sv: &StoreValidator) -> Result<u64, StoreValidatorError> {
+    let mut expected = HashMap::<Vec<u8>, HashSet<ChunkHash>>::new();
+    for item in sv.store.iter_raw_bytes(DBCol::Chunks) {
+        let (key, value) = item?;
+        let chunk_hash = ChunkHash::try_from_slice(&key)?;
+        let shard_chunk = ShardChunk::try_from_slice(&value)?;
+        let height = index_to_bytes(shard_chunk.height_created()).to_vec();
+        expected.entry(height).or_default().insert(chunk_hash);
+    }
+    sync_column(&sv.store, DBCol::ChunkHashesByHeight, expected, |_| true)
+}
+
+/// `DBCol::BlockRefCount` from `DBCol::Block`: the refcount of a block is the
+/// number of blocks built on top of it.
+///
+/// Blocks whose previous block has been garbage collected don't count, and
+/// the refcount of genesis is left as is if nothing is built on top of it,
+/// as garbage collection doesn't maintain it then.
+pub(crate) fn block_refcount(sv: &StoreValidator) -> Result<u64, StoreValidatorError> {
+    let mut block_hashes = HashSet::new();
+    let mut prev_hashes = vec![];
+    for item in sv.store.iter_raw_bytes(DBCol::Block) {
+        let (key, value) = item?;
+        let block = Block::try_from_slice(&value)?;
+        block_hashes.insert(CryptoHash::try_from(key.as_ref())?);
+        if block.header().height() != sv.config.genesis_height {
+            prev_hashes.push(*block.header().prev_hash());
+        }
+    }
+    let mut expected = HashMap::<Vec<u8>, u64>::new();
+    for prev_hash in prev_hashes {
+        if block_hashes.contains(&prev_hash) {
+            *expected.entry(prev_hash.as_ref().to_vec()).or_default() += 1;
+        }
+    }
+    let genesis_hash = sv
+        .store
+        .get_ser::<CryptoHash>(DBCol::BlockHeight, &index_to_bytes(sv.config.genesis_height))?;
+    sync_column(&sv.store, DBCol::BlockRefCount, expected, |key| {
+        genesis_hash.map_or(true, |hash| key != hash.as_ref())
+    })
+}
+
+fn chunk_tail(sv: &StoreValidator) -> Result<BlockHeight, StoreValidatorError> {
+    let chunk_tail = sv.store.get_ser::<BlockHeight>(DBCol::BlockMisc, CHUNK_TAIL_KEY)?;
+    Ok(chunk_tail.unwrap_or(sv.config.genesis_height))
+}
+
+/// Makes `col` contain exactly the `expected` rows, apart from the rows
+/// `is_derived` returns false for, which are kept.  Returns the number of
+/// rows written or deleted.
+fn sync_column<T: BorshSerialize + BorshDeserialize + PartialEq>(
+    store: &Store,
+    col: DBCol,
+    mut expected: HashMap<Vec<u8>, T>,
+    is_derived: impl Fn(&[u8]) -> bool,
+) -> Result<u64, StoreValidatorError> {
+    let mut store_update = store.store_update();
+    let mut num_repaired = 0;
+    for item in store.iter_raw_bytes(col) {
+        let (key, value) = item?;
+        match expected.remove(key.as_ref()) {
+            Some(expected) => {
+                if T::try_from_slice(&value).ok().as_ref() != Some(&expected) {
+                    store_update.set_ser(col, &key, &expected)?;
+                    num_repaired += 1;
+                }
+            }
+            None => {
+                if is_derived(&key) {
+                    store_update.delete(col, &key);
+                    num_repaired += 1;
+                }
+            }
+        }
+    }
+    for (key, value) in expected {
+        store_update.set_ser(col, &key, &value)?;
+        num_repaired += 1;
+    }
+    store_update.commit()?;
+    Ok(num_repaired)
+}
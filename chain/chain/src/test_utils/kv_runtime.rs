@@ -39,7 +39,8 @@ use near_primitives::types::{
 use near_primitives::version::{ProtocolVersion, PROTOCOL_VERSION};
 use near_primitives::views::{
     AccessKeyInfoView, AccessKeyList, CallResult, ContractCodeView, EpochValidatorInfo,
-    QueryRequest, QueryResponse, QueryResponseKind, ViewStateResult,
+    QueryRequest, QueryResponse, QueryResponseKind, RewardProjectionView, StakeDelegationView,
+    ViewStateResult,
 };
 use near_store::{
     DBCol, PartialStorage, ShardTries, Store, StoreUpdate, Trie, TrieChanges, WrappedTrieChanges,
@@ -651,6 +652,15 @@ impl EpochManagerAdapter for KeyValueRuntime {
         })
     }
 
+    fn get_reward_projection(
+        &self,
+        _block_hash: &CryptoHash,
+        _num_epochs: u64,
+        _delegations: Vec<StakeDelegationView>,
+    ) -> Result<RewardProjectionView, Error> {
+        unimplemented!();
+    }
+
     fn get_epoch_minted_amount(&self, _epoch_id: &EpochId) -> Result<Balance, Error> {
         Ok(0)
     }
@@ -1259,6 +1269,16 @@ impl RuntimeAdapter for KeyValueRuntime {
         Ok(())
     }
 
+    fn precompile_contracts_in_background(
+        &self,
+        _shard_id: ShardId,
+        _state_root: &StateRoot,
+        _epoch_id: &EpochId,
+        _next_epoch_id: &EpochId,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
     fn get_state_root_node(
         &self,
         _shard_id: ShardId,
@@ -242,11 +242,20 @@ pub struct ChainConfig {
     /// Number of threads to execute background migration work.
     /// Currently used for flat storage background creation.
     pub background_migration_threads: usize,
+    /// Whether to index the activity of accounts on block application.
+    pub account_activity_index: bool,
+    /// Whether to index events emitted by contracts on block application.
+    pub event_index: bool,
 }
 
 impl ChainConfig {
     pub fn test() -> Self {
-        Self { save_trie_changes: true, background_migration_threads: 1 }
+        Self {
+            save_trie_changes: true,
+            background_migration_threads: 1,
+            account_activity_index: false,
+            event_index: false,
+        }
     }
 }
 
@@ -553,6 +562,19 @@ pub trait RuntimeAdapter: Send + Sync {
         epoch_id: &EpochId,
     ) -> Result<(), Error>;
 
+    /// Starts compiling all contracts in the state of the shard in the background, so that the
+    /// first calls to them after the node starts tracking the shard don't pay for compilation.
+    /// Contracts are compiled for the protocol versions of both `epoch_id` and `next_epoch_id`.
+    /// Shards are compiled one at a time, in the order of the calls. Should be executed after
+    /// all state parts of the shard are applied.
+    fn precompile_contracts_in_background(
+        &self,
+        shard_id: ShardId,
+        state_root: &StateRoot,
+        epoch_id: &EpochId,
+        next_epoch_id: &EpochId,
+    ) -> Result<(), Error>;
+
     /// Returns StateRootNode of a state.
     /// `block_hash` is a block whose `prev_state_root` is `state_root`
     /// Panics if requested hash is not in storage.
@@ -590,6 +612,16 @@ pub struct LatestKnown {
     pub seen: u64,
 }
 
+/// Sandbox snapshot the chain can be rolled back to with `Chain::sandbox_reset_head`.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct SandboxSnapshot {
+    /// Head block at the time of the snapshot.
+    pub block_hash: CryptoHash,
+    pub height: BlockHeight,
+    /// Fast forward delta accrued by the client at the time of the snapshot.
+    pub accrued_fastforward_delta: BlockHeightDelta,
+}
+
 #[cfg(test)]
 mod tests {
     use chrono::Utc;
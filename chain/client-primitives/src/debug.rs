@@ -11,7 +11,7 @@ use near_primitives::{
     block_header::ApprovalInner,
     hash::CryptoHash,
     sharding::ChunkHash,
-    types::{AccountId, BlockHeight},
+    types::{AccountId, BlockHeight, ShardId},
     views::ValidatorInfo,
 };
 use std::collections::HashMap;
@@ -164,6 +164,50 @@ pub struct ValidatorStatus {
     pub production: Vec<(BlockHeight, ProductionAtHeight)>,
     // Chunk producers that this node has banned.
     pub banned_chunk_producers: Vec<(EpochId, Vec<AccountId>)>,
+    // Blocks & chunks that we were responsible for but which didn't make it into the canonical
+    // chain, most recent first.
+    pub missed_production: Vec<MissedProduction>,
+    // Production counters of this node in the current epoch.
+    pub uptime: Option<ValidatorUptime>,
+}
+
+// Most likely reason why a block or chunk that we were responsible for is missing.
+#[derive(serde::Serialize, Debug, Clone, Copy, PartialEq, Eq, strum::AsRefStr)]
+pub enum MissReason {
+    // The node was syncing and didn't try to produce.
+    NotSynced,
+    // State of the previous block was not available yet (e.g. state sync at the epoch boundary
+    // was still in progress).
+    MissingState,
+    // The previous block was waiting for chunk parts, so it couldn't be applied in time.
+    NoChunkParts,
+    // Not enough approvals were received to produce the block.
+    LateApproval,
+    // Validator key of the node doesn't match the key staked for this epoch.
+    KeyMismatch,
+    // We produced it, but it was not included in the canonical chain.
+    NotIncluded,
+    Unknown,
+}
+
+#[derive(serde::Serialize, Debug, Clone)]
+pub struct MissedProduction {
+    pub height: BlockHeight,
+    // None for blocks, shard of the chunk otherwise.
+    pub shard_id: Option<ShardId>,
+    pub reason: MissReason,
+}
+
+#[derive(serde::Serialize, Debug, Clone, Default)]
+pub struct ValidatorUptime {
+    pub epoch_id: EpochId,
+    pub blocks_produced: u64,
+    pub blocks_expected: u64,
+    pub chunks_produced: u64,
+    pub chunks_expected: u64,
+    // Kickout thresholds of the epoch, in percent.
+    pub block_producer_kickout_threshold: u8,
+    pub chunk_producer_kickout_threshold: u8,
 }
 
 // Different debug requests that can be sent by HTML pages, via GET.
@@ -12,11 +12,12 @@ use near_primitives::types::{
 };
 use near_primitives::views::validator_stake_view::ValidatorStakeView;
 use near_primitives::views::{
-    BlockView, ChunkView, DownloadStatusView, EpochValidatorInfo, ExecutionOutcomeWithIdView,
-    FinalExecutionOutcomeViewEnum, GasPriceView, LightClientBlockLiteView, LightClientBlockView,
-    MaintenanceWindowsView, QueryRequest, QueryResponse, ReceiptView, ShardSyncDownloadView,
-    SplitStorageInfoView, StateChangesKindsView, StateChangesRequestView, StateChangesView,
-    SyncStatusView,
+    AccountActivityPageView, BlockView, ChunkView, ContractEventsPageView, DownloadStatusView,
+    EpochValidatorInfo, ExecutionOutcomeWithIdView, FinalExecutionOutcomeViewEnum, GasPriceView,
+    LightClientBlockLiteView, LightClientBlockView, MaintenanceWindowsView, QueryRequest,
+    QueryResponse, ReceiptView, RewardProjectionView, ShardSyncDownloadView, SplitStorageInfoView,
+    StakeDelegationView, StateChangesKindsView, StateChangesRequestView, StateChangesView,
+    SyncStatusView, TxTraceView,
 };
 pub use near_primitives::views::{StatusResponse, StatusSyncInfo};
 use once_cell::sync::OnceCell;
@@ -645,6 +646,8 @@ pub enum GetValidatorInfoError {
     UnknownEpoch,
     #[error("Validator info unavailable")]
     ValidatorInfoUnavailable,
+    #[error("Invalid delegation: {0}")]
+    InvalidDelegation(String),
     // NOTE: Currently, the underlying errors are too broad, and while we tried to handle
     // expected cases, we cannot statically guarantee that no other errors will be returned
     // in the future.
@@ -672,6 +675,16 @@ impl Message for GetValidatorOrdered {
     type Result = Result<Vec<ValidatorStakeView>, GetValidatorInfoError>;
 }
 
+pub struct GetRewardProjection {
+    pub block_id: MaybeBlockId,
+    pub num_epochs: u64,
+    pub delegations: Vec<StakeDelegationView>,
+}
+
+impl Message for GetRewardProjection {
+    type Result = Result<RewardProjectionView, GetValidatorInfoError>;
+}
+
 pub struct GetStateChanges {
     pub block_hash: CryptoHash,
     pub state_changes_request: StateChangesRequestView,
@@ -998,6 +1011,96 @@ impl From<std::io::Error> for GetSplitStorageInfoError {
     }
 }
 
+pub struct GetAccountActivity {
+    pub account_id: AccountId,
+    /// Height to start from (inclusive), the latest indexed block if not set.
+    pub from_block_height: Option<BlockHeight>,
+    pub limit: usize,
+}
+
+impl Message for GetAccountActivity {
+    type Result = Result<AccountActivityPageView, GetAccountActivityError>;
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum GetAccountActivityError {
+    #[error("Account activity index is not enabled on this node")]
+    IndexDisabled,
+    #[error("IO Error: {0}")]
+    IOError(String),
+    #[error("It is a bug if you receive this error type, please, report this incident: https://github.com/near/nearcore/issues/new/choose. Details: {0}")]
+    Unreachable(String),
+}
+
+impl From<near_chain_primitives::Error> for GetAccountActivityError {
+    fn from(error: near_chain_primitives::Error) -> Self {
+        match error {
+            near_chain_primitives::Error::IOErr(error) => Self::IOError(error.to_string()),
+            _ => Self::Unreachable(error.to_string()),
+        }
+    }
+}
+
+pub struct GetEvents {
+    pub contract_id: Option<AccountId>,
+    pub standard: Option<String>,
+    pub event: Option<String>,
+    pub from_block_height: BlockHeight,
+    /// Last height to return events of (inclusive), the head of the chain if not set.
+    pub to_block_height: Option<BlockHeight>,
+    pub limit: usize,
+}
+
+impl Message for GetEvents {
+    type Result = Result<ContractEventsPageView, GetEventsError>;
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum GetEventsError {
+    #[error("Event index is not enabled on this node")]
+    IndexDisabled,
+    #[error("IO Error: {0}")]
+    IOError(String),
+    #[error("It is a bug if you receive this error type, please, report this incident: https://github.com/near/nearcore/issues/new/choose. Details: {0}")]
+    Unreachable(String),
+}
+
+impl From<near_chain_primitives::Error> for GetEventsError {
+    fn from(error: near_chain_primitives::Error) -> Self {
+        match error {
+            near_chain_primitives::Error::IOErr(error) => Self::IOError(error.to_string()),
+            _ => Self::Unreachable(error.to_string()),
+        }
+    }
+}
+
+pub struct GetTransactionTrace {
+    pub tx_hash: CryptoHash,
+}
+
+impl Message for GetTransactionTrace {
+    type Result = Result<TxTraceView, GetTransactionTraceError>;
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum GetTransactionTraceError {
+    #[error("Transaction {0} is not known or was not executed yet")]
+    UnknownTransaction(CryptoHash),
+    #[error("IO Error: {0}")]
+    IOError(String),
+    #[error("It is a bug if you receive this error type, please, report this incident: https://github.com/near/nearcore/issues/new/choose. Details: {0}")]
+    Unreachable(String),
+}
+
+impl From<near_chain_primitives::Error> for GetTransactionTraceError {
+    fn from(error: near_chain_primitives::Error) -> Self {
+        match error {
+            near_chain_primitives::Error::IOErr(error) => Self::IOError(error.to_string()),
+            _ => Self::Unreachable(error.to_string()),
+        }
+    }
+}
+
 #[cfg(feature = "sandbox")]
 #[derive(Debug)]
 pub enum SandboxMessage {
@@ -1005,6 +1108,15 @@ pub enum SandboxMessage {
     SandboxPatchStateStatus,
     SandboxFastForward(near_primitives::types::BlockHeightDelta),
     SandboxFastForwardStatus,
+    SandboxSnapshot,
+    SandboxRestore(u64),
+    SandboxSetTime(u64),
+    SandboxFreezeTime(bool),
+    SandboxImpersonatedTransaction {
+        signer_id: AccountId,
+        receiver_id: AccountId,
+        actions: Vec<near_primitives::transaction::Action>,
+    },
 }
 
 #[cfg(feature = "sandbox")]
@@ -1013,6 +1125,15 @@ pub enum SandboxResponse {
     SandboxPatchStateFinished(bool),
     SandboxFastForwardFinished(bool),
     SandboxFastForwardFailed(String),
+    SandboxSnapshotTaken(u64),
+    SandboxSnapshotFailed(String),
+    SandboxRestoreFinished,
+    SandboxRestoreFailed(String),
+    SandboxSetTimeFinished,
+    SandboxSetTimeFailed(String),
+    SandboxFreezeTimeFinished,
+    SandboxImpersonatedTransaction(near_primitives::transaction::SignedTransaction),
+    SandboxImpersonationFailed(String),
     SandboxNoResponse,
 }
 #[cfg(feature = "sandbox")]
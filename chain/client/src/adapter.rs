@@ -11,6 +11,7 @@ use near_primitives::errors::InvalidTxError;
 use near_primitives::hash::CryptoHash;
 use near_primitives::network::{AnnounceAccount, PeerId};
 use near_primitives::sharding::PartialEncodedChunk;
+use near_primitives::syncing::{EpochSyncFinalizationResponse, EpochSyncResponse};
 use near_primitives::transaction::SignedTransaction;
 use near_primitives::types::{AccountId, EpochId, ShardId};
 use near_primitives::views::FinalExecutionOutcomeView;
@@ -56,6 +57,29 @@ pub(crate) struct BlockHeadersRequest(pub Vec<CryptoHash>);
 #[rtype(result = "Result<(),ReasonForBan>")]
 pub(crate) struct BlockHeadersResponse(pub Vec<BlockHeader>, pub PeerId);
 
+/// Request the light client block of the last final block of an epoch.
+#[derive(actix::Message)]
+#[rtype(result = "Option<Box<EpochSyncResponse>>")]
+pub(crate) struct EpochSyncRequest(pub EpochId);
+
+/// Response to epoch sync request.
+#[derive(actix::Message, Debug)]
+#[rtype(result = "()")]
+pub(crate) struct RecvEpochSyncResponse(pub Box<EpochSyncResponse>, pub PeerId);
+
+/// Request data to finish epoch sync after the given last final block of an epoch.
+#[derive(actix::Message)]
+#[rtype(result = "Option<Box<EpochSyncFinalizationResponse>>")]
+pub(crate) struct EpochSyncFinalizationRequest(pub CryptoHash);
+
+/// Response to epoch sync finalization request.
+#[derive(actix::Message, Debug)]
+#[rtype(result = "()")]
+pub(crate) struct RecvEpochSyncFinalizationResponse(
+    pub Box<EpochSyncFinalizationResponse>,
+    pub PeerId,
+);
+
 /// State request header.
 #[derive(actix::Message)]
 #[rtype(result = "Option<StateResponse>")]
@@ -321,6 +345,59 @@ impl near_network::client::Client for Adapter {
         }
     }
 
+    async fn epoch_sync_request(&self, epoch_id: EpochId) -> Option<Box<EpochSyncResponse>> {
+        match self.view_client_addr.send(EpochSyncRequest(epoch_id).with_span_context()).await {
+            Ok(res) => res,
+            Err(err) => {
+                tracing::error!("mailbox error: {err}");
+                None
+            }
+        }
+    }
+
+    async fn epoch_sync_response(&self, response: Box<EpochSyncResponse>, peer_id: PeerId) {
+        match self
+            .client_addr
+            .send(RecvEpochSyncResponse(response, peer_id).with_span_context())
+            .await
+        {
+            Ok(()) => {}
+            Err(err) => tracing::error!("mailbox error: {err}"),
+        }
+    }
+
+    async fn epoch_sync_finalization_request(
+        &self,
+        block_hash: CryptoHash,
+    ) -> Option<Box<EpochSyncFinalizationResponse>> {
+        match self
+            .view_client_addr
+            .send(EpochSyncFinalizationRequest(block_hash).with_span_context())
+            .await
+        {
+            Ok(res) => res,
+            Err(err) => {
+                tracing::error!("mailbox error: {err}");
+                None
+            }
+        }
+    }
+
+    async fn epoch_sync_finalization_response(
+        &self,
+        response: Box<EpochSyncFinalizationResponse>,
+        peer_id: PeerId,
+    ) {
+        match self
+            .client_addr
+            .send(RecvEpochSyncFinalizationResponse(response, peer_id).with_span_context())
+            .await
+        {
+            Ok(()) => {}
+            Err(err) => tracing::error!("mailbox error: {err}"),
+        }
+    }
+
     async fn network_info(&self, info: NetworkInfo) {
         match self.client_addr.send(SetNetworkInfo(info).with_span_context()).await {
             Ok(()) => {}
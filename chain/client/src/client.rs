@@ -13,7 +13,7 @@ use near_chunks::client::ShardedTransactionPool;
 use near_chunks::logic::{
     cares_about_shard_this_or_next_epoch, decode_encoded_chunk, persist_chunk,
 };
-use near_client_primitives::debug::ChunkProduction;
+use near_client_primitives::debug::{ChunkProduction, MissReason};
 use near_primitives::static_clock::StaticClock;
 use near_store::metadata::DbKind;
 use tracing::{debug, error, info, trace, warn};
@@ -32,6 +32,7 @@ use near_chain::{
 };
 use near_chain_configs::{ClientConfig, UpdateableClientConfig};
 use near_chunks::ShardsManager;
+use near_crypto::KeyFile;
 use near_network::types::{
     HighestHeightPeerInfo, NetworkRequests, PeerManagerAdapter, ReasonForBan,
 };
@@ -49,18 +50,21 @@ use near_primitives::types::chunk_extra::ChunkExtra;
 use near_primitives::types::{AccountId, ApprovalStake, BlockHeight, EpochId, NumBlocks, ShardId};
 use near_primitives::unwrap_or_return;
 use near_primitives::utils::MaybeValidated;
-use near_primitives::validator_signer::ValidatorSigner;
+use near_primitives::validator_signer::{InMemoryValidatorSigner, ValidatorSigner};
 
 use crate::adapter::ProcessTxResponse;
 use crate::debug::BlockProductionTracker;
 use crate::debug::PRODUCTION_TIMES_CACHE_SIZE;
+use crate::missed_production::MissedProductionTracker;
 use crate::sync::block::BlockSync;
 use crate::sync::epoch::EpochSync;
 use crate::sync::header::HeaderSync;
 use crate::sync::state::{StateSync, StateSyncResult};
 use crate::{metrics, SyncStatus};
 use near_client_primitives::types::{Error, ShardSyncDownload, ShardSyncStatus};
-use near_network::types::{AccountKeys, ChainInfo, PeerManagerMessageRequest, SetChainInfo};
+use near_network::types::{
+    AccountKeys, ChainInfo, PeerManagerMessageRequest, SetChainInfo, SetValidatorSigner,
+};
 use near_o11y::log_assert;
 use near_primitives::block_header::ApprovalType;
 use near_primitives::epoch_manager::RngSeed;
@@ -98,6 +102,12 @@ pub struct Client {
     /// Fast Forward accrued delta height used to calculate fast forwarded timestamps for each block.
     #[cfg(feature = "sandbox")]
     pub(crate) accrued_fastforward_delta: near_primitives::types::BlockHeightDelta,
+    /// Offset of the sandbox time from the wall clock set by `sandbox_set_time`.
+    #[cfg(feature = "sandbox")]
+    sandbox_time_offset: chrono::Duration,
+    /// Wall clock time at which the sandbox time was frozen, if it is frozen.
+    #[cfg(feature = "sandbox")]
+    sandbox_time_frozen_at: Option<chrono::DateTime<chrono::Utc>>,
 
     pub config: ClientConfig,
     pub sync_status: SyncStatus,
@@ -115,6 +125,9 @@ pub struct Client {
     network_adapter: PeerManagerAdapter,
     /// Signer for block producer (if present).
     pub validator_signer: Option<Arc<dyn ValidatorSigner>>,
+    /// Signer with a rotated validator key, which replaces `validator_signer` once its key is
+    /// staked for the upcoming epoch.
+    pending_validator_signer: Option<Arc<dyn ValidatorSigner>>,
     /// Approvals for which we do not have the block yet
     pub pending_approvals:
         lru::LruCache<ApprovalInner, HashMap<AccountId, (Approval, ApprovalType)>>,
@@ -145,6 +158,9 @@ pub struct Client {
     pub block_production_info: BlockProductionTracker,
     /// Chunk production timing information. Used only for debug purposes.
     pub chunk_production_info: lru::LruCache<(BlockHeight, ShardId), ChunkProduction>,
+    /// Blocks and chunks this node was responsible for which didn't make it into the canonical
+    /// chain, and production uptime of this node in the current epoch.
+    pub missed_production: MissedProductionTracker,
 
     /// Cached precomputed set of TIER1 accounts.
     /// See send_network_chain_info().
@@ -154,8 +170,74 @@ pub struct Client {
 }
 
 impl Client {
-    pub(crate) fn update_client_config(&self, update_client_config: UpdateableClientConfig) {
+    pub(crate) fn update_client_config(&mut self, update_client_config: UpdateableClientConfig) {
         self.config.expected_shutdown.update(update_client_config.expected_shutdown);
+        if let Some(validator_key) = update_client_config.validator_key {
+            self.set_pending_validator_key(validator_key);
+        }
+    }
+
+    /// Schedules switching to a rotated validator key. The switch happens only once the new key
+    /// is staked for the epoch of the next block (see `maybe_rotate_validator_key`), so that
+    /// blocks, chunks and approvals are always signed with the key other validators expect.
+    pub fn set_pending_validator_key(&mut self, validator_key: KeyFile) {
+        let signer = InMemoryValidatorSigner::from_signer(validator_key.into());
+        let current = match &self.validator_signer {
+            Some(current) => current,
+            None => {
+                warn!(target: "client", "Node was started without a validator key, restart it to become a validator");
+                return;
+            }
+        };
+        if current.validator_id() != signer.validator_id() {
+            warn!(target: "client", current = %current.validator_id(), new = %signer.validator_id(), "Ignoring validator key of a different account, restart the node to change the validator account");
+            return;
+        }
+        if current.public_key() == signer.public_key() {
+            // Rotation was cancelled, or the key file didn't change.
+            self.pending_validator_signer = None;
+            return;
+        }
+        if self
+            .pending_validator_signer
+            .as_ref()
+            .map_or(false, |pending| pending.public_key() == signer.public_key())
+        {
+            return;
+        }
+        info!(target: "client", public_key = %signer.public_key(), "Loaded new validator key, will switch to it once it is staked for the upcoming epoch");
+        self.pending_validator_signer = Some(Arc::new(signer));
+        self.maybe_rotate_validator_key();
+    }
+
+    /// Switches to the pending validator key if it's the key staked for the epoch of the next
+    /// block. Must be called whenever the head changes.
+    fn maybe_rotate_validator_key(&mut self) {
+        let signer = match &self.pending_validator_signer {
+            Some(signer) => signer.clone(),
+            None => return,
+        };
+        let head = unwrap_or_return!(self.chain.head());
+        let epoch_id = unwrap_or_return!(self
+            .runtime_adapter
+            .get_epoch_id_from_prev_block(&head.last_block_hash));
+        // Keep waiting if we are not a validator in the upcoming epoch.
+        let staked_key = match self.runtime_adapter.get_validator_by_account_id(
+            &epoch_id,
+            &head.last_block_hash,
+            signer.validator_id(),
+        ) {
+            Ok((validator_stake, _)) => validator_stake.take_public_key(),
+            Err(_) => return,
+        };
+        if staked_key != signer.public_key() {
+            return;
+        }
+        info!(target: "client", validator_id = %signer.validator_id(), public_key = %signer.public_key(), ?epoch_id, "Switching to the rotated validator key");
+        self.doomslug.set_signer(Some(signer.clone()));
+        self.network_adapter.send(SetValidatorSigner(signer.clone()));
+        self.validator_signer = Some(signer);
+        self.pending_validator_signer = None;
     }
 }
 
@@ -202,6 +284,8 @@ impl Client {
         let chain_config = ChainConfig {
             save_trie_changes: config.save_trie_changes,
             background_migration_threads: config.client_background_migration_threads,
+            account_activity_index: config.account_activity_index.is_some(),
+            event_index: config.event_index.is_some(),
         };
         let chain = Chain::new(
             runtime_adapter.clone(),
@@ -222,8 +306,7 @@ impl Client {
         let genesis_block = chain.genesis_block();
         let epoch_sync = EpochSync::new(
             network_adapter.clone(),
-            genesis_block.header().epoch_id().clone(),
-            genesis_block.header().next_epoch_id().clone(),
+            genesis_block.header(),
             runtime_adapter
                 .get_epoch_block_producers_ordered(
                     genesis_block.header().epoch_id(),
@@ -269,6 +352,11 @@ impl Client {
             produce_invalid_tx_in_chunks: false,
             #[cfg(feature = "sandbox")]
             accrued_fastforward_delta: 0,
+            #[cfg(feature = "sandbox")]
+            #[cfg(feature = "sandbox")]
+            sandbox_time_offset: chrono::Duration::zero(),
+            #[cfg(feature = "sandbox")]
+            sandbox_time_frozen_at: None,
             config,
             sync_status,
             chain,
@@ -284,6 +372,7 @@ impl Client {
             ),
             network_adapter,
             validator_signer,
+            pending_validator_signer: None,
             pending_approvals: lru::LruCache::new(num_block_producer_seats),
             catchup_state_syncs: HashMap::new(),
             epoch_sync,
@@ -296,6 +385,7 @@ impl Client {
             last_time_head_progress_made: StaticClock::instant(),
             block_production_info: BlockProductionTracker::new(),
             chunk_production_info: lru::LruCache::new(PRODUCTION_TIMES_CACHE_SIZE),
+            missed_production: MissedProductionTracker::new(),
             tier1_accounts_cache: None,
             flat_storage_creator,
         })
@@ -396,7 +486,7 @@ impl Client {
     }
 
     fn should_reschedule_block(
-        &self,
+        &mut self,
         head: &Tip,
         prev_hash: &CryptoHash,
         prev_prev_hash: &CryptoHash,
@@ -431,6 +521,7 @@ impl Client {
                 // block, which is the current epoch for this block, so this block cannot be applied
                 // at all yet, block production must to be rescheduled
                 debug!(target: "client", "Produce block: prev block is not caught up");
+                self.missed_production.record_skip(next_height, None, MissReason::MissingState);
                 return Ok(true);
             }
         }
@@ -539,6 +630,7 @@ impl Client {
         let validator_pk = validator_stake.take_public_key();
         if validator_pk != validator_signer.public_key() {
             debug!(target: "client", "Local validator key {} does not match expected validator key {}, skipping block production", validator_signer.public_key(), validator_pk);
+            self.missed_production.record_skip(next_height, None, MissReason::KeyMismatch);
             #[cfg(not(feature = "test_features"))]
             return Ok(None);
             #[cfg(feature = "test_features")]
@@ -610,7 +702,7 @@ impl Client {
         };
 
         #[cfg(feature = "sandbox")]
-        let timestamp_override = Some(StaticClock::utc() + self.sandbox_delta_time());
+        let timestamp_override = Some(self.sandbox_now());
         #[cfg(not(feature = "sandbox"))]
         let timestamp_override = None;
 
@@ -739,6 +831,11 @@ impl Client {
             if !self.chain.prev_block_is_caught_up(&prev_prev_hash, &prev_block_hash)? {
                 // See comment in similar snipped in `produce_block`
                 debug!(target: "client", "Produce chunk: prev block is not caught up");
+                self.missed_production.record_skip(
+                    next_height,
+                    Some(shard_id),
+                    MissReason::MissingState,
+                );
                 return Err(Error::ChunkProducer(
                     "State for the epoch is not downloaded yet, skipping chunk production"
                         .to_string(),
@@ -1202,6 +1299,11 @@ impl Client {
         self.send_challenges(challenges);
         // For any missing chunk, let the ShardsManager know of the chunk header so that it may
         // apply forwarded parts. This may end up completing the chunk.
+        for block in &blocks_missing_chunks {
+            if let Some(chunk) = block.missing_chunks.first() {
+                self.missed_production.record_block_missing_chunks(chunk.height_included());
+            }
+        }
         let missing_chunks = blocks_missing_chunks
             .iter()
             .flat_map(|block| block.missing_chunks.iter())
@@ -1354,6 +1456,117 @@ impl Client {
         Ok(())
     }
 
+    /// Skips `delta_height` heights, so that the next block is produced above them with the
+    /// timestamp it would have if blocks were produced at all of them.
+    #[cfg(feature = "sandbox")]
+    pub fn sandbox_skip_heights(
+        &mut self,
+        delta_height: near_primitives::types::BlockHeightDelta,
+    ) -> Result<(), Error> {
+        let latest_known = self.chain.mut_store().get_latest_known()?;
+        self.accrued_fastforward_delta += delta_height;
+        let new_latest_known = LatestKnown {
+            height: latest_known.height + delta_height,
+            seen: near_primitives::utils::to_timestamp(self.sandbox_now()),
+        };
+        self.chain.mut_store().save_latest_known(new_latest_known.clone())?;
+        self.sandbox_update_tip(new_latest_known.height)
+    }
+
+    /// Remembers the current head, so that the sandbox can be rolled back to it later with
+    /// `sandbox_restore`. Returns the id of the snapshot.
+    ///
+    /// A snapshot doesn't copy the database. It only records the head block and keeps it from
+    /// being garbage collected, which is enough because trie nodes are never overwritten.
+    #[cfg(feature = "sandbox")]
+    pub fn sandbox_snapshot(&mut self) -> Result<u64, Error> {
+        self.chain.sandbox_save_snapshot(self.accrued_fastforward_delta)
+    }
+
+    /// Rolls the chain back to the head at the time of the snapshot, by re-rooting the chain head
+    /// at the snapshot block rather than restoring the database. The blocks produced since are
+    /// left on an abandoned fork, and the next block is produced on top of the snapshot block, at
+    /// a height above all the blocks produced so far.
+    #[cfg(feature = "sandbox")]
+    pub fn sandbox_restore(&mut self, snapshot_id: u64) -> Result<(), Error> {
+        let snapshot = self.chain.sandbox_get_snapshot(snapshot_id)?;
+        self.chain.sandbox_reset_head(&snapshot.block_hash)?;
+        self.accrued_fastforward_delta = snapshot.accrued_fastforward_delta;
+        // Heights up to the latest known one were already used by the abandoned blocks, same as
+        // for fast forwarding skip to the height above them.
+        let latest_known = self.chain.store().get_latest_known()?;
+        let height = latest_known.height + 1;
+        self.chain
+            .mut_store()
+            .save_latest_known(LatestKnown { height, seen: latest_known.seen })?;
+        self.sandbox_update_tip(height)
+    }
+
+    /// Current time of the sandbox, used as the timestamp of the produced blocks. It is the wall
+    /// clock time adjusted by `sandbox_set_time`, `sandbox_freeze_time` and fast forwarding.
+    #[cfg(feature = "sandbox")]
+    pub fn sandbox_now(&self) -> chrono::DateTime<chrono::Utc> {
+        self.sandbox_time_frozen_at.unwrap_or_else(StaticClock::utc)
+            + self.sandbox_time_offset
+            + self.sandbox_delta_time()
+    }
+
+    /// Sets the sandbox time, so that the next produced block has the given timestamp in
+    /// nanoseconds. The timestamp must be later than the timestamp of the head block, and fit
+    /// into an i64 like all the block timestamps.
+    #[cfg(feature = "sandbox")]
+    pub fn sandbox_set_time(&mut self, timestamp: u64) -> Result<(), Error> {
+        if i64::try_from(timestamp).is_err() {
+            return Err(Error::Other(format!("Timestamp {} is out of range", timestamp)));
+        }
+        let head = self.chain.head_header()?;
+        if timestamp <= head.raw_timestamp() {
+            return Err(Error::Other(format!(
+                "Timestamp {} is not later than the timestamp {} of the head block",
+                timestamp,
+                head.raw_timestamp()
+            )));
+        }
+        let timestamp = near_primitives::utils::from_timestamp(timestamp);
+        self.sandbox_time_offset = self.sandbox_time_offset + (timestamp - self.sandbox_now());
+        Ok(())
+    }
+
+    /// Freezes or unfreezes the sandbox time. While it is frozen, every produced block gets
+    /// the timestamp of its previous block plus one nanosecond, since timestamps must increase.
+    /// After unfreezing, the time continues from where it was frozen.
+    #[cfg(feature = "sandbox")]
+    pub fn sandbox_freeze_time(&mut self, frozen: bool) {
+        match (self.sandbox_time_frozen_at, frozen) {
+            (None, true) => self.sandbox_time_frozen_at = Some(StaticClock::utc()),
+            (Some(frozen_at), false) => {
+                self.sandbox_time_offset =
+                    self.sandbox_time_offset - (StaticClock::utc() - frozen_at);
+                self.sandbox_time_frozen_at = None;
+            }
+            _ => {}
+        }
+    }
+
+    /// Creates a transaction on top of the head which acts on behalf of `signer_id` without
+    /// holding any of its keys.
+    #[cfg(feature = "sandbox")]
+    pub fn sandbox_impersonated_transaction(
+        &self,
+        signer_id: AccountId,
+        receiver_id: AccountId,
+        actions: Vec<near_primitives::transaction::Action>,
+    ) -> Result<SignedTransaction, Error> {
+        let head = self.chain.head()?;
+        Ok(near_primitives::sandbox::impersonation::impersonated_transaction(
+            near_primitives::utils::to_timestamp(StaticClock::utc()),
+            signer_id,
+            receiver_id,
+            actions,
+            head.last_block_hash,
+        ))
+    }
+
     /// Gets the advanced timestamp delta in nanoseconds for sandbox once it has been fast-forwarded
     #[cfg(feature = "sandbox")]
     pub fn sandbox_delta_time(&self) -> chrono::Duration {
@@ -1456,6 +1669,12 @@ impl Client {
             if let Err(err) = self.send_network_chain_info() {
                 error!(target:"client","Failed to update network chain info: {err}");
             }
+
+            self.maybe_rotate_validator_key();
+
+            if let Err(err) = self.update_missed_production(&block) {
+                debug!(target: "client", ?err, "Failed to update missed production");
+            }
         }
 
         if let Some(validator_signer) = self.validator_signer.clone() {
@@ -1575,6 +1794,85 @@ impl Client {
             .send(ShardsManagerRequestFromClient::CheckIncompleteChunks(*block.hash()));
     }
 
+    /// Accounts for the blocks and chunks this node was responsible for, given a block which just
+    /// became the head of the chain. Heights skipped between the block and its parent are
+    /// attributed to the epoch of the block, same as in `EpochInfoAggregator`.
+    fn update_missed_production(&mut self, block: &Block) -> Result<(), Error> {
+        let validator_id = match &self.validator_signer {
+            Some(signer) => signer.validator_id().clone(),
+            None => return Ok(()),
+        };
+        let height = block.header().height();
+        let first_unchecked_height = match self.missed_production.first_unchecked_height(height) {
+            Some(first_unchecked_height) => first_unchecked_height,
+            None => return Ok(()),
+        };
+        let prev_height = self.chain.get_block_header(block.header().prev_hash())?.height();
+        let epoch_id = block.header().epoch_id();
+        let epoch_config = self.runtime_adapter.get_epoch_config(epoch_id)?;
+        self.missed_production.start_block(
+            height,
+            epoch_id,
+            epoch_config.block_producer_kickout_threshold,
+            epoch_config.chunk_producer_kickout_threshold,
+        );
+        let is_syncing = self.sync_status.is_syncing();
+        let prev_missed_chunks = self.missed_production.block_missed_chunks(prev_height);
+
+        for block_height in max(prev_height + 1, first_unchecked_height)..=height {
+            if self.runtime_adapter.get_block_producer(epoch_id, block_height)? != validator_id {
+                continue;
+            }
+            if block_height == height {
+                self.missed_production.block_produced();
+                continue;
+            }
+            let production = self.block_production_info.get(block_height);
+            let reason = match self.missed_production.take_skip_reason(block_height, None) {
+                Some(reason) => reason,
+                None if production.block_production_time.is_some() => MissReason::NotIncluded,
+                None if is_syncing => MissReason::NotSynced,
+                None if prev_missed_chunks => MissReason::NoChunkParts,
+                None if production.approvals.ready_at.is_none() => MissReason::LateApproval,
+                None => MissReason::Unknown,
+            };
+            self.missed_production.block_missed(block_height, reason);
+        }
+
+        // Chunks included in this block were produced on top of its parent.
+        let chunk_height = prev_height + 1;
+        if chunk_height >= first_unchecked_height {
+            for (shard_id, chunk_header) in block.chunks().iter().enumerate() {
+                let shard_id = shard_id as ShardId;
+                if self.runtime_adapter.get_chunk_producer(epoch_id, chunk_height, shard_id)?
+                    != validator_id
+                {
+                    continue;
+                }
+                if chunk_header.height_included() == height {
+                    self.missed_production.chunk_produced();
+                    continue;
+                }
+                let produced = self
+                    .chunk_production_info
+                    .peek(&(chunk_height, shard_id))
+                    .map_or(false, |production| production.chunk_production_time.is_some());
+                let reason =
+                    match self.missed_production.take_skip_reason(chunk_height, Some(shard_id)) {
+                        Some(reason) => reason,
+                        None if produced => MissReason::NotIncluded,
+                        None if is_syncing => MissReason::NotSynced,
+                        None if prev_missed_chunks => MissReason::NoChunkParts,
+                        None => MissReason::Unknown,
+                    };
+                self.missed_production.chunk_missed(chunk_height, shard_id, reason);
+            }
+        }
+
+        self.missed_production.check_uptime(&validator_id);
+        Ok(())
+    }
+
     pub fn persist_and_distribute_encoded_chunk(
         &mut self,
         encoded_chunk: EncodedShardChunk,
@@ -2210,6 +2508,21 @@ impl Client {
     }
 
     fn clear_data(&mut self) -> Result<(), near_chain::Error> {
+        // The account activity and event indices have their own retention, independent of the
+        // one of blocks.
+        if let Some(account_activity_index) = &self.config.account_activity_index {
+            let head = self.chain.head()?;
+            let height = head.height.saturating_sub(account_activity_index.retention_blocks);
+            self.chain
+                .mut_store()
+                .prune_account_activity(height, self.config.gc.gc_blocks_limit as usize)?;
+        }
+        if let Some(event_index) = &self.config.event_index {
+            let head = self.chain.head()?;
+            let height = head.height.saturating_sub(event_index.retention_blocks);
+            self.chain.mut_store().prune_events(height, self.config.gc.gc_blocks_limit as usize)?;
+        }
+
         // A RPC node should do regular garbage collection.
         if !self.config.archive {
             let tries = self.runtime_adapter.get_tries();
@@ -7,7 +7,8 @@
 
 use crate::adapter::{
     BlockApproval, BlockHeadersResponse, BlockResponse, ProcessTxRequest, ProcessTxResponse,
-    RecvChallenge, SetNetworkInfo, StateResponse,
+    RecvChallenge, RecvEpochSyncFinalizationResponse, RecvEpochSyncResponse, SetNetworkInfo,
+    StateResponse,
 };
 use crate::client::{Client, EPOCH_START_INFO_BLOCKS};
 use crate::config_updater::ConfigUpdater;
@@ -564,6 +565,38 @@ impl Handler<WithSpanContext<RecvChallenge>> for ClientActor {
     }
 }
 
+impl Handler<WithSpanContext<RecvEpochSyncResponse>> for ClientActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: WithSpanContext<RecvEpochSyncResponse>, ctx: &mut Context<Self>) {
+        self.wrap(msg, ctx, "RecvEpochSyncResponse", |this, msg| {
+            let RecvEpochSyncResponse(response, peer_id) = msg;
+            this.client.epoch_sync.on_response(*response, peer_id);
+        });
+    }
+}
+
+impl Handler<WithSpanContext<RecvEpochSyncFinalizationResponse>> for ClientActor {
+    type Result = ();
+
+    fn handle(
+        &mut self,
+        msg: WithSpanContext<RecvEpochSyncFinalizationResponse>,
+        ctx: &mut Context<Self>,
+    ) {
+        self.wrap(msg, ctx, "RecvEpochSyncFinalizationResponse", |this, msg| {
+            let RecvEpochSyncFinalizationResponse(response, peer_id) = msg;
+            if let Err(err) = this.client.epoch_sync.on_finalization_response(
+                &mut this.client.chain,
+                *response,
+                peer_id,
+            ) {
+                error!(target: "sync", "Error finalizing epoch sync: {}", err);
+            }
+        });
+    }
+}
+
 impl Handler<WithSpanContext<SetNetworkInfo>> for ClientActor {
     type Result = ();
 
@@ -611,6 +644,79 @@ impl Handler<WithSpanContext<near_client_primitives::types::SandboxMessage>> for
                     self.fastforward_delta == 0,
                 )
             }
+            near_client_primitives::types::SandboxMessage::SandboxSnapshot => {
+                if self.client.chain.patch_state_in_progress() {
+                    return near_client_primitives::types::SandboxResponse::SandboxSnapshotFailed(
+                        "Cannot take a snapshot while a state patch is pending.".to_string(),
+                    );
+                }
+                match self.client.sandbox_snapshot() {
+                    Ok(snapshot_id) => {
+                        near_client_primitives::types::SandboxResponse::SandboxSnapshotTaken(
+                            snapshot_id,
+                        )
+                    }
+                    Err(err) => {
+                        near_client_primitives::types::SandboxResponse::SandboxSnapshotFailed(
+                            err.to_string(),
+                        )
+                    }
+                }
+            }
+            near_client_primitives::types::SandboxMessage::SandboxRestore(snapshot_id) => {
+                if self.fastforward_delta > 0 || self.client.chain.patch_state_in_progress() {
+                    return near_client_primitives::types::SandboxResponse::SandboxRestoreFailed(
+                        "Cannot restore a snapshot while a fast_forward or a state patch is going on.".to_string());
+                }
+                match self.client.sandbox_restore(snapshot_id) {
+                    Ok(()) => {
+                        near_client_primitives::types::SandboxResponse::SandboxRestoreFinished
+                    }
+                    Err(err) => {
+                        near_client_primitives::types::SandboxResponse::SandboxRestoreFailed(
+                            err.to_string(),
+                        )
+                    }
+                }
+            }
+            near_client_primitives::types::SandboxMessage::SandboxSetTime(timestamp) => {
+                if self.fastforward_delta > 0 {
+                    return near_client_primitives::types::SandboxResponse::SandboxSetTimeFailed(
+                        "Cannot set the time while a fast_forward is going on.".to_string(),
+                    );
+                }
+                match self.client.sandbox_set_time(timestamp) {
+                    Ok(()) => {
+                        near_client_primitives::types::SandboxResponse::SandboxSetTimeFinished
+                    }
+                    Err(err) => {
+                        near_client_primitives::types::SandboxResponse::SandboxSetTimeFailed(
+                            err.to_string(),
+                        )
+                    }
+                }
+            }
+            near_client_primitives::types::SandboxMessage::SandboxFreezeTime(frozen) => {
+                self.client.sandbox_freeze_time(frozen);
+                near_client_primitives::types::SandboxResponse::SandboxFreezeTimeFinished
+            }
+            near_client_primitives::types::SandboxMessage::SandboxImpersonatedTransaction {
+                signer_id,
+                receiver_id,
+                actions,
+            } => match self.client.sandbox_impersonated_transaction(signer_id, receiver_id, actions)
+            {
+                Ok(transaction) => {
+                    near_client_primitives::types::SandboxResponse::SandboxImpersonatedTransaction(
+                        transaction,
+                    )
+                }
+                Err(err) => {
+                    near_client_primitives::types::SandboxResponse::SandboxImpersonationFailed(
+                        err.to_string(),
+                    )
+                }
+            },
         }
     }
 }
@@ -869,7 +975,7 @@ impl ClientActor {
     fn sandbox_process_fast_forward(
         &mut self,
         block_height: BlockHeight,
-    ) -> Result<Option<near_chain::types::LatestKnown>, Error> {
+    ) -> Result<Option<near_primitives::types::BlockHeightDelta>, Error> {
         let mut delta_height = std::mem::replace(&mut self.fastforward_delta, 0);
         if delta_height == 0 {
             return Ok(None);
@@ -905,25 +1011,15 @@ impl ClientActor {
             delta_height
         };
 
-        self.client.accrued_fastforward_delta += delta_height;
-        let delta_time = self.client.sandbox_delta_time();
-        let new_latest_known = near_chain::types::LatestKnown {
-            height: block_height + delta_height,
-            seen: near_primitives::utils::to_timestamp(StaticClock::utc() + delta_time),
-        };
-
-        Ok(Some(new_latest_known))
+        Ok(Some(delta_height))
     }
 
     fn pre_block_production(&mut self) -> Result<(), Error> {
         #[cfg(feature = "sandbox")]
         {
             let latest_known = self.client.chain.mut_store().get_latest_known()?;
-            if let Some(new_latest_known) =
-                self.sandbox_process_fast_forward(latest_known.height)?
-            {
-                self.client.chain.mut_store().save_latest_known(new_latest_known.clone())?;
-                self.client.sandbox_update_tip(new_latest_known.height)?;
+            if let Some(delta_height) = self.sandbox_process_fast_forward(latest_known.height)? {
+                self.client.sandbox_skip_heights(delta_height)?;
             }
         }
         Ok(())
@@ -1056,7 +1152,7 @@ impl ClientActor {
     /// min(time until the closest trigger, 1 second).
     fn check_triggers(&mut self, ctx: &mut Context<ClientActor>) -> Duration {
         if let Some(config_updater) = &mut self.config_updater {
-            config_updater.try_update(&|updateable_client_config| {
+            config_updater.try_update(&mut |updateable_client_config| {
                 self.client.update_client_config(updateable_client_config)
             });
         }
@@ -1545,6 +1641,19 @@ impl ClientActor {
                 self.check_send_announce_account(head.prev_block_hash);
             }
         } else {
+            // Skip to a recent epoch first if we are starting from genesis.
+            if self.client.config.epoch_sync_enabled && !self.client.config.archive {
+                let epoch_sync_in_progress = unwrap_and_report!(self.client.epoch_sync.run(
+                    &mut self.client.sync_status,
+                    &self.client.chain,
+                    highest_height,
+                    self.client.config.block_fetch_horizon,
+                    &self.network_info.highest_height_peers
+                ));
+                if epoch_sync_in_progress {
+                    return;
+                }
+            }
             // Run each step of syncing separately.
             unwrap_and_report!(self.client.header_sync.run(
                 &mut self.client.sync_status,
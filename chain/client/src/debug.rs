@@ -636,6 +636,8 @@ impl ClientActor {
                 .into_iter()
                 .map(|(k, vs)| (k, vs.map(|(_, v)| v).collect()))
                 .collect(),
+            missed_production: self.client.missed_production.missed(),
+            uptime: self.client.missed_production.uptime(),
         })
     }
 }
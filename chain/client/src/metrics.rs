@@ -126,6 +126,40 @@ pub(crate) static VALIDATORS_BLOCKS_EXPECTED: Lazy<IntGaugeVec> = Lazy::new(|| {
     .unwrap()
 });
 
+pub(crate) static VALIDATOR_MISSED_BLOCKS_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    try_create_int_counter_vec(
+        "near_validator_missed_blocks_total",
+        "Number of blocks this node was responsible for which are missing from the canonical chain, by most likely reason",
+        &["reason"],
+    )
+    .unwrap()
+});
+
+pub(crate) static VALIDATOR_MISSED_CHUNKS_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    try_create_int_counter_vec(
+        "near_validator_missed_chunks_total",
+        "Number of chunks this node was responsible for which are missing from the canonical chain, by most likely reason",
+        &["shard_id", "reason"],
+    )
+    .unwrap()
+});
+
+pub(crate) static VALIDATOR_BLOCK_UPTIME_RATIO: Lazy<Gauge> = Lazy::new(|| {
+    try_create_gauge(
+        "near_validator_block_uptime_ratio",
+        "Ratio of produced to expected blocks of this node in the current epoch",
+    )
+    .unwrap()
+});
+
+pub(crate) static VALIDATOR_CHUNK_UPTIME_RATIO: Lazy<Gauge> = Lazy::new(|| {
+    try_create_gauge(
+        "near_validator_chunk_uptime_ratio",
+        "Ratio of produced to expected chunks of this node in the current epoch",
+    )
+    .unwrap()
+});
+
 pub(crate) static SYNC_STATUS: Lazy<IntGauge> =
     Lazy::new(|| try_create_int_gauge("near_sync_status", "Node sync status").unwrap());
 
@@ -0,0 +1,255 @@
+//! Tracks blocks and chunks which this node was responsible for but which are missing from the
+//! canonical chain, together with the most likely reason, so that operators notice a struggling
+//! validator before it gets kicked out at the end of the epoch.
+use crate::metrics;
+use near_client_primitives::debug::{MissReason, MissedProduction, ValidatorUptime};
+use near_primitives::types::{AccountId, BlockHeight, EpochId, ShardId};
+use std::collections::VecDeque;
+use tracing::{info, warn};
+
+/// Number of most recent misses kept for the debug page.
+const MISSED_PRODUCTION_TO_KEEP: usize = 100;
+
+/// Number of heights for which we remember why production was skipped.
+const SKIP_REASONS_CACHE_SIZE: usize = 1000;
+
+/// Minimum number of blocks (or chunks) expected from us in the epoch before we start warning
+/// about the uptime. Below that a single miss moves the ratio too much.
+const MIN_EXPECTED_FOR_UPTIME_WARNING: u64 = 10;
+
+pub struct MissedProductionTracker {
+    /// Reasons for which we didn't produce a block (`None` shard) or a chunk at given height,
+    /// recorded at the time of the production attempt.
+    skip_reasons: lru::LruCache<(BlockHeight, Option<ShardId>), MissReason>,
+    /// Heights of blocks which had to wait for chunk parts before they could be applied.
+    blocks_missing_chunks: lru::LruCache<BlockHeight, ()>,
+    /// Most recent misses, newest at the back.
+    missed: VecDeque<MissedProduction>,
+    /// Height of the last head block which was already accounted for.
+    last_checked_height: BlockHeight,
+    /// Counters for the current epoch.
+    uptime: Option<ValidatorUptime>,
+    warned_about_blocks: bool,
+    warned_about_chunks: bool,
+}
+
+impl MissedProductionTracker {
+    pub(crate) fn new() -> Self {
+        Self {
+            skip_reasons: lru::LruCache::new(SKIP_REASONS_CACHE_SIZE),
+            blocks_missing_chunks: lru::LruCache::new(SKIP_REASONS_CACHE_SIZE),
+            missed: VecDeque::new(),
+            last_checked_height: 0,
+            uptime: None,
+            warned_about_blocks: false,
+            warned_about_chunks: false,
+        }
+    }
+
+    /// Records why we skipped producing the block (if `shard_id` is `None`) or the chunk at
+    /// given height.
+    pub(crate) fn record_skip(
+        &mut self,
+        height: BlockHeight,
+        shard_id: Option<ShardId>,
+        reason: MissReason,
+    ) {
+        self.skip_reasons.put((height, shard_id), reason);
+    }
+
+    /// Records that the block at given height couldn't be applied because its chunks were not
+    /// available yet.
+    pub(crate) fn record_block_missing_chunks(&mut self, height: BlockHeight) {
+        self.blocks_missing_chunks.put(height, ());
+    }
+
+    pub(crate) fn take_skip_reason(
+        &mut self,
+        height: BlockHeight,
+        shard_id: Option<ShardId>,
+    ) -> Option<MissReason> {
+        self.skip_reasons.pop(&(height, shard_id))
+    }
+
+    pub(crate) fn block_missed_chunks(&self, height: BlockHeight) -> bool {
+        self.blocks_missing_chunks.contains(&height)
+    }
+
+    /// Returns the first height which hasn't been accounted for yet, or `None` if a head at
+    /// `height` was already processed.
+    pub(crate) fn first_unchecked_height(&self, height: BlockHeight) -> Option<BlockHeight> {
+        (height > self.last_checked_height).then(|| self.last_checked_height + 1)
+    }
+
+    /// Starts accounting for a new head block. Resets the counters if the block is in a new
+    /// epoch.
+    pub(crate) fn start_block(
+        &mut self,
+        height: BlockHeight,
+        epoch_id: &EpochId,
+        block_producer_kickout_threshold: u8,
+        chunk_producer_kickout_threshold: u8,
+    ) {
+        self.last_checked_height = height;
+        if self.uptime.as_ref().map_or(false, |uptime| &uptime.epoch_id == epoch_id) {
+            return;
+        }
+        self.uptime = Some(ValidatorUptime {
+            epoch_id: epoch_id.clone(),
+            block_producer_kickout_threshold,
+            chunk_producer_kickout_threshold,
+            ..Default::default()
+        });
+        self.warned_about_blocks = false;
+        self.warned_about_chunks = false;
+    }
+
+    pub(crate) fn block_produced(&mut self) {
+        if let Some(uptime) = &mut self.uptime {
+            uptime.blocks_produced += 1;
+            uptime.blocks_expected += 1;
+        }
+    }
+
+    pub(crate) fn chunk_produced(&mut self) {
+        if let Some(uptime) = &mut self.uptime {
+            uptime.chunks_produced += 1;
+            uptime.chunks_expected += 1;
+        }
+    }
+
+    pub(crate) fn block_missed(&mut self, height: BlockHeight, reason: MissReason) {
+        metrics::VALIDATOR_MISSED_BLOCKS_TOTAL.with_label_values(&[reason.as_ref()]).inc();
+        if let Some(uptime) = &mut self.uptime {
+            uptime.blocks_expected += 1;
+        }
+        self.push_missed(MissedProduction { height, shard_id: None, reason });
+    }
+
+    pub(crate) fn chunk_missed(
+        &mut self,
+        height: BlockHeight,
+        shard_id: ShardId,
+        reason: MissReason,
+    ) {
+        metrics::VALIDATOR_MISSED_CHUNKS_TOTAL
+            .with_label_values(&[&shard_id.to_string(), reason.as_ref()])
+            .inc();
+        if let Some(uptime) = &mut self.uptime {
+            uptime.chunks_expected += 1;
+        }
+        self.push_missed(MissedProduction { height, shard_id: Some(shard_id), reason });
+    }
+
+    fn push_missed(&mut self, missed: MissedProduction) {
+        info!(target: "client", height = missed.height, shard_id = ?missed.shard_id, reason = ?missed.reason, "Missed production");
+        if self.missed.len() == MISSED_PRODUCTION_TO_KEEP {
+            self.missed.pop_front();
+        }
+        self.missed.push_back(missed);
+    }
+
+    /// Exports the uptime of this epoch so far and warns if it is below the kickout threshold,
+    /// i.e. the validator will be kicked out if it keeps performing like this until the end of
+    /// the epoch.
+    pub(crate) fn check_uptime(&mut self, validator_id: &AccountId) {
+        let uptime = match &self.uptime {
+            Some(uptime) => uptime,
+            None => return,
+        };
+        if uptime.blocks_expected > 0 {
+            let ratio = uptime.blocks_produced as f64 / uptime.blocks_expected as f64;
+            metrics::VALIDATOR_BLOCK_UPTIME_RATIO.set(ratio);
+            let below_threshold = uptime.blocks_expected >= MIN_EXPECTED_FOR_UPTIME_WARNING
+                && ratio * 100. < uptime.block_producer_kickout_threshold as f64;
+            if below_threshold && !self.warned_about_blocks {
+                warn!(
+                    target: "client",
+                    %validator_id,
+                    produced = uptime.blocks_produced,
+                    expected = uptime.blocks_expected,
+                    threshold = uptime.block_producer_kickout_threshold,
+                    "Block production uptime is below the kickout threshold for this epoch"
+                );
+            }
+            self.warned_about_blocks = below_threshold;
+        }
+        if uptime.chunks_expected > 0 {
+            let ratio = uptime.chunks_produced as f64 / uptime.chunks_expected as f64;
+            metrics::VALIDATOR_CHUNK_UPTIME_RATIO.set(ratio);
+            let below_threshold = uptime.chunks_expected >= MIN_EXPECTED_FOR_UPTIME_WARNING
+                && ratio * 100. < uptime.chunk_producer_kickout_threshold as f64;
+            if below_threshold && !self.warned_about_chunks {
+                warn!(
+                    target: "client",
+                    %validator_id,
+                    produced = uptime.chunks_produced,
+                    expected = uptime.chunks_expected,
+                    threshold = uptime.chunk_producer_kickout_threshold,
+                    "Chunk production uptime is below the kickout threshold for this epoch"
+                );
+            }
+            self.warned_about_chunks = below_threshold;
+        }
+    }
+
+    /// Most recent misses, newest first.
+    pub(crate) fn missed(&self) -> Vec<MissedProduction> {
+        self.missed.iter().rev().cloned().collect()
+    }
+
+    pub(crate) fn uptime(&self) -> Option<ValidatorUptime> {
+        self.uptime.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uptime_resets_on_new_epoch() {
+        let mut tracker = MissedProductionTracker::new();
+        let epoch_id = EpochId::default();
+        tracker.start_block(1, &epoch_id, 90, 90);
+        tracker.block_produced();
+        tracker.block_missed(2, MissReason::LateApproval);
+        tracker.chunk_missed(2, 0, MissReason::NoChunkParts);
+        let uptime = tracker.uptime().unwrap();
+        assert_eq!((uptime.blocks_produced, uptime.blocks_expected), (1, 2));
+        assert_eq!((uptime.chunks_produced, uptime.chunks_expected), (0, 1));
+        assert_eq!(tracker.missed()[0].shard_id, Some(0));
+
+        assert_eq!(tracker.first_unchecked_height(1), None);
+        assert_eq!(tracker.first_unchecked_height(5), Some(2));
+        tracker.start_block(5, &epoch_id, 90, 90);
+        assert_eq!(tracker.uptime().unwrap().blocks_expected, 2);
+
+        let next_epoch_id = EpochId(near_primitives::hash::hash(&[1]));
+        tracker.start_block(6, &next_epoch_id, 90, 90);
+        let uptime = tracker.uptime().unwrap();
+        assert_eq!(uptime.epoch_id, next_epoch_id);
+        assert_eq!(uptime.blocks_expected, 0);
+        assert_eq!(tracker.missed().len(), 2);
+    }
+
+    #[test]
+    fn test_uptime_warning() {
+        let mut tracker = MissedProductionTracker::new();
+        tracker.start_block(1, &EpochId::default(), 90, 90);
+        let validator_id: AccountId = "test".parse().unwrap();
+        for height in 0..MIN_EXPECTED_FOR_UPTIME_WARNING - 1 {
+            tracker.block_missed(height, MissReason::Unknown);
+        }
+        tracker.check_uptime(&validator_id);
+        assert!(!tracker.warned_about_blocks);
+        tracker.block_missed(MIN_EXPECTED_FOR_UPTIME_WARNING, MissReason::Unknown);
+        tracker.check_uptime(&validator_id);
+        assert!(tracker.warned_about_blocks);
+        for _ in 0..10 * MIN_EXPECTED_FOR_UPTIME_WARNING {
+            tracker.block_produced();
+        }
+        tracker.check_uptime(&validator_id);
+        assert!(!tracker.warned_about_blocks);
+    }
+}
@@ -1,60 +1,77 @@
 use chrono::{DateTime, Duration, Utc};
-use near_network::types::PeerManagerAdapter;
-use near_primitives::hash::CryptoHash;
+use near_async::messaging::CanSend;
+use near_chain::{validate_epoch_sync_finalization, validate_light_client_block, Chain};
+use near_client_primitives::types::SyncStatus;
+use near_network::types::{
+    HighestHeightPeerInfo, NetworkRequests, PeerManagerAdapter, PeerManagerMessageRequest,
+    ReasonForBan,
+};
+use near_primitives::block::BlockHeader;
 use near_primitives::network::PeerId;
 use near_primitives::static_clock::StaticClock;
+use near_primitives::syncing::{EpochSyncFinalizationResponse, EpochSyncResponse};
 use near_primitives::types::validator_stake::ValidatorStake;
-use near_primitives::types::EpochId;
-use std::collections::{HashMap, HashSet};
+use near_primitives::types::{BlockHeight, BlockHeightDelta, EpochId};
+use near_primitives::views::validator_stake_view::ValidatorStakeView;
+use near_primitives::views::LightClientBlockLiteView;
+use rand::seq::IteratorRandom;
+use rand::thread_rng;
+use std::collections::HashMap;
 use std::time::Duration as TimeDuration;
+use tracing::{debug, info, warn};
 
-/// Helper to keep track of the Epoch Sync
-// TODO #3488
-#[allow(dead_code)]
+/// Number of requests in a row which may be sent without any progress before
+/// epoch sync is abandoned, e.g. because no peer serves it, and the node falls
+/// back to header sync from genesis.
+pub const MAX_REQUESTS_WITHOUT_PROGRESS: u32 = 20;
+
+/// Helper to keep track of the Epoch Sync.
+///
+/// A node which starts from genesis far behind the network first follows the
+/// light client blocks of every epoch, which only requires verifying block
+/// producer signatures, until it gets to the current epoch.  Then it receives
+/// the headers and the epoch manager data around the start of the current
+/// epoch, and header sync continues from there instead of from genesis.
 pub struct EpochSync {
     network_adapter: PeerManagerAdapter,
     /// Datastructure to keep track of when the last request to each peer was made.
-    /// Peers do not respond to Epoch Sync requests more frequently than once per a certain time
-    /// interval, thus there's no point in requesting more frequently.
+    /// Peers throttle the epoch sync requests they serve, so requests to the same peer are
+    /// spread out in time.
     peer_to_last_request_time: HashMap<PeerId, DateTime<Utc>>,
-    /// Tracks all the peers who have reported that we are already up to date
-    peers_reporting_up_to_date: HashSet<PeerId>,
-    /// The last epoch we are synced to
+    /// The last verified block, which is the last final block of the previous
+    /// epoch once we advanced past genesis.
+    head: LightClientBlockLiteView,
+    /// The epoch whose light client block we need next.
     current_epoch_id: EpochId,
-    /// The next epoch id we need to sync
-    next_epoch_id: EpochId,
-    /// The block producers set to validate the light client block view for the next epoch
-    next_block_producers: Vec<ValidatorStake>,
-    /// The last epoch id that we have requested
-    requested_epoch_id: EpochId,
+    /// The block producers of `current_epoch_id`, to validate its light client block and the
+    /// first block of that epoch in finalization.
+    epoch_block_producers: Vec<ValidatorStakeView>,
+    /// Number of epochs we advanced through.
+    epoch_ord: u64,
     /// When and to whom was the last request made
     last_request_time: DateTime<Utc>,
     last_request_peer_id: Option<PeerId>,
+    /// Number of requests sent since we last advanced, see `MAX_REQUESTS_WITHOUT_PROGRESS`.
+    requests_without_progress: u32,
 
     /// How long to wait for a response before re-requesting the same light client block view
     request_timeout: Duration,
     /// How frequently to send request to the same peer
     peer_timeout: Duration,
 
-    /// True, if all peers agreed that we're at the last Epoch.
+    /// True, if a peer reported that we're at the last Epoch.
     /// Only finalization is needed.
     have_all_epochs: bool,
-    /// Whether the Epoch Sync was performed to completion previously.
+    /// Whether the Epoch Sync was performed to completion previously, or
+    /// was deemed unnecessary.
     /// Current state machine allows for only one Epoch Sync.
     pub done: bool,
-
-    pub sync_hash: CryptoHash,
-
-    received_epoch: bool,
-
-    is_just_started: bool,
 }
 
 impl EpochSync {
     pub fn new(
         network_adapter: PeerManagerAdapter,
-        genesis_epoch_id: EpochId,
-        genesis_next_epoch_id: EpochId,
+        genesis_header: &BlockHeader,
         first_epoch_block_producers: Vec<ValidatorStake>,
         request_timeout: TimeDuration,
         peer_timeout: TimeDuration,
@@ -62,20 +79,189 @@ impl EpochSync {
         Self {
             network_adapter,
             peer_to_last_request_time: HashMap::new(),
-            peers_reporting_up_to_date: HashSet::new(),
-            current_epoch_id: genesis_epoch_id.clone(),
-            next_epoch_id: genesis_next_epoch_id,
-            next_block_producers: first_epoch_block_producers,
-            requested_epoch_id: genesis_epoch_id,
+            head: genesis_header.clone().into(),
+            current_epoch_id: genesis_header.epoch_id().clone(),
+            epoch_block_producers: first_epoch_block_producers
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            epoch_ord: 0,
             last_request_time: StaticClock::utc(),
             last_request_peer_id: None,
+            requests_without_progress: 0,
             request_timeout: Duration::from_std(request_timeout).unwrap(),
             peer_timeout: Duration::from_std(peer_timeout).unwrap(),
-            received_epoch: false,
             have_all_epochs: false,
             done: false,
-            sync_hash: CryptoHash::default(),
-            is_just_started: true,
         }
     }
+
+    /// Makes progress in epoch sync.  Returns true while epoch sync is in
+    /// progress and other kinds of sync should wait.
+    ///
+    /// Epoch sync only starts for a node which doesn't have any headers past
+    /// genesis and is more than `block_fetch_horizon` blocks behind, since
+    /// such a node would have to catch up with state sync anyway.  It is
+    /// abandoned in favour of header sync if peers don't help us advance.
+    pub fn run(
+        &mut self,
+        sync_status: &mut SyncStatus,
+        chain: &Chain,
+        highest_height: BlockHeight,
+        block_fetch_horizon: BlockHeightDelta,
+        highest_height_peers: &[HighestHeightPeerInfo],
+    ) -> Result<bool, near_chain::Error> {
+        if self.done {
+            return Ok(false);
+        }
+        let header_head = chain.header_head()?;
+        if !matches!(sync_status, SyncStatus::EpochSync { .. }) {
+            if header_head.height != chain.genesis().height()
+                || highest_height <= header_head.height + block_fetch_horizon
+            {
+                self.done = true;
+                return Ok(false);
+            }
+            info!(target: "sync", highest_height, "Starting epoch sync");
+        }
+        *sync_status = SyncStatus::EpochSync { epoch_ord: self.epoch_ord };
+
+        let now = StaticClock::utc();
+        if self.last_request_peer_id.is_some()
+            && now < self.last_request_time + self.request_timeout
+        {
+            return Ok(true);
+        }
+        let peer = highest_height_peers
+            .iter()
+            .filter(|peer| {
+                self.peer_to_last_request_time
+                    .get(&peer.peer_info.id)
+                    .map_or(true, |time| now >= *time + self.peer_timeout)
+            })
+            .choose(&mut thread_rng());
+        let peer_id = match peer {
+            Some(peer) => peer.peer_info.id.clone(),
+            None => return Ok(true),
+        };
+        if self.requests_without_progress >= MAX_REQUESTS_WITHOUT_PROGRESS {
+            warn!(
+                target: "sync",
+                epoch_ord = self.epoch_ord,
+                requests = self.requests_without_progress,
+                "Epoch sync: peers don't serve epoch sync, falling back to header sync"
+            );
+            self.done = true;
+            return Ok(false);
+        }
+        self.requests_without_progress += 1;
+        let request = if self.have_all_epochs {
+            let block_hash = self.head.hash();
+            debug!(target: "sync", %peer_id, ?block_hash, "Epoch sync: request finalization");
+            NetworkRequests::EpochSyncFinalizationRequest { block_hash, peer_id: peer_id.clone() }
+        } else {
+            let epoch_id = self.current_epoch_id.clone();
+            debug!(target: "sync", %peer_id, ?epoch_id, "Epoch sync: request light client block");
+            NetworkRequests::EpochSyncRequest { epoch_id, peer_id: peer_id.clone() }
+        };
+        self.network_adapter.send(PeerManagerMessageRequest::NetworkRequests(request));
+        self.peer_to_last_request_time.insert(peer_id.clone(), now);
+        self.last_request_time = now;
+        self.last_request_peer_id = Some(peer_id);
+        Ok(true)
+    }
+
+    /// Processes the light client block of the epoch we requested last.
+    pub fn on_response(&mut self, response: EpochSyncResponse, peer_id: PeerId) {
+        if self.done || self.have_all_epochs || !self.take_request_from(&peer_id) {
+            return;
+        }
+        let light_client_block_view = match response {
+            EpochSyncResponse::UpToDate => {
+                self.requests_without_progress = 0;
+                // The genesis epoch hasn't finished yet, there is nothing to skip.
+                if self.epoch_ord == 0 {
+                    info!(target: "sync", "Epoch sync: the chain is still in its first epoch");
+                    self.done = true;
+                } else {
+                    self.have_all_epochs = true;
+                }
+                return;
+            }
+            EpochSyncResponse::Advance { light_client_block_view } => light_client_block_view,
+        };
+        if light_client_block_view.inner_lite.epoch_id != self.current_epoch_id.0 {
+            self.ban_peer(peer_id, "light client block of a wrong epoch");
+            return;
+        }
+        if let Err(err) = validate_light_client_block(
+            &self.head,
+            &light_client_block_view,
+            &self.epoch_block_producers,
+        ) {
+            self.ban_peer(peer_id, &err.to_string());
+            return;
+        }
+        let next_block_producers = match light_client_block_view.next_bps.clone() {
+            Some(next_bps) => next_bps,
+            None => {
+                self.ban_peer(peer_id, "light client block without next block producers");
+                return;
+            }
+        };
+        self.current_epoch_id = EpochId(light_client_block_view.inner_lite.next_epoch_id);
+        self.epoch_block_producers = next_block_producers;
+        self.head = LightClientBlockLiteView {
+            prev_block_hash: light_client_block_view.prev_block_hash,
+            inner_rest_hash: light_client_block_view.inner_rest_hash,
+            inner_lite: light_client_block_view.inner_lite,
+        };
+        self.epoch_ord += 1;
+        self.requests_without_progress = 0;
+        debug!(target: "sync", epoch_ord = self.epoch_ord, height = self.head.inner_lite.height, "Epoch sync: advanced");
+    }
+
+    /// Validates the finalization data and initializes the chain with it,
+    /// which completes epoch sync.
+    pub fn on_finalization_response(
+        &mut self,
+        chain: &mut Chain,
+        response: EpochSyncFinalizationResponse,
+        peer_id: PeerId,
+    ) -> Result<(), near_chain::Error> {
+        if self.done || !self.have_all_epochs || !self.take_request_from(&peer_id) {
+            return Ok(());
+        }
+        if let Err(err) = validate_epoch_sync_finalization(
+            &self.head.hash(),
+            &self.epoch_block_producers,
+            &response,
+        ) {
+            self.ban_peer(peer_id, &err.to_string());
+            return Ok(());
+        }
+        let height = response.header_sync_init_header.height();
+        chain.init_after_epoch_sync(response)?;
+        info!(target: "sync", epochs = self.epoch_ord, height, "Epoch sync: done, continuing with header sync");
+        self.done = true;
+        Ok(())
+    }
+
+    /// Returns whether a response from `peer_id` is the one we are waiting
+    /// for, and if so, allows sending the next request right away.
+    fn take_request_from(&mut self, peer_id: &PeerId) -> bool {
+        if self.last_request_peer_id.as_ref() != Some(peer_id) {
+            debug!(target: "sync", %peer_id, "Epoch sync: ignoring unrequested response");
+            return false;
+        }
+        self.last_request_peer_id = None;
+        true
+    }
+
+    fn ban_peer(&self, peer_id: PeerId, reason: &str) {
+        warn!(target: "sync", %peer_id, reason, "Epoch sync: ban a peer which sent invalid data");
+        self.network_adapter.send(PeerManagerMessageRequest::NetworkRequests(
+            NetworkRequests::BanPeer { peer_id, ban_reason: ReasonForBan::BadBlockHeader },
+        ));
+    }
 }
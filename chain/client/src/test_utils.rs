@@ -50,6 +50,7 @@ use near_network::types::{
 };
 use near_network::types::{
     NetworkInfo, PeerManagerMessageRequest, PeerManagerMessageResponse, SetChainInfo,
+    SetValidatorSigner,
 };
 use near_o11y::testonly::TracingCapture;
 use near_o11y::WithSpanContextExt;
@@ -81,8 +82,9 @@ use near_telemetry::TelemetryActor;
 
 use crate::adapter::{
     AnnounceAccountRequest, BlockApproval, BlockHeadersRequest, BlockHeadersResponse, BlockRequest,
-    BlockResponse, ProcessTxResponse, SetNetworkInfo, StateRequestHeader, StateRequestPart,
-    StateResponse,
+    BlockResponse, EpochSyncFinalizationRequest, EpochSyncRequest, ProcessTxResponse,
+    RecvEpochSyncFinalizationResponse, RecvEpochSyncResponse, SetNetworkInfo, StateRequestHeader,
+    StateRequestPart, StateResponse,
 };
 
 pub struct PeerManagerMock {
@@ -122,6 +124,11 @@ impl actix::Handler<SetChainInfo> for PeerManagerMock {
     fn handle(&mut self, _msg: SetChainInfo, _ctx: &mut Self::Context) {}
 }
 
+impl actix::Handler<SetValidatorSigner> for PeerManagerMock {
+    type Result = ();
+    fn handle(&mut self, _msg: SetValidatorSigner, _ctx: &mut Self::Context) {}
+}
+
 /// min block production time in milliseconds
 pub const MIN_BLOCK_PROD_TIME: Duration = Duration::from_millis(100);
 /// max block production time in milliseconds
@@ -232,7 +239,12 @@ pub fn setup(
         runtime.clone(),
         &chain_genesis,
         doomslug_threshold_mode,
-        ChainConfig { save_trie_changes: true, background_migration_threads: 1 },
+        ChainConfig {
+            save_trie_changes: true,
+            background_migration_threads: 1,
+            account_activity_index: false,
+            event_index: false,
+        },
     )
     .unwrap();
     let genesis_block = chain.get_block(&chain.genesis().hash().clone()).unwrap();
@@ -338,7 +350,12 @@ pub fn setup_only_view(
         runtime.clone(),
         &chain_genesis,
         doomslug_threshold_mode,
-        ChainConfig { save_trie_changes: true, background_migration_threads: 1 },
+        ChainConfig {
+            save_trie_changes: true,
+            background_migration_threads: 1,
+            account_activity_index: false,
+            event_index: false,
+        },
     )
     .unwrap();
 
@@ -874,6 +891,58 @@ pub fn setup_mock_all_validators(
                                 }
                             }
                         }
+                        NetworkRequests::EpochSyncRequest { epoch_id, peer_id } => {
+                            for (i, peer_info) in key_pairs.iter().enumerate() {
+                                let peer_id = peer_id.clone();
+                                if peer_info.id == peer_id {
+                                    let me = connectors1[my_ord].client_actor.clone();
+                                    actix::spawn(
+                                        connectors1[i]
+                                            .view_client_actor
+                                            .send(
+                                                EpochSyncRequest(epoch_id.clone())
+                                                    .with_span_context(),
+                                            )
+                                            .then(move |response| {
+                                                if let Some(response) = response.unwrap() {
+                                                    me.do_send(
+                                                        RecvEpochSyncResponse(response, peer_id)
+                                                            .with_span_context(),
+                                                    );
+                                                }
+                                                future::ready(())
+                                            }),
+                                    );
+                                }
+                            }
+                        }
+                        NetworkRequests::EpochSyncFinalizationRequest { block_hash, peer_id } => {
+                            for (i, peer_info) in key_pairs.iter().enumerate() {
+                                let peer_id = peer_id.clone();
+                                if peer_info.id == peer_id {
+                                    let me = connectors1[my_ord].client_actor.clone();
+                                    actix::spawn(
+                                        connectors1[i]
+                                            .view_client_actor
+                                            .send(
+                                                EpochSyncFinalizationRequest(*block_hash)
+                                                    .with_span_context(),
+                                            )
+                                            .then(move |response| {
+                                                if let Some(response) = response.unwrap() {
+                                                    me.do_send(
+                                                        RecvEpochSyncFinalizationResponse(
+                                                            response, peer_id,
+                                                        )
+                                                        .with_span_context(),
+                                                    );
+                                                }
+                                                future::ready(())
+                                            }),
+                                    );
+                                }
+                            }
+                        }
                         NetworkRequests::StateRequestHeader {
                             shard_id,
                             sync_hash,
@@ -1196,7 +1265,12 @@ pub fn setup_synchronous_shards_manager(
         runtime_adapter.clone(),
         chain_genesis,
         DoomslugThresholdMode::TwoThirds, // irrelevant
-        ChainConfig { save_trie_changes: true, background_migration_threads: 1 }, // irrelevant
+        ChainConfig {
+            save_trie_changes: true,
+            background_migration_threads: 1,
+            account_activity_index: false,
+            event_index: false,
+        }, // irrelevant
     )
     .unwrap();
     let chain_head = chain.head().unwrap();
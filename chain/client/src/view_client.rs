@@ -21,13 +21,14 @@ use near_chain::{
 };
 use near_chain_configs::{ClientConfig, ProtocolConfigView};
 use near_client_primitives::types::{
-    Error, GetBlock, GetBlockError, GetBlockProof, GetBlockProofError, GetBlockProofResponse,
-    GetBlockWithMerkleTree, GetChunkError, GetExecutionOutcome, GetExecutionOutcomeError,
-    GetExecutionOutcomesForBlock, GetGasPrice, GetGasPriceError, GetMaintenanceWindows,
-    GetMaintenanceWindowsError, GetNextLightClientBlockError, GetProtocolConfig,
-    GetProtocolConfigError, GetReceipt, GetReceiptError, GetSplitStorageInfo,
-    GetSplitStorageInfoError, GetStateChangesError, GetStateChangesWithCauseInBlock,
-    GetStateChangesWithCauseInBlockForTrackedShards, GetValidatorInfoError, Query, QueryError,
+    Error, GetAccountActivity, GetAccountActivityError, GetBlock, GetBlockError, GetBlockProof,
+    GetBlockProofError, GetBlockProofResponse, GetBlockWithMerkleTree, GetChunkError, GetEvents,
+    GetEventsError, GetExecutionOutcome, GetExecutionOutcomeError, GetExecutionOutcomesForBlock,
+    GetGasPrice, GetGasPriceError, GetMaintenanceWindows, GetMaintenanceWindowsError,
+    GetNextLightClientBlockError, GetProtocolConfig, GetProtocolConfigError, GetReceipt,
+    GetReceiptError, GetSplitStorageInfo, GetSplitStorageInfoError, GetStateChangesError,
+    GetStateChangesWithCauseInBlock, GetStateChangesWithCauseInBlockForTrackedShards,
+    GetTransactionTrace, GetTransactionTraceError, GetValidatorInfoError, Query, QueryError,
     TxStatus, TxStatusError,
 };
 use near_network::types::{
@@ -43,8 +44,8 @@ use near_primitives::merkle::{merklize, PartialMerkleTree};
 use near_primitives::network::AnnounceAccount;
 use near_primitives::sharding::ShardChunk;
 use near_primitives::syncing::{
-    ShardStateSyncResponse, ShardStateSyncResponseHeader, ShardStateSyncResponseV1,
-    ShardStateSyncResponseV2,
+    EpochSyncFinalizationResponse, EpochSyncResponse, ShardStateSyncResponse,
+    ShardStateSyncResponseHeader, ShardStateSyncResponseV1, ShardStateSyncResponseV2,
 };
 use near_primitives::types::{
     AccountId, BlockHeight, BlockId, BlockReference, EpochReference, Finality, MaybeBlockId,
@@ -52,19 +53,22 @@ use near_primitives::types::{
 };
 use near_primitives::views::validator_stake_view::ValidatorStakeView;
 use near_primitives::views::{
-    BlockView, ChunkView, EpochValidatorInfo, ExecutionOutcomeWithIdView,
-    FinalExecutionOutcomeView, FinalExecutionOutcomeViewEnum, GasPriceView, LightClientBlockView,
-    MaintenanceWindowsView, QueryRequest, QueryResponse, ReceiptView, SplitStorageInfoView,
-    StateChangesKindsView, StateChangesView,
+    AccountActivityPageView, BlockView, ChunkView, ContractEventsPageView, EpochValidatorInfo,
+    ExecutionOutcomeWithIdView, FinalExecutionOutcomeView, FinalExecutionOutcomeViewEnum,
+    GasPriceView, LightClientBlockView, MaintenanceWindowsView, QueryRequest, QueryResponse,
+    ReceiptView, RewardProjectionView, SplitStorageInfoView, StateChangesKindsView,
+    StateChangesView, TxTraceView,
 };
 
 use crate::adapter::{
-    AnnounceAccountRequest, BlockHeadersRequest, BlockRequest, StateRequestHeader,
-    StateRequestPart, StateResponse, TxStatusRequest, TxStatusResponse,
+    AnnounceAccountRequest, BlockHeadersRequest, BlockRequest, EpochSyncFinalizationRequest,
+    EpochSyncRequest, StateRequestHeader, StateRequestPart, StateResponse, TxStatusRequest,
+    TxStatusResponse,
 };
 use crate::{
-    metrics, sync, GetChunk, GetExecutionOutcomeResponse, GetNextLightClientBlock, GetStateChanges,
-    GetStateChangesInBlock, GetValidatorInfo, GetValidatorOrdered,
+    metrics, sync, GetChunk, GetExecutionOutcomeResponse, GetNextLightClientBlock,
+    GetRewardProjection, GetStateChanges, GetStateChangesInBlock, GetValidatorInfo,
+    GetValidatorOrdered,
 };
 
 /// Max number of queries that we keep.
@@ -100,6 +104,7 @@ pub struct ViewClientActor {
     pub config: ClientConfig,
     request_manager: Arc<RwLock<ViewClientRequestManager>>,
     state_request_cache: Arc<Mutex<VecDeque<Instant>>>,
+    epoch_sync_request_cache: Arc<Mutex<VecDeque<Instant>>>,
 }
 
 impl ViewClientRequestManager {
@@ -117,6 +122,8 @@ impl ViewClientRequestManager {
 impl ViewClientActor {
     /// Maximum number of state requests allowed per `view_client_throttle_period`.
     const MAX_NUM_STATE_REQUESTS: usize = 30;
+    /// Maximum number of epoch sync requests allowed per `view_client_throttle_period`.
+    const MAX_NUM_EPOCH_SYNC_REQUESTS: usize = 30;
 
     pub fn new(
         validator_account_id: Option<AccountId>,
@@ -143,6 +150,7 @@ impl ViewClientActor {
             config,
             request_manager,
             state_request_cache: Arc::new(Mutex::new(VecDeque::default())),
+            epoch_sync_request_cache: Arc::new(Mutex::new(VecDeque::default())),
         })
     }
 
@@ -506,7 +514,21 @@ impl ViewClientActor {
     }
 
     fn check_state_sync_request(&self) -> bool {
-        let mut cache = self.state_request_cache.lock().expect(POISONED_LOCK_ERR);
+        self.check_request_rate(&self.state_request_cache, Self::MAX_NUM_STATE_REQUESTS)
+    }
+
+    fn check_epoch_sync_request(&self) -> bool {
+        self.check_request_rate(&self.epoch_sync_request_cache, Self::MAX_NUM_EPOCH_SYNC_REQUESTS)
+    }
+
+    /// Returns whether a request fits into `max_num_requests` per `view_client_throttle_period`,
+    /// given the times of the previous requests in `cache`.
+    fn check_request_rate(
+        &self,
+        cache: &Mutex<VecDeque<Instant>>,
+        max_num_requests: usize,
+    ) -> bool {
+        let mut cache = cache.lock().expect(POISONED_LOCK_ERR);
         let now = StaticClock::instant();
         while let Some(&instant) = cache.front() {
             if now.saturating_duration_since(instant) > self.config.view_client_throttle_period {
@@ -517,7 +539,7 @@ impl ViewClientActor {
                 break;
             }
         }
-        if cache.len() >= Self::MAX_NUM_STATE_REQUESTS {
+        if cache.len() >= max_num_requests {
             return false;
         }
         cache.push_back(now);
@@ -721,6 +743,37 @@ impl Handler<WithSpanContext<GetValidatorOrdered>> for ViewClientActor {
         })?)
     }
 }
+
+impl Handler<WithSpanContext<GetRewardProjection>> for ViewClientActor {
+    type Result = Result<RewardProjectionView, GetValidatorInfoError>;
+
+    #[perf]
+    fn handle(
+        &mut self,
+        msg: WithSpanContext<GetRewardProjection>,
+        _: &mut Self::Context,
+    ) -> Self::Result {
+        let (_span, msg) = handler_debug_span!(target: "client", msg);
+        let _timer = metrics::VIEW_CLIENT_MESSAGE_TIME
+            .with_label_values(&["GetRewardProjection"])
+            .start_timer();
+        let header = self.maybe_block_id_to_block_header(msg.block_id)?;
+        if let Some(delegation) =
+            msg.delegations.iter().find(|delegation| delegation.amount > *header.total_supply())
+        {
+            return Err(GetValidatorInfoError::InvalidDelegation(format!(
+                "amount delegated to {} exceeds the total supply",
+                delegation.validator_id
+            )));
+        }
+        Ok(self.runtime_adapter.get_reward_projection(
+            header.hash(),
+            msg.num_epochs,
+            msg.delegations,
+        )?)
+    }
+}
+
 /// Returns a list of change kinds per account in a store for a given block.
 impl Handler<WithSpanContext<GetStateChangesInBlock>> for ViewClientActor {
     type Result = Result<StateChangesKindsView, GetStateChangesError>;
@@ -1187,6 +1240,60 @@ impl Handler<WithSpanContext<BlockHeadersRequest>> for ViewClientActor {
     }
 }
 
+impl Handler<WithSpanContext<EpochSyncRequest>> for ViewClientActor {
+    type Result = Option<Box<EpochSyncResponse>>;
+
+    #[perf]
+    fn handle(
+        &mut self,
+        msg: WithSpanContext<EpochSyncRequest>,
+        _ctx: &mut Self::Context,
+    ) -> Self::Result {
+        let (_span, msg) = handler_debug_span!(target: "client", msg);
+        let _timer = metrics::VIEW_CLIENT_MESSAGE_TIME
+            .with_label_values(&["EpochSyncRequest"])
+            .start_timer();
+        let EpochSyncRequest(epoch_id) = msg;
+        if !self.check_epoch_sync_request() {
+            return None;
+        }
+        match self.chain.get_epoch_sync_response(&epoch_id) {
+            Ok(response) => Some(Box::new(response)),
+            Err(err) => {
+                error!(target: "sync", ?epoch_id, ?err, "Cannot respond to epoch sync request");
+                None
+            }
+        }
+    }
+}
+
+impl Handler<WithSpanContext<EpochSyncFinalizationRequest>> for ViewClientActor {
+    type Result = Option<Box<EpochSyncFinalizationResponse>>;
+
+    #[perf]
+    fn handle(
+        &mut self,
+        msg: WithSpanContext<EpochSyncFinalizationRequest>,
+        _ctx: &mut Self::Context,
+    ) -> Self::Result {
+        let (_span, msg) = handler_debug_span!(target: "client", msg);
+        let _timer = metrics::VIEW_CLIENT_MESSAGE_TIME
+            .with_label_values(&["EpochSyncFinalizationRequest"])
+            .start_timer();
+        let EpochSyncFinalizationRequest(block_hash) = msg;
+        if !self.check_epoch_sync_request() {
+            return None;
+        }
+        match self.chain.get_epoch_sync_finalization_response(&block_hash) {
+            Ok(response) => Some(Box::new(response)),
+            Err(err) => {
+                debug!(target: "sync", ?block_hash, ?err, "Cannot respond to epoch sync finalization request");
+                None
+            }
+        }
+    }
+}
+
 impl Handler<WithSpanContext<StateRequestHeader>> for ViewClientActor {
     type Result = Option<StateResponse>;
 
@@ -1447,6 +1554,82 @@ impl Handler<WithSpanContext<GetSplitStorageInfo>> for ViewClientActor {
     }
 }
 
+impl Handler<WithSpanContext<GetAccountActivity>> for ViewClientActor {
+    type Result = Result<AccountActivityPageView, GetAccountActivityError>;
+
+    #[perf]
+    fn handle(
+        &mut self,
+        msg: WithSpanContext<GetAccountActivity>,
+        _ctx: &mut Self::Context,
+    ) -> Self::Result {
+        let (_span, msg) = handler_debug_span!(target: "client", msg);
+        let _timer = metrics::VIEW_CLIENT_MESSAGE_TIME
+            .with_label_values(&["GetAccountActivity"])
+            .start_timer();
+        if self.config.account_activity_index.is_none() {
+            return Err(GetAccountActivityError::IndexDisabled);
+        }
+        Ok(self.chain.store().get_account_activity(
+            &msg.account_id,
+            msg.from_block_height,
+            msg.limit,
+        )?)
+    }
+}
+
+impl Handler<WithSpanContext<GetTransactionTrace>> for ViewClientActor {
+    type Result = Result<TxTraceView, GetTransactionTraceError>;
+
+    #[perf]
+    fn handle(
+        &mut self,
+        msg: WithSpanContext<GetTransactionTrace>,
+        _ctx: &mut Self::Context,
+    ) -> Self::Result {
+        let (_span, msg) = handler_debug_span!(target: "client", msg);
+        let _timer = metrics::VIEW_CLIENT_MESSAGE_TIME
+            .with_label_values(&["GetTransactionTrace"])
+            .start_timer();
+        self.chain.get_transaction_trace(&msg.tx_hash).map_err(|err| match err {
+            near_chain::Error::DBNotFoundErr(_) => {
+                GetTransactionTraceError::UnknownTransaction(msg.tx_hash)
+            }
+            err => err.into(),
+        })
+    }
+}
+
+impl Handler<WithSpanContext<GetEvents>> for ViewClientActor {
+    type Result = Result<ContractEventsPageView, GetEventsError>;
+
+    #[perf]
+    fn handle(
+        &mut self,
+        msg: WithSpanContext<GetEvents>,
+        _ctx: &mut Self::Context,
+    ) -> Self::Result {
+        let (_span, msg) = handler_debug_span!(target: "client", msg);
+        let _timer =
+            metrics::VIEW_CLIENT_MESSAGE_TIME.with_label_values(&["GetEvents"]).start_timer();
+        if self.config.event_index.is_none() {
+            return Err(GetEventsError::IndexDisabled);
+        }
+        let to_block_height = match msg.to_block_height {
+            Some(height) => height,
+            None => self.chain.head()?.height,
+        };
+        Ok(self.chain.store().get_events(
+            msg.contract_id.as_ref(),
+            msg.standard.as_deref(),
+            msg.event.as_deref(),
+            msg.from_block_height,
+            to_block_height,
+            msg.limit,
+        )?)
+    }
+}
+
 /// Starts the View Client in a new arbiter (thread).
 pub fn start_view_client(
     validator_account_id: Option<AccountId>,
@@ -16,7 +16,7 @@ use near_primitives::types::{
     ValidatorInfoIdentifier,
 };
 use near_primitives::version::ProtocolVersion;
-use near_primitives::views::EpochValidatorInfo;
+use near_primitives::views::{EpochValidatorInfo, RewardProjectionView, StakeDelegationView};
 use near_store::ShardUId;
 use std::cmp::Ordering;
 use std::sync::{Arc, RwLockReadGuard, RwLockWriteGuard};
@@ -189,6 +189,17 @@ pub trait EpochManagerAdapter: Send + Sync {
         epoch_id: ValidatorInfoIdentifier,
     ) -> Result<EpochValidatorInfo, Error>;
 
+    /// Projected rewards for `num_epochs` epochs following the epoch of the
+    /// given block.
+    ///
+    /// WARNING: this call may be expensive.
+    fn get_reward_projection(
+        &self,
+        block_hash: &CryptoHash,
+        num_epochs: u64,
+        delegations: Vec<StakeDelegationView>,
+    ) -> Result<RewardProjectionView, Error>;
+
     /// Amount of tokens minted in given epoch.
     fn get_epoch_minted_amount(&self, epoch_id: &EpochId) -> Result<Balance, Error>;
 
@@ -620,6 +631,18 @@ impl<T: HasEpochMangerHandle + Send + Sync> EpochManagerAdapter for T {
         epoch_manager.get_validator_info(epoch_id).map_err(|e| e.into())
     }
 
+    /// WARNING: this function calls EpochManager::get_epoch_info_aggregator_upto_last
+    /// underneath which can be very expensive.
+    fn get_reward_projection(
+        &self,
+        block_hash: &CryptoHash,
+        num_epochs: u64,
+        delegations: Vec<StakeDelegationView>,
+    ) -> Result<RewardProjectionView, Error> {
+        let epoch_manager = self.read();
+        Ok(epoch_manager.get_reward_projection(block_hash, num_epochs, delegations)?)
+    }
+
     fn get_epoch_minted_amount(&self, epoch_id: &EpochId) -> Result<Balance, Error> {
         let epoch_manager = self.read();
         Ok(epoch_manager.get_epoch_info(epoch_id)?.minted_amount())
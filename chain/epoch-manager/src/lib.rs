@@ -1,4 +1,3 @@
-use crate::proposals::proposals_to_epoch_info;
 use crate::types::EpochInfoAggregator;
 use near_cache::SyncLruCache;
 use near_chain_configs::GenesisConfig;
@@ -19,7 +18,9 @@ use near_primitives::types::{
 };
 use near_primitives::version::{ProtocolVersion, UPGRADABILITY_FIX_PROTOCOL_VERSION};
 use near_primitives::views::{
-    CurrentEpochValidatorInfo, EpochValidatorInfo, NextEpochValidatorInfo, ValidatorKickoutView,
+    CurrentEpochValidatorInfo, DelegationRewardProjectionView, EpochRewardProjectionView,
+    EpochValidatorInfo, NextEpochValidatorInfo, RewardProjectionView, StakeDelegationView,
+    StakeRewardView, ValidatorKickoutView, ValidatorRewardProjectionView,
 };
 use near_store::{DBCol, Store, StoreUpdate};
 use num_rational::Rational64;
@@ -30,6 +31,7 @@ use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
 use tracing::{debug, warn};
 
 pub use crate::adapter::{EpochManagerAdapter, HasEpochMangerHandle};
+pub use crate::proposals::proposals_to_epoch_info;
 pub use crate::reward_calculator::RewardCalculator;
 pub use crate::reward_calculator::NUM_SECONDS_IN_A_YEAR;
 pub use crate::types::RngSeed;
@@ -302,16 +304,26 @@ impl EpochManager {
         next_epoch_info: EpochInfo,
     ) -> Result<StoreUpdate, EpochError> {
         let mut store_update = self.store.store_update();
+        self.save_epoch_start(
+            &mut store_update,
+            prev_epoch_id,
+            prev_epoch_first_block_info.height(),
+        )?;
+        // Blocks of the previous epoch before its last one are unknown, so
+        // the aggregator starts from its last block.  Final blocks up to that
+        // height don't need to be aggregated, the previous epoch was already
+        // finalized.
+        self.epoch_info_aggregator =
+            EpochInfoAggregator::new(prev_epoch_id.clone(), *prev_epoch_last_block_info.hash());
+        self.largest_final_height = prev_epoch_last_block_info.height();
+        store_update.set_ser(DBCol::EpochInfo, AGGREGATOR_KEY, &self.epoch_info_aggregator)?;
         self.save_block_info(&mut store_update, Arc::new(prev_epoch_first_block_info))?;
         self.save_block_info(&mut store_update, Arc::new(prev_epoch_prev_last_block_info))?;
         self.save_block_info(&mut store_update, Arc::new(prev_epoch_last_block_info))?;
         self.save_epoch_info(&mut store_update, prev_epoch_id, Arc::new(prev_epoch_info))?;
         self.save_epoch_info(&mut store_update, epoch_id, Arc::new(epoch_info))?;
         self.save_epoch_info(&mut store_update, next_epoch_id, Arc::new(next_epoch_info))?;
-        // TODO #3488
-        // put unreachable! here to avoid warnings
-        unreachable!();
-        // Ok(store_update)
+        Ok(store_update)
     }
 
     /// When computing validators to kickout, we exempt some validators first so that
@@ -1342,6 +1354,184 @@ impl EpochManager {
         })
     }
 
+    /// Projects rewards for `num_epochs` epochs following the epoch of the
+    /// given block, assuming the validators, their uptime so far and the
+    /// duration of an epoch stay the same. Validators which weren't expected
+    /// to produce anything yet are assumed to be fully online.
+    ///
+    /// WARNING: this call may be expensive, see
+    /// `get_epoch_info_aggregator_upto_last`.
+    pub fn get_reward_projection(
+        &self,
+        block_hash: &CryptoHash,
+        num_epochs: u64,
+        delegations: Vec<StakeDelegationView>,
+    ) -> Result<RewardProjectionView, EpochError> {
+        let block_info = self.get_block_info(block_hash)?;
+        let epoch_id = block_info.epoch_id().clone();
+        let epoch_info = self.get_epoch_info(&epoch_id)?;
+        let epoch_length = self.get_epoch_config(&epoch_id)?.epoch_length;
+        let aggregator = self.get_epoch_info_aggregator_upto_last(block_hash)?;
+
+        let mut validator_block_chunk_stats = HashMap::new();
+        for (validator_id, info) in epoch_info.validators_iter().enumerate() {
+            let validator_id = validator_id as ValidatorId;
+            let block_stats = aggregator
+                .block_tracker
+                .get(&validator_id)
+                .cloned()
+                .unwrap_or(ValidatorStats { produced: 0, expected: 0 });
+            let mut chunk_stats = ValidatorStats { produced: 0, expected: 0 };
+            for tracker in aggregator.shard_tracker.values() {
+                if let Some(stats) = tracker.get(&validator_id) {
+                    chunk_stats.produced += stats.produced;
+                    chunk_stats.expected += stats.expected;
+                }
+            }
+            let stats = if block_stats.expected == 0 && chunk_stats.expected == 0 {
+                BlockChunkValidatorStats {
+                    block_stats: ValidatorStats { produced: 1, expected: 1 },
+                    chunk_stats: ValidatorStats { produced: 1, expected: 1 },
+                }
+            } else {
+                BlockChunkValidatorStats { block_stats, chunk_stats }
+            };
+            validator_block_chunk_stats.insert(info.account_id().clone(), stats);
+        }
+        let validator_stake =
+            epoch_info.validators_iter().map(|r| r.account_and_stake()).collect::<HashMap<_, _>>();
+
+        // Measure from the last block of the previous epoch, like the
+        // duration used for the actual rewards.
+        let first_block_info = self.get_block_info(block_info.epoch_first_block())?;
+        let start_block_info = match self.get_block_info(first_block_info.prev_hash()) {
+            Ok(prev_block_info) => prev_block_info,
+            Err(EpochError::MissingBlock(_)) => first_block_info,
+            Err(err) => return Err(err),
+        };
+        let num_heights = block_info.height() - start_block_info.height();
+        let epoch_duration = if num_heights == 0 {
+            // Nothing to measure yet, use the block rate of the config.
+            self.reward_calculator.num_seconds_per_year * reward_calculator::NUM_NS_IN_SECOND
+                / self.reward_calculator.num_blocks_per_year
+                * epoch_length
+        } else {
+            let elapsed = block_info
+                .timestamp_nanosec()
+                .saturating_sub(*start_block_info.timestamp_nanosec());
+            (elapsed as u128 * epoch_length as u128 / num_heights as u128) as u64
+        };
+
+        let projection = self.reward_calculator.project_rewards(
+            &validator_block_chunk_stats,
+            &validator_stake,
+            *block_info.total_supply(),
+            epoch_info.protocol_version(),
+            self.genesis_protocol_version,
+            epoch_duration,
+            num_epochs,
+        );
+        let epochs_per_year = (self.reward_calculator.num_seconds_per_year
+            * reward_calculator::NUM_NS_IN_SECOND) as f64
+            / epoch_duration.max(1) as f64;
+        let annual_yield = |first: Option<&StakeRewardView>, stake: Balance| match first {
+            Some(first) if stake > 0 => {
+                (1.0 + first.reward as f64 / stake as f64).powf(epochs_per_year) - 1.0
+            }
+            _ => 0.0,
+        };
+
+        let mut total_supply = *block_info.total_supply();
+        let epochs = projection
+            .iter()
+            .zip(epoch_info.epoch_height() + 1..)
+            .map(|((_, minted_amount), epoch_height)| {
+                total_supply += minted_amount;
+                EpochRewardProjectionView {
+                    epoch_height,
+                    minted_amount: *minted_amount,
+                    total_supply,
+                }
+            })
+            .collect();
+
+        // Reward and stake of every validator in each projected epoch.
+        let validator_rewards = |account_id: &AccountId| {
+            let mut stake = validator_stake.get(account_id).copied().unwrap_or(0);
+            projection
+                .iter()
+                .map(|(rewards, _)| {
+                    let reward = rewards.get(account_id).copied().unwrap_or(0);
+                    stake += reward;
+                    StakeRewardView { reward, stake }
+                })
+                .collect::<Vec<_>>()
+        };
+        let validators = epoch_info
+            .validators_iter()
+            .map(|info| {
+                let (account_id, stake) = info.account_and_stake();
+                let rewards = validator_rewards(&account_id);
+                let apy = annual_yield(rewards.first(), stake);
+                ValidatorRewardProjectionView { account_id, stake, rewards, apy }
+            })
+            .collect();
+
+        // A staking pool distributes its rewards proportionally to the
+        // delegated stake, after taking its fee.
+        let delegations = delegations
+            .into_iter()
+            .map(|delegation| {
+                if delegation.amount > *block_info.total_supply() {
+                    return Err(EpochError::InvalidDelegation(format!(
+                        "amount delegated to {} exceeds the total supply",
+                        delegation.validator_id
+                    )));
+                }
+                let overflow = || {
+                    EpochError::InvalidDelegation(format!(
+                        "projected stake delegated to {} overflows",
+                        delegation.validator_id
+                    ))
+                };
+                let fee = delegation.reward_fee_fraction;
+                let fee_denom = U256::from((*fee.denom()).max(1) as u64);
+                let fee_numer = U256::from((*fee.numer()).max(0) as u64).min(fee_denom);
+                let mut validator_stake =
+                    validator_stake.get(&delegation.validator_id).copied().unwrap_or(0);
+                let mut amount = delegation.amount;
+                let mut rewards = vec![];
+                for validator_reward in validator_rewards(&delegation.validator_id) {
+                    let reward = if validator_stake == 0 {
+                        0
+                    } else {
+                        let reward = U256::from(validator_reward.reward)
+                            .checked_mul(fee_denom - fee_numer)
+                            .and_then(|reward| reward.checked_mul(U256::from(amount)))
+                            .ok_or_else(overflow)?
+                            / fee_denom
+                            / U256::from(validator_stake);
+                        u128::try_from(reward).map_err(|_| overflow())?
+                    };
+                    validator_stake = validator_reward.stake;
+                    amount = amount.checked_add(reward).ok_or_else(overflow)?;
+                    rewards.push(StakeRewardView { reward, stake: amount });
+                }
+                let apy = annual_yield(rewards.first(), delegation.amount);
+                Ok(DelegationRewardProjectionView { delegation, rewards, apy })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(RewardProjectionView {
+            epoch_id,
+            epoch_height: epoch_info.epoch_height(),
+            epoch_duration_ns: epoch_duration,
+            epochs,
+            validators,
+            delegations,
+        })
+    }
+
     /// Compare two epoch ids based on their start height. This works because finality gadget
     /// guarantees that we cannot have two different epochs on two forks
     pub fn compare_epoch_id(
@@ -148,6 +148,40 @@ impl RewardCalculator {
         }
         (res, epoch_actual_reward)
     }
+
+    /// Projects rewards for the next `num_epochs` epochs, assuming that the
+    /// validators keep their stats, that each epoch lasts `epoch_duration`
+    /// and that rewards are restaked.
+    /// Returns rewards and minted amount of each epoch, as `calculate_reward`.
+    pub fn project_rewards(
+        &self,
+        validator_block_chunk_stats: &HashMap<AccountId, BlockChunkValidatorStats>,
+        validator_stake: &HashMap<AccountId, Balance>,
+        mut total_supply: Balance,
+        protocol_version: ProtocolVersion,
+        genesis_protocol_version: ProtocolVersion,
+        epoch_duration: u64,
+        num_epochs: u64,
+    ) -> Vec<(HashMap<AccountId, Balance>, Balance)> {
+        let mut validator_stake = validator_stake.clone();
+        (0..num_epochs)
+            .map(|_| {
+                let (rewards, minted_amount) = self.calculate_reward(
+                    validator_block_chunk_stats.clone(),
+                    &validator_stake,
+                    total_supply,
+                    protocol_version,
+                    genesis_protocol_version,
+                    epoch_duration,
+                );
+                for (account_id, stake) in validator_stake.iter_mut() {
+                    *stake += rewards.get(account_id).copied().unwrap_or(0);
+                }
+                total_supply += minted_amount;
+                (rewards, minted_amount)
+            })
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -388,4 +422,58 @@ mod tests {
             epoch_length * NUM_NS_IN_SECOND,
         );
     }
+
+    /// Test that projected rewards compound with the restaked rewards and the
+    /// growing total supply.
+    #[test]
+    fn test_project_rewards() {
+        let epoch_length = 1000;
+        let reward_calculator = RewardCalculator {
+            max_inflation_rate: Ratio::new(1, 100),
+            num_blocks_per_year: 1000,
+            epoch_length,
+            protocol_reward_rate: Ratio::new(0, 10),
+            protocol_treasury_account: "near".parse().unwrap(),
+            online_min_threshold: Ratio::new(9, 10),
+            online_max_threshold: Ratio::new(99, 100),
+            num_seconds_per_year: 1000,
+        };
+        let validator_block_chunk_stats = HashMap::from([(
+            "test".parse().unwrap(),
+            BlockChunkValidatorStats {
+                block_stats: ValidatorStats { produced: 1000, expected: 1000 },
+                chunk_stats: ValidatorStats { produced: 1000, expected: 1000 },
+            },
+        )]);
+        let validator_stake = HashMap::from([("test".parse().unwrap(), 1_000_000)]);
+        let projection = reward_calculator.project_rewards(
+            &validator_block_chunk_stats,
+            &validator_stake,
+            1_000_000_000,
+            PROTOCOL_VERSION,
+            PROTOCOL_VERSION,
+            epoch_length * NUM_NS_IN_SECOND,
+            2,
+        );
+        // Every epoch mints 1% of the total supply, all of it for the only validator.
+        assert_eq!(
+            projection,
+            vec![
+                (
+                    HashMap::from([
+                        ("near".parse().unwrap(), 0),
+                        ("test".parse().unwrap(), 10_000_000u128)
+                    ]),
+                    10_000_000u128
+                ),
+                (
+                    HashMap::from([
+                        ("near".parse().unwrap(), 0),
+                        ("test".parse().unwrap(), 10_100_000u128)
+                    ]),
+                    10_100_000u128
+                ),
+            ]
+        );
+    }
 }
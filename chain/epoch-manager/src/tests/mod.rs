@@ -754,6 +754,129 @@ fn test_validator_reward_one_validator() {
     assert_eq!(epoch_info.minted_amount(), inflation);
 }
 
+#[test]
+fn test_reward_projection() {
+    let stake_amount = 1_000_000;
+    let validators = vec![("test1".parse().unwrap(), stake_amount)];
+    let epoch_length = 10;
+    let total_supply = 10 * stake_amount;
+    let reward_calculator = RewardCalculator {
+        max_inflation_rate: Ratio::new(5, 100),
+        num_blocks_per_year: 50,
+        epoch_length,
+        protocol_reward_rate: Ratio::new(1, 10),
+        protocol_treasury_account: "near".parse().unwrap(),
+        online_min_threshold: Ratio::new(90, 100),
+        online_max_threshold: Ratio::new(99, 100),
+        num_seconds_per_year: 50,
+    };
+    let mut epoch_manager = setup_epoch_manager(
+        validators,
+        epoch_length,
+        1,
+        1,
+        0,
+        90,
+        60,
+        100,
+        reward_calculator.clone(),
+    );
+    let rng_seed = [0; 32];
+    let h = hash_range(4);
+    epoch_manager
+        .record_block_info(
+            block_info(
+                h[0],
+                0,
+                0,
+                Default::default(),
+                Default::default(),
+                h[0],
+                vec![true],
+                total_supply,
+            ),
+            rng_seed,
+        )
+        .unwrap()
+        .commit()
+        .unwrap();
+    for i in 1..4 {
+        epoch_manager
+            .record_block_info(
+                block_info(
+                    h[i],
+                    i as u64,
+                    i as u64 - 1,
+                    h[i - 1],
+                    h[i - 1],
+                    h[1],
+                    vec![true],
+                    total_supply,
+                ),
+                rng_seed,
+            )
+            .unwrap()
+            .commit()
+            .unwrap();
+    }
+
+    let delegation = StakeDelegationView {
+        validator_id: "test1".parse().unwrap(),
+        amount: stake_amount / 2,
+        reward_fee_fraction: Ratio::new(1, 10),
+    };
+    let projection = epoch_manager.get_reward_projection(&h[3], 2, vec![delegation]).unwrap();
+    // Blocks are a second apart.
+    assert_eq!(projection.epoch_duration_ns, epoch_length * NUM_NS_IN_SECOND);
+
+    let validator_stats = HashMap::from([(
+        "test1".parse().unwrap(),
+        BlockChunkValidatorStats {
+            block_stats: ValidatorStats { produced: 3, expected: 3 },
+            chunk_stats: ValidatorStats { produced: 3, expected: 3 },
+        },
+    )]);
+    let expected = reward_calculator.project_rewards(
+        &validator_stats,
+        &HashMap::from([("test1".parse().unwrap(), stake_amount)]),
+        total_supply,
+        PROTOCOL_VERSION,
+        PROTOCOL_VERSION,
+        epoch_length * NUM_NS_IN_SECOND,
+        2,
+    );
+    let reward1 = expected[0].0["test1"];
+    let reward2 = expected[1].0["test1"];
+    assert!(reward2 > reward1);
+    assert_eq!(
+        projection.epochs.iter().map(|epoch| epoch.minted_amount).collect::<Vec<_>>(),
+        vec![expected[0].1, expected[1].1]
+    );
+    assert_eq!(projection.validators.len(), 1);
+    assert_eq!(
+        projection.validators[0].rewards,
+        vec![
+            StakeRewardView { reward: reward1, stake: stake_amount + reward1 },
+            StakeRewardView { reward: reward2, stake: stake_amount + reward1 + reward2 },
+        ]
+    );
+    // The delegation owns half of the pool and pays a tenth of its rewards.
+    let delegation_reward1 = reward1 * 9 / 10 / 2;
+    assert_eq!(projection.delegations[0].rewards[0].reward, delegation_reward1);
+    assert!(projection.delegations[0].apy < projection.validators[0].apy);
+
+    // Delegating more than the total supply is an error.
+    let delegation = StakeDelegationView {
+        validator_id: "test1".parse().unwrap(),
+        amount: total_supply + 1,
+        reward_fee_fraction: Ratio::new(0, 1),
+    };
+    assert!(matches!(
+        epoch_manager.get_reward_projection(&h[3], 2, vec![delegation]),
+        Err(EpochError::InvalidDelegation(_))
+    ));
+}
+
 #[test]
 fn test_validator_reward_weight_by_stake() {
     let stake_amount1 = 1_000_000;
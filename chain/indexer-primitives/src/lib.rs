@@ -32,6 +32,24 @@ pub struct IndexerExecutionOutcomeWithOptionalReceipt {
 pub struct IndexerExecutionOutcomeWithReceipt {
     pub execution_outcome: views::ExecutionOutcomeWithIdView,
     pub receipt: views::ReceiptView,
+    /// Events (NEP-297) parsed from the logs of the outcome.
+    #[serde(default)]
+    pub events: Vec<views::EventView>,
+}
+
+impl IndexerExecutionOutcomeWithReceipt {
+    pub fn new(
+        execution_outcome: views::ExecutionOutcomeWithIdView,
+        receipt: views::ReceiptView,
+    ) -> Self {
+        let events = execution_outcome
+            .outcome
+            .logs
+            .iter()
+            .filter_map(|log| views::EventView::from_log(log))
+            .collect();
+        Self { execution_outcome, receipt, events }
+    }
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
@@ -180,7 +180,7 @@ async fn build_streamer_message(
                 }
             };
             receipt_execution_outcomes
-                .push(IndexerExecutionOutcomeWithReceipt { execution_outcome, receipt: receipt });
+                .push(IndexerExecutionOutcomeWithReceipt::new(execution_outcome, receipt));
         }
 
         // Blocks #47317863 and #47317864
@@ -223,9 +223,11 @@ async fn build_streamer_message(
     // That might happen before the fix https://github.com/near/nearcore/pull/4228
     for (shard_id, outcomes) in shards_outcomes {
         indexer_shards[shard_id as usize].receipt_execution_outcomes.extend(
-            outcomes.into_iter().map(|outcome| IndexerExecutionOutcomeWithReceipt {
-                execution_outcome: outcome.execution_outcome,
-                receipt: outcome.receipt.expect("`receipt` must be present at this moment"),
+            outcomes.into_iter().map(|outcome| {
+                IndexerExecutionOutcomeWithReceipt::new(
+                    outcome.execution_outcome,
+                    outcome.receipt.expect("`receipt` must be present at this moment"),
+                )
             }),
         )
     }
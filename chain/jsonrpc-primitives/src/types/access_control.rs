@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Reason a request was rejected by the access control of the JSON RPC server.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "name", content = "info", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RpcAccessError {
+    #[error("The node requires an API key passed in the `x-api-key` header")]
+    MissingApiKey,
+    #[error("The API key is not known to the node")]
+    UnknownApiKey,
+    #[error("Method {method_name} is not allowed for the API key")]
+    MethodNotAllowed { method_name: String },
+    #[error("The request quota is exhausted. Try again later")]
+    QuotaExceeded {
+        /// Time after which the request would fit into the quota, if it ever does.
+        retry_after_ms: Option<u64>,
+    },
+}
+
+impl From<RpcAccessError> for crate::errors::RpcError {
+    fn from(error: RpcAccessError) -> Self {
+        let error_data = Some(Value::String(error.to_string()));
+
+        let error_data_value = match serde_json::to_value(error) {
+            Ok(value) => value,
+            Err(err) => {
+                return Self::new_internal_error(
+                    None,
+                    format!("Failed to serialize RpcAccessError: {:?}", err),
+                )
+            }
+        };
+
+        Self::new_internal_or_handler_error(error_data, error_data_value)
+    }
+}
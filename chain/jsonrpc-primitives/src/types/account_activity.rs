@@ -0,0 +1,55 @@
+use serde_json::Value;
+
+pub type RpcAccountActivityResponse = near_primitives::views::AccountActivityPageView;
+
+#[derive(thiserror::Error, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "name", content = "info", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RpcAccountActivityError {
+    #[error("Account activity index is not enabled on this node")]
+    IndexDisabled,
+    #[error("The node reached its limits. Try again later. More details: {error_message}")]
+    InternalError { error_message: String },
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct RpcAccountActivityRequest {
+    pub account_id: near_primitives::types::AccountId,
+    /// Height to start from (inclusive), the latest indexed block by default. Use
+    /// `next_block_height` of the previous response to get the next page.
+    #[serde(default)]
+    pub from_block_height: Option<near_primitives::types::BlockHeight>,
+    #[serde(default = "RpcAccountActivityRequest::default_limit")]
+    pub limit: usize,
+}
+
+impl RpcAccountActivityRequest {
+    /// Maximum number of entries which can be requested at once.
+    pub const MAX_LIMIT: usize = 1000;
+
+    fn default_limit() -> usize {
+        100
+    }
+}
+
+impl From<RpcAccountActivityError> for crate::errors::RpcError {
+    fn from(error: RpcAccountActivityError) -> Self {
+        let error_data = match &error {
+            RpcAccountActivityError::IndexDisabled => {
+                Some(Value::String("Account activity index is disabled".to_string()))
+            }
+            RpcAccountActivityError::InternalError { .. } => Some(Value::String(error.to_string())),
+        };
+
+        let error_data_value = match serde_json::to_value(error) {
+            Ok(value) => value,
+            Err(err) => {
+                return Self::new_internal_error(
+                    None,
+                    format!("Failed to serialize RpcAccountActivityError: {:?}", err),
+                )
+            }
+        };
+
+        Self::new_internal_or_handler_error(error_data, error_data_value)
+    }
+}
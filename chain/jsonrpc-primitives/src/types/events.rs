@@ -0,0 +1,65 @@
+use serde_json::Value;
+
+pub type RpcEventsResponse = near_primitives::views::ContractEventsPageView;
+
+#[derive(thiserror::Error, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "name", content = "info", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RpcEventsError {
+    #[error("Event index is not enabled on this node")]
+    IndexDisabled,
+    #[error("The node reached its limits. Try again later. More details: {error_message}")]
+    InternalError { error_message: String },
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct RpcEventsRequest {
+    /// Contract which emitted the events, any contract by default.
+    #[serde(default)]
+    pub contract_id: Option<near_primitives::types::AccountId>,
+    /// Standard of the events, e.g. `nep171`, any standard by default.
+    #[serde(default)]
+    pub standard: Option<String>,
+    /// Name of the events, e.g. `nft_mint`, any event by default.
+    #[serde(default)]
+    pub event: Option<String>,
+    /// First height to return events of (inclusive). Use `next_block_height` of the previous
+    /// response to get the next page.
+    pub from_block_height: near_primitives::types::BlockHeight,
+    /// Last height to return events of (inclusive), the head of the chain by default.
+    #[serde(default)]
+    pub to_block_height: Option<near_primitives::types::BlockHeight>,
+    #[serde(default = "RpcEventsRequest::default_limit")]
+    pub limit: usize,
+}
+
+impl RpcEventsRequest {
+    /// Maximum number of events which can be requested at once.
+    pub const MAX_LIMIT: usize = 1000;
+
+    fn default_limit() -> usize {
+        100
+    }
+}
+
+impl From<RpcEventsError> for crate::errors::RpcError {
+    fn from(error: RpcEventsError) -> Self {
+        let error_data = match &error {
+            RpcEventsError::IndexDisabled => {
+                Some(Value::String("Event index is disabled".to_string()))
+            }
+            RpcEventsError::InternalError { .. } => Some(Value::String(error.to_string())),
+        };
+
+        let error_data_value = match serde_json::to_value(error) {
+            Ok(value) => value,
+            Err(err) => {
+                return Self::new_internal_error(
+                    None,
+                    format!("Failed to serialize RpcEventsError: {:?}", err),
+                )
+            }
+        };
+
+        Self::new_internal_or_handler_error(error_data, error_data_value)
+    }
+}
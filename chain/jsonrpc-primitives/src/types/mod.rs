@@ -1,8 +1,11 @@
+pub mod access_control;
+pub mod account_activity;
 pub mod blocks;
 pub mod changes;
 pub mod chunks;
 pub mod client_config;
 pub mod config;
+pub mod events;
 pub mod gas_price;
 pub mod light_client;
 pub mod maintenance;
@@ -45,6 +45,13 @@ pub struct RpcTransactionResponse {
     pub final_execution_outcome: near_primitives::views::FinalExecutionOutcomeViewEnum,
 }
 
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct RpcTransactionTraceRequest {
+    pub tx_hash: near_primitives::hash::CryptoHash,
+}
+
+pub type RpcTransactionTraceResponse = near_primitives::views::TxTraceView;
+
 #[derive(serde::Serialize, serde::Deserialize, Debug)]
 pub struct RpcBroadcastTxSyncResponse {
     pub transaction_hash: near_primitives::hash::CryptoHash,
@@ -3,6 +3,8 @@ use serde_json::Value;
 pub type RpcValidatorsOrderedResponse =
     Vec<near_primitives::views::validator_stake_view::ValidatorStakeView>;
 
+pub type RpcRewardProjectionResponse = near_primitives::views::RewardProjectionView;
+
 #[derive(thiserror::Error, Debug, serde::Serialize, serde::Deserialize)]
 #[serde(tag = "name", content = "info", rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum RpcValidatorError {
@@ -10,6 +12,8 @@ pub enum RpcValidatorError {
     UnknownEpoch,
     #[error("Validator info unavailable")]
     ValidatorInfoUnavailable,
+    #[error("Invalid delegation: {error_message}")]
+    InvalidDelegation { error_message: String },
     #[error("The node reached its limits. Try again later. More details: {error_message}")]
     InternalError { error_message: String },
 }
@@ -25,6 +29,29 @@ pub struct RpcValidatorsOrderedRequest {
     pub block_id: near_primitives::types::MaybeBlockId,
 }
 
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct RpcRewardProjectionRequest {
+    /// Block to project from, the latest one by default.
+    #[serde(default)]
+    pub block_id: near_primitives::types::MaybeBlockId,
+    #[serde(default = "RpcRewardProjectionRequest::default_num_epochs")]
+    pub num_epochs: u64,
+    /// Stake delegated to staking pools to also project the rewards of.
+    #[serde(default)]
+    pub delegations: Vec<near_primitives::views::StakeDelegationView>,
+}
+
+impl RpcRewardProjectionRequest {
+    /// Maximum number of epochs rewards can be projected for.
+    pub const MAX_NUM_EPOCHS: u64 = 1000;
+    /// Maximum number of delegations a single request can project rewards for.
+    pub const MAX_NUM_DELEGATIONS: usize = 100;
+
+    fn default_num_epochs() -> u64 {
+        10
+    }
+}
+
 #[derive(serde::Serialize, serde::Deserialize, Debug)]
 pub struct RpcValidatorResponse {
     #[serde(flatten)]
@@ -38,6 +65,7 @@ impl From<RpcValidatorError> for crate::errors::RpcError {
             RpcValidatorError::ValidatorInfoUnavailable => {
                 Some(Value::String("Validator info unavailable".to_string()))
             }
+            RpcValidatorError::InvalidDelegation { .. } => Some(Value::String(error.to_string())),
             RpcValidatorError::InternalError { .. } => Some(Value::String(error.to_string())),
         };
 
@@ -13,6 +13,7 @@ use near_primitives::views::{
     BlockView, ChunkView, EpochValidatorInfo, FinalExecutionOutcomeView, GasPriceView,
     StatusResponse,
 };
+use std::collections::HashMap;
 use std::time::Duration;
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
@@ -67,6 +68,62 @@ where
         .boxed_local()
 }
 
+/// Prepare a `RPCRequest` sending a batch of `(method, params)` requests with a given client and
+/// server address. Results are returned in the order of the requests.
+fn call_batch(
+    client: &Client,
+    server_addr: &str,
+    requests: Vec<(String, serde_json::Value)>,
+) -> RpcRequest<Vec<Result<serde_json::Value, RpcError>>> {
+    let requests: Vec<Message> =
+        requests.into_iter().map(|(method, params)| Message::request(method, params)).collect();
+    let ids: Vec<_> = requests.iter().map(Message::id).collect();
+    client
+        .post(server_addr)
+        .insert_header(("Content-Type", "application/json"))
+        .send_json(&Message::Batch(requests))
+        .map_err(|err| RpcError::new_internal_error(None, format!("{:?}", err)))
+        .and_then(|mut response| {
+            response.body().limit(PAYLOAD_LIMIT).map(|body| match body {
+                Ok(bytes) => from_slice(&bytes).map_err(|err| {
+                    RpcError::parse_error(format!("Error {:?} in {:?}", err, bytes))
+                }),
+                Err(err) => {
+                    Err(RpcError::parse_error(format!("Failed to retrieve payload: {:?}", err)))
+                }
+            })
+        })
+        .and_then(move |message| {
+            future::ready(match message {
+                Message::Batch(messages) => {
+                    let mut results: HashMap<_, _> = messages
+                        .into_iter()
+                        .filter_map(|message| match message {
+                            Message::Response(resp) => Some((resp.id.to_string(), resp.result)),
+                            _ => None,
+                        })
+                        .collect();
+                    Ok(ids
+                        .iter()
+                        .map(|id| {
+                            results.remove(&id.to_string()).unwrap_or_else(|| {
+                                Err(RpcError::parse_error(format!(
+                                    "Missing JSON RPC response for request {}",
+                                    id
+                                )))
+                            })
+                        })
+                        .collect())
+                }
+                Message::Response(resp) => Err(resp.result.err().unwrap_or_else(|| {
+                    RpcError::parse_error("Failed to parse JSON RPC batch response".to_string())
+                })),
+                _ => Err(RpcError::parse_error("Failed to parse JSON RPC response".to_string())),
+            })
+        })
+        .boxed_local()
+}
+
 /// Prepare a `HttpRequest` with a given client, server address and parameters.
 fn call_http_get<R, P>(
     client: &Client,
@@ -222,6 +279,15 @@ impl JsonRpcClient {
         call_method(&self.client, &self.server_addr, "block", request)
     }
 
+    /// Sends `(method, params)` requests as a single JSON RPC batch. The server executes them
+    /// concurrently; results are returned in the order of the requests.
+    pub fn batch(
+        &self,
+        requests: Vec<(String, serde_json::Value)>,
+    ) -> RpcRequest<Vec<Result<serde_json::Value, RpcError>>> {
+        call_batch(&self.client, &self.server_addr, requests)
+    }
+
     #[allow(non_snake_case)]
     pub fn EXPERIMENTAL_changes(
         &self,
@@ -254,6 +320,14 @@ impl JsonRpcClient {
         call_method(&self.client, &self.server_addr, "EXPERIMENTAL_protocol_config", request)
     }
 
+    #[allow(non_snake_case)]
+    pub fn EXPERIMENTAL_reward_projection(
+        &self,
+        request: near_jsonrpc_primitives::types::validator::RpcRewardProjectionRequest,
+    ) -> RpcRequest<near_jsonrpc_primitives::types::validator::RpcRewardProjectionResponse> {
+        call_method(&self.client, &self.server_addr, "EXPERIMENTAL_reward_projection", request)
+    }
+
     #[allow(non_snake_case)]
     pub fn EXPERIMENTAL_split_storage_info(
         &self,
@@ -262,6 +336,14 @@ impl JsonRpcClient {
     {
         call_method(&self.client, &self.server_addr, "EXPERIMENTAL_split_storage_info", request)
     }
+
+    #[allow(non_snake_case)]
+    pub fn EXPERIMENTAL_tx_trace(
+        &self,
+        request: near_jsonrpc_primitives::types::transactions::RpcTransactionTraceRequest,
+    ) -> RpcRequest<near_jsonrpc_primitives::types::transactions::RpcTransactionTraceResponse> {
+        call_method(&self.client, &self.server_addr, "EXPERIMENTAL_tx_trace", request)
+    }
 }
 
 fn create_client() -> Client {
@@ -26,6 +26,23 @@ pub fn start_all_with_validity_period_and_no_epoch_sync(
     node_type: NodeType,
     transaction_validity_period: NumBlocks,
     enable_doomslug: bool,
+) -> (Addr<ViewClientActor>, tcp::ListenerAddr) {
+    start_all_with_config(node_type, transaction_validity_period, enable_doomslug, |_| {})
+}
+
+/// Like `start_all`, but with the JSON RPC server config adjusted by `configure`.
+pub fn start_all_with_rpc_config(
+    node_type: NodeType,
+    configure: impl FnOnce(&mut RpcConfig),
+) -> (Addr<ViewClientActor>, tcp::ListenerAddr) {
+    start_all_with_config(node_type, 100, false, configure)
+}
+
+fn start_all_with_config(
+    node_type: NodeType,
+    transaction_validity_period: NumBlocks,
+    enable_doomslug: bool,
+    configure: impl FnOnce(&mut RpcConfig),
 ) -> (Addr<ViewClientActor>, tcp::ListenerAddr) {
     let actor_handles = setup_no_network_with_validity_period_and_no_epoch_sync(
         vec!["test1".parse().unwrap(), "test2".parse().unwrap()],
@@ -40,8 +57,10 @@ pub fn start_all_with_validity_period_and_no_epoch_sync(
     );
 
     let addr = tcp::ListenerAddr::reserve_for_test();
+    let mut rpc_config = RpcConfig::new(addr);
+    configure(&mut rpc_config);
     start_http(
-        RpcConfig::new(addr),
+        rpc_config,
         TEST_GENESIS_CONFIG.clone(),
         actor_handles.client_actor,
         actor_handles.view_client_actor.clone(),
@@ -1,5 +1,6 @@
 use std::ops::ControlFlow;
 use std::str::FromStr;
+use std::time::Duration;
 
 use actix::System;
 use futures::{future, FutureExt};
@@ -8,6 +9,9 @@ use serde_json::json;
 use near_actix_test_utils::run_actix;
 use near_crypto::{KeyType, PublicKey, Signature};
 use near_jsonrpc::client::{new_client, ChunkId};
+use near_jsonrpc::{RpcAccessControlConfig, RpcQuotaConfig};
+use near_jsonrpc_primitives::errors::RpcErrorKind;
+use near_jsonrpc_primitives::types::access_control::RpcAccessError;
 use near_jsonrpc_primitives::types::query::QueryResponseKind;
 use near_jsonrpc_primitives::types::validator::RpcValidatorsOrderedRequest;
 use near_network::test_utils::wait_or_timeout;
@@ -15,7 +19,7 @@ use near_o11y::testonly::init_test_logger;
 use near_primitives::account::{AccessKey, AccessKeyPermission};
 use near_primitives::hash::CryptoHash;
 use near_primitives::types::{BlockId, BlockReference, EpochId, SyncCheckpoint};
-use near_primitives::views::QueryRequest;
+use near_primitives::views::{BlockView, QueryRequest};
 
 use near_jsonrpc_tests::{self as test_utils, test_with_client};
 
@@ -84,6 +88,107 @@ fn test_block_query() {
     });
 }
 
+/// Retrieve several blocks and an unknown method in a single JSON RPC batch, and check the
+/// limits applied to batches.
+#[test]
+fn test_batch() {
+    init_test_logger();
+    run_actix(async {
+        let (_, addr) =
+            test_utils::start_all_with_rpc_config(test_utils::NodeType::NonValidator, |config| {
+                config.batch_config.max_batch_size = 3;
+                config.access_control = Some(RpcAccessControlConfig {
+                    ip_quota: Some(RpcQuotaConfig { burst: 7, per_second: 0 }),
+                    ..Default::default()
+                });
+            });
+        let (_, timeout_addr) =
+            test_utils::start_all_with_rpc_config(test_utils::NodeType::NonValidator, |config| {
+                config.batch_config.timeout = Duration::ZERO
+            });
+        let server_addr = format!("http://{}", addr);
+        let client = new_client(&server_addr);
+        let timeout_client = new_client(&format!("http://{}", timeout_addr));
+
+        actix::spawn(async move {
+            let results = client
+                .batch(vec![
+                    ("block".to_string(), json!({ "block_id": 0 })),
+                    ("block".to_string(), json!({ "finality": "optimistic" })),
+                    ("unknown_method".to_string(), json!({})),
+                ])
+                .await
+                .unwrap();
+            assert_eq!(results.len(), 3);
+            for result in &results[..2] {
+                let block: BlockView = serde_json::from_value(result.clone().unwrap()).unwrap();
+                assert_eq!(block.header.height, 0);
+            }
+            assert!(results[2].is_err());
+
+            // Batches over the maximum size are rejected as a whole and aren't charged.
+            let err = client
+                .batch(vec![("block".to_string(), json!({ "block_id": 0 })); 4])
+                .await
+                .unwrap_err();
+            assert_eq!(err.code, -32_600);
+
+            // Notifications are processed and charged, but aren't answered.
+            let notification = json!({
+                "jsonrpc": "2.0",
+                "method": "block",
+                "params": { "block_id": 0 },
+            });
+            let request = json!({
+                "jsonrpc": "2.0",
+                "method": "block",
+                "params": { "block_id": 0 },
+                "id": "request",
+            });
+            let http_client = awc::Client::new();
+            let mut response = http_client
+                .post(server_addr.as_str())
+                .send_json(&json!([notification, request]))
+                .await
+                .unwrap();
+            let responses: Vec<serde_json::Value> = response.json().await.unwrap();
+            assert_eq!(responses.len(), 1);
+            assert_eq!(responses[0]["id"], "request");
+            assert_eq!(responses[0]["result"]["header"]["height"], 0);
+            let response = http_client
+                .post(server_addr.as_str())
+                .send_json(&json!([notification]))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), awc::http::StatusCode::NO_CONTENT);
+
+            // Each request of a batch is charged separately, so only the requests within the
+            // remaining quota succeed.
+            let results = client
+                .batch(vec![("block".to_string(), json!({ "block_id": 0 })); 2])
+                .await
+                .unwrap();
+            assert!(results[0].is_ok());
+            assert_eq!(
+                results[1].as_ref().unwrap_err().error_struct,
+                Some(RpcErrorKind::HandlerError(
+                    serde_json::to_value(RpcAccessError::QuotaExceeded { retry_after_ms: None })
+                        .unwrap()
+                ))
+            );
+
+            // Requests which don't finish before the batch timeout are answered with an error.
+            let results = timeout_client
+                .batch(vec![("block".to_string(), json!({ "block_id": 0 }))])
+                .await
+                .unwrap();
+            assert_eq!(results[0].as_ref().unwrap_err().data, Some(json!("Timeout")));
+
+            System::current().stop();
+        });
+    });
+}
+
 /// Retrieve chunk via json rpc
 #[test]
 fn test_chunk_by_hash() {
@@ -324,6 +429,7 @@ fn test_query_call_function() {
                     account_id: "test".parse().unwrap(),
                     method_name: "method".to_string(),
                     args: vec![].into(),
+                    state_overrides: None,
                 },
             })
             .await
@@ -7,6 +7,7 @@ use futures::{future, FutureExt, TryFutureExt};
 use near_actix_test_utils::run_actix;
 use near_crypto::{InMemorySigner, KeyType};
 use near_jsonrpc::client::new_client;
+use near_jsonrpc_primitives::types::transactions::RpcTransactionTraceRequest;
 use near_network::test_utils::WaitOrTimeoutActor;
 use near_o11y::testonly::{init_integration_logger, init_test_logger};
 use near_primitives::hash::{hash, CryptoHash};
@@ -97,6 +98,45 @@ fn test_send_tx_commit() {
     });
 }
 
+/// Test that the trace of a committed transaction points at where it and its receipts executed.
+#[test]
+fn test_tx_trace() {
+    test_with_client!(test_utils::NodeType::Validator, client, async move {
+        let block_hash = client.block(BlockReference::latest()).await.unwrap().header.hash;
+        let signer = InMemorySigner::from_seed("test1".parse().unwrap(), KeyType::ED25519, "test1");
+        let tx = SignedTransaction::send_money(
+            1,
+            "test1".parse().unwrap(),
+            "test2".parse().unwrap(),
+            &signer,
+            100,
+            block_hash,
+        );
+        let bytes = tx.try_to_vec().unwrap();
+        let result = client.broadcast_tx_commit(to_base64(&bytes)).await.unwrap();
+        let trace = client
+            .EXPERIMENTAL_tx_trace(RpcTransactionTraceRequest { tx_hash: tx.get_hash() })
+            .await
+            .unwrap();
+        assert_eq!(trace.transaction.hash, tx.get_hash());
+        assert_eq!(trace.execution.block_hash, result.transaction_outcome.block_hash);
+        assert_eq!(trace.execution.outcome, result.transaction_outcome.outcome);
+        assert_eq!(trace.receipts.len(), result.transaction_outcome.outcome.receipt_ids.len());
+        // Every block has a chunk, so the transfer is executed in the next block and the refunds
+        // in the block after their parent.
+        let transfer = &trace.receipts[0];
+        assert_eq!(transfer.delay_blocks, Some(0));
+        for refund in &transfer.children {
+            assert!(refund.is_refund);
+            assert_eq!(refund.delay_blocks, Some(0));
+        }
+
+        let unknown =
+            client.EXPERIMENTAL_tx_trace(RpcTransactionTraceRequest { tx_hash: hash(&[1]) }).await;
+        assert!(unknown.is_err());
+    });
+}
+
 /// Test that expired transaction should be rejected
 #[test]
 fn test_expired_tx() {
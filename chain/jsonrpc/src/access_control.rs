@@ -0,0 +1,431 @@
+use crate::metrics;
+use actix_web::http::{header, StatusCode};
+use actix_web::{HttpRequest, HttpResponse};
+use lru::LruCache;
+use near_jsonrpc_primitives::errors::RpcError;
+use near_jsonrpc_primitives::message::{Message, Request};
+use near_jsonrpc_primitives::types::access_control::RpcAccessError;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::net::{IpAddr, Ipv6Addr};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Header in which clients pass their API key.
+pub(crate) const API_KEY_HEADER: &str = "x-api-key";
+
+/// Cost of methods not listed in `RpcAccessControlConfig::method_costs`.
+const DEFAULT_METHOD_COST: u64 = 1;
+
+/// Number of tracked quotas above which the quotas of the least recently seen clients are
+/// forgotten.
+const MAX_TRACKED_QUOTAS: usize = 100_000;
+
+/// Access control of the JSON RPC endpoint. Besides JSON RPC requests, it applies to the
+/// `GET /status`, `GET /network_info` and `GET /tier1_network_info` endpoints, which are checked
+/// as calls of the methods of the same name. `/health`, `/metrics` and the debug pages are not
+/// checked.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default)]
+pub struct RpcAccessControlConfig {
+    /// If true, requests without an API key are rejected.
+    #[serde(default)]
+    pub require_api_key: bool,
+    /// API keys accepted in the `x-api-key` header.
+    #[serde(default)]
+    pub api_keys: Vec<RpcApiKeyConfig>,
+    /// Quota of each client IP address, applied to requests without an API key. IPv6 addresses
+    /// of the same /64 network share a quota.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ip_quota: Option<RpcQuotaConfig>,
+    /// Cost of a request charged against the quota, by method name. Queries may also be priced
+    /// by their request type, e.g. `query.call_function`. Methods not listed cost 1.
+    #[serde(default)]
+    pub method_costs: HashMap<String, u64>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct RpcApiKeyConfig {
+    pub key: String,
+    /// Quota of the key. Requests made with the key are not limited if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quota: Option<RpcQuotaConfig>,
+    /// If set, only these methods may be called with the key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_methods: Option<Vec<String>>,
+    /// Methods which may not be called with the key.
+    #[serde(default)]
+    pub forbidden_methods: Vec<String>,
+}
+
+/// Token bucket quota, in units of method cost.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug)]
+pub struct RpcQuotaConfig {
+    /// Maximum cost of requests which may be made at once.
+    pub burst: u64,
+    /// Cost of requests replenished every second.
+    pub per_second: u64,
+}
+
+struct ApiKey {
+    quota: Option<RpcQuotaConfig>,
+    allowed_methods: Option<HashSet<String>>,
+    forbidden_methods: HashSet<String>,
+}
+
+impl ApiKey {
+    fn allows(&self, method: &str) -> bool {
+        !self.forbidden_methods.contains(method)
+            && self.allowed_methods.as_ref().map_or(true, |allowed| allowed.contains(method))
+    }
+}
+
+/// Identity of the client making a request, as seen by the access control.
+pub(crate) struct RpcCaller {
+    pub api_key: Option<String>,
+    pub ip: Option<IpAddr>,
+}
+
+impl RpcCaller {
+    pub(crate) fn new(req: &HttpRequest) -> Self {
+        let api_key = req
+            .headers()
+            .get(API_KEY_HEADER)
+            .map(|api_key| api_key.to_str().unwrap_or_default().to_string());
+        Self { api_key, ip: req.peer_addr().map(|addr| addr.ip()) }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum QuotaOwner {
+    ApiKey(String),
+    Ip(IpAddr),
+}
+
+impl QuotaOwner {
+    /// IPv6 clients usually get a whole /64 network, so the quota is shared by the network.
+    fn ip(ip: IpAddr) -> Self {
+        let ip = match ip {
+            IpAddr::V4(_) => ip,
+            IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+                Some(ip) => IpAddr::V4(ip),
+                None => {
+                    let [a, b, c, d, ..] = ip.segments();
+                    IpAddr::V6(Ipv6Addr::new(a, b, c, d, 0, 0, 0, 0))
+                }
+            },
+        };
+        QuotaOwner::Ip(ip)
+    }
+}
+
+struct TokenBucket {
+    quota: RpcQuotaConfig,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn new(quota: RpcQuotaConfig, now: Instant) -> Self {
+        Self { quota, tokens: quota.burst as f64, updated: now }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens =
+            (self.tokens + elapsed * self.quota.per_second as f64).min(self.quota.burst as f64);
+        self.updated = self.updated.max(now);
+    }
+
+    /// Takes `cost` tokens from the bucket, or returns the time after which they would be
+    /// available. Requests costing more than the burst take the whole bucket.
+    fn take(&mut self, cost: u64, now: Instant) -> Result<(), Option<Duration>> {
+        self.refill(now);
+        let cost = cost.min(self.quota.burst) as f64;
+        if self.tokens >= cost {
+            self.tokens -= cost;
+            return Ok(());
+        }
+        if self.quota.per_second == 0 {
+            return Err(None);
+        }
+        Err(Some(Duration::from_secs_f64((cost - self.tokens) / self.quota.per_second as f64)))
+    }
+}
+
+/// Authenticates JSON RPC requests and enforces the method allowlists and quotas of
+/// [`RpcAccessControlConfig`]. Shared by all workers of the server.
+pub(crate) struct RpcAccessControl {
+    require_api_key: bool,
+    api_keys: HashMap<String, ApiKey>,
+    ip_quota: Option<RpcQuotaConfig>,
+    method_costs: HashMap<String, u64>,
+    buckets: Mutex<LruCache<QuotaOwner, TokenBucket>>,
+}
+
+impl RpcAccessControl {
+    pub(crate) fn new(config: RpcAccessControlConfig) -> Self {
+        let api_keys = config
+            .api_keys
+            .into_iter()
+            .map(|api_key| {
+                let key = ApiKey {
+                    quota: api_key.quota,
+                    allowed_methods: api_key
+                        .allowed_methods
+                        .map(|methods| methods.into_iter().collect()),
+                    forbidden_methods: api_key.forbidden_methods.into_iter().collect(),
+                };
+                (api_key.key, key)
+            })
+            .collect();
+        Self {
+            require_api_key: config.require_api_key,
+            api_keys,
+            ip_quota: config.ip_quota,
+            method_costs: config.method_costs,
+            buckets: Mutex::new(LruCache::new(MAX_TRACKED_QUOTAS)),
+        }
+    }
+
+    /// Checks whether the client identified by its API key and IP address may call the method,
+    /// charging its cost against the client's quota if so.
+    pub(crate) fn check(
+        &self,
+        api_key: Option<&str>,
+        ip: Option<IpAddr>,
+        method: &str,
+        params: &Value,
+        now: Instant,
+    ) -> Result<(), RpcAccessError> {
+        let (owner, quota) = match api_key {
+            Some(key) => {
+                let api_key = self.api_keys.get(key).ok_or(RpcAccessError::UnknownApiKey)?;
+                if !api_key.allows(method) {
+                    return Err(RpcAccessError::MethodNotAllowed {
+                        method_name: method.to_string(),
+                    });
+                }
+                (QuotaOwner::ApiKey(key.to_string()), api_key.quota)
+            }
+            None if self.require_api_key => return Err(RpcAccessError::MissingApiKey),
+            None => match ip {
+                Some(ip) => (QuotaOwner::ip(ip), self.ip_quota),
+                None => return Ok(()),
+            },
+        };
+        let quota = match quota {
+            Some(quota) => quota,
+            None => return Ok(()),
+        };
+        let cost = self.method_cost(method, params);
+        let mut buckets = self.buckets.lock().unwrap();
+        let mut bucket = buckets.pop(&owner).unwrap_or_else(|| TokenBucket::new(quota, now));
+        let result = bucket.take(cost, now);
+        // Evicts the least recently charged bucket if too many are tracked.
+        buckets.put(owner, bucket);
+        result.map_err(|retry_after| RpcAccessError::QuotaExceeded {
+            retry_after_ms: retry_after.map(|retry_after| retry_after.as_millis() as u64),
+        })
+    }
+
+    fn method_cost(&self, method: &str, params: &Value) -> u64 {
+        let request_type = params.get("request_type").and_then(Value::as_str);
+        request_type
+            .and_then(|request_type| self.method_costs.get(&format!("{}.{}", method, request_type)))
+            .or_else(|| self.method_costs.get(method))
+            .copied()
+            .unwrap_or(DEFAULT_METHOD_COST)
+    }
+}
+
+/// Records the rejection of a request and converts the reason into an RPC error.
+pub(crate) fn reject(error: RpcAccessError) -> RpcError {
+    metrics::RPC_ACCESS_REJECTED_COUNT.with_label_values(&[rejection_reason(&error)]).inc();
+    error.into()
+}
+
+/// Builds the response to a request rejected by the access control. `request` is `None` for
+/// requests to the GET endpoints.
+pub(crate) fn rejection_response(request: Option<&Request>, error: RpcAccessError) -> HttpResponse {
+    let mut response = HttpResponse::build(status_code(&error));
+    if let RpcAccessError::QuotaExceeded { retry_after_ms: Some(retry_after_ms) } = &error {
+        let retry_after_secs = (retry_after_ms + 999) / 1000;
+        response.insert_header((header::RETRY_AFTER, retry_after_secs.to_string()));
+    }
+    let error = reject(error);
+    response.json(&match request {
+        Some(request) => request.error(error),
+        None => Message::error(error),
+    })
+}
+
+/// HTTP status of the response to a rejected request.
+fn status_code(error: &RpcAccessError) -> StatusCode {
+    match error {
+        RpcAccessError::MissingApiKey | RpcAccessError::UnknownApiKey => StatusCode::UNAUTHORIZED,
+        RpcAccessError::MethodNotAllowed { .. } => StatusCode::FORBIDDEN,
+        RpcAccessError::QuotaExceeded { .. } => StatusCode::TOO_MANY_REQUESTS,
+    }
+}
+
+/// Label of the rejection metric.
+fn rejection_reason(error: &RpcAccessError) -> &'static str {
+    match error {
+        RpcAccessError::MissingApiKey => "missing_api_key",
+        RpcAccessError::UnknownApiKey => "unknown_api_key",
+        RpcAccessError::MethodNotAllowed { .. } => "method_not_allowed",
+        RpcAccessError::QuotaExceeded { .. } => "quota_exceeded",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_jsonrpc_primitives::message::Message;
+    use serde_json::json;
+
+    fn request(method: &str, params: Value) -> Request {
+        match Message::request(method.to_string(), params) {
+            Message::Request(request) => request,
+            _ => unreachable!(),
+        }
+    }
+
+    impl RpcAccessControl {
+        fn check_request(
+            &self,
+            api_key: Option<&str>,
+            ip: Option<IpAddr>,
+            request: &Request,
+            now: Instant,
+        ) -> Result<(), RpcAccessError> {
+            self.check(api_key, ip, &request.method, &request.params, now)
+        }
+    }
+
+    fn api_key(key: &str) -> RpcApiKeyConfig {
+        RpcApiKeyConfig {
+            key: key.to_string(),
+            quota: None,
+            allowed_methods: None,
+            forbidden_methods: vec![],
+        }
+    }
+
+    #[test]
+    fn test_api_keys_and_allowlists() {
+        let access_control = RpcAccessControl::new(RpcAccessControlConfig {
+            require_api_key: true,
+            api_keys: vec![
+                RpcApiKeyConfig {
+                    forbidden_methods: vec!["broadcast_tx_commit".to_string()],
+                    ..api_key("forbidding")
+                },
+                RpcApiKeyConfig {
+                    allowed_methods: Some(vec!["status".to_string()]),
+                    ..api_key("allowing")
+                },
+            ],
+            ..Default::default()
+        });
+        let now = Instant::now();
+        let status = request("status", json!([]));
+        let commit = request("broadcast_tx_commit", json!([]));
+
+        assert_eq!(
+            access_control.check_request(None, None, &status, now),
+            Err(RpcAccessError::MissingApiKey)
+        );
+        assert_eq!(
+            access_control.check_request(Some("other"), None, &status, now),
+            Err(RpcAccessError::UnknownApiKey)
+        );
+        assert_eq!(access_control.check_request(Some("forbidding"), None, &status, now), Ok(()));
+        assert_eq!(
+            access_control.check_request(Some("forbidding"), None, &commit, now),
+            Err(RpcAccessError::MethodNotAllowed {
+                method_name: "broadcast_tx_commit".to_string()
+            })
+        );
+        assert_eq!(access_control.check_request(Some("allowing"), None, &status, now), Ok(()));
+        assert_eq!(
+            access_control.check_request(Some("allowing"), None, &commit, now),
+            Err(RpcAccessError::MethodNotAllowed {
+                method_name: "broadcast_tx_commit".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_quotas() {
+        let access_control = RpcAccessControl::new(RpcAccessControlConfig {
+            api_keys: vec![RpcApiKeyConfig {
+                quota: Some(RpcQuotaConfig { burst: 2, per_second: 1 }),
+                ..api_key("key")
+            }],
+            ip_quota: Some(RpcQuotaConfig { burst: 10, per_second: 10 }),
+            method_costs: HashMap::from([
+                ("query".to_string(), 2),
+                ("query.call_function".to_string(), 5),
+            ]),
+            ..Default::default()
+        });
+        let now = Instant::now();
+        let ip1: IpAddr = "127.0.0.1".parse().unwrap();
+        let ip2: IpAddr = "127.0.0.2".parse().unwrap();
+        let status = request("status", json!([]));
+        let view_account = request("query", json!({"request_type": "view_account"}));
+        let call_function = request("query", json!({"request_type": "call_function"}));
+
+        // Requests are weighted by the cost of the method and the request type.
+        assert_eq!(access_control.check_request(None, Some(ip1), &call_function, now), Ok(()));
+        assert_eq!(access_control.check_request(None, Some(ip1), &view_account, now), Ok(()));
+        assert_eq!(access_control.check_request(None, Some(ip1), &view_account, now), Ok(()));
+        assert_eq!(access_control.check_request(None, Some(ip1), &status, now), Ok(()));
+        assert_eq!(
+            access_control.check_request(None, Some(ip1), &view_account, now),
+            Err(RpcAccessError::QuotaExceeded { retry_after_ms: Some(200) })
+        );
+        // Other addresses have their own quotas, and quotas are replenished over time.
+        assert_eq!(access_control.check_request(None, Some(ip2), &call_function, now), Ok(()));
+        let later = now + Duration::from_millis(200);
+        assert_eq!(access_control.check_request(None, Some(ip1), &view_account, later), Ok(()));
+
+        // Requests with an API key are charged against the quota of the key. Requests costing
+        // more than the burst drain the whole bucket.
+        assert_eq!(
+            access_control.check_request(Some("key"), Some(ip1), &call_function, now),
+            Ok(())
+        );
+        assert_eq!(
+            access_control.check_request(Some("key"), Some(ip2), &status, now),
+            Err(RpcAccessError::QuotaExceeded { retry_after_ms: Some(1000) })
+        );
+    }
+
+    #[test]
+    fn test_ip_quota_owners() {
+        let access_control = RpcAccessControl::new(RpcAccessControlConfig {
+            ip_quota: Some(RpcQuotaConfig { burst: 1, per_second: 0 }),
+            ..Default::default()
+        });
+        let now = Instant::now();
+        let status = request("status", json!([]));
+        let ip = |ip: &str| Some(ip.parse::<IpAddr>().unwrap());
+
+        // Addresses of the same IPv6 /64 network share a quota.
+        assert_eq!(access_control.check_request(None, ip("2001:db8::1"), &status, now), Ok(()));
+        assert_eq!(
+            access_control.check_request(None, ip("2001:db8::2:3"), &status, now),
+            Err(RpcAccessError::QuotaExceeded { retry_after_ms: None })
+        );
+        assert_eq!(access_control.check_request(None, ip("2001:db8:0:1::1"), &status, now), Ok(()));
+        // IPv4 addresses mapped to IPv6 are treated as IPv4 addresses.
+        assert_eq!(access_control.check_request(None, ip("10.0.0.1"), &status, now), Ok(()));
+        assert_eq!(
+            access_control.check_request(None, ip("::ffff:10.0.0.1"), &status, now),
+            Err(RpcAccessError::QuotaExceeded { retry_after_ms: None })
+        );
+        assert_eq!(access_control.check_request(None, ip("::ffff:10.0.0.2"), &status, now), Ok(()));
+    }
+}
@@ -0,0 +1,40 @@
+use serde_json::Value;
+
+use near_client_primitives::types::GetAccountActivityError;
+use near_jsonrpc_primitives::errors::RpcParseError;
+use near_jsonrpc_primitives::types::account_activity::{
+    RpcAccountActivityError, RpcAccountActivityRequest,
+};
+
+use super::{Params, RpcFrom, RpcRequest};
+
+impl RpcRequest for RpcAccountActivityRequest {
+    fn parse(value: Value) -> Result<Self, RpcParseError> {
+        let request: Self = Params::parse(value)?;
+        if request.limit == 0 || request.limit > Self::MAX_LIMIT {
+            return Err(RpcParseError(format!("limit must be between 1 and {}", Self::MAX_LIMIT)));
+        }
+        Ok(request)
+    }
+}
+
+impl RpcFrom<actix::MailboxError> for RpcAccountActivityError {
+    fn rpc_from(error: actix::MailboxError) -> Self {
+        Self::InternalError { error_message: error.to_string() }
+    }
+}
+
+impl RpcFrom<GetAccountActivityError> for RpcAccountActivityError {
+    fn rpc_from(error: GetAccountActivityError) -> Self {
+        match error {
+            GetAccountActivityError::IndexDisabled => Self::IndexDisabled,
+            GetAccountActivityError::IOError(error_message) => {
+                Self::InternalError { error_message }
+            }
+            GetAccountActivityError::Unreachable(ref error_message) => {
+                tracing::warn!(target: "jsonrpc", "Unreachable error occurred: {}", error_message);
+                Self::InternalError { error_message: error.to_string() }
+            }
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use serde_json::Value;
+
+use near_client_primitives::types::GetEventsError;
+use near_jsonrpc_primitives::errors::RpcParseError;
+use near_jsonrpc_primitives::types::events::{RpcEventsError, RpcEventsRequest};
+
+use super::{Params, RpcFrom, RpcRequest};
+
+impl RpcRequest for RpcEventsRequest {
+    fn parse(value: Value) -> Result<Self, RpcParseError> {
+        let request: Self = Params::parse(value)?;
+        if request.limit == 0 || request.limit > Self::MAX_LIMIT {
+            return Err(RpcParseError(format!("limit must be between 1 and {}", Self::MAX_LIMIT)));
+        }
+        if request.to_block_height.map_or(false, |to| to < request.from_block_height) {
+            return Err(RpcParseError(
+                "to_block_height must not be lower than from_block_height".to_string(),
+            ));
+        }
+        Ok(request)
+    }
+}
+
+impl RpcFrom<actix::MailboxError> for RpcEventsError {
+    fn rpc_from(error: actix::MailboxError) -> Self {
+        Self::InternalError { error_message: error.to_string() }
+    }
+}
+
+impl RpcFrom<GetEventsError> for RpcEventsError {
+    fn rpc_from(error: GetEventsError) -> Self {
+        match error {
+            GetEventsError::IndexDisabled => Self::IndexDisabled,
+            GetEventsError::IOError(error_message) => Self::InternalError { error_message },
+            GetEventsError::Unreachable(ref error_message) => {
+                tracing::warn!(target: "jsonrpc", "Unreachable error occurred: {}", error_message);
+                Self::InternalError { error_message: error.to_string() }
+            }
+        }
+    }
+}
@@ -3,11 +3,13 @@ use serde_json::Value;
 use near_jsonrpc_primitives::errors::RpcParseError;
 use near_jsonrpc_primitives::errors::{RpcError, ServerError};
 
+mod account_activity;
 mod blocks;
 mod changes;
 mod chunks;
 mod client_config;
 mod config;
+mod events;
 mod gas_price;
 mod light_client;
 mod maintenance;
@@ -77,6 +77,7 @@ fn parse_path_data(path: String, data: String) -> Result<RpcQueryRequest, RpcPar
                 account_id,
                 method_name: method_name.to_string(),
                 args: parse_data()?.into(),
+                state_overrides: None,
             },
             None => return Err(RpcParseError("Method name is missing".to_string())),
         },
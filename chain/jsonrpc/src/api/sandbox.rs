@@ -2,8 +2,10 @@ use serde_json::Value;
 
 use near_jsonrpc_primitives::errors::RpcParseError;
 use near_jsonrpc_primitives::types::sandbox::{
-    RpcSandboxFastForwardError, RpcSandboxFastForwardRequest, RpcSandboxPatchStateError,
-    RpcSandboxPatchStateRequest,
+    RpcSandboxFastForwardError, RpcSandboxFastForwardRequest, RpcSandboxFreezeTimeError,
+    RpcSandboxFreezeTimeRequest, RpcSandboxPatchStateError, RpcSandboxPatchStateRequest,
+    RpcSandboxRestoreError, RpcSandboxRestoreRequest, RpcSandboxSendTxAsRequest,
+    RpcSandboxSetTimeError, RpcSandboxSetTimeRequest, RpcSandboxSnapshotError,
 };
 
 use super::{Params, RpcFrom, RpcRequest};
@@ -20,6 +22,30 @@ impl RpcRequest for RpcSandboxFastForwardRequest {
     }
 }
 
+impl RpcRequest for RpcSandboxRestoreRequest {
+    fn parse(value: Value) -> Result<Self, RpcParseError> {
+        Params::parse(value)
+    }
+}
+
+impl RpcRequest for RpcSandboxSetTimeRequest {
+    fn parse(value: Value) -> Result<Self, RpcParseError> {
+        Params::parse(value)
+    }
+}
+
+impl RpcRequest for RpcSandboxFreezeTimeRequest {
+    fn parse(value: Value) -> Result<Self, RpcParseError> {
+        Params::parse(value)
+    }
+}
+
+impl RpcRequest for RpcSandboxSendTxAsRequest {
+    fn parse(value: Value) -> Result<Self, RpcParseError> {
+        Params::parse(value)
+    }
+}
+
 impl RpcFrom<actix::MailboxError> for RpcSandboxPatchStateError {
     fn rpc_from(error: actix::MailboxError) -> Self {
         Self::InternalError { error_message: error.to_string() }
@@ -31,3 +57,27 @@ impl RpcFrom<actix::MailboxError> for RpcSandboxFastForwardError {
         Self::InternalError { error_message: error.to_string() }
     }
 }
+
+impl RpcFrom<actix::MailboxError> for RpcSandboxSnapshotError {
+    fn rpc_from(error: actix::MailboxError) -> Self {
+        Self::InternalError { error_message: error.to_string() }
+    }
+}
+
+impl RpcFrom<actix::MailboxError> for RpcSandboxRestoreError {
+    fn rpc_from(error: actix::MailboxError) -> Self {
+        Self::InternalError { error_message: error.to_string() }
+    }
+}
+
+impl RpcFrom<actix::MailboxError> for RpcSandboxSetTimeError {
+    fn rpc_from(error: actix::MailboxError) -> Self {
+        Self::InternalError { error_message: error.to_string() }
+    }
+}
+
+impl RpcFrom<actix::MailboxError> for RpcSandboxFreezeTimeError {
+    fn rpc_from(error: actix::MailboxError) -> Self {
+        Self::InternalError { error_message: error.to_string() }
+    }
+}
@@ -1,10 +1,10 @@
 use serde_json::Value;
 
-use near_client_primitives::types::TxStatusError;
+use near_client_primitives::types::{GetTransactionTraceError, TxStatusError};
 use near_jsonrpc_primitives::errors::RpcParseError;
 use near_jsonrpc_primitives::types::transactions::{
     RpcBroadcastTransactionRequest, RpcTransactionError, RpcTransactionResponse,
-    RpcTransactionStatusCommonRequest, TransactionInfo,
+    RpcTransactionStatusCommonRequest, RpcTransactionTraceRequest, TransactionInfo,
 };
 use near_primitives::borsh::BorshDeserialize;
 use near_primitives::serialize::Base64Bytes;
@@ -31,6 +31,12 @@ impl RpcRequest for RpcTransactionStatusCommonRequest {
     }
 }
 
+impl RpcRequest for RpcTransactionTraceRequest {
+    fn parse(value: Value) -> Result<Self, RpcParseError> {
+        Params::new(value).try_singleton(|tx_hash| Ok(Self { tx_hash })).unwrap_or_parse()
+    }
+}
+
 impl RpcFrom<actix::MailboxError> for RpcTransactionError {
     fn rpc_from(error: actix::MailboxError) -> Self {
         Self::InternalError { debug_info: error.to_string() }
@@ -52,6 +58,21 @@ impl RpcFrom<TxStatusError> for RpcTransactionError {
     }
 }
 
+impl RpcFrom<GetTransactionTraceError> for RpcTransactionError {
+    fn rpc_from(error: GetTransactionTraceError) -> Self {
+        match error {
+            GetTransactionTraceError::UnknownTransaction(requested_transaction_hash) => {
+                Self::UnknownTransaction { requested_transaction_hash }
+            }
+            GetTransactionTraceError::IOError(debug_info) => Self::InternalError { debug_info },
+            GetTransactionTraceError::Unreachable(ref debug_info) => {
+                tracing::warn!(target: "jsonrpc", "Unreachable error occurred: {}", debug_info);
+                Self::InternalError { debug_info: error.to_string() }
+            }
+        }
+    }
+}
+
 impl RpcFrom<FinalExecutionOutcomeViewEnum> for RpcTransactionResponse {
     fn rpc_from(final_execution_outcome: FinalExecutionOutcomeViewEnum) -> Self {
         Self { final_execution_outcome }
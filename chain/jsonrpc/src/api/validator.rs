@@ -3,7 +3,7 @@ use serde_json::Value;
 use near_client_primitives::types::GetValidatorInfoError;
 use near_jsonrpc_primitives::errors::RpcParseError;
 use near_jsonrpc_primitives::types::validator::{
-    RpcValidatorError, RpcValidatorRequest, RpcValidatorsOrderedRequest,
+    RpcRewardProjectionRequest, RpcValidatorError, RpcValidatorRequest, RpcValidatorsOrderedRequest,
 };
 use near_primitives::types::EpochReference;
 
@@ -27,6 +27,25 @@ impl RpcRequest for RpcValidatorsOrderedRequest {
     }
 }
 
+impl RpcRequest for RpcRewardProjectionRequest {
+    fn parse(value: Value) -> Result<Self, RpcParseError> {
+        let request: Self = Params::parse(value)?;
+        if request.num_epochs > Self::MAX_NUM_EPOCHS {
+            return Err(RpcParseError(format!(
+                "num_epochs must not exceed {}",
+                Self::MAX_NUM_EPOCHS
+            )));
+        }
+        if request.delegations.len() > Self::MAX_NUM_DELEGATIONS {
+            return Err(RpcParseError(format!(
+                "number of delegations must not exceed {}",
+                Self::MAX_NUM_DELEGATIONS
+            )));
+        }
+        Ok(request)
+    }
+}
+
 impl RpcFrom<actix::MailboxError> for RpcValidatorError {
     fn rpc_from(error: actix::MailboxError) -> Self {
         Self::InternalError { error_message: error.to_string() }
@@ -38,6 +57,9 @@ impl RpcFrom<GetValidatorInfoError> for RpcValidatorError {
         match error {
             GetValidatorInfoError::UnknownEpoch => Self::UnknownEpoch,
             GetValidatorInfoError::ValidatorInfoUnavailable => Self::ValidatorInfoUnavailable,
+            GetValidatorInfoError::InvalidDelegation(error_message) => {
+                Self::InvalidDelegation { error_message }
+            }
             GetValidatorInfoError::IOError(error_message) => Self::InternalError { error_message },
             GetValidatorInfoError::Unreachable(ref error_message) => {
                 tracing::warn!(target: "jsonrpc", "Unreachable error occurred: {}", error_message);
@@ -1,5 +1,6 @@
 #![doc = include_str!("../README.md")]
 
+use access_control::{RpcAccessControl, RpcCaller};
 use actix::{Addr, MailboxError};
 use actix_cors::Cors;
 use actix_web::http::header;
@@ -7,20 +8,22 @@ use actix_web::HttpRequest;
 use actix_web::{get, http, middleware, web, App, Error as HttpError, HttpResponse, HttpServer};
 use api::RpcRequest;
 pub use api::{RpcFrom, RpcInto};
-use futures::Future;
 use futures::FutureExt;
+use futures::{future, Future};
 use near_chain_configs::GenesisConfig;
 use near_client::{
-    ClientActor, DebugStatus, GetBlock, GetBlockProof, GetChunk, GetClientConfig,
-    GetExecutionOutcome, GetGasPrice, GetMaintenanceWindows, GetNetworkInfo,
-    GetNextLightClientBlock, GetProtocolConfig, GetReceipt, GetStateChanges,
-    GetStateChangesInBlock, GetValidatorInfo, GetValidatorOrdered, ProcessTxRequest,
-    ProcessTxResponse, Query, Status, TxStatus, ViewClientActor,
+    ClientActor, DebugStatus, GetAccountActivity, GetBlock, GetBlockProof, GetChunk,
+    GetClientConfig, GetEvents, GetExecutionOutcome, GetGasPrice, GetMaintenanceWindows,
+    GetNetworkInfo, GetNextLightClientBlock, GetProtocolConfig, GetReceipt, GetRewardProjection,
+    GetStateChanges, GetStateChangesInBlock, GetTransactionTrace, GetValidatorInfo,
+    GetValidatorOrdered, ProcessTxRequest, ProcessTxResponse, Query, Status, TxStatus,
+    ViewClientActor,
 };
 use near_client_primitives::types::GetSplitStorageInfo;
 pub use near_jsonrpc_client as client;
-use near_jsonrpc_primitives::errors::RpcError;
+use near_jsonrpc_primitives::errors::{RpcError, ServerError};
 use near_jsonrpc_primitives::message::{Message, Request};
+use near_jsonrpc_primitives::types::access_control::RpcAccessError;
 use near_jsonrpc_primitives::types::config::RpcProtocolConfigResponse;
 use near_jsonrpc_primitives::types::split_storage::RpcSplitStorageInfoResponse;
 use near_network::tcp;
@@ -33,13 +36,17 @@ use near_primitives::types::{AccountId, BlockHeight};
 use near_primitives::views::FinalExecutionOutcomeViewEnum;
 use serde_json::{json, Value};
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::time::{sleep, timeout};
+use tokio::time::{sleep, timeout, timeout_at};
 use tracing::info;
 
+mod access_control;
 mod api;
 mod metrics;
 
+pub use access_control::{RpcAccessControlConfig, RpcApiKeyConfig, RpcQuotaConfig};
+
 #[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug)]
 pub struct RpcPollingConfig {
     pub polling_interval: Duration,
@@ -55,6 +62,21 @@ impl Default for RpcPollingConfig {
     }
 }
 
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug)]
+pub struct RpcBatchConfig {
+    /// Maximum number of requests in a JSON RPC batch.
+    pub max_batch_size: usize,
+    /// Time after which the requests of a batch which haven't finished yet are answered with a
+    /// timeout error.
+    pub timeout: Duration,
+}
+
+impl Default for RpcBatchConfig {
+    fn default() -> Self {
+        Self { max_batch_size: 100, timeout: Duration::from_secs(30) }
+    }
+}
+
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
 pub struct RpcLimitsConfig {
     /// Maximum byte size of the json payload.
@@ -80,6 +102,8 @@ pub struct RpcConfig {
     pub polling_config: RpcPollingConfig,
     #[serde(default)]
     pub limits_config: RpcLimitsConfig,
+    #[serde(default)]
+    pub batch_config: RpcBatchConfig,
     // If true, enable some debug RPC endpoints (like one to get the latest block).
     // We disable it by default, as some of those endpoints might be quite CPU heavy.
     #[serde(default = "default_enable_debug_rpc")]
@@ -88,6 +112,9 @@ pub struct RpcConfig {
     // be read from this directory, instead of the contents compiled into the binary. This allows
     // for quick iterative development.
     pub experimental_debug_pages_src_path: Option<String>,
+    /// If set, requests are authenticated with API keys and limited by per-client quotas.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access_control: Option<RpcAccessControlConfig>,
 }
 
 impl Default for RpcConfig {
@@ -98,8 +125,10 @@ impl Default for RpcConfig {
             cors_allowed_origins: vec!["*".to_owned()],
             polling_config: Default::default(),
             limits_config: Default::default(),
+            batch_config: Default::default(),
             enable_debug_rpc: false,
             experimental_debug_pages_src_path: None,
+            access_control: None,
         }
     }
 }
@@ -225,18 +254,95 @@ struct JsonRpcHandler {
     genesis_config: GenesisConfig,
     enable_debug_rpc: bool,
     debug_pages_src_path: Option<PathBuf>,
+    access_control: Option<Arc<RpcAccessControl>>,
+    batch_config: RpcBatchConfig,
 }
 
 impl JsonRpcHandler {
-    pub async fn process(&self, message: Message) -> Result<Message, HttpError> {
+    /// Processes a single request or a batch of them. Access to single requests is expected to
+    /// be checked by the caller, while requests of a batch are checked one by one. Returns
+    /// `None` if there is nothing to respond with, i.e. for a batch of notifications.
+    pub async fn process(
+        &self,
+        message: Message,
+        caller: &RpcCaller,
+    ) -> Result<Option<Message>, HttpError> {
         let id = message.id();
         match message {
             Message::Request(request) => {
-                Ok(Message::response(id, self.process_request(request).await))
+                Ok(Some(Message::response(id, self.process_request(request).await)))
             }
-            _ => Ok(Message::error(RpcError::parse_error(
+            Message::Batch(messages) => Ok(self.process_batch(messages, caller).await),
+            _ => Ok(Some(Message::error(RpcError::parse_error(
                 "JSON RPC Request format was expected".to_owned(),
-            ))),
+            )))),
+        }
+    }
+
+    /// Processes requests of a batch concurrently. Requests which don't finish before the batch
+    /// timeout are answered with a timeout error. Notifications are processed as well but, as
+    /// required by JSON RPC, aren't answered.
+    async fn process_batch(&self, messages: Vec<Message>, caller: &RpcCaller) -> Option<Message> {
+        if messages.is_empty() || messages.len() > self.batch_config.max_batch_size {
+            return Some(Message::error(RpcError::new(
+                -32_600,
+                "Invalid Request".to_owned(),
+                Some(Value::String(format!(
+                    "JSON RPC batch must contain between 1 and {} requests",
+                    self.batch_config.max_batch_size
+                ))),
+            )));
+        }
+        metrics::RPC_BATCH_SIZE.observe(messages.len() as f64);
+        let deadline = tokio::time::Instant::now() + self.batch_config.timeout;
+        let responses = messages.into_iter().map(|message| async move {
+            let (request, respond) = match message {
+                Message::Request(request) => (request, true),
+                Message::Notification(notification) => (notification.into_request(), false),
+                _ => {
+                    return Some(Message::error(RpcError::parse_error(
+                        "JSON RPC Request format was expected".to_owned(),
+                    )))
+                }
+            };
+            let response = match self.check_access(caller, &request.method, &request.params) {
+                Ok(()) => {
+                    let id = request.id.clone();
+                    match timeout_at(deadline, self.process_request(request)).await {
+                        Ok(result) => Message::response(id, result),
+                        Err(_) => {
+                            metrics::RPC_TIMEOUT_TOTAL.inc();
+                            Message::response(id, Err(ServerError::Timeout.into()))
+                        }
+                    }
+                }
+                Err(err) => request.error(access_control::reject(err)),
+            };
+            respond.then_some(response)
+        });
+        let responses: Vec<_> = future::join_all(responses).await.into_iter().flatten().collect();
+        if responses.is_empty() {
+            None
+        } else {
+            Some(Message::Batch(responses))
+        }
+    }
+
+    fn check_access(
+        &self,
+        caller: &RpcCaller,
+        method: &str,
+        params: &Value,
+    ) -> Result<(), RpcAccessError> {
+        match &self.access_control {
+            Some(access_control) => access_control.check(
+                caller.api_key.as_deref(),
+                caller.ip,
+                method,
+                params,
+                Instant::now(),
+            ),
+            None => Ok(()),
         }
     }
 
@@ -319,6 +425,9 @@ impl JsonRpcHandler {
             "EXPERIMENTAL_broadcast_tx_sync" => {
                 process_method_call(request, |params| self.send_tx_sync(params)).await
             }
+            "EXPERIMENTAL_account_activity" => {
+                process_method_call(request, |params| self.account_activity(params)).await
+            }
             "EXPERIMENTAL_changes" => {
                 process_method_call(request, |params| self.changes_in_block_by_type(params)).await
             }
@@ -328,6 +437,9 @@ impl JsonRpcHandler {
             "EXPERIMENTAL_check_tx" => {
                 process_method_call(request, |params| self.check_tx(params)).await
             }
+            "EXPERIMENTAL_events" => {
+                process_method_call(request, |params| self.events(params)).await
+            }
             "EXPERIMENTAL_genesis_config" => {
                 process_method_call(request, |_params: ()| async {
                     Result::<_, std::convert::Infallible>::Ok(&self.genesis_config)
@@ -343,12 +455,18 @@ impl JsonRpcHandler {
             "EXPERIMENTAL_protocol_config" => {
                 process_method_call(request, |params| self.protocol_config(params)).await
             }
+            "EXPERIMENTAL_reward_projection" => {
+                process_method_call(request, |params| self.reward_projection(params)).await
+            }
             "EXPERIMENTAL_receipt" => {
                 process_method_call(request, |params| self.receipt(params)).await
             }
             "EXPERIMENTAL_tx_status" => {
                 process_method_call(request, |params| self.tx_status_common(params, true)).await
             }
+            "EXPERIMENTAL_tx_trace" => {
+                process_method_call(request, |params| self.tx_trace(params)).await
+            }
             "EXPERIMENTAL_validators_ordered" => {
                 process_method_call(request, |params| self.validators_ordered(params)).await
             }
@@ -366,6 +484,26 @@ impl JsonRpcHandler {
             "sandbox_fast_forward" => {
                 process_method_call(request, |params| self.sandbox_fast_forward(params)).await
             }
+            #[cfg(feature = "sandbox")]
+            "sandbox_snapshot" => {
+                process_method_call(request, |_params: ()| self.sandbox_snapshot()).await
+            }
+            #[cfg(feature = "sandbox")]
+            "sandbox_restore" => {
+                process_method_call(request, |params| self.sandbox_restore(params)).await
+            }
+            #[cfg(feature = "sandbox")]
+            "sandbox_set_time" => {
+                process_method_call(request, |params| self.sandbox_set_time(params)).await
+            }
+            #[cfg(feature = "sandbox")]
+            "sandbox_freeze_time" => {
+                process_method_call(request, |params| self.sandbox_freeze_time(params)).await
+            }
+            #[cfg(feature = "sandbox")]
+            "sandbox_send_tx_as" => {
+                process_method_call(request, |params| self.sandbox_send_tx_as(params)).await
+            }
             _ => Err(RpcError::method_not_found(request.method)),
         }
     }
@@ -444,12 +582,37 @@ impl JsonRpcHandler {
         }
     }
 
+    /// Views the signer and the receiver of the transaction, which makes a forked sandbox fetch
+    /// them from the upstream network if they are missing, so that they are there by the time the
+    /// client validates the transaction.
+    #[cfg(feature = "sandbox")]
+    async fn sandbox_fetch_tx_accounts(&self, tx: &SignedTransaction) {
+        for account_id in [&tx.transaction.signer_id, &tx.transaction.receiver_id] {
+            let request = near_primitives::views::QueryRequest::ViewAccount {
+                account_id: account_id.clone(),
+            };
+            let block_reference = near_primitives::types::BlockReference::Finality(
+                near_primitives::types::Finality::None,
+            );
+            // Missing accounts are reported by the client, the view only fetches them.
+            let _ = self
+                .view_client_addr
+                .send(Query::new(block_reference, request).with_span_context())
+                .await;
+        }
+    }
+
+    #[cfg(not(feature = "sandbox"))]
+    #[inline(always)]
+    async fn sandbox_fetch_tx_accounts(&self, _tx: &SignedTransaction) {}
+
     async fn send_tx_async(
         &self,
         request_data: near_jsonrpc_primitives::types::transactions::RpcBroadcastTransactionRequest,
     ) -> CryptoHash {
         let tx = request_data.signed_transaction;
         let hash = tx.get_hash();
+        self.sandbox_fetch_tx_accounts(&tx).await;
         self.client_addr.do_send(
             ProcessTxRequest {
                 transaction: tx,
@@ -615,6 +778,7 @@ impl JsonRpcHandler {
     {
         let tx_hash = tx.get_hash();
         let signer_account_id = tx.transaction.signer_id.clone();
+        self.sandbox_fetch_tx_accounts(&tx).await;
         let response = self
             .client_addr
             .send(
@@ -870,6 +1034,18 @@ impl JsonRpcHandler {
         Ok(query_response.rpc_into())
     }
 
+    /// Returns the tree of the receipts caused by the transaction.
+    async fn tx_trace(
+        &self,
+        request_data: near_jsonrpc_primitives::types::transactions::RpcTransactionTraceRequest,
+    ) -> Result<
+        near_jsonrpc_primitives::types::transactions::RpcTransactionTraceResponse,
+        near_jsonrpc_primitives::types::transactions::RpcTransactionError,
+    > {
+        let tx_hash = request_data.tx_hash;
+        Ok(self.view_client_send(GetTransactionTrace { tx_hash }).await?)
+    }
+
     async fn tx_status_common(
         &self,
         request_data: near_jsonrpc_primitives::types::transactions::RpcTransactionStatusCommonRequest,
@@ -1074,6 +1250,26 @@ impl JsonRpcHandler {
         Ok(validators)
     }
 
+    /// Projects rewards of the current validators and of the given delegations
+    /// for the following epochs.
+    async fn reward_projection(
+        &self,
+        request: near_jsonrpc_primitives::types::validator::RpcRewardProjectionRequest,
+    ) -> Result<
+        near_jsonrpc_primitives::types::validator::RpcRewardProjectionResponse,
+        near_jsonrpc_primitives::types::validator::RpcValidatorError,
+    > {
+        let near_jsonrpc_primitives::types::validator::RpcRewardProjectionRequest {
+            block_id,
+            num_epochs,
+            delegations,
+        } = request;
+        let projection = self
+            .view_client_send(GetRewardProjection { block_id, num_epochs, delegations })
+            .await?;
+        Ok(projection)
+    }
+
     /// If experimental_debug_pages_src_path config is set, reads the html file from that
     /// directory. Otherwise, returns None.
     fn read_html_file_override(&self, html_file: &'static str) -> Option<String> {
@@ -1102,6 +1298,52 @@ impl JsonRpcHandler {
         Ok(windows.iter().map(|r| (r.start, r.end)).collect())
     }
 
+    /// Returns a page of the transactions and receipts touching the account, newest first.
+    async fn account_activity(
+        &self,
+        request: near_jsonrpc_primitives::types::account_activity::RpcAccountActivityRequest,
+    ) -> Result<
+        near_jsonrpc_primitives::types::account_activity::RpcAccountActivityResponse,
+        near_jsonrpc_primitives::types::account_activity::RpcAccountActivityError,
+    > {
+        let near_jsonrpc_primitives::types::account_activity::RpcAccountActivityRequest {
+            account_id,
+            from_block_height,
+            limit,
+        } = request;
+        Ok(self
+            .view_client_send(GetAccountActivity { account_id, from_block_height, limit })
+            .await?)
+    }
+
+    /// Returns a page of the events (NEP-297) emitted by contracts, oldest first.
+    async fn events(
+        &self,
+        request: near_jsonrpc_primitives::types::events::RpcEventsRequest,
+    ) -> Result<
+        near_jsonrpc_primitives::types::events::RpcEventsResponse,
+        near_jsonrpc_primitives::types::events::RpcEventsError,
+    > {
+        let near_jsonrpc_primitives::types::events::RpcEventsRequest {
+            contract_id,
+            standard,
+            event,
+            from_block_height,
+            to_block_height,
+            limit,
+        } = request;
+        Ok(self
+            .view_client_send(GetEvents {
+                contract_id,
+                standard,
+                event,
+                from_block_height,
+                to_block_height,
+                limit,
+            })
+            .await?)
+    }
+
     async fn client_config(
         &self,
     ) -> Result<
@@ -1223,6 +1465,186 @@ impl JsonRpcHandler {
 
         Ok(near_jsonrpc_primitives::types::sandbox::RpcSandboxFastForwardResponse {})
     }
+
+    async fn sandbox_snapshot(
+        &self,
+    ) -> Result<
+        near_jsonrpc_primitives::types::sandbox::RpcSandboxSnapshotResponse,
+        near_jsonrpc_primitives::types::sandbox::RpcSandboxSnapshotError,
+    > {
+        use near_client_primitives::types::SandboxResponse;
+
+        let response = self
+            .client_addr
+            .send(
+                near_client_primitives::types::SandboxMessage::SandboxSnapshot.with_span_context(),
+            )
+            .await
+            .map_err(RpcFrom::rpc_from)?;
+        match response {
+            SandboxResponse::SandboxSnapshotTaken(snapshot_id) => {
+                Ok(near_jsonrpc_primitives::types::sandbox::RpcSandboxSnapshotResponse {
+                    snapshot_id,
+                })
+            }
+            SandboxResponse::SandboxSnapshotFailed(error_message) => Err(
+                near_jsonrpc_primitives::types::sandbox::RpcSandboxSnapshotError::InternalError {
+                    error_message,
+                },
+            ),
+            response => Err(
+                near_jsonrpc_primitives::types::sandbox::RpcSandboxSnapshotError::InternalError {
+                    error_message: format!("unexpected response: {:?}", response),
+                },
+            ),
+        }
+    }
+
+    async fn sandbox_restore(
+        &self,
+        restore_request: near_jsonrpc_primitives::types::sandbox::RpcSandboxRestoreRequest,
+    ) -> Result<
+        near_jsonrpc_primitives::types::sandbox::RpcSandboxRestoreResponse,
+        near_jsonrpc_primitives::types::sandbox::RpcSandboxRestoreError,
+    > {
+        use near_client_primitives::types::SandboxResponse;
+
+        let response = self
+            .client_addr
+            .send(
+                near_client_primitives::types::SandboxMessage::SandboxRestore(
+                    restore_request.snapshot_id,
+                )
+                .with_span_context(),
+            )
+            .await
+            .map_err(RpcFrom::rpc_from)?;
+        match response {
+            SandboxResponse::SandboxRestoreFinished => {
+                Ok(near_jsonrpc_primitives::types::sandbox::RpcSandboxRestoreResponse {})
+            }
+            SandboxResponse::SandboxRestoreFailed(error_message) => Err(
+                near_jsonrpc_primitives::types::sandbox::RpcSandboxRestoreError::InternalError {
+                    error_message,
+                },
+            ),
+            response => Err(
+                near_jsonrpc_primitives::types::sandbox::RpcSandboxRestoreError::InternalError {
+                    error_message: format!("unexpected response: {:?}", response),
+                },
+            ),
+        }
+    }
+
+    async fn sandbox_set_time(
+        &self,
+        set_time_request: near_jsonrpc_primitives::types::sandbox::RpcSandboxSetTimeRequest,
+    ) -> Result<
+        near_jsonrpc_primitives::types::sandbox::RpcSandboxSetTimeResponse,
+        near_jsonrpc_primitives::types::sandbox::RpcSandboxSetTimeError,
+    > {
+        use near_client_primitives::types::SandboxResponse;
+
+        let response = self
+            .client_addr
+            .send(
+                near_client_primitives::types::SandboxMessage::SandboxSetTime(
+                    set_time_request.timestamp_nanosec,
+                )
+                .with_span_context(),
+            )
+            .await
+            .map_err(RpcFrom::rpc_from)?;
+        match response {
+            SandboxResponse::SandboxSetTimeFinished => {
+                Ok(near_jsonrpc_primitives::types::sandbox::RpcSandboxSetTimeResponse {})
+            }
+            SandboxResponse::SandboxSetTimeFailed(error_message) => Err(
+                near_jsonrpc_primitives::types::sandbox::RpcSandboxSetTimeError::InternalError {
+                    error_message,
+                },
+            ),
+            response => Err(
+                near_jsonrpc_primitives::types::sandbox::RpcSandboxSetTimeError::InternalError {
+                    error_message: format!("unexpected response: {:?}", response),
+                },
+            ),
+        }
+    }
+
+    async fn sandbox_freeze_time(
+        &self,
+        freeze_time_request: near_jsonrpc_primitives::types::sandbox::RpcSandboxFreezeTimeRequest,
+    ) -> Result<
+        near_jsonrpc_primitives::types::sandbox::RpcSandboxFreezeTimeResponse,
+        near_jsonrpc_primitives::types::sandbox::RpcSandboxFreezeTimeError,
+    > {
+        use near_client_primitives::types::SandboxResponse;
+
+        let response = self
+            .client_addr
+            .send(
+                near_client_primitives::types::SandboxMessage::SandboxFreezeTime(
+                    freeze_time_request.frozen,
+                )
+                .with_span_context(),
+            )
+            .await
+            .map_err(RpcFrom::rpc_from)?;
+        match response {
+            SandboxResponse::SandboxFreezeTimeFinished => {
+                Ok(near_jsonrpc_primitives::types::sandbox::RpcSandboxFreezeTimeResponse {})
+            }
+            response => Err(
+                near_jsonrpc_primitives::types::sandbox::RpcSandboxFreezeTimeError::InternalError {
+                    error_message: format!("unexpected response: {:?}", response),
+                },
+            ),
+        }
+    }
+
+    async fn sandbox_send_tx_as(
+        &self,
+        send_tx_as_request: near_jsonrpc_primitives::types::sandbox::RpcSandboxSendTxAsRequest,
+    ) -> Result<
+        near_jsonrpc_primitives::types::transactions::RpcTransactionResponse,
+        near_jsonrpc_primitives::types::transactions::RpcTransactionError,
+    > {
+        use near_client_primitives::types::SandboxResponse;
+
+        let response = self
+            .client_addr
+            .send(
+                near_client_primitives::types::SandboxMessage::SandboxImpersonatedTransaction {
+                    signer_id: send_tx_as_request.signer_id,
+                    receiver_id: send_tx_as_request.receiver_id,
+                    actions: send_tx_as_request.actions,
+                }
+                .with_span_context(),
+            )
+            .await
+            .map_err(RpcFrom::rpc_from)?;
+        match response {
+            SandboxResponse::SandboxImpersonatedTransaction(signed_transaction) => {
+                self.send_tx_commit(
+                    near_jsonrpc_primitives::types::transactions::RpcBroadcastTransactionRequest {
+                        signed_transaction,
+                    },
+                )
+                .await
+            }
+            SandboxResponse::SandboxImpersonationFailed(debug_info) => Err(
+                near_jsonrpc_primitives::types::transactions::RpcTransactionError::InternalError {
+                    debug_info,
+                },
+            ),
+            response => Err(
+                near_jsonrpc_primitives::types::transactions::RpcTransactionError::InternalError {
+                    debug_info: format!("unexpected response: {:?}", response),
+                },
+            ),
+        }
+    }
 }
 
 #[cfg(feature = "test_features")]
@@ -1334,22 +1756,36 @@ impl JsonRpcHandler {
 }
 
 fn rpc_handler(
+    req: HttpRequest,
     message: web::Json<Message>,
     handler: web::Data<JsonRpcHandler>,
 ) -> impl Future<Output = Result<HttpResponse, HttpError>> {
+    let caller = RpcCaller::new(&req);
     let response = async move {
-        let message = handler.process(message.0).await?;
-        Ok(HttpResponse::Ok().json(&message))
+        if let Message::Request(request) = &message.0 {
+            if let Err(err) = handler.check_access(&caller, &request.method, &request.params) {
+                return Ok(access_control::rejection_response(Some(request), err));
+            }
+        }
+        match handler.process(message.0, &caller).await? {
+            Some(message) => Ok(HttpResponse::Ok().json(&message)),
+            None => Ok(HttpResponse::NoContent().finish()),
+        }
     };
     response.boxed()
 }
 
 fn status_handler(
+    req: HttpRequest,
     handler: web::Data<JsonRpcHandler>,
 ) -> impl Future<Output = Result<HttpResponse, HttpError>> {
     metrics::HTTP_STATUS_REQUEST_COUNT.inc();
 
+    let caller = RpcCaller::new(&req);
     let response = async move {
+        if let Err(err) = handler.check_access(&caller, "status", &Value::Null) {
+            return Ok(access_control::rejection_response(None, err));
+        }
         match handler.status().await {
             Ok(value) => Ok(HttpResponse::Ok().json(&value)),
             Err(_) => Ok(HttpResponse::ServiceUnavailable().finish()),
@@ -1401,9 +1837,14 @@ fn health_handler(
 }
 
 fn network_info_handler(
+    req: HttpRequest,
     handler: web::Data<JsonRpcHandler>,
 ) -> impl Future<Output = Result<HttpResponse, HttpError>> {
+    let caller = RpcCaller::new(&req);
     let response = async move {
+        if let Err(err) = handler.check_access(&caller, "network_info", &Value::Null) {
+            return Ok(access_control::rejection_response(None, err));
+        }
         match handler.network_info().await {
             Ok(value) => Ok(HttpResponse::Ok().json(&value)),
             Err(_) => Ok(HttpResponse::ServiceUnavailable().finish()),
@@ -1413,9 +1854,14 @@ fn network_info_handler(
 }
 
 fn tier1_network_info_handler(
+    req: HttpRequest,
     handler: web::Data<JsonRpcHandler>,
 ) -> impl Future<Output = Result<HttpResponse, HttpError>> {
+    let caller = RpcCaller::new(&req);
     let response = async move {
+        if let Err(err) = handler.check_access(&caller, "tier1_network_info", &Value::Null) {
+            return Ok(access_control::rejection_response(None, err));
+        }
         match handler.tier1_network_info().await {
             Ok(value) => Ok(HttpResponse::Ok().json(&value)),
             Err(_) => Ok(HttpResponse::ServiceUnavailable().finish()),
@@ -1459,6 +1905,7 @@ fn get_cors(cors_allowed_origins: &[String]) -> Cors {
     cors.allowed_methods(vec!["GET", "POST"])
         .allowed_headers(vec![http::header::AUTHORIZATION, http::header::ACCEPT])
         .allowed_header(http::header::CONTENT_TYPE)
+        .allowed_header(access_control::API_KEY_HEADER)
         .max_age(3600)
 }
 
@@ -1530,9 +1977,12 @@ pub fn start_http(
         cors_allowed_origins,
         polling_config,
         limits_config,
+        batch_config,
         enable_debug_rpc,
         experimental_debug_pages_src_path: debug_pages_src_path,
+        access_control,
     } = config;
+    let access_control = access_control.map(|config| Arc::new(RpcAccessControl::new(config)));
     let prometheus_addr = prometheus_addr.filter(|it| it != &addr.to_string());
     let cors_allowed_origins_clone = cors_allowed_origins.clone();
     info!(target:"network", "Starting http server at {}", addr);
@@ -1548,6 +1998,8 @@ pub fn start_http(
                 genesis_config: genesis_config.clone(),
                 enable_debug_rpc,
                 debug_pages_src_path: debug_pages_src_path.clone().map(Into::into),
+                access_control: access_control.clone(),
+                batch_config,
             }))
             .app_data(web::JsonConfig::default().limit(limits_config.json_payload_max_size))
             .wrap(middleware::Logger::default())
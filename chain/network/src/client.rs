@@ -6,6 +6,7 @@ use near_primitives::block::{Approval, Block, BlockHeader};
 use near_primitives::challenge::Challenge;
 use near_primitives::hash::CryptoHash;
 use near_primitives::network::{AnnounceAccount, PeerId};
+use near_primitives::syncing::{EpochSyncFinalizationResponse, EpochSyncResponse};
 use near_primitives::transaction::SignedTransaction;
 use near_primitives::types::{AccountId, EpochId, ShardId};
 use near_primitives::views::FinalExecutionOutcomeView;
@@ -56,6 +57,21 @@ pub trait Client: Send + Sync + 'static {
 
     async fn challenge(&self, challenge: Challenge);
 
+    async fn epoch_sync_request(&self, epoch_id: EpochId) -> Option<Box<EpochSyncResponse>>;
+
+    async fn epoch_sync_response(&self, response: Box<EpochSyncResponse>, peer_id: PeerId);
+
+    async fn epoch_sync_finalization_request(
+        &self,
+        block_hash: CryptoHash,
+    ) -> Option<Box<EpochSyncFinalizationResponse>>;
+
+    async fn epoch_sync_finalization_response(
+        &self,
+        response: Box<EpochSyncFinalizationResponse>,
+        peer_id: PeerId,
+    );
+
     async fn network_info(&self, info: NetworkInfo);
 
     async fn announce_account(
@@ -121,6 +137,26 @@ impl Client for Noop {
 
     async fn challenge(&self, _challenge: Challenge) {}
 
+    async fn epoch_sync_request(&self, _epoch_id: EpochId) -> Option<Box<EpochSyncResponse>> {
+        None
+    }
+
+    async fn epoch_sync_response(&self, _response: Box<EpochSyncResponse>, _peer_id: PeerId) {}
+
+    async fn epoch_sync_finalization_request(
+        &self,
+        _block_hash: CryptoHash,
+    ) -> Option<Box<EpochSyncFinalizationResponse>> {
+        None
+    }
+
+    async fn epoch_sync_finalization_response(
+        &self,
+        _response: Box<EpochSyncFinalizationResponse>,
+        _peer_id: PeerId,
+    ) {
+    }
+
     async fn network_info(&self, _info: NetworkInfo) {}
 
     async fn announce_account(
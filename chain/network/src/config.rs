@@ -54,6 +54,8 @@ pub enum ValidatorProxies {
 
 #[derive(Clone)]
 pub struct ValidatorConfig {
+    /// Signer the node starts with. Once the client switches to a rotated validator key, the
+    /// PeerManager uses the signer received in `SetValidatorSigner` instead.
     pub signer: Arc<dyn ValidatorSigner>,
     pub proxies: ValidatorProxies,
 }
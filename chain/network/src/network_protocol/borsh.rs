@@ -10,7 +10,9 @@ use near_primitives::block::{Block, BlockHeader, GenesisId};
 use near_primitives::challenge::Challenge;
 use near_primitives::hash::CryptoHash;
 use near_primitives::network::{AnnounceAccount, PeerId};
+use near_primitives::syncing::{EpochSyncFinalizationResponse, EpochSyncResponse};
 use near_primitives::transaction::SignedTransaction;
+use near_primitives::types::EpochId;
 use std::fmt;
 use std::fmt::Formatter;
 
@@ -134,10 +136,10 @@ pub(super) enum PeerMessage {
     Challenge(Challenge),
 
     _HandshakeV2,
-    _EpochSyncRequest,
-    _EpochSyncResponse,
-    _EpochSyncFinalizationRequest,
-    _EpochSyncFinalizationResponse,
+    EpochSyncRequest(EpochId),
+    EpochSyncResponse(Box<EpochSyncResponse>),
+    EpochSyncFinalizationRequest(CryptoHash),
+    EpochSyncFinalizationResponse(Box<EpochSyncFinalizationResponse>),
     _RoutingTableSyncV2,
 }
 #[cfg(target_arch = "x86_64")] // Non-x86_64 doesn't match this requirement yet but it's not bad as it's not production-ready
@@ -96,8 +96,6 @@ pub enum ParsePeerMessageError {
     DeprecatedHandshakeV2,
     #[error("RoutingTableSyncV2 is deprecated")]
     DeprecatedRoutingTableSyncV2,
-    #[error("EpochSync is deprecated")]
-    DeprecatedEpochSync,
     #[error("ResponseUpdateNonce is deprecated")]
     DeprecatedResponseUpdateNonce,
 }
@@ -142,13 +140,15 @@ impl TryFrom<&net::PeerMessage> for mem::PeerMessage {
             }),
             net::PeerMessage::Challenge(c) => mem::PeerMessage::Challenge(c),
             net::PeerMessage::_HandshakeV2 => return Err(Self::Error::DeprecatedHandshakeV2),
-            net::PeerMessage::_EpochSyncRequest => return Err(Self::Error::DeprecatedEpochSync),
-            net::PeerMessage::_EpochSyncResponse => return Err(Self::Error::DeprecatedEpochSync),
-            net::PeerMessage::_EpochSyncFinalizationRequest => {
-                return Err(Self::Error::DeprecatedEpochSync)
+            net::PeerMessage::EpochSyncRequest(epoch_id) => {
+                mem::PeerMessage::EpochSyncRequest(epoch_id)
             }
-            net::PeerMessage::_EpochSyncFinalizationResponse => {
-                return Err(Self::Error::DeprecatedEpochSync)
+            net::PeerMessage::EpochSyncResponse(r) => mem::PeerMessage::EpochSyncResponse(r),
+            net::PeerMessage::EpochSyncFinalizationRequest(bh) => {
+                mem::PeerMessage::EpochSyncFinalizationRequest(bh)
+            }
+            net::PeerMessage::EpochSyncFinalizationResponse(r) => {
+                mem::PeerMessage::EpochSyncFinalizationResponse(r)
             }
             net::PeerMessage::_RoutingTableSyncV2 => {
                 return Err(Self::Error::DeprecatedRoutingTableSyncV2)
@@ -192,6 +192,16 @@ impl From<&mem::PeerMessage> for net::PeerMessage {
             mem::PeerMessage::Routed(r) => net::PeerMessage::Routed(Box::new(r.msg.clone())),
             mem::PeerMessage::Disconnect(_) => net::PeerMessage::Disconnect,
             mem::PeerMessage::Challenge(c) => net::PeerMessage::Challenge(c),
+            mem::PeerMessage::EpochSyncRequest(epoch_id) => {
+                net::PeerMessage::EpochSyncRequest(epoch_id)
+            }
+            mem::PeerMessage::EpochSyncResponse(r) => net::PeerMessage::EpochSyncResponse(r),
+            mem::PeerMessage::EpochSyncFinalizationRequest(bh) => {
+                net::PeerMessage::EpochSyncFinalizationRequest(bh)
+            }
+            mem::PeerMessage::EpochSyncFinalizationResponse(r) => {
+                net::PeerMessage::EpochSyncFinalizationResponse(r)
+            }
         }
     }
 }
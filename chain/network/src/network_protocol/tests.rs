@@ -129,6 +129,9 @@ fn serialize_deserialize() -> anyhow::Result<()> {
         PeerMessage::Routed(routed_message2),
         PeerMessage::Disconnect(Disconnect { remove_from_connection_store: false }),
         PeerMessage::Challenge(data::make_challenge(&mut rng)),
+        PeerMessage::EpochSyncRequest(chain.blocks[5].header().epoch_id().clone()),
+        PeerMessage::EpochSyncResponse(Box::new(EpochSyncResponse::UpToDate)),
+        PeerMessage::EpochSyncFinalizationRequest(*chain.blocks[5].hash()),
     ];
 
     // Check that serialize;deserialize = 1
@@ -429,7 +429,7 @@ impl PeerActor {
                 archival: self.network_state.config.archive,
             },
             partial_edge_info: spec.partial_edge_info,
-            owned_account: self.network_state.config.validator.as_ref().map(|vc| {
+            owned_account: self.network_state.validator.load().as_ref().as_ref().map(|vc| {
                 OwnedAccount {
                     account_key: vc.signer.public_key(),
                     peer_id: self.network_state.config.node_id(),
@@ -1048,6 +1048,20 @@ impl PeerActor {
                     network_state.client.challenge(challenge).await;
                     None
                 }
+                PeerMessage::EpochSyncRequest(epoch_id) => {
+                    network_state.client.epoch_sync_request(epoch_id).await.map(PeerMessage::EpochSyncResponse)
+                }
+                PeerMessage::EpochSyncResponse(response) => {
+                    network_state.client.epoch_sync_response(response, peer_id).await;
+                    None
+                }
+                PeerMessage::EpochSyncFinalizationRequest(hash) => {
+                    network_state.client.epoch_sync_finalization_request(hash).await.map(PeerMessage::EpochSyncFinalizationResponse)
+                }
+                PeerMessage::EpochSyncFinalizationResponse(response) => {
+                    network_state.client.epoch_sync_finalization_response(response, peer_id).await;
+                    None
+                }
                 msg => {
                     tracing::error!(target: "network", "Peer received unexpected type: {:?}", msg);
                     None
@@ -28,6 +28,7 @@ use near_primitives::hash::CryptoHash;
 use near_primitives::network::PeerId;
 use near_primitives::time;
 use near_primitives::types::AccountId;
+use near_primitives::validator_signer::ValidatorSigner;
 use parking_lot::Mutex;
 use std::net::SocketAddr;
 use std::sync::atomic::AtomicUsize;
@@ -91,6 +92,9 @@ pub(crate) struct NetworkState {
     runtime: Runtime,
     /// PeerManager config.
     pub config: config::VerifiedConfig,
+    /// Validator config of this node, with the signer replaced once the client switches to a
+    /// rotated validator key. Use it instead of `config.validator`.
+    pub validator: ArcSwap<Option<config::ValidatorConfig>>,
     /// When network state has been constructed.
     pub created_at: time::Instant,
     /// GenesisId of the chain.
@@ -187,6 +191,7 @@ impl NetworkState {
             whitelist_nodes,
             add_edges_demux: demux::Demux::new(config.routing_table_update_rate_limit),
             set_chain_info_mutex: Mutex::new(()),
+            validator: ArcSwap::new(Arc::new(config.validator.clone())),
             config,
             created_at: clock.now(),
             tier1_advertise_proxies_mutex: tokio::sync::Mutex::new(()),
@@ -745,4 +750,18 @@ impl NetworkState {
         }
         has_changed
     }
+
+    /// Switches this node to a rotated validator key.
+    pub fn set_validator_signer(&self, signer: Arc<dyn ValidatorSigner>) {
+        let validator = self.validator.load();
+        let vc = match validator.as_ref() {
+            Some(vc) => vc,
+            None => {
+                tracing::warn!(target: "network", account_id = ?signer.validator_id(), "Ignoring the validator key of a node without a validator config");
+                return;
+            }
+        };
+        let vc = config::ValidatorConfig { signer, proxies: vc.proxies.clone() };
+        self.validator.store(Arc::new(Some(vc)));
+    }
 }
@@ -22,14 +22,16 @@ impl super::NetworkState {
     pub fn tier1_validator_config(
         &self,
         accounts_data: &accounts_data::CacheSnapshot,
-    ) -> Option<&config::ValidatorConfig> {
+    ) -> Option<config::ValidatorConfig> {
         if self.config.tier1.is_none() {
             return None;
         }
-        self.config
-            .validator
+        self.validator
+            .load()
+            .as_ref()
             .as_ref()
             .filter(|cfg| accounts_data.keys.contains(&cfg.signer.public_key()))
+            .cloned()
     }
 
     async fn tier1_connect_to_my_proxies(
@@ -271,7 +273,7 @@ impl super::NetworkState {
         // Construct a safe set of connections.
         let mut safe_set: HashSet<PeerId> = safe.values().map(|v| (*v).clone()).collect();
         // Add proxies of our node to the safe set.
-        if let Some(vc) = validator_cfg {
+        if let Some(vc) = &validator_cfg {
             match &vc.proxies {
                 config::ValidatorProxies::Dynamic(_) => {
                     safe_set.insert(self.config.node_id());
@@ -290,7 +292,7 @@ impl super::NetworkState {
                 conn.stop(None);
             }
         }
-        if let Some(vc) = validator_cfg {
+        if let Some(vc) = &validator_cfg {
             // Try to establish new TIER1 connections to accounts in random order.
             let mut handles = vec![];
             let mut account_keys: Vec<_> = proxies_by_account.keys().copied().collect();
@@ -16,7 +16,7 @@ use crate::tcp;
 use crate::types::{
     ConnectedPeerInfo, HighestHeightPeerInfo, KnownProducer, NetworkInfo, NetworkRequests,
     NetworkResponses, PeerInfo, PeerManagerMessageRequest, PeerManagerMessageResponse, PeerType,
-    SetChainInfo,
+    SetChainInfo, SetValidatorSigner,
 };
 use actix::fut::future::wrap_future;
 use actix::{Actor as _, AsyncContext as _};
@@ -789,6 +789,27 @@ impl PeerManagerActor {
                     NetworkResponses::RouteNotFound
                 }
             }
+            NetworkRequests::EpochSyncRequest { epoch_id, peer_id } => {
+                if self
+                    .state
+                    .tier2
+                    .send_message(peer_id, Arc::new(PeerMessage::EpochSyncRequest(epoch_id)))
+                {
+                    NetworkResponses::NoResponse
+                } else {
+                    NetworkResponses::RouteNotFound
+                }
+            }
+            NetworkRequests::EpochSyncFinalizationRequest { block_hash, peer_id } => {
+                if self.state.tier2.send_message(
+                    peer_id,
+                    Arc::new(PeerMessage::EpochSyncFinalizationRequest(block_hash)),
+                ) {
+                    NetworkResponses::NoResponse
+                } else {
+                    NetworkResponses::RouteNotFound
+                }
+            }
             NetworkRequests::StateRequestHeader { shard_id, sync_hash, target } => {
                 if self.send_message_to_account_or_peer_or_hash(
                     &target,
@@ -1028,6 +1049,28 @@ impl actix::Handler<WithSpanContext<SetChainInfo>> for PeerManagerActor {
     }
 }
 
+impl actix::Handler<WithSpanContext<SetValidatorSigner>> for PeerManagerActor {
+    type Result = ();
+    fn handle(&mut self, msg: WithSpanContext<SetValidatorSigner>, ctx: &mut Self::Context) {
+        let (_span, SetValidatorSigner(signer)) = handler_trace_span!(target: "network", msg);
+        let _timer = metrics::PEER_MANAGER_MESSAGES_TIME
+            .with_label_values(&["SetValidatorSigner"])
+            .start_timer();
+        self.state.set_validator_signer(signer);
+
+        let state = self.state.clone();
+        let clock = self.clock.clone();
+        ctx.spawn(wrap_future(
+            async move {
+                // Readvertise the proxies, so that AccountData signed with the new key replaces
+                // the data signed with the old one.
+                state.tier1_advertise_proxies(&clock).await;
+            }
+            .in_current_span(),
+        ));
+    }
+}
+
 impl actix::Handler<WithSpanContext<PeerManagerMessageRequest>> for PeerManagerActor {
     type Result = PeerManagerMessageResponse;
     fn handle(
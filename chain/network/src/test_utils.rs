@@ -1,7 +1,7 @@
 use crate::network_protocol::PeerInfo;
 use crate::types::{
     NetworkInfo, NetworkResponses, PeerManagerMessageRequest, PeerManagerMessageResponse,
-    SetChainInfo,
+    SetChainInfo, SetValidatorSigner,
 };
 use crate::PeerManagerActor;
 use actix::{Actor, ActorContext, Context, Handler};
@@ -14,6 +14,7 @@ use near_primitives::hash::hash;
 use near_primitives::network::PeerId;
 use near_primitives::types::EpochId;
 use near_primitives::utils::index_to_bytes;
+use near_primitives::validator_signer::ValidatorSigner;
 use rand::{thread_rng, RngCore};
 use std::collections::{HashMap, VecDeque};
 use std::ops::ControlFlow;
@@ -232,6 +233,8 @@ impl Handler<WithSpanContext<StopSignal>> for PeerManagerActor {
 pub struct MockPeerManagerAdapter {
     pub requests: Arc<RwLock<VecDeque<PeerManagerMessageRequest>>>,
     pub notify: Notify,
+    /// The signer from the last `SetValidatorSigner` message.
+    pub validator_signer: RwLock<Option<Arc<dyn ValidatorSigner>>>,
 }
 
 impl CanSendAsync<PeerManagerMessageRequest, Result<PeerManagerMessageResponse, ()>>
@@ -259,6 +262,12 @@ impl CanSend<SetChainInfo> for MockPeerManagerAdapter {
     fn send(&self, _msg: SetChainInfo) {}
 }
 
+impl CanSend<SetValidatorSigner> for MockPeerManagerAdapter {
+    fn send(&self, msg: SetValidatorSigner) {
+        *self.validator_signer.write().unwrap() = Some(msg.0);
+    }
+}
+
 impl MockPeerManagerAdapter {
     pub fn pop(&self) -> Option<PeerManagerMessageRequest> {
         self.requests.write().unwrap().pop_front()
@@ -9,7 +9,10 @@ use near_primitives::challenge::Challenge;
 use near_primitives::hash::CryptoHash;
 use near_primitives::network::{AnnounceAccount, PeerId};
 use near_primitives::sharding::{ChunkHash, PartialEncodedChunkPart};
-use near_primitives::syncing::{ShardStateSyncResponse, ShardStateSyncResponseV2};
+use near_primitives::syncing::{
+    EpochSyncFinalizationResponse, EpochSyncResponse, ShardStateSyncResponse,
+    ShardStateSyncResponseV2,
+};
 use near_primitives::transaction::SignedTransaction;
 use near_primitives::types::{AccountId, EpochId, ShardId};
 use near_primitives::views::FinalExecutionOutcomeView;
@@ -25,6 +28,8 @@ pub enum Event {
     Challenge(Challenge),
     Chunk(Vec<PartialEncodedChunkPart>),
     ChunkRequest(ChunkHash),
+    EpochSyncRequest(EpochId),
+    EpochSyncFinalizationRequest(CryptoHash),
     Transaction(SignedTransaction),
 }
 
@@ -108,6 +113,31 @@ impl client::Client for Fake {
         self.event_sink.push(Event::Challenge(challenge));
     }
 
+    async fn epoch_sync_request(&self, epoch_id: EpochId) -> Option<Box<EpochSyncResponse>> {
+        self.event_sink.push(Event::EpochSyncRequest(epoch_id));
+        None
+    }
+
+    async fn epoch_sync_response(&self, _response: Box<EpochSyncResponse>, _peer_id: PeerId) {
+        unimplemented!();
+    }
+
+    async fn epoch_sync_finalization_request(
+        &self,
+        block_hash: CryptoHash,
+    ) -> Option<Box<EpochSyncFinalizationResponse>> {
+        self.event_sink.push(Event::EpochSyncFinalizationRequest(block_hash));
+        None
+    }
+
+    async fn epoch_sync_finalization_response(
+        &self,
+        _response: Box<EpochSyncFinalizationResponse>,
+        _peer_id: PeerId,
+    ) {
+        unimplemented!();
+    }
+
     async fn network_info(&self, _info: NetworkInfo) {}
 
     async fn announce_account(
@@ -15,8 +15,9 @@ use near_primitives::network::{AnnounceAccount, PeerId};
 use near_primitives::sharding::PartialEncodedChunkWithArcReceipts;
 use near_primitives::time;
 use near_primitives::transaction::SignedTransaction;
-use near_primitives::types::BlockHeight;
 use near_primitives::types::{AccountId, ShardId};
+use near_primitives::types::{BlockHeight, EpochId};
+use near_primitives::validator_signer::ValidatorSigner;
 use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
 use std::net::SocketAddr;
@@ -153,6 +154,12 @@ pub struct ChainInfo {
 #[rtype(result = "()")]
 pub struct SetChainInfo(pub ChainInfo);
 
+/// Sent by the client when it switches to a rotated validator key, so that TIER1 data and
+/// handshakes get signed with the new key.
+#[derive(actix::Message)]
+#[rtype(result = "()")]
+pub struct SetValidatorSigner(pub Arc<dyn ValidatorSigner>);
+
 /// Public actix interface of `PeerManagerActor`.
 #[derive(actix::Message, Debug, strum::IntoStaticStr)]
 #[rtype(result = "PeerManagerMessageResponse")]
@@ -222,6 +229,10 @@ pub enum NetworkRequests {
     BlockRequest { hash: CryptoHash, peer_id: PeerId },
     /// Request given block headers.
     BlockHeadersRequest { hashes: Vec<CryptoHash>, peer_id: PeerId },
+    /// Request the light client block of the last final block of given epoch.
+    EpochSyncRequest { epoch_id: EpochId, peer_id: PeerId },
+    /// Request data to finish epoch sync after the last final block with given hash.
+    EpochSyncFinalizationRequest { block_hash: CryptoHash, peer_id: PeerId },
     /// Request state header for given shard at given state root.
     StateRequestHeader { shard_id: ShardId, sync_hash: CryptoHash, target: AccountOrPeerIdOrHash },
     /// Request state part for given shard at given state root.
@@ -374,12 +385,14 @@ pub struct PeerManagerAdapter {
         AsyncSender<PeerManagerMessageRequest, Result<PeerManagerMessageResponse, ()>>,
     pub request_sender: Sender<PeerManagerMessageRequest>,
     pub set_chain_info_sender: Sender<SetChainInfo>,
+    pub set_validator_signer_sender: Sender<SetValidatorSigner>,
 }
 
 impl<
         A: CanSendAsync<PeerManagerMessageRequest, Result<PeerManagerMessageResponse, ()>>
             + CanSend<PeerManagerMessageRequest>
-            + CanSend<SetChainInfo>,
+            + CanSend<SetChainInfo>
+            + CanSend<SetValidatorSigner>,
     > From<Arc<A>> for PeerManagerAdapter
 {
     fn from(arc: Arc<A>) -> Self {
@@ -387,6 +400,7 @@ impl<
             async_request_sender: arc.as_async_sender(),
             request_sender: arc.as_sender(),
             set_chain_info_sender: arc.as_sender(),
+            set_validator_signer_sender: arc.as_sender(),
         }
     }
 }
@@ -59,6 +59,64 @@ fn default_gc_fork_clean_step() -> u64 {
     GCConfig::default().gc_fork_clean_step
 }
 
+/// Configuration of the index of transactions and receipts touching each account.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, PartialEq)]
+pub struct AccountActivityIndexConfig {
+    /// Number of blocks for which index entries are kept.
+    #[serde(default = "default_account_activity_retention_blocks")]
+    pub retention_blocks: BlockHeightDelta,
+}
+
+impl Default for AccountActivityIndexConfig {
+    fn default() -> Self {
+        Self { retention_blocks: 1_000_000 }
+    }
+}
+
+fn default_account_activity_retention_blocks() -> BlockHeightDelta {
+    AccountActivityIndexConfig::default().retention_blocks
+}
+
+/// Configuration of the index of events (NEP-297) emitted by contracts.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, PartialEq)]
+pub struct EventIndexConfig {
+    /// Number of blocks for which events are kept.
+    #[serde(default = "default_event_retention_blocks")]
+    pub retention_blocks: BlockHeightDelta,
+}
+
+impl Default for EventIndexConfig {
+    fn default() -> Self {
+        Self { retention_blocks: 1_000_000 }
+    }
+}
+
+fn default_event_retention_blocks() -> BlockHeightDelta {
+    EventIndexConfig::default().retention_blocks
+}
+
+/// Configuration of the background compilation of contracts of shards the node starts tracking.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, PartialEq)]
+pub struct ContractPrecompilationConfig {
+    /// Whether contracts of a shard are compiled after its state is synced.
+    #[serde(default = "default_contract_precompilation_enabled")]
+    pub enabled: bool,
+    /// Maximum number of contracts compiled at the same time. If not set, up to half of the
+    /// threads of the global rayon pool are used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_threads: Option<usize>,
+}
+
+impl Default for ContractPrecompilationConfig {
+    fn default() -> Self {
+        Self { enabled: true, max_threads: None }
+    }
+}
+
+fn default_contract_precompilation_enabled() -> bool {
+    ContractPrecompilationConfig::default().enabled
+}
+
 fn default_gc_num_epochs_to_keep() -> u64 {
     GCConfig::default().gc_num_epochs_to_keep()
 }
@@ -167,6 +225,12 @@ pub struct ClientConfig {
     pub client_background_migration_threads: usize,
     /// Duration to perform background flat storage creation step.
     pub flat_storage_creation_period: Duration,
+    /// If set, the node indexes transactions and receipts by the accounts they touch.
+    pub account_activity_index: Option<AccountActivityIndexConfig>,
+    /// If set, the node indexes events emitted by contracts.
+    pub event_index: Option<EventIndexConfig>,
+    /// Background compilation of contracts of newly synced shards.
+    pub contract_precompilation: ContractPrecompilationConfig,
 }
 
 impl ClientConfig {
@@ -237,6 +301,9 @@ impl ClientConfig {
             enable_statistics_export: true,
             client_background_migration_threads: 1,
             flat_storage_creation_period: Duration::from_secs(1),
+            account_activity_index: None,
+            event_index: None,
+            contract_precompilation: ContractPrecompilationConfig::default(),
         }
     }
 }
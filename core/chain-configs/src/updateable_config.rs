@@ -1,5 +1,6 @@
 use crate::metrics;
 use chrono::{DateTime, Utc};
+use near_crypto::KeyFile;
 use near_primitives::static_clock::StaticClock;
 use near_primitives::types::BlockHeight;
 use serde::{Deserialize, Serialize, Serializer};
@@ -85,4 +86,8 @@ impl<T: Copy + PartialEq + Debug> MutableConfigValue<T> {
 pub struct UpdateableClientConfig {
     /// Graceful shutdown at expected block height.
     pub expected_shutdown: Option<BlockHeight>,
+    /// Contents of the validator key file. The client switches to this key once it matches the
+    /// key staked for the upcoming epoch. Never serialized to avoid leaking the secret key.
+    #[serde(skip)]
+    pub validator_key: Option<KeyFile>,
 }
@@ -5,7 +5,7 @@ use std::io;
 use std::io::{Read, Write};
 use std::path::Path;
 
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
 pub struct KeyFile {
     pub account_id: AccountId,
     pub public_key: PublicKey,
@@ -836,6 +836,8 @@ pub enum EpochError {
         num_validators: u64,
         num_shards: u64,
     },
+    /// A stake delegation given to project rewards for is invalid.
+    InvalidDelegation(String),
 }
 
 impl std::error::Error for EpochError {}
@@ -860,6 +862,7 @@ impl Display for EpochError {
             EpochError::NotEnoughValidators { num_shards, num_validators } => {
                 write!(f, "There were not enough validator proposals to fill all shards. num_proposals: {}, num_shards: {}", num_validators, num_shards)
             }
+            EpochError::InvalidDelegation(err) => write!(f, "Invalid delegation: {}", err),
         }
     }
 }
@@ -880,6 +883,7 @@ impl Debug for EpochError {
             EpochError::NotEnoughValidators { num_shards, num_validators } => {
                 write!(f, "NotEnoughValidators({}, {})", num_validators, num_shards)
             }
+            EpochError::InvalidDelegation(err) => write!(f, "InvalidDelegation({})", err),
         }
     }
 }
@@ -78,3 +78,44 @@ pub mod state_patch {
         }
     }
 }
+
+#[cfg(feature = "sandbox")]
+pub mod impersonation {
+    use crate::hash::CryptoHash;
+    use crate::transaction::{Action, SignedTransaction, Transaction};
+    use crate::types::{AccountId, Nonce};
+    use near_crypto::{KeyType, PublicKey, Signature};
+
+    /// Public key of transactions which act on behalf of their signer without holding any of
+    /// its keys. The sandbox runtime accepts such transactions without checking the signature
+    /// and the access key of the signer.
+    pub fn impersonation_public_key() -> PublicKey {
+        PublicKey::empty(KeyType::ED25519)
+    }
+
+    pub fn is_impersonated(transaction: &SignedTransaction) -> bool {
+        transaction.transaction.public_key == impersonation_public_key()
+    }
+
+    /// Creates a transaction which acts on behalf of `signer_id`. The nonce isn't checked, it
+    /// only has to make the hash of the transaction unique.
+    pub fn impersonated_transaction(
+        nonce: Nonce,
+        signer_id: AccountId,
+        receiver_id: AccountId,
+        actions: Vec<Action>,
+        block_hash: CryptoHash,
+    ) -> SignedTransaction {
+        SignedTransaction::new(
+            Signature::empty(KeyType::ED25519),
+            Transaction {
+                signer_id,
+                public_key: impersonation_public_key(),
+                nonce,
+                receiver_id,
+                block_hash,
+                actions,
+            },
+        )
+    }
+}
@@ -418,6 +418,11 @@ pub mod trie_key_parsers {
         res.extend(prefix);
         res
     }
+
+    /// Returns the prefix shared by the keys of contract codes of all accounts.
+    pub fn get_raw_prefix_for_contract_codes() -> Vec<u8> {
+        vec![col::CONTRACT_CODE]
+    }
 }
 
 #[cfg(test)]
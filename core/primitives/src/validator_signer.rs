@@ -159,9 +159,12 @@ impl InMemoryValidatorSigner {
         self.signer.public_key()
     }
 
+    pub fn from_signer(signer: InMemorySigner) -> Self {
+        Self { account_id: signer.account_id.clone(), signer: Arc::new(signer) }
+    }
+
     pub fn from_file(path: &Path) -> std::io::Result<Self> {
-        let signer = InMemorySigner::from_file(path)?;
-        Ok(Self { account_id: signer.account_id.clone(), signer: Arc::new(signer) })
+        InMemorySigner::from_file(path).map(Self::from_signer)
     }
 }
 
@@ -226,6 +226,35 @@ pub struct StateItem {
     pub proof: Vec<()>,
 }
 
+/// Changes applied to the state of a contract before a view call. They are never persisted and
+/// allow previewing the result of a call after e.g. a contract upgrade.
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct StateOverrides {
+    /// Replaces the code of the contract.
+    #[serde(
+        rename = "code_base64",
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "option_base64_format"
+    )]
+    pub code: Option<Vec<u8>>,
+    /// Replaces the balance of the contract account.
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "dec_format")]
+    pub balance: Option<Balance>,
+    /// Values written to the contract storage, replacing existing ones.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub storage: Vec<StateOverrideItem>,
+}
+
+/// Key and value of the contract storage, serialized in base64.
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct StateOverrideItem {
+    #[serde(with = "base64_format")]
+    pub key: Vec<u8>,
+    #[serde(with = "base64_format")]
+    pub value: Vec<u8>,
+}
+
 #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq, Clone)]
 pub struct ViewStateResult {
     pub values: Vec<StateItem>,
@@ -323,6 +352,9 @@ pub enum QueryRequest {
         method_name: String,
         #[serde(rename = "args_base64", with = "base64_format")]
         args: FunctionArgs,
+        /// Changes to the state of the called contract visible only to this call.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        state_overrides: Option<StateOverrides>,
     },
 }
 
@@ -1660,6 +1692,50 @@ pub struct FinalExecutionOutcomeWithReceiptView {
     pub receipts: Vec<ReceiptView>,
 }
 
+/// Where a transaction or a receipt was executed, as part of a transaction trace.
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct TraceExecutionView {
+    pub block_hash: CryptoHash,
+    pub block_height: BlockHeight,
+    pub shard_id: ShardId,
+    pub outcome: ExecutionOutcomeView,
+}
+
+/// Receipt in the causal tree of a transaction.
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct ReceiptTraceView {
+    pub receipt_id: CryptoHash,
+    /// The receipt itself, `None` if the node doesn't store it. Receipts executed in the shard
+    /// they were created in are not stored.
+    pub receipt: Option<ReceiptView>,
+    /// Whether the receipt is a refund, i.e. was issued by the system account.
+    pub is_refund: bool,
+    /// Receipts whose results this receipt waits for, i.e. this receipt is their callback.
+    pub data_dependencies: Vec<CryptoHash>,
+    /// `None` if the receipt was not executed yet.
+    pub execution: Option<TraceExecutionView>,
+    /// Number of blocks between the first block in which the receipt could have been executed
+    /// and the block in which it was executed, e.g. spent in the delayed receipt queue or waiting
+    /// for a chunk of its shard. A receipt can be executed in the block after its parent and its
+    /// data dependencies were executed, or in the same block as the transaction that sent it to
+    /// the signer itself. Skipped heights are not counted.
+    pub delay_blocks: Option<NumBlocks>,
+    /// Receipts created by the execution of this one.
+    pub children: Vec<ReceiptTraceView>,
+    /// Whether some of `children` were left out because the trace reached its depth or size
+    /// limit.
+    pub truncated: bool,
+}
+
+/// Transaction with the tree of the receipts it caused.
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct TxTraceView {
+    pub transaction: SignedTransactionView,
+    /// Where the transaction was converted into a receipt.
+    pub execution: TraceExecutionView,
+    pub receipts: Vec<ReceiptTraceView>,
+}
+
 pub mod validator_stake_view {
     use crate::types::validator_stake::ValidatorStake;
     use borsh::{BorshDeserialize, BorshSerialize};
@@ -1954,6 +2030,157 @@ pub struct NextEpochValidatorInfo {
     pub shards: Vec<ShardId>,
 }
 
+/// Stake delegated to a validator's staking pool, for which rewards are
+/// projected.
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct StakeDelegationView {
+    pub validator_id: AccountId,
+    #[serde(with = "dec_format")]
+    pub amount: Balance,
+    /// Part of the rewards kept by the staking pool owner.
+    #[serde(default = "StakeDelegationView::default_reward_fee_fraction")]
+    pub reward_fee_fraction: Rational32,
+}
+
+impl StakeDelegationView {
+    fn default_reward_fee_fraction() -> Rational32 {
+        Rational32::from_integer(0)
+    }
+}
+
+/// Rewards for the epochs following the given block, projected from the
+/// current validators, their uptime so far and the protocol config, assuming
+/// all of them stay the same and rewards are restaked.
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Clone)]
+pub struct RewardProjectionView {
+    pub epoch_id: EpochId,
+    pub epoch_height: EpochHeight,
+    /// Duration of an epoch estimated from the current epoch so far.
+    #[serde(with = "dec_format")]
+    pub epoch_duration_ns: u64,
+    /// Total supply and minted tokens of each of the projected epochs.
+    pub epochs: Vec<EpochRewardProjectionView>,
+    pub validators: Vec<ValidatorRewardProjectionView>,
+    pub delegations: Vec<DelegationRewardProjectionView>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct EpochRewardProjectionView {
+    pub epoch_height: EpochHeight,
+    #[serde(with = "dec_format")]
+    pub minted_amount: Balance,
+    /// Total supply at the end of the epoch.
+    #[serde(with = "dec_format")]
+    pub total_supply: Balance,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Clone)]
+pub struct ValidatorRewardProjectionView {
+    pub account_id: AccountId,
+    #[serde(with = "dec_format")]
+    pub stake: Balance,
+    /// Reward and resulting stake in each of the projected epochs.
+    pub rewards: Vec<StakeRewardView>,
+    /// Annual percentage yield at the rate of the first projected epoch.
+    pub apy: f64,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Clone)]
+pub struct DelegationRewardProjectionView {
+    #[serde(flatten)]
+    pub delegation: StakeDelegationView,
+    /// Reward and resulting stake in each of the projected epochs, after the
+    /// staking pool fee.
+    pub rewards: Vec<StakeRewardView>,
+    pub apy: f64,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct StakeRewardView {
+    #[serde(with = "dec_format")]
+    pub reward: Balance,
+    #[serde(with = "dec_format")]
+    pub stake: Balance,
+}
+
+/// How an account took part in a transaction or receipt.
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    serde::Serialize,
+    serde::Deserialize,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountActivityRole {
+    TransactionSigner,
+    TransactionReceiver,
+    ReceiptPredecessor,
+    ReceiptReceiver,
+}
+
+/// Transaction or receipt touching an account, as stored in the account activity index.
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct AccountActivityView {
+    pub block_height: BlockHeight,
+    pub block_hash: CryptoHash,
+    /// Transaction hash or receipt id.
+    pub id: CryptoHash,
+    pub role: AccountActivityRole,
+}
+
+/// Page of the activity of an account, newest first.
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct AccountActivityPageView {
+    pub activity: Vec<AccountActivityView>,
+    /// Height to request the next page from, if there are older entries.
+    pub next_block_height: Option<BlockHeight>,
+}
+
+/// Prefix of the logs holding events in the format of NEP-297.
+pub const EVENT_JSON_LOG_PREFIX: &str = "EVENT_JSON:";
+
+/// Event emitted by a contract as a log, see NEP-297.
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct EventView {
+    pub standard: String,
+    pub version: String,
+    pub event: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
+}
+
+impl EventView {
+    /// Parses a log emitted by a contract, returns `None` if it's not a valid event.
+    pub fn from_log(log: &str) -> Option<Self> {
+        serde_json::from_str(log.strip_prefix(EVENT_JSON_LOG_PREFIX)?).ok()
+    }
+}
+
+/// Event stored in the event index.
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct ContractEventView {
+    pub block_height: BlockHeight,
+    pub block_hash: CryptoHash,
+    /// Id of the receipt whose execution emitted the event.
+    pub receipt_id: CryptoHash,
+    pub contract_id: AccountId,
+    #[serde(flatten)]
+    pub event: EventView,
+}
+
+/// Page of events, oldest first.
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct ContractEventsPageView {
+    pub events: Vec<ContractEventView>,
+    /// Height to request the next page from, if there are more events in the requested range.
+    pub next_block_height: Option<BlockHeight>,
+}
+
 #[derive(
     PartialEq,
     Eq,
@@ -2830,6 +3057,26 @@ mod tests {
     #[cfg(not(feature = "nightly"))]
     use near_primitives_core::profile::{ProfileDataV2, ProfileDataV3};
 
+    #[test]
+    fn test_event_from_log() {
+        let event = super::EventView::from_log(
+            r#"EVENT_JSON:{"standard":"nep171","version":"1.0.0","event":"nft_mint","data":[{"owner_id":"alice.near","token_ids":["1"]}]}"#,
+        )
+        .unwrap();
+        assert_eq!(event.standard, "nep171");
+        assert_eq!(event.version, "1.0.0");
+        assert_eq!(event.event, "nft_mint");
+        assert_eq!(event.data.unwrap()[0]["owner_id"], "alice.near");
+        assert!(super::EventView::from_log(
+            r#"EVENT_JSON:{"standard":"nep171","version":"1.0.0","event":"nft_mint"}"#
+        )
+        .unwrap()
+        .data
+        .is_none());
+        assert_eq!(super::EventView::from_log("EVENT_JSON:{\"standard\":\"nep171\"}"), None);
+        assert_eq!(super::EventView::from_log("Transfer 10 from alice.near"), None);
+    }
+
     /// The JSON representation used in RPC responses must not remove or rename
     /// fields, only adding fields is allowed or we risk breaking clients.
     #[test]
@@ -274,6 +274,25 @@ pub enum DBCol {
     // TODO (#7327): use only during testing, come up with proper format.
     #[cfg(feature = "protocol_feature_flat_state")]
     FlatStateMisc,
+    /// Optional index of transactions and receipts touching an account. Only populated if
+    /// `account_activity_index` is enabled in the config.
+    /// - *Rows*: AccountId || 0 || (u64::MAX - BlockHeight) as big-endian || OutcomeId || role (u8)
+    /// - *Column type*: BlockHash
+    AccountActivity,
+    /// Keys of `AccountActivity` rows added for a block, used to enforce the retention of the
+    /// index.
+    /// - *Rows*: BlockHeight as big-endian || BlockHash
+    /// - *Column type*: Vec<Vec<u8>>
+    AccountActivityByHeight,
+    /// Optional index of events (NEP-297) emitted by contracts. Only populated if `event_index`
+    /// is enabled in the config.
+    /// - *Rows*: BlockHeight as big-endian || BlockHash || OutcomeId || log index (u32 big-endian)
+    /// - *Column type*: StoredEvent (contract account id and the log)
+    Events,
+    /// Keys of `Events` rows by the account of the contract which emitted the event.
+    /// - *Rows*: AccountId || 0 || `Events` row
+    /// - *Column type*: empty
+    EventsByContract,
 }
 
 /// Defines different logical parts of a db key.
@@ -477,6 +496,17 @@ impl DBCol {
             DBCol::FlatStateDeltas => &[DBKeyType::ShardId, DBKeyType::BlockHash],
             #[cfg(feature = "protocol_feature_flat_state")]
             DBCol::FlatStateMisc => &[DBKeyType::ShardId],
+            DBCol::AccountActivity => {
+                &[DBKeyType::AccountId, DBKeyType::BlockHeight, DBKeyType::OutcomeId]
+            }
+            DBCol::AccountActivityByHeight => &[DBKeyType::BlockHeight, DBKeyType::BlockHash],
+            DBCol::Events => &[DBKeyType::BlockHeight, DBKeyType::BlockHash, DBKeyType::OutcomeId],
+            DBCol::EventsByContract => &[
+                DBKeyType::AccountId,
+                DBKeyType::BlockHeight,
+                DBKeyType::BlockHash,
+                DBKeyType::OutcomeId,
+            ],
         }
     }
 }
@@ -31,6 +31,9 @@ pub const LARGEST_TARGET_HEIGHT_KEY: &[u8; 21] = b"LARGEST_TARGET_HEIGHT";
 pub const GENESIS_JSON_HASH_KEY: &[u8; 17] = b"GENESIS_JSON_HASH";
 pub const GENESIS_STATE_ROOTS_KEY: &[u8; 19] = b"GENESIS_STATE_ROOTS";
 pub const COLD_HEAD_KEY: &[u8; 9] = b"COLD_HEAD";
+pub const STORE_VALIDATOR_HEIGHT_KEY: &[u8; 22] = b"STORE_VALIDATOR_HEIGHT";
+pub const SANDBOX_SNAPSHOTS_KEY: &[u8; 17] = b"SANDBOX_SNAPSHOTS";
+pub const SANDBOX_FORK_DELETED_ACCOUNTS_KEY: &[u8; 29] = b"SANDBOX_FORK_DELETED_ACCOUNTS";
 
 #[derive(Default)]
 pub struct DBTransaction {
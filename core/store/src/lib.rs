@@ -13,7 +13,8 @@ use strum;
 pub use columns::DBCol;
 pub use db::{
     CHUNK_TAIL_KEY, COLD_HEAD_KEY, FINAL_HEAD_KEY, FORK_TAIL_KEY, HEADER_HEAD_KEY, HEAD_KEY,
-    LARGEST_TARGET_HEIGHT_KEY, LATEST_KNOWN_KEY, TAIL_KEY,
+    LARGEST_TARGET_HEIGHT_KEY, LATEST_KNOWN_KEY, SANDBOX_FORK_DELETED_ACCOUNTS_KEY,
+    SANDBOX_SNAPSHOTS_KEY, STORE_VALIDATOR_HEIGHT_KEY, TAIL_KEY,
 };
 use near_crypto::PublicKey;
 use near_o11y::pretty;
@@ -31,7 +32,9 @@ use crate::db::{
     GENESIS_JSON_HASH_KEY, GENESIS_STATE_ROOTS_KEY,
 };
 pub use crate::trie::iterator::{TrieIterator, TrieTraversalItem};
-pub use crate::trie::update::{TrieUpdate, TrieUpdateIterator, TrieUpdateValuePtr};
+pub use crate::trie::update::{
+    TrieAccesses, TrieRead, TrieUpdate, TrieUpdateIterator, TrieUpdateValuePtr,
+};
 pub use crate::trie::{
     estimator, split_state, ApplyStatePartResult, KeyForStateChanges, KeyLookupMode, NibbleSlice,
     PartialStorage, PrefetchApi, PrefetchError, RawTrieNode, RawTrieNodeWithSize, ShardTries, Trie,
@@ -8,7 +8,6 @@ use borsh::{BorshDeserialize, BorshSerialize};
 use byteorder::{LittleEndian, ReadBytesExt};
 
 use near_primitives::challenge::PartialState;
-use near_primitives::contract::ContractCode;
 use near_primitives::hash::{hash, CryptoHash};
 pub use near_primitives::shard_layout::ShardUId;
 use near_primitives::state::ValueRef;
@@ -25,7 +24,9 @@ pub use crate::trie::nibble_slice::NibbleSlice;
 pub use crate::trie::prefetching_trie_storage::{PrefetchApi, PrefetchError};
 pub use crate::trie::shard_tries::{KeyForStateChanges, ShardTries, WrappedTrieChanges};
 pub use crate::trie::trie_storage::{TrieCache, TrieCachingStorage, TrieDBStorage, TrieStorage};
-use crate::trie::trie_storage::{TrieMemoryPartialStorage, TrieRecordingStorage};
+use crate::trie::trie_storage::{
+    TrieMemoryPartialStorage, TrieOverlayStorage, TrieRecordingStorage,
+};
 use crate::StorageError;
 pub use near_primitives::types::TrieNodesCount;
 
@@ -541,6 +542,26 @@ impl TrieChanges {
     pub fn insertions(&self) -> &[TrieRefcountChange] {
         self.insertions.as_slice()
     }
+
+    /// Combines these changes with `next`, which were made on top of them, into the changes from
+    /// `self.old_root` to `next.new_root`. Nodes inserted by one and deleted by the other cancel
+    /// out.
+    pub fn then(self, next: TrieChanges) -> TrieChanges {
+        assert_eq!(self.new_root, next.old_root, "trie changes don't follow each other");
+        let mut refcount_changes = HashMap::<CryptoHash, (Vec<u8>, i32)>::new();
+        for (changes, sign) in
+            [(self.insertions, 1), (self.deletions, -1), (next.insertions, 1), (next.deletions, -1)]
+        {
+            for TrieRefcountChange { trie_node_or_value_hash, trie_node_or_value, rc } in changes {
+                let (_value, total) = refcount_changes
+                    .entry(trie_node_or_value_hash)
+                    .or_insert_with(|| (trie_node_or_value, 0));
+                *total += sign * rc.get() as i32;
+            }
+        }
+        let (insertions, deletions) = Trie::convert_to_insertions_and_deletions(refcount_changes);
+        TrieChanges { old_root: self.old_root, new_root: next.new_root, insertions, deletions }
+    }
 }
 
 /// Result of applying state part to Trie.
@@ -549,8 +570,6 @@ pub struct ApplyStatePartResult {
     pub trie_changes: TrieChanges,
     /// Flat state changes after applying state part, stored as delta.
     pub flat_state_delta: FlatStateDelta,
-    /// Contract codes belonging to the state part.
-    pub contract_codes: Vec<ContractCode>,
 }
 
 enum NodeOrValue {
@@ -588,6 +607,22 @@ impl Trie {
         Some(PartialStorage { nodes: PartialState(nodes) })
     }
 
+    /// Returns the trie at the new root of `changes`, which were made on top of this trie but
+    /// aren't written to DB yet. The nodes and values inserted by them are kept in memory. Flat
+    /// storage doesn't have the changes, so it isn't used.
+    pub fn with_changes(self, changes: &TrieChanges) -> Self {
+        assert_eq!(self.root, changes.old_root, "trie changes were made on top of another root");
+        let inserted = changes
+            .insertions
+            .iter()
+            .map(|change| {
+                (change.trie_node_or_value_hash, change.trie_node_or_value.as_slice().into())
+            })
+            .collect();
+        let storage = TrieOverlayStorage { base: self.storage, inserted };
+        Trie::new(Box::new(storage), changes.new_root, None)
+    }
+
     pub fn from_recorded_storage(partial_storage: PartialStorage, root: StateRoot) -> Self {
         let recorded_storage =
             partial_storage.nodes.0.into_iter().map(|value| (hash(&value), value)).collect();
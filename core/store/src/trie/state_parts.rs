@@ -12,9 +12,7 @@ use crate::trie::{
     ApplyStatePartResult, NodeHandle, RawTrieNodeWithSize, TrieNode, TrieNodeWithSize,
 };
 use crate::{PartialStorage, StorageError, Trie, TrieChanges};
-use near_primitives::contract::ContractCode;
 use near_primitives::state::ValueRef;
-use near_primitives::state_record::is_contract_code_key;
 
 impl Trie {
     /// Computes the set of trie nodes for a state part.
@@ -201,7 +199,6 @@ impl Trie {
             return Ok(ApplyStatePartResult {
                 trie_changes: TrieChanges::empty(Trie::EMPTY_ROOT),
                 flat_state_delta: Default::default(),
-                contract_codes: vec![],
             });
         }
         let trie =
@@ -212,16 +209,12 @@ impl Trie {
         let trie_traversal_items = iterator.visit_nodes_interval(&path_begin, &path_end)?;
         let mut map = HashMap::new();
         let mut flat_state_delta = FlatStateDelta::default();
-        let mut contract_codes = Vec::new();
         for TrieTraversalItem { hash, key } in trie_traversal_items {
             let value = trie.storage.retrieve_raw_bytes(&hash)?;
             map.entry(hash).or_insert_with(|| (value.to_vec(), 0)).1 += 1;
             if let Some(trie_key) = key {
                 let value_ref = ValueRef::new(&value);
-                flat_state_delta.insert(trie_key.clone(), Some(value_ref));
-                if is_contract_code_key(&trie_key) {
-                    contract_codes.push(ContractCode::new(value.to_vec(), None));
-                }
+                flat_state_delta.insert(trie_key, Some(value_ref));
             }
         }
         let (insertions, deletions) = Trie::convert_to_insertions_and_deletions(map);
@@ -233,11 +226,10 @@ impl Trie {
                 deletions,
             },
             flat_state_delta,
-            contract_codes,
         })
     }
 
-    /// Applies state part and returns the storage changes for the state part and all contract codes extracted from it.
+    /// Applies state part and returns the storage changes for the state part.
     /// Writing all storage changes gives the complete trie.
     pub fn apply_state_part(
         state_root: &StateRoot,
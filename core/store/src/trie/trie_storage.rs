@@ -308,6 +308,26 @@ pub trait TrieStorage {
     fn get_trie_nodes_count(&self) -> TrieNodesCount;
 }
 
+/// Serves the nodes and values inserted by some trie changes before reading the underlying
+/// storage, so that the trie can be read at the new root before the changes are written to DB.
+pub(crate) struct TrieOverlayStorage {
+    pub(crate) base: Box<dyn TrieStorage>,
+    pub(crate) inserted: HashMap<CryptoHash, Arc<[u8]>>,
+}
+
+impl TrieStorage for TrieOverlayStorage {
+    fn retrieve_raw_bytes(&self, hash: &CryptoHash) -> Result<Arc<[u8]>, StorageError> {
+        match self.inserted.get(hash) {
+            Some(value) => Ok(value.clone()),
+            None => self.base.retrieve_raw_bytes(hash),
+        }
+    }
+
+    fn get_trie_nodes_count(&self) -> TrieNodesCount {
+        self.base.get_trie_nodes_count()
+    }
+}
+
 /// Records every value read by retrieve_raw_bytes.
 /// Used for obtaining state parts (and challenges in the future).
 /// TODO (#6316): implement proper nodes counting logic as in TrieCachingStorage
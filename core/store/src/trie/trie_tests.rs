@@ -1,4 +1,6 @@
-use crate::test_utils::{create_tries_complex, gen_changes, simplify_changes, test_populate_trie};
+use crate::test_utils::{
+    create_tries, create_tries_complex, gen_changes, simplify_changes, test_populate_trie,
+};
 use crate::trie::trie_storage::{TrieMemoryPartialStorage, TrieStorage};
 use crate::{PartialStorage, Trie, TrieUpdate};
 use near_primitives::errors::StorageError;
@@ -132,6 +134,47 @@ fn test_reads_with_incomplete_storage() {
     }
 }
 
+/// Changes made on top of changes which aren't written yet combine into the same trie as the one
+/// built in one go.
+#[test]
+fn test_trie_with_changes() {
+    let tries = create_tries();
+    let shard_uid = ShardUId::single_shard();
+    let root = test_populate_trie(
+        &tries,
+        &Trie::EMPTY_ROOT,
+        shard_uid,
+        vec![(b"a".to_vec(), Some(b"1".to_vec())), (b"b".to_vec(), Some(b"2".to_vec()))],
+    );
+    let first = tries
+        .get_trie_for_shard(shard_uid, root)
+        .update(vec![(b"c".to_vec(), Some(b"3".to_vec())), (b"d".to_vec(), Some(b"4".to_vec()))])
+        .unwrap();
+    let trie = tries.get_trie_for_shard(shard_uid, root).with_changes(&first);
+    assert_eq!(trie.get(b"b"), Ok(Some(b"2".to_vec())));
+    assert_eq!(trie.get(b"d"), Ok(Some(b"4".to_vec())));
+    let second = trie.update(vec![(b"a".to_vec(), None), (b"d".to_vec(), None)]).unwrap();
+
+    let combined = first.then(second);
+    let expected = tries
+        .get_trie_for_shard(shard_uid, root)
+        .update(vec![(b"a".to_vec(), None), (b"c".to_vec(), Some(b"3".to_vec()))])
+        .unwrap();
+    assert_eq!(combined.old_root, root);
+    assert_eq!(combined.new_root, expected.new_root);
+    // Nodes of the first changes removed by the second ones are not inserted at all.
+    assert_eq!(combined.insertions(), expected.insertions());
+
+    let mut store_update = tries.store_update();
+    let new_root = tries.apply_all(&combined, shard_uid, &mut store_update);
+    store_update.commit().unwrap();
+    let trie = tries.get_trie_for_shard(shard_uid, new_root);
+    assert_eq!(trie.get(b"a"), Ok(None));
+    assert_eq!(trie.get(b"b"), Ok(Some(b"2".to_vec())));
+    assert_eq!(trie.get(b"c"), Ok(Some(b"3".to_vec())));
+    assert_eq!(trie.get(b"d"), Ok(None));
+}
+
 #[cfg(test)]
 mod nodes_counter_tests {
     use super::*;
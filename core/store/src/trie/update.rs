@@ -1,6 +1,7 @@
+use std::cell::RefCell;
 use std::collections::BTreeMap;
 
-use near_primitives::hash::CryptoHash;
+use near_primitives::hash::{hash, CryptoHash};
 use near_primitives::types::{
     RawStateChange, RawStateChanges, RawStateChangesWithTrieKey, StateChangeCause, StateRoot,
     TrieCacheMode,
@@ -26,12 +27,57 @@ pub struct TrieKeyValueUpdate {
 /// key that was updated -> the update.
 pub type TrieUpdates = BTreeMap<Vec<u8>, TrieKeyValueUpdate>;
 
+/// A read done through a [`TrieUpdate`] while recording accesses.
+#[derive(Debug, PartialEq)]
+pub struct TrieRead {
+    pub key: Vec<u8>,
+    /// Hash of the value read, `None` if the key was missing.
+    pub value_hash: Option<CryptoHash>,
+    /// The value read.  Values which were only referenced by hash aren't
+    /// known until [`TrieAccesses::resolve_values`] is called.
+    pub value: Option<Vec<u8>>,
+}
+
+/// Reads and writes done by a [`TrieUpdate`] between two commits.
+#[derive(Debug)]
+pub struct TrieAccesses {
+    /// Cause of the commit which ended this group of accesses.
+    pub cause: StateChangeCause,
+    /// Reads in the order they happened.
+    pub reads: Vec<TrieRead>,
+    /// Committed writes with the new values (`None` if the key was removed).
+    pub writes: Vec<(Vec<u8>, Option<Vec<u8>>)>,
+}
+
+impl TrieAccesses {
+    /// Fills in the values of reads which were only referenced by hash.
+    ///
+    /// This goes to the storage directly, so it should be done only once
+    /// nothing depends on the storage access counters anymore.
+    pub fn resolve_values(&mut self, trie: &Trie) -> Result<(), StorageError> {
+        for read in self.reads.iter_mut() {
+            if let (None, Some(value_hash)) = (&read.value, &read.value_hash) {
+                read.value = Some(trie.storage.retrieve_raw_bytes(value_hash)?.to_vec());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct AccessRecorder {
+    reads: Vec<TrieRead>,
+    committed: Vec<TrieAccesses>,
+}
+
 /// Provides a way to access Storage and record changes with future commit.
 /// TODO (#7327): rename to StateUpdate
 pub struct TrieUpdate {
     pub trie: Rc<Trie>,
     committed: RawStateChanges,
     prospective: TrieUpdates,
+    /// Set if accesses are recorded, see [`TrieUpdate::start_recording_accesses`].
+    recorder: Option<RefCell<AccessRecorder>>,
 }
 
 pub enum TrieUpdateValuePtr<'a> {
@@ -59,7 +105,37 @@ impl<'a> TrieUpdateValuePtr<'a> {
 
 impl TrieUpdate {
     pub fn new(trie: Rc<Trie>) -> Self {
-        TrieUpdate { trie, committed: Default::default(), prospective: Default::default() }
+        TrieUpdate {
+            trie,
+            committed: Default::default(),
+            prospective: Default::default(),
+            recorder: None,
+        }
+    }
+
+    /// Starts recording all reads and committed writes done through this
+    /// update.  This is used for debugging only and is expensive.
+    ///
+    /// Values obtained with [`TrieUpdate::get_ref`] are recorded by hash only,
+    /// because dereferencing them would affect the storage access counters and
+    /// hence the gas.  See [`TrieAccesses::resolve_values`].
+    pub fn start_recording_accesses(&mut self) {
+        self.recorder = Some(Default::default());
+    }
+
+    /// Returns the accesses recorded since the previous call, grouped by
+    /// commit.  Reads not followed by a commit yet are not returned.
+    pub fn take_recorded_accesses(&self) -> Vec<TrieAccesses> {
+        self.recorder
+            .as_ref()
+            .map_or_else(Vec::new, |recorder| std::mem::take(&mut recorder.borrow_mut().committed))
+    }
+
+    fn record_read(&self, key: &[u8], value_hash: Option<CryptoHash>, value: Option<&[u8]>) {
+        if let Some(recorder) = &self.recorder {
+            let read = TrieRead { key: key.to_vec(), value_hash, value: value.map(<[u8]>::to_vec) };
+            recorder.borrow_mut().reads.push(read);
+        }
     }
 
     pub fn trie(&self) -> &Rc<Trie> {
@@ -72,15 +148,36 @@ impl TrieUpdate {
         mode: KeyLookupMode,
     ) -> Result<Option<TrieUpdateValuePtr<'_>>, StorageError> {
         let key = key.to_vec();
-        if let Some(key_value) = self.prospective.get(&key) {
+        let result = self.get_ref_impl(&key, mode);
+        if self.recorder.is_some() {
+            match &result {
+                Ok(Some(TrieUpdateValuePtr::MemoryRef(data))) => {
+                    self.record_read(&key, Some(hash(data)), Some(data))
+                }
+                Ok(Some(TrieUpdateValuePtr::HashAndSize(_, _, value_hash))) => {
+                    self.record_read(&key, Some(*value_hash), None)
+                }
+                Ok(None) => self.record_read(&key, None, None),
+                Err(_) => {}
+            }
+        }
+        result
+    }
+
+    fn get_ref_impl(
+        &self,
+        key: &[u8],
+        mode: KeyLookupMode,
+    ) -> Result<Option<TrieUpdateValuePtr<'_>>, StorageError> {
+        if let Some(key_value) = self.prospective.get(key) {
             return Ok(key_value.value.as_deref().map(TrieUpdateValuePtr::MemoryRef));
-        } else if let Some(changes_with_trie_key) = self.committed.get(&key) {
+        } else if let Some(changes_with_trie_key) = self.committed.get(key) {
             if let Some(RawStateChange { data, .. }) = changes_with_trie_key.changes.last() {
                 return Ok(data.as_deref().map(TrieUpdateValuePtr::MemoryRef));
             }
         }
 
-        self.trie.get_ref(&key, mode).map(|option| {
+        self.trie.get_ref(key, mode).map(|option| {
             option.map(|ValueRef { length, hash }| {
                 TrieUpdateValuePtr::HashAndSize(&self.trie, length, hash)
             })
@@ -89,14 +186,24 @@ impl TrieUpdate {
 
     pub fn get(&self, key: &TrieKey) -> Result<Option<Vec<u8>>, StorageError> {
         let key = key.to_vec();
-        if let Some(key_value) = self.prospective.get(&key) {
+        let result = self.get_impl(&key);
+        if self.recorder.is_some() {
+            if let Ok(value) = &result {
+                self.record_read(&key, value.as_deref().map(hash), value.as_deref());
+            }
+        }
+        result
+    }
+
+    fn get_impl(&self, key: &[u8]) -> Result<Option<Vec<u8>>, StorageError> {
+        if let Some(key_value) = self.prospective.get(key) {
             return Ok(key_value.value.as_ref().map(<Vec<u8>>::clone));
-        } else if let Some(changes_with_trie_key) = self.committed.get(&key) {
+        } else if let Some(changes_with_trie_key) = self.committed.get(key) {
             if let Some(RawStateChange { data, .. }) = changes_with_trie_key.changes.last() {
                 return Ok(data.as_ref().map(<Vec<u8>>::clone));
             }
         }
-        self.trie.get(&key)
+        self.trie.get(key)
     }
 
     pub fn set(&mut self, trie_key: TrieKey, value: Vec<u8>) {
@@ -113,6 +220,15 @@ impl TrieUpdate {
 
     pub fn commit(&mut self, event: StateChangeCause) {
         let prospective = std::mem::take(&mut self.prospective);
+        if let Some(recorder) = &self.recorder {
+            let mut recorder = recorder.borrow_mut();
+            let reads = std::mem::take(&mut recorder.reads);
+            let writes = prospective
+                .iter()
+                .map(|(key, update)| (key.clone(), update.value.clone()))
+                .collect();
+            recorder.committed.push(TrieAccesses { cause: event.clone(), reads, writes });
+        }
         for (raw_key, TrieKeyValueUpdate { trie_key, value }) in prospective.into_iter() {
             self.committed
                 .entry(raw_key)
@@ -253,6 +369,40 @@ mod tests {
         assert_eq!(new_root, Trie::EMPTY_ROOT);
     }
 
+    #[test]
+    fn trie_record_accesses() {
+        let tries = create_tries();
+        let mut trie_update = tries.new_trie_update(ShardUId::single_shard(), Trie::EMPTY_ROOT);
+        trie_update.start_recording_accesses();
+        assert_eq!(trie_update.get(&test_key(b"dog".to_vec())), Ok(None));
+        trie_update.set(test_key(b"dog".to_vec()), b"puppy".to_vec());
+        assert_eq!(trie_update.get(&test_key(b"dog".to_vec())), Ok(Some(b"puppy".to_vec())));
+        let cause = StateChangeCause::TransactionProcessing { tx_hash: CryptoHash::default() };
+        trie_update.commit(cause);
+        trie_update.remove(test_key(b"dog".to_vec()));
+        trie_update.commit(StateChangeCause::UpdatedDelayedReceipts);
+
+        let key = test_key(b"dog".to_vec()).to_vec();
+        let accesses = trie_update.take_recorded_accesses();
+        assert_eq!(accesses.len(), 2);
+        assert!(matches!(accesses[0].cause, StateChangeCause::TransactionProcessing { .. }));
+        assert_eq!(
+            accesses[0].reads,
+            vec![
+                TrieRead { key: key.clone(), value_hash: None, value: None },
+                TrieRead {
+                    key: key.clone(),
+                    value_hash: Some(hash(b"puppy")),
+                    value: Some(b"puppy".to_vec()),
+                },
+            ]
+        );
+        assert_eq!(accesses[0].writes, vec![(key.clone(), Some(b"puppy".to_vec()))]);
+        assert!(accesses[1].reads.is_empty());
+        assert_eq!(accesses[1].writes, vec![(key, None)]);
+        assert!(trie_update.take_recorded_accesses().is_empty());
+    }
+
     #[test]
     fn trie_iter() {
         let tries = create_tries();
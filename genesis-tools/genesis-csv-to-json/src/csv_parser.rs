@@ -2,6 +2,7 @@
 use chrono::DateTime;
 use chrono::Utc;
 use csv::ReaderBuilder;
+use near_chain_configs::get_initial_supply;
 use near_crypto::{KeyType, PublicKey};
 use near_network::types::PeerInfo;
 use near_primitives::account::{AccessKey, AccessKeyPermission, Account, FunctionCallPermission};
@@ -10,10 +11,13 @@ use near_primitives::receipt::{ActionReceipt, Receipt, ReceiptEnum};
 use near_primitives::state_record::StateRecord;
 use near_primitives::transaction::{Action, FunctionCallAction};
 use near_primitives::types::{AccountId, AccountInfo, Balance, Gas};
+use std::collections::HashSet;
 use std::fs::File;
 use std::io::Read;
 use std::path::PathBuf;
 
+use crate::lockup::{lockup_records, LockupConfig, VestingSchedule};
+
 /// Methods that can be called by a non-privileged access key.
 const REGULAR_METHOD_NAMES: &[&str] = &["stake", "transfer"];
 /// Methods that can be called by a privileged access key.
@@ -87,8 +91,39 @@ impl Row {
                 )));
             }
         }
+
+        if self.lockup_amount.unwrap_or(0) > 0 {
+            if self.lockup.is_none() {
+                return Err(Box::new(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "Lockup date must be specified if lockup amount is not 0.",
+                )));
+            }
+            if self.smart_contract.is_some() {
+                return Err(Box::new(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "Smart contract cannot be set together with lockup amount.",
+                )));
+            }
+            if self.vesting_start.is_some() && self.lockup_foundation_account_id.is_none() {
+                return Err(Box::new(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "Lockup foundation account must be specified for a lockup with vesting.",
+                )));
+            }
+        } else if self.release_duration.is_some() || self.lockup_foundation_account_id.is_some() {
+            return Err(Box::new(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "Release duration and lockup foundation account require lockup amount.",
+            )));
+        }
         Ok(())
     }
+
+    /// Tokens the row puts into the genesis, across all accounts it creates.
+    fn total_balance(&self) -> Balance {
+        self.amount + self.validator_stake + self.lockup_amount.unwrap_or(0)
+    }
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Debug)]
@@ -114,6 +149,18 @@ struct Row {
     vesting_start: Option<DateTime<Utc>>,
     vesting_end: Option<DateTime<Utc>>,
     vesting_cliff: Option<DateTime<Utc>>,
+    /// Tokens held in a lockup contract account owned by this account, on top
+    /// of `amount`. The tokens unlock at `lockup`, and vest according to the
+    /// vesting dates if they're set.
+    #[serde(default)]
+    lockup_amount: Option<Balance>,
+    /// Duration in nanoseconds over which the lockup amount is released
+    /// linearly once the lockup ends.
+    #[serde(default)]
+    release_duration: Option<u64>,
+    /// Account which may terminate the vesting of the lockup amount.
+    #[serde(default)]
+    lockup_foundation_account_id: Option<AccountId>,
 }
 
 /// Given path to the csv file produces:
@@ -122,9 +169,12 @@ struct Row {
 /// * `PeerInfo`s that represent boot nodes;
 /// * `AccountId` of the treasury.
 /// *  Genesis time
+///
+/// `lockup_config` is required if any of the rows has a lockup amount.
 pub fn keys_to_state_records<R>(
     reader: R,
     gas_price: Balance,
+    lockup_config: Option<&LockupConfig>,
 ) -> Result<(Vec<StateRecord>, Vec<AccountInfo>, Vec<PeerInfo>, AccountId, DateTime<Utc>)>
 where
     R: std::io::Read,
@@ -136,9 +186,11 @@ where
     let mut boot_nodes = vec![];
     let mut treasury = None;
     let mut genesis_time = None;
+    let mut total_balance = 0;
     for row in reader.deserialize() {
         let row: Row = row?;
         row.verify()?;
+        total_balance += row.total_balance();
         if row.is_treasury {
             if treasury.is_none() {
                 treasury = Some(row.account_id.clone());
@@ -151,6 +203,28 @@ where
         }
 
         state_records.extend(account_records(&row, gas_price));
+        if let Some(lockup_amount) = row.lockup_amount.filter(|amount| *amount > 0) {
+            let lockup_config = lockup_config.ok_or_else(|| {
+                format!("Lockup contract must be given for the lockup of {}.", row.account_id)
+            })?;
+            let vesting_schedule = match (row.vesting_start, row.vesting_end) {
+                (Some(start), Some(end)) => Some(VestingSchedule {
+                    start_timestamp: start.timestamp_nanos() as u64,
+                    cliff_timestamp: row.vesting_cliff.unwrap_or(start).timestamp_nanos() as u64,
+                    end_timestamp: end.timestamp_nanos() as u64,
+                }),
+                _ => None,
+            };
+            state_records.extend(lockup_records(
+                lockup_config,
+                &row.account_id,
+                lockup_amount,
+                row.lockup.unwrap().timestamp_nanos() as u64,
+                row.release_duration,
+                vesting_schedule,
+                row.lockup_foundation_account_id.clone(),
+            )?);
+        }
         if let Some(ref validator_key) = row.validator_key {
             initial_validators.push(AccountInfo {
                 account_id: row.account_id.clone(),
@@ -163,6 +237,22 @@ where
             boot_nodes.push(peer_info);
         }
     }
+    let mut account_ids = HashSet::new();
+    for record in state_records.iter() {
+        if let StateRecord::Account { account_id, .. } = record {
+            if !account_ids.insert(account_id) {
+                return Err(format!("Account {} is created more than once.", account_id).into());
+            }
+        }
+    }
+    let initial_supply = get_initial_supply(&state_records);
+    if initial_supply != total_balance {
+        return Err(format!(
+            "Accounts hold {} tokens in total, while the rows give {}.",
+            initial_supply, total_balance
+        )
+        .into());
+    }
     let treasury = treasury.expect("At least one account should be marked as treasury");
     let genesis_time = genesis_time.expect("Genesis time must be set");
     Ok((state_records, initial_validators, boot_nodes, treasury, genesis_time))
@@ -279,6 +369,7 @@ mod tests {
     use csv::WriterBuilder;
     use tempfile::NamedTempFile;
 
+    use borsh::BorshDeserialize;
     use near_crypto::KeyType;
 
     use super::*;
@@ -313,6 +404,9 @@ mod tests {
                 }),
                 is_treasury: false,
                 smart_contract: None,
+                lockup_amount: None,
+                release_duration: None,
+                lockup_foundation_account_id: None,
             })
             .unwrap();
         writer
@@ -333,16 +427,85 @@ mod tests {
                 peer_info: None,
                 is_treasury: true,
                 smart_contract: None,
+                lockup_amount: None,
+                release_duration: None,
+                lockup_foundation_account_id: None,
             })
             .unwrap();
         writer.flush().unwrap();
-        keys_to_state_records(file.reopen().unwrap(), 1).unwrap();
+        keys_to_state_records(file.reopen().unwrap(), 1, None).unwrap();
     }
 
     #[test]
     fn test_res_file() {
         let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("res/test_accounts.csv");
         let res = std::fs::read(path).unwrap();
-        keys_to_state_records(&res[..], 1).unwrap();
+        keys_to_state_records(&res[..], 1, None).unwrap();
+    }
+
+    #[test]
+    fn test_lockup() {
+        let file = NamedTempFile::new().unwrap();
+        let mut writer = WriterBuilder::new().has_headers(true).from_writer(file.reopen().unwrap());
+        writer
+            .serialize(Row {
+                genesis_time: Some(Utc::now()),
+                account_id: "alice.near".parse().unwrap(),
+                regular_pks: vec![],
+                privileged_pks: vec![],
+                foundation_pks: vec![],
+                full_pks: vec![PublicKey::empty(KeyType::ED25519)],
+                amount: 1000,
+                lockup: Some(Utc.ymd(2020, 12, 21).and_hms(0, 0, 0)),
+                vesting_start: Some(Utc.ymd(2019, 12, 21).and_hms(0, 0, 0)),
+                vesting_end: Some(Utc.ymd(2023, 12, 21).and_hms(0, 0, 0)),
+                vesting_cliff: None,
+                validator_stake: 0,
+                validator_key: None,
+                peer_info: None,
+                is_treasury: true,
+                smart_contract: None,
+                lockup_amount: Some(5000),
+                release_duration: Some(1_000_000_000),
+                lockup_foundation_account_id: Some("foundation.near".parse().unwrap()),
+            })
+            .unwrap();
+        writer.flush().unwrap();
+
+        assert!(keys_to_state_records(file.reopen().unwrap(), 1, None).is_err());
+        let lockup_config = LockupConfig {
+            code: vec![1, 2, 3],
+            account_suffix: "lockup.near".to_string(),
+            staking_pool_whitelist_account_id: "whitelist.near".parse().unwrap(),
+        };
+        let (records, ..) =
+            keys_to_state_records(file.reopen().unwrap(), 1, Some(&lockup_config)).unwrap();
+        assert_eq!(near_chain_configs::get_initial_supply(&records), 6000);
+
+        let lockup_account_id =
+            lockup_config.lockup_account_id(&"alice.near".parse().unwrap()).unwrap();
+        assert_eq!(lockup_account_id.as_ref().len(), 40 + ".lockup.near".len());
+        let state = records
+            .iter()
+            .find_map(|record| match record {
+                StateRecord::Data { account_id, data_key, value }
+                    if account_id == &lockup_account_id && data_key == b"STATE" =>
+                {
+                    Some(crate::lockup::LockupContract::try_from_slice(value).unwrap())
+                }
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(state.owner_account_id.as_ref(), "alice.near");
+        assert_eq!(state.lockup_information.lockup_amount, 5000);
+        assert_eq!(state.lockup_information.release_duration, Some(1_000_000_000));
+        assert_eq!(
+            state.vesting_information,
+            crate::lockup::VestingInformation::VestingSchedule(VestingSchedule {
+                start_timestamp: Utc.ymd(2019, 12, 21).and_hms(0, 0, 0).timestamp_nanos() as u64,
+                cliff_timestamp: Utc.ymd(2019, 12, 21).and_hms(0, 0, 0).timestamp_nanos() as u64,
+                end_timestamp: Utc.ymd(2023, 12, 21).and_hms(0, 0, 0).timestamp_nanos() as u64,
+            })
+        );
     }
 }
@@ -14,6 +14,8 @@ use nearcore::config::{
 };
 use nearcore::NEAR_BASE;
 
+use crate::lockup::LockupConfig;
+
 const ACCOUNTS_FILE: &str = "accounts.csv";
 const NUM_SHARDS: NumShards = 8;
 
@@ -33,7 +35,15 @@ fn verify_total_supply(total_supply: Balance, chain_id: &str) {
 
 /// Generates `config.json` and `genesis.config` from csv files.
 /// Verifies that `validator_key.json`, and `node_key.json` are present.
-pub fn csv_to_json_configs(home: &Path, chain_id: String, tracked_shards: Vec<ShardId>) {
+/// If `expected_total_supply` is given, verifies that the accounts hold exactly that many
+/// tokens, including the ones in lockup contracts.
+pub fn csv_to_json_configs(
+    home: &Path,
+    chain_id: String,
+    tracked_shards: Vec<ShardId>,
+    lockup_config: Option<LockupConfig>,
+    expected_total_supply: Option<Balance>,
+) {
     // Verify that key files exist.
     assert!(home.join(NODE_KEY_FILE).as_path().exists(), "Node key file should exist");
 
@@ -50,6 +60,7 @@ pub fn csv_to_json_configs(home: &Path, chain_id: String, tracked_shards: Vec<Sh
         crate::csv_parser::keys_to_state_records(
             File::open(home.join(ACCOUNTS_FILE)).expect("Error opening accounts file."),
             MIN_GAS_PRICE,
+            lockup_config.as_ref(),
         )
         .expect("Error parsing accounts file.");
     config.network.boot_nodes =
@@ -84,6 +95,12 @@ pub fn csv_to_json_configs(home: &Path, chain_id: String, tracked_shards: Vec<Sh
     };
     let genesis = Genesis::new(genesis_config, records.into()).unwrap();
     verify_total_supply(genesis.config.total_supply, &chain_id);
+    if let Some(expected_total_supply) = expected_total_supply {
+        assert_eq!(
+            genesis.config.total_supply, expected_total_supply,
+            "Total supply doesn't match the expected one"
+        );
+    }
 
     // Write all configs to files.
     config.write_to_file(&home.join(CONFIG_FILENAME)).expect("Error writing config");
@@ -0,0 +1,158 @@
+//! Lockup contract accounts created for token holders with locked up tokens.
+//!
+//! Instead of calling `init` on the contract after genesis, the contract state
+//! is written directly into the genesis records, so that the lockup accounts
+//! are fully functional from the first block.  The types below mirror the
+//! state of the lockup contract from `near/core-contracts` and have to be kept
+//! in sync with the version of the contract which is deployed.
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_primitives::account::Account;
+use near_primitives::hash::{hash, CryptoHash};
+use near_primitives::state_record::StateRecord;
+use near_primitives::types::{AccountId, Balance};
+use std::path::Path;
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Key under which near-sdk contracts store their state.
+const STATE_KEY: &[u8] = b"STATE";
+
+/// Nanoseconds since the Unix epoch.
+pub type Timestamp = u64;
+/// Nanoseconds.
+pub type Duration = u64;
+
+/// Parameters shared by all lockup accounts of the genesis.
+pub struct LockupConfig {
+    /// Code of the lockup contract deployed to every lockup account.
+    pub code: Vec<u8>,
+    /// Lockup accounts are sub-accounts of this account.
+    pub account_suffix: String,
+    /// Account of the contract with the staking pools the lockups may
+    /// delegate to.
+    pub staking_pool_whitelist_account_id: AccountId,
+}
+
+impl LockupConfig {
+    pub fn new(
+        contract_path: &Path,
+        account_suffix: String,
+        staking_pool_whitelist_account_id: AccountId,
+    ) -> Result<Self> {
+        let code = std::fs::read(contract_path).map_err(|err| {
+            format!("failed reading lockup contract {}: {}", contract_path.display(), err)
+        })?;
+        Ok(Self { code, account_suffix, staking_pool_whitelist_account_id })
+    }
+
+    /// The lockup account of `owner_account_id`, named the same way as the
+    /// lockup accounts on mainnet: the first 20 bytes of the hash of the owner
+    /// account ID in hex.
+    pub fn lockup_account_id(&self, owner_account_id: &AccountId) -> Result<AccountId> {
+        let owner_hash = hash(owner_account_id.as_ref().as_bytes());
+        let prefix = hex::encode(&owner_hash.as_ref()[..20]);
+        Ok(format!("{}.{}", prefix, self.account_suffix).parse()?)
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+pub struct LockupContract {
+    pub owner_account_id: AccountId,
+    pub lockup_information: LockupInformation,
+    pub vesting_information: VestingInformation,
+    pub staking_pool_whitelist_account_id: AccountId,
+    pub staking_information: Option<StakingInformation>,
+    pub foundation_account_id: Option<AccountId>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+pub struct LockupInformation {
+    pub lockup_amount: Balance,
+    pub termination_withdrawn_tokens: Balance,
+    pub lockup_duration: Duration,
+    /// Tokens are released linearly over this duration once the lockup ends.
+    pub release_duration: Option<Duration>,
+    pub lockup_timestamp: Option<Timestamp>,
+    pub transfers_information: TransfersInformation,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+pub enum TransfersInformation {
+    TransfersEnabled { transfers_timestamp: Timestamp },
+    TransfersDisabled { transfer_poll_account_id: AccountId },
+}
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+pub enum VestingInformation {
+    None,
+    VestingHash(Vec<u8>),
+    VestingSchedule(VestingSchedule),
+    Terminating(TerminationInformation),
+}
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+pub struct VestingSchedule {
+    pub start_timestamp: Timestamp,
+    pub cliff_timestamp: Timestamp,
+    pub end_timestamp: Timestamp,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+pub struct TerminationInformation {
+    pub unvested_amount: Balance,
+    pub status: u8,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+pub struct StakingInformation {
+    pub staking_pool_account_id: AccountId,
+    pub status: u8,
+    pub deposit_amount: Balance,
+}
+
+/// Returns the records of the lockup account holding `lockup_amount` for
+/// `owner_account_id`: the account itself, the contract code and its state.
+///
+/// The tokens unlock at `lockup_timestamp`, and are then released over
+/// `release_duration` if given.  With a vesting schedule only the vested part
+/// unlocks and the foundation may terminate the vesting.
+pub fn lockup_records(
+    config: &LockupConfig,
+    owner_account_id: &AccountId,
+    lockup_amount: Balance,
+    lockup_timestamp: Timestamp,
+    release_duration: Option<Duration>,
+    vesting_schedule: Option<VestingSchedule>,
+    foundation_account_id: Option<AccountId>,
+) -> Result<Vec<StateRecord>> {
+    let account_id = config.lockup_account_id(owner_account_id)?;
+    let state = LockupContract {
+        owner_account_id: owner_account_id.clone(),
+        lockup_information: LockupInformation {
+            lockup_amount,
+            termination_withdrawn_tokens: 0,
+            lockup_duration: 0,
+            release_duration,
+            lockup_timestamp: Some(lockup_timestamp),
+            transfers_information: TransfersInformation::TransfersEnabled {
+                transfers_timestamp: lockup_timestamp,
+            },
+        },
+        vesting_information: match vesting_schedule {
+            Some(vesting_schedule) => VestingInformation::VestingSchedule(vesting_schedule),
+            None => VestingInformation::None,
+        },
+        staking_pool_whitelist_account_id: config.staking_pool_whitelist_account_id.clone(),
+        staking_information: None,
+        foundation_account_id,
+    };
+    let code_hash: CryptoHash = hash(&config.code);
+    Ok(vec![
+        StateRecord::Account {
+            account_id: account_id.clone(),
+            account: Account::new(lockup_amount, 0, code_hash, 0),
+        },
+        StateRecord::Contract { account_id: account_id.clone(), code: config.code.clone() },
+        StateRecord::Data { account_id, data_key: STATE_KEY.to_vec(), value: state.try_to_vec()? },
+    ])
+}
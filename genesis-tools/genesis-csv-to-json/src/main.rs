@@ -1,11 +1,12 @@
 use clap::{Arg, Command};
-use near_primitives::types::ShardId;
+use near_primitives::types::{Balance, ShardId};
 use nearcore::get_default_home;
 use std::collections::HashSet;
 use std::path::Path;
 
 pub mod csv_parser;
 pub mod csv_to_json_configs;
+pub mod lockup;
 pub mod serde_with;
 
 fn main() {
@@ -26,6 +27,32 @@ fn main() {
                 .takes_value(true)
                 .help("Set of shards that this node wants to track (default empty)"),
         )
+        .arg(
+            Arg::new("lockup-contract")
+                .long("lockup-contract")
+                .takes_value(true)
+                .help("Lockup contract deployed for accounts with a lockup amount"),
+        )
+        .arg(
+            Arg::new("lockup-account-suffix")
+                .long("lockup-account-suffix")
+                .default_value("lockup.near")
+                .takes_value(true)
+                .help("Account that lockup accounts are created as sub-accounts of"),
+        )
+        .arg(
+            Arg::new("lockup-whitelist-account-id")
+                .long("lockup-whitelist-account-id")
+                .default_value("lockup-whitelist.near")
+                .takes_value(true)
+                .help("Staking pool whitelist contract the lockup contracts use"),
+        )
+        .arg(
+            Arg::new("total-supply")
+                .long("total-supply")
+                .takes_value(true)
+                .help("Fail unless the accounts hold exactly this many tokens in total"),
+        )
         .get_matches();
 
     let home_dir = matches.value_of("home").map(|dir| Path::new(dir)).unwrap();
@@ -40,9 +67,26 @@ fn main() {
         }
         None => HashSet::default(),
     };
+    let lockup_config = matches.value_of("lockup-contract").map(|path| {
+        lockup::LockupConfig::new(
+            Path::new(path),
+            matches.value_of("lockup-account-suffix").unwrap().to_string(),
+            matches
+                .value_of("lockup-whitelist-account-id")
+                .unwrap()
+                .parse()
+                .expect("Invalid lockup whitelist account id"),
+        )
+        .expect("Error reading lockup contract")
+    });
+    let total_supply = matches
+        .value_of("total-supply")
+        .map(|s| s.parse::<Balance>().expect("Total supply must be a number"));
     csv_to_json_configs::csv_to_json_configs(
         home_dir,
         chain_id.to_string(),
         tracked_shards.into_iter().collect(),
+        lockup_config,
+        total_supply,
     );
 }
@@ -0,0 +1,304 @@
+use crate::tests::client::process_blocks::create_nightshade_runtimes;
+use near_chain::{
+    get_epoch_block_producers_view, validate_epoch_sync_finalization, ChainGenesis, Provenance,
+};
+use near_chain_configs::Genesis;
+use near_client::sync::epoch::{EpochSync, MAX_REQUESTS_WITHOUT_PROGRESS};
+use near_client::test_utils::TestEnv;
+use near_client::SyncStatus;
+use near_crypto::{KeyType, PublicKey};
+use near_epoch_manager::EpochManagerAdapter;
+use near_network::types::{HighestHeightPeerInfo, NetworkRequests, PeerInfo};
+use near_o11y::testonly::init_test_logger;
+use near_primitives::hash::CryptoHash;
+use near_primitives::types::BlockHeight;
+use near_primitives::views::validator_stake_view::ValidatorStakeView;
+use nearcore::checkpoint::EpochCheckpoint;
+use nearcore::config::GenesisExt;
+use std::time::Duration;
+
+const EPOCH_LENGTH: BlockHeight = 5;
+
+/// Produces a few epochs on the first client and returns the environment.
+fn setup_env(max_height: BlockHeight) -> TestEnv {
+    let mut genesis = Genesis::test(vec!["test0".parse().unwrap(), "test1".parse().unwrap()], 1);
+    genesis.config.epoch_length = EPOCH_LENGTH;
+    let mut chain_genesis = ChainGenesis::test();
+    chain_genesis.epoch_length = EPOCH_LENGTH;
+    let mut env = TestEnv::builder(chain_genesis)
+        .clients_count(2)
+        .runtime_adapters(create_nightshade_runtimes(&genesis, 2))
+        .build();
+    for height in 1..=max_height {
+        let block = env.clients[0].produce_block(height).unwrap().unwrap();
+        env.process_block(0, block, Provenance::PRODUCED);
+    }
+    env
+}
+
+/// A peer at the head of the first client.
+fn highest_height_peer(env: &TestEnv) -> HighestHeightPeerInfo {
+    let head = env.clients[0].chain.head().unwrap();
+    HighestHeightPeerInfo {
+        peer_info: PeerInfo::random(),
+        genesis_id: Default::default(),
+        highest_block_height: head.height,
+        highest_block_hash: head.last_block_hash,
+        tracked_shards: vec![],
+        archival: false,
+    }
+}
+
+/// Runs epoch sync of the second client against the chain of the first one
+/// and returns the number of requests it took.
+fn run_epoch_sync(env: &mut TestEnv) -> usize {
+    let head = env.clients[0].chain.head().unwrap();
+    let peer = highest_height_peer(env);
+    let mut sync_status = SyncStatus::AwaitingPeers;
+    for num_requests in 0..20 {
+        // Don't hit the per-peer request rate limit.
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        let client = &mut env.clients[1];
+        let in_progress = client
+            .epoch_sync
+            .run(&mut sync_status, &client.chain, head.height, EPOCH_LENGTH, &[peer.clone()])
+            .unwrap();
+        if !in_progress {
+            return num_requests;
+        }
+        assert!(matches!(sync_status, SyncStatus::EpochSync { .. }));
+        match env.network_adapters[1].pop_most_recent().unwrap().as_network_requests() {
+            NetworkRequests::EpochSyncRequest { epoch_id, peer_id } => {
+                let response = env.clients[0].chain.get_epoch_sync_response(&epoch_id).unwrap();
+                env.clients[1].epoch_sync.on_response(response, peer_id);
+            }
+            NetworkRequests::EpochSyncFinalizationRequest { block_hash, peer_id } => {
+                let response =
+                    env.clients[0].chain.get_epoch_sync_finalization_response(&block_hash).unwrap();
+                let client = &mut env.clients[1];
+                client
+                    .epoch_sync
+                    .on_finalization_response(&mut client.chain, response, peer_id)
+                    .unwrap();
+            }
+            request => panic!("unexpected request {:?}", request),
+        }
+    }
+    panic!("epoch sync didn't finish");
+}
+
+/// A fresh node skips to the last epoch with epoch sync and then syncs the
+/// remaining headers.
+#[test]
+fn test_epoch_sync() {
+    init_test_logger();
+    let max_height = EPOCH_LENGTH * 5 + 2;
+    let mut env = setup_env(max_height);
+
+    let num_requests = run_epoch_sync(&mut env);
+    assert!(env.clients[1].epoch_sync.done);
+    // One light client block for every finished epoch, one up to date
+    // response and finalization.
+    assert!(num_requests >= 6, "{}", num_requests);
+
+    let header_head = env.clients[1].chain.header_head().unwrap();
+    let final_head = env.clients[1].chain.final_head().unwrap();
+    assert_eq!(header_head, final_head);
+    let header = env.clients[0].chain.get_block_header(&header_head.last_block_hash).unwrap();
+    assert_eq!(header.height(), header_head.height);
+    // Header sync continues from the end of the previous epoch.
+    let next_header = env.clients[0].chain.get_block_header_by_height(header.height() + 1).unwrap();
+    assert_ne!(next_header.epoch_id(), header.epoch_id());
+    assert!(header_head.height > max_height - 2 * EPOCH_LENGTH);
+
+    let headers = (header_head.height + 1..=max_height)
+        .map(|height| env.clients[0].chain.get_block_header_by_height(height).unwrap())
+        .collect();
+    env.clients[1].sync_block_headers(headers).unwrap();
+    assert_eq!(
+        env.clients[1].chain.header_head().unwrap().last_block_hash,
+        env.clients[0].chain.head().unwrap().last_block_hash
+    );
+}
+
+/// Epoch sync doesn't start for a chain which is only a few blocks long.
+#[test]
+fn test_epoch_sync_not_needed() {
+    init_test_logger();
+    let mut env = setup_env(EPOCH_LENGTH - 1);
+    assert_eq!(run_epoch_sync(&mut env), 0);
+    assert!(env.clients[1].epoch_sync.done);
+    assert_eq!(
+        env.clients[1].chain.header_head().unwrap().last_block_hash,
+        *env.clients[1].chain.genesis().hash()
+    );
+}
+
+/// Epoch sync is abandoned in favour of header sync when peers don't answer.
+#[test]
+fn test_epoch_sync_falls_back_to_header_sync() {
+    init_test_logger();
+    let mut env = setup_env(EPOCH_LENGTH * 5 + 2);
+    let head = env.clients[0].chain.head().unwrap();
+    let peer = highest_height_peer(&env);
+    let client = &mut env.clients[1];
+    let genesis_header = client.chain.genesis().clone();
+    let block_producers = client
+        .runtime_adapter
+        .get_epoch_block_producers_ordered(genesis_header.epoch_id(), genesis_header.hash())
+        .unwrap()
+        .into_iter()
+        .map(|(block_producer, _)| block_producer)
+        .collect();
+    // Re-request right away instead of waiting for responses which never come.
+    client.epoch_sync = EpochSync::new(
+        env.network_adapters[1].clone().into(),
+        &genesis_header,
+        block_producers,
+        Duration::ZERO,
+        Duration::ZERO,
+    );
+
+    let mut sync_status = SyncStatus::AwaitingPeers;
+    for _ in 0..MAX_REQUESTS_WITHOUT_PROGRESS {
+        let in_progress = client
+            .epoch_sync
+            .run(&mut sync_status, &client.chain, head.height, EPOCH_LENGTH, &[peer.clone()])
+            .unwrap();
+        assert!(in_progress);
+        assert!(matches!(
+            env.network_adapters[1].pop_most_recent().unwrap().as_network_requests(),
+            NetworkRequests::EpochSyncRequest { .. }
+        ));
+    }
+    let in_progress = client
+        .epoch_sync
+        .run(&mut sync_status, &client.chain, head.height, EPOCH_LENGTH, &[peer])
+        .unwrap();
+    assert!(!in_progress);
+    assert!(client.epoch_sync.done);
+    assert!(env.network_adapters[1].pop().is_none());
+}
+
+/// Finalization data which doesn't match the hashes in the first block of the
+/// epoch is rejected.
+#[test]
+fn test_epoch_sync_finalization_validation() {
+    init_test_logger();
+    let max_height = EPOCH_LENGTH * 3 + 2;
+    let env = setup_env(max_height);
+    let chain = &env.clients[0].chain;
+
+    // The last final block of the second epoch.
+    let epoch_id = chain.get_block_header_by_height(EPOCH_LENGTH + 1).unwrap().epoch_id().clone();
+    let light_client_block = match chain.get_epoch_sync_response(&epoch_id).unwrap() {
+        near_primitives::syncing::EpochSyncResponse::Advance { light_client_block_view } => {
+            light_client_block_view
+        }
+        response => panic!("unexpected response {:?}", response),
+    };
+    let next_block_producers = light_client_block.next_bps.clone().unwrap();
+    let final_block_hash = near_primitives::views::LightClientBlockLiteView {
+        prev_block_hash: light_client_block.prev_block_hash,
+        inner_rest_hash: light_client_block.inner_rest_hash,
+        inner_lite: light_client_block.inner_lite.clone(),
+    }
+    .hash();
+
+    let response = chain.get_epoch_sync_finalization_response(&final_block_hash).unwrap();
+    validate_epoch_sync_finalization(&final_block_hash, &next_block_producers, &response).unwrap();
+
+    let mut bad_response = response.clone();
+    bad_response.prev_epoch_first_block_info = response.prev_epoch_last_block_info.clone();
+    assert!(matches!(
+        validate_epoch_sync_finalization(&final_block_hash, &next_block_producers, &bad_response),
+        Err(near_chain::Error::InvalidEpochSyncData(_))
+    ));
+
+    let mut bad_response = response.clone();
+    bad_response.header_sync_init_header_tree.insert(final_block_hash);
+    assert!(matches!(
+        validate_epoch_sync_finalization(&final_block_hash, &next_block_producers, &bad_response),
+        Err(near_chain::Error::InvalidBlockMerkleRoot)
+    ));
+
+    let mut bad_response = response;
+    bad_response.prev_epoch_headers.remove(0);
+    assert!(validate_epoch_sync_finalization(
+        &final_block_hash,
+        &next_block_producers,
+        &bad_response
+    )
+    .is_err());
+}
+
+/// A checkpoint created from a node's chain verifies against genesis, and
+/// with a trusted hash only the light client blocks after it are checked.
+#[test]
+fn test_epoch_checkpoint_verification() {
+    init_test_logger();
+    let max_height = EPOCH_LENGTH * 4 + 2;
+    let env = setup_env(max_height);
+    let chain = &env.clients[0].chain;
+    let genesis_header = chain.genesis().clone();
+    let genesis_block_producers = get_epoch_block_producers_view(
+        genesis_header.epoch_id(),
+        genesis_header.hash(),
+        &*chain.runtime_adapter(),
+    )
+    .unwrap();
+
+    let checkpoint = EpochCheckpoint::create(chain, None).unwrap();
+    assert!(checkpoint.light_client_blocks.len() >= 3);
+    checkpoint.verify(&genesis_header, genesis_block_producers.clone(), None).unwrap();
+
+    // Signatures in the first epoch are made by the genesis block producers,
+    // so they don't verify against other keys.
+    let other_block_producers: Vec<_> = genesis_block_producers
+        .iter()
+        .map(|block_producer| {
+            let ValidatorStakeView::V1(mut block_producer) = block_producer.clone();
+            block_producer.public_key = PublicKey::empty(KeyType::ED25519);
+            ValidatorStakeView::V1(block_producer)
+        })
+        .collect();
+    assert!(checkpoint.verify(&genesis_header, other_block_producers.clone(), None).is_err());
+
+    let second = &checkpoint.light_client_blocks[1];
+    let trusted_hash = near_primitives::views::LightClientBlockLiteView {
+        prev_block_hash: second.prev_block_hash,
+        inner_rest_hash: second.inner_rest_hash,
+        inner_lite: second.inner_lite.clone(),
+    }
+    .hash();
+    checkpoint.verify(&genesis_header, other_block_producers.clone(), Some(&trusted_hash)).unwrap();
+    assert!(checkpoint
+        .verify(&genesis_header, genesis_block_producers.clone(), Some(&CryptoHash::default()))
+        .is_err());
+
+    // A checkpoint created from the trusted block leaves out the epochs before it.
+    let trusted_checkpoint = EpochCheckpoint::create(chain, Some(&trusted_hash)).unwrap();
+    assert_eq!(
+        trusted_checkpoint.light_client_blocks.len(),
+        checkpoint.light_client_blocks.len() - 1
+    );
+    assert_eq!(trusted_checkpoint.height(), checkpoint.height());
+    trusted_checkpoint.verify(&genesis_header, other_block_producers, Some(&trusted_hash)).unwrap();
+    assert!(trusted_checkpoint
+        .verify(&genesis_header, genesis_block_producers.clone(), None)
+        .is_err());
+    // The next block producers of the trusted block must match its next_bp_hash.
+    let mut tampered_checkpoint = trusted_checkpoint.clone();
+    let next_bps = tampered_checkpoint.light_client_blocks[0].next_bps.as_mut().unwrap();
+    let ValidatorStakeView::V1(block_producer) = &mut next_bps[0];
+    block_producer.stake += 1;
+    assert!(tampered_checkpoint
+        .verify(&genesis_header, genesis_block_producers.clone(), Some(&trusted_hash))
+        .is_err());
+    let first_block_hash = chain.get_block_header_by_height(1).unwrap().hash().clone();
+    assert!(EpochCheckpoint::create(chain, Some(&first_block_hash)).is_err());
+
+    let mut bad_checkpoint = checkpoint;
+    bad_checkpoint.light_client_blocks.remove(1);
+    assert!(bad_checkpoint.verify(&genesis_header, genesis_block_producers, None).is_err());
+}
@@ -2,6 +2,7 @@ mod benchmarks;
 mod challenges;
 mod chunks_management;
 mod cold_storage;
+mod epoch_sync;
 mod features;
 mod flat_storage;
 mod process_blocks;
@@ -32,7 +32,7 @@ use near_client::{
     BlockApproval, BlockResponse, Client, GetBlock, GetBlockWithMerkleTree, ProcessTxRequest,
     ProcessTxResponse, SetNetworkInfo,
 };
-use near_crypto::{InMemorySigner, KeyType, PublicKey, Signature, Signer};
+use near_crypto::{InMemorySigner, KeyFile, KeyType, PublicKey, Signature, Signer};
 use near_network::test_utils::{wait_or_panic, MockPeerManagerAdapter};
 use near_network::types::{
     BlockInfo, ConnectedPeerInfo, HighestHeightPeerInfo, NetworkInfo, PeerChainInfo,
@@ -1969,6 +1969,51 @@ fn test_tx_forward_around_epoch_boundary() {
     );
 }
 
+/// A validator can switch to a rotated key without a restart. The new key is only used once it is
+/// staked for the upcoming epoch, so the validator doesn't skip any of its blocks.
+#[test]
+fn test_validator_key_rotation() {
+    let epoch_length = 5;
+    let mut genesis = Genesis::test(vec!["test0".parse().unwrap()], 1);
+    genesis.config.epoch_length = epoch_length;
+    let mut chain_genesis = ChainGenesis::test();
+    chain_genesis.epoch_length = epoch_length;
+    let mut env = TestEnv::builder(chain_genesis)
+        .runtime_adapters(create_nightshade_runtimes(&genesis, 1))
+        .build();
+    let genesis_hash = *env.clients[0].chain.genesis().hash();
+    let signer = InMemorySigner::from_seed("test0".parse().unwrap(), KeyType::ED25519, "test0");
+    let new_signer =
+        InMemorySigner::from_seed("test0".parse().unwrap(), KeyType::ED25519, "rotated");
+    let tx = SignedTransaction::stake(
+        1,
+        "test0".parse().unwrap(),
+        &signer,
+        TESTING_INIT_STAKE,
+        new_signer.public_key.clone(),
+        genesis_hash,
+    );
+    env.clients[0].process_tx(tx, false, false);
+    env.clients[0].set_pending_validator_key(KeyFile::from(&new_signer));
+
+    env.produce_block(0, 1);
+    assert_eq!(env.clients[0].validator_signer.as_ref().unwrap().public_key(), signer.public_key);
+    assert!(env.network_adapters[0].validator_signer.read().unwrap().is_none());
+    for height in 2..=epoch_length * 4 {
+        // Panics if the client doesn't produce the block.
+        env.produce_block(0, height);
+    }
+    assert_eq!(
+        env.clients[0].validator_signer.as_ref().unwrap().public_key(),
+        new_signer.public_key
+    );
+    // The network signs TIER1 data with the new key as well.
+    assert_eq!(
+        env.network_adapters[0].validator_signer.read().unwrap().as_ref().unwrap().public_key(),
+        new_signer.public_key
+    );
+}
+
 /// Blocks that have already been gc'ed should not be accepted again.
 #[test]
 fn test_not_resync_old_blocks() {
@@ -3370,6 +3415,112 @@ fn test_block_ordinal() {
     assert_eq!(fork_ordinal_block_hash, *fork1_block.hash());
 }
 
+/// Test that the trace of a cross-contract call with a callback counts the delays in blocks
+/// from the earliest block in which each receipt could have been executed.
+#[test]
+fn test_transaction_trace_delays() {
+    let genesis = Genesis::test(vec!["test0".parse().unwrap(), "test1".parse().unwrap()], 1);
+    let chain_genesis = ChainGenesis::new(&genesis);
+    let mut env = TestEnv::builder(chain_genesis)
+        .runtime_adapters(create_nightshade_runtimes(&genesis, 1))
+        .build();
+    let genesis_hash = *env.clients[0].chain.genesis().hash();
+    let signer0 = InMemorySigner::from_seed("test0".parse().unwrap(), KeyType::ED25519, "test0");
+    let signer1 = InMemorySigner::from_seed("test1".parse().unwrap(), KeyType::ED25519, "test1");
+
+    let tx = SignedTransaction::from_actions(
+        1,
+        "test0".parse().unwrap(),
+        "test0".parse().unwrap(),
+        &signer0,
+        vec![Action::DeployContract(DeployContractAction {
+            code: near_test_contracts::base_rs_contract().to_vec(),
+        })],
+        genesis_hash,
+    );
+    assert_eq!(env.clients[0].process_tx(tx, false, false), ProcessTxResponse::ValidTx);
+    for i in 1..4 {
+        env.produce_block(0, i);
+    }
+
+    // `noop` is called on test0 and `noop` is called again as its callback.
+    let gas = 10_000_000_000_000u64;
+    let args = serde_json::json!([
+        {"create": {
+            "account_id": "test0",
+            "method_name": "noop",
+            "arguments": [],
+            "amount": "0",
+            "gas": gas,
+        }, "id": 0 },
+        {"then": {
+            "promise_index": 0,
+            "account_id": "test0",
+            "method_name": "noop",
+            "arguments": [],
+            "amount": "0",
+            "gas": gas,
+        }, "id": 1 }
+    ]);
+    let tx = SignedTransaction::from_actions(
+        1,
+        "test1".parse().unwrap(),
+        "test0".parse().unwrap(),
+        &signer1,
+        vec![Action::FunctionCall(FunctionCallAction {
+            method_name: "call_promise".to_string(),
+            args: serde_json::to_vec(&args).unwrap(),
+            gas: 10 * gas,
+            deposit: 0,
+        })],
+        genesis_hash,
+    );
+    let tx_hash = tx.get_hash();
+    assert_eq!(env.clients[0].process_tx(tx, false, false), ProcessTxResponse::ValidTx);
+    // Every other height is skipped, skipped heights must not be counted as delays.
+    for i in 2..12 {
+        env.produce_block(0, 2 * i + 1);
+    }
+
+    let trace = env.clients[0].chain.get_transaction_trace(&tx_hash).unwrap();
+    assert_eq!(trace.receipts.len(), 1);
+    let call = &trace.receipts[0];
+    assert!(call.execution.is_some());
+    assert_eq!(call.delay_blocks, Some(0));
+    assert!(call.data_dependencies.is_empty());
+
+    let (refunds, calls): (Vec<_>, Vec<_>) = call.children.iter().partition(|r| r.is_refund);
+    assert_eq!(calls.len(), 2);
+    let (callee, callback) = (calls[0], calls[1]);
+    assert!(callee.data_dependencies.is_empty());
+    assert_eq!(callback.data_dependencies, vec![callee.receipt_id]);
+    // The callback is created along with the callee, but can only be executed in the block
+    // after the callee was executed.
+    let callee_execution = callee.execution.as_ref().unwrap();
+    let callback_execution = callback.execution.as_ref().unwrap();
+    assert_eq!(callee_execution.block_height + 2, callback_execution.block_height);
+    assert_eq!(callee.delay_blocks, Some(0));
+    assert_eq!(callback.delay_blocks, Some(0));
+    for receipt in refunds.into_iter().chain(calls.iter().flat_map(|r| r.children.iter())) {
+        if receipt.is_refund {
+            assert_eq!(receipt.delay_blocks, Some(0));
+        } else {
+            // The data receipt of the callee is not executed on its own.
+            assert_eq!(receipt.execution, None);
+        }
+    }
+    assert!(!call.truncated);
+
+    // The tree is cut off at the limits, receipts whose children were left out are marked.
+    let chain = &env.clients[0].chain;
+    let trace = chain.get_transaction_trace_with_limits(&tx_hash, 1, 100).unwrap();
+    assert!(trace.receipts[0].children.is_empty());
+    assert!(trace.receipts[0].truncated);
+    let trace = chain.get_transaction_trace_with_limits(&tx_hash, 100, 2).unwrap();
+    assert_eq!(trace.receipts[0].children.len(), 1);
+    assert!(trace.receipts[0].truncated);
+}
+
 #[test]
 fn test_congestion_receipt_execution() {
     let (mut env, tx_hashes) = prepare_env_with_congestion(PROTOCOL_VERSION, None, 3);
@@ -3499,7 +3650,11 @@ mod contract_precompilation_tests {
 
     const EPOCH_LENGTH: u64 = 5;
 
-    fn state_sync_on_height(env: &mut TestEnv, height: BlockHeight) {
+    fn state_sync_on_height(
+        env: &mut TestEnv,
+        runtime: &nearcore::NightshadeRuntime,
+        height: BlockHeight,
+    ) {
         let sync_block = env.clients[0].chain.get_block_by_height(height).unwrap();
         let sync_hash = *sync_block.hash();
         let chunk_extra =
@@ -3520,6 +3675,14 @@ mod contract_precompilation_tests {
                 &epoch_id,
             )
             .unwrap();
+        runtime
+            .precompile_shard_contracts(
+                0,
+                chunk_extra.state_root(),
+                &epoch_id,
+                sync_block.header().next_epoch_id(),
+            )
+            .unwrap();
     }
 
     #[test]
@@ -3530,16 +3693,20 @@ mod contract_precompilation_tests {
         let mut genesis =
             Genesis::test(vec!["test0".parse().unwrap(), "test1".parse().unwrap()], 1);
         genesis.config.epoch_length = EPOCH_LENGTH;
-        let runtime_adapters = stores
+        let runtimes: Vec<Arc<nearcore::NightshadeRuntime>> = stores
             .iter()
             .map(|store| {
                 Arc::new(nearcore::NightshadeRuntime::test(
                     Path::new("../../../.."),
                     store.clone(),
                     &genesis,
-                )) as Arc<dyn RuntimeWithEpochManagerAdapter>
+                ))
             })
             .collect();
+        let runtime_adapters = runtimes
+            .iter()
+            .map(|runtime| runtime.clone() as Arc<dyn RuntimeWithEpochManagerAdapter>)
+            .collect();
 
         let mut env = TestEnv::builder(ChainGenesis::test())
             .clients_count(num_clients)
@@ -3558,7 +3725,7 @@ mod contract_precompilation_tests {
         );
 
         // Perform state sync for the second client.
-        state_sync_on_height(&mut env, height - 1);
+        state_sync_on_height(&mut env, &runtimes[1], height - 1);
 
         // Check existence of contract in both caches.
         let mut caches: Vec<StoreCompiledContractCache> =
@@ -3633,16 +3800,20 @@ mod contract_precompilation_tests {
         let mut genesis =
             Genesis::test(vec!["test0".parse().unwrap(), "test1".parse().unwrap()], 1);
         genesis.config.epoch_length = EPOCH_LENGTH;
-        let runtime_adapters = stores
+        let runtimes: Vec<Arc<nearcore::NightshadeRuntime>> = stores
             .iter()
             .map(|store| {
                 Arc::new(nearcore::NightshadeRuntime::test(
                     Path::new("../../../.."),
                     store.clone(),
                     &genesis,
-                )) as Arc<dyn RuntimeWithEpochManagerAdapter>
+                ))
             })
             .collect();
+        let runtime_adapters = runtimes
+            .iter()
+            .map(|runtime| runtime.clone() as Arc<dyn RuntimeWithEpochManagerAdapter>)
+            .collect();
 
         let mut env = TestEnv::builder(ChainGenesis::test())
             .clients_count(num_clients)
@@ -3674,7 +3845,7 @@ mod contract_precompilation_tests {
         );
 
         // Perform state sync for the second client on the last produced height.
-        state_sync_on_height(&mut env, height - 1);
+        state_sync_on_height(&mut env, &runtimes[1], height - 1);
 
         let caches: Vec<StoreCompiledContractCache> =
             stores.iter().map(StoreCompiledContractCache::new).collect();
@@ -3717,16 +3888,20 @@ mod contract_precompilation_tests {
             1,
         );
         genesis.config.epoch_length = EPOCH_LENGTH;
-        let runtime_adapters = stores
+        let runtimes: Vec<Arc<nearcore::NightshadeRuntime>> = stores
             .iter()
             .map(|store| {
                 Arc::new(nearcore::NightshadeRuntime::test(
                     Path::new("../../../.."),
                     store.clone(),
                     &genesis,
-                )) as Arc<dyn RuntimeWithEpochManagerAdapter>
+                ))
             })
             .collect();
+        let runtime_adapters = runtimes
+            .iter()
+            .map(|runtime| runtime.clone() as Arc<dyn RuntimeWithEpochManagerAdapter>)
+            .collect();
 
         let mut env = TestEnv::builder(ChainGenesis::test())
             .clients_count(num_clients)
@@ -3759,7 +3934,7 @@ mod contract_precompilation_tests {
         height = produce_blocks_from_height(&mut env, EPOCH_LENGTH, height);
 
         // Perform state sync for the second client.
-        state_sync_on_height(&mut env, height - 1);
+        state_sync_on_height(&mut env, &runtimes[1], height - 1);
 
         let caches: Vec<StoreCompiledContractCache> =
             stores.iter().map(StoreCompiledContractCache::new).collect();
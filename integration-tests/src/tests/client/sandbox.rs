@@ -4,12 +4,13 @@ use std::sync::Arc;
 use near_chain::{ChainGenesis, Provenance, RuntimeWithEpochManagerAdapter};
 use near_chain_configs::Genesis;
 use near_client::test_utils::TestEnv;
+use near_client::ProcessTxResponse;
 use near_crypto::{InMemorySigner, KeyType};
 use near_primitives::account::Account;
 use near_primitives::sandbox::state_patch::SandboxStatePatch;
 use near_primitives::state_record::StateRecord;
 use near_primitives::transaction::{
-    Action, DeployContractAction, FunctionCallAction, SignedTransaction,
+    Action, DeployContractAction, FunctionCallAction, SignedTransaction, TransferAction,
 };
 use near_primitives::types::{AccountId, BlockHeight, Nonce};
 use near_store::test_utils::create_test_store;
@@ -107,3 +108,106 @@ fn test_patch_account() {
     let test1_after = env.query_account("test1".parse().unwrap());
     assert_eq!(test1_after.amount, 10);
 }
+
+#[test]
+fn test_snapshot_restore() {
+    let (mut env, _signer) = test_setup();
+    let test1: Account = env.query_account("test1".parse().unwrap()).into();
+    let amount = test1.amount();
+    let snapshot_id = env.clients[0].sandbox_snapshot().unwrap();
+
+    let mut patched = test1.clone();
+    patched.set_amount(10);
+    env.clients[0].chain.patch_state(SandboxStatePatch::new(vec![StateRecord::Account {
+        account_id: "test1".parse().unwrap(),
+        account: patched,
+    }]));
+    do_blocks(&mut env, 9, 20);
+    assert_eq!(env.query_account("test1".parse().unwrap()).amount, 10);
+
+    // Snapshots are kept in the database, so they survive a restart.
+    env.restart(0);
+    env.clients[0].sandbox_restore(snapshot_id).unwrap();
+    assert_eq!(env.query_account("test1".parse().unwrap()).amount, amount);
+    do_blocks(&mut env, 21, 25);
+    assert_eq!(env.query_account("test1".parse().unwrap()).amount, amount);
+    assert!(env.clients[0].sandbox_restore(snapshot_id + 1).is_err());
+}
+
+#[test]
+fn test_snapshot_restore_after_fast_forward() {
+    let (mut env, _signer) = test_setup();
+    let amount = env.query_account("test1".parse().unwrap()).amount;
+    let snapshot_height = env.clients[0].chain.head().unwrap().height;
+    let snapshot_id = env.clients[0].sandbox_snapshot().unwrap();
+
+    // Fast forward the way the client actor does, skipping heights only within an epoch, past
+    // more epochs than garbage collection keeps.
+    let mut height = snapshot_height + 1;
+    while height < 60 {
+        do_blocks(&mut env, height, height + 1);
+        if height % 5 == 1 {
+            env.clients[0].sandbox_skip_heights(1).unwrap();
+            height += 1;
+        }
+        height += 1;
+    }
+    send_tx(
+        &mut env,
+        3,
+        "test0".parse().unwrap(),
+        "test1".parse().unwrap(),
+        &InMemorySigner::from_seed("test0".parse().unwrap(), KeyType::ED25519, "test0"),
+        vec![Action::Transfer(TransferAction { deposit: 1 })],
+    );
+    do_blocks(&mut env, height, height + 3);
+    assert_eq!(env.query_account("test1".parse().unwrap()).amount, amount + 1);
+    assert!(env.clients[0].sandbox_delta_time() > chrono::Duration::zero());
+
+    // Garbage collection ran, but kept the snapshot.
+    let tail = env.clients[0].chain.tail().unwrap();
+    assert!(tail > env.clients[0].chain.genesis().height() && tail < snapshot_height);
+    env.clients[0].sandbox_restore(snapshot_id).unwrap();
+    assert_eq!(env.query_account("test1".parse().unwrap()).amount, amount);
+    assert_eq!(env.clients[0].sandbox_delta_time(), chrono::Duration::zero());
+    let height = env.clients[0].chain.store().get_latest_known().unwrap().height + 1;
+    do_blocks(&mut env, height, height + 3);
+    assert_eq!(env.query_account("test1".parse().unwrap()).amount, amount);
+}
+
+#[test]
+fn test_set_and_freeze_time() {
+    let (mut env, _signer) = test_setup();
+    let head_timestamp = env.clients[0].chain.head_header().unwrap().raw_timestamp();
+    assert!(env.clients[0].sandbox_set_time(head_timestamp).is_err());
+    assert!(env.clients[0].sandbox_set_time(i64::MAX as u64 + 1).is_err());
+
+    let timestamp = head_timestamp + 1_000_000_000_000;
+    env.clients[0].sandbox_freeze_time(true);
+    env.clients[0].sandbox_set_time(timestamp).unwrap();
+    do_blocks(&mut env, 9, 12);
+    let header = env.clients[0].chain.head_header().unwrap();
+    assert_eq!(header.raw_timestamp(), timestamp + 2);
+
+    env.clients[0].sandbox_freeze_time(false);
+    do_blocks(&mut env, 12, 13);
+    let header = env.clients[0].chain.head_header().unwrap();
+    assert!(header.raw_timestamp() > timestamp + 2);
+    assert!(header.raw_timestamp() < timestamp + 1_000_000_000_000);
+}
+
+#[test]
+fn test_impersonated_transaction() {
+    let (mut env, _signer) = test_setup();
+    let test0_amount = env.query_account("test0".parse().unwrap()).amount;
+    let tx = env.clients[0]
+        .sandbox_impersonated_transaction(
+            "test1".parse().unwrap(),
+            "test0".parse().unwrap(),
+            vec![Action::Transfer(TransferAction { deposit: 100 })],
+        )
+        .unwrap();
+    assert_eq!(env.clients[0].process_tx(tx, false, false), ProcessTxResponse::ValidTx);
+    do_blocks(&mut env, 9, 15);
+    assert_eq!(env.query_account("test0".parse().unwrap()).amount, test0_amount + 100);
+}
@@ -5,10 +5,11 @@ use near_primitives::{
     account::Account,
     hash::hash as sha256,
     hash::CryptoHash,
+    runtime::config_store::RuntimeConfigStore,
     serialize::to_base64,
     trie_key::trie_key_parsers,
     types::{AccountId, StateRoot},
-    views::{StateItem, ViewApplyState},
+    views::{StateItem, StateOverrideItem, StateOverrides, ViewApplyState},
 };
 use near_primitives::{
     test_utils::MockEpochInfoProvider,
@@ -180,6 +181,75 @@ fn test_view_call_with_args() {
     assert_eq!(view_call_result.unwrap(), 3u64.to_le_bytes().to_vec());
 }
 
+#[test]
+fn test_view_call_with_state_overrides() {
+    let (viewer, mut root) = get_test_trie_viewer();
+    let contract_id: AccountId = "test.contract".parse().unwrap();
+    let view_state = || ViewApplyState {
+        block_height: 1,
+        prev_block_hash: CryptoHash::default(),
+        block_hash: CryptoHash::default(),
+        epoch_id: EpochId::default(),
+        epoch_height: 0,
+        block_timestamp: 1,
+        current_protocol_version: PROTOCOL_VERSION,
+        cache: None,
+    };
+    let key = 1u64.to_le_bytes().to_vec();
+    let value = 42u64.to_le_bytes().to_vec();
+    let overrides = StateOverrides {
+        storage: vec![StateOverrideItem { key: key.clone(), value: value.clone() }],
+        ..Default::default()
+    };
+    viewer.apply_state_overrides(&mut root, &contract_id, &overrides).unwrap();
+    let mut logs = vec![];
+    let result = viewer.call_function(
+        root,
+        view_state(),
+        &contract_id,
+        "read_value",
+        &key,
+        &mut logs,
+        &MockEpochInfoProvider::default(),
+    );
+    assert_eq!(result.unwrap(), value);
+
+    let (viewer, mut root) = get_test_trie_viewer();
+    let overrides = StateOverrides {
+        code: Some(near_test_contracts::trivial_contract().to_vec()),
+        ..Default::default()
+    };
+    viewer.apply_state_overrides(&mut root, &contract_id, &overrides).unwrap();
+    let result = viewer.call_function(
+        root,
+        view_state(),
+        &contract_id,
+        "run_test",
+        &[],
+        &mut logs,
+        &MockEpochInfoProvider::default(),
+    );
+    let err = result.unwrap_err();
+    assert!(err.to_string().contains("MethodNotFound"), "Got unexpected error: {}", err);
+
+    let (viewer, mut root) = get_test_trie_viewer();
+    let err = viewer
+        .apply_state_overrides(&mut root, &"missing.near".parse().unwrap(), &overrides)
+        .unwrap_err();
+    assert!(matches!(err, errors::CallFunctionError::AccountDoesNotExist { .. }));
+
+    let (viewer, mut root) = get_test_trie_viewer();
+    let limit = RuntimeConfigStore::new(None)
+        .get_config(PROTOCOL_VERSION)
+        .wasm_config
+        .limit_config
+        .max_contract_size;
+    let overrides =
+        StateOverrides { code: Some(vec![0; limit as usize + 1]), ..Default::default() };
+    let err = viewer.apply_state_overrides(&mut root, &contract_id, &overrides).unwrap_err();
+    assert!(matches!(err, errors::CallFunctionError::ContractTooLarge { .. }));
+}
+
 fn assert_view_state(
     trie_viewer: &TrieViewer,
     state_update: &near_store::TrieUpdate,
@@ -0,0 +1,245 @@
+//! Bootstrapping a node from an epoch checkpoint.
+//!
+//! A checkpoint is what a node would download from its peers during epoch
+//! sync, packed into a single file: the light client block of every finished
+//! epoch since genesis (or since a block the operator trusts) and the data
+//! needed to start header sync from the end of the last of them.  The
+//! checkpoint is verified before it's used: block producer signatures are
+//! checked along the whole epoch chain starting from the genesis validators
+//! (or from the trusted block), so a forged checkpoint is rejected rather than
+//! discovered later when the node fails to sync.
+//!
+//! Checkpoints of `tools/speedy_sync` can't be converted to this format,
+//! since they don't contain the light client blocks needed for verification.
+use crate::{open_storage, NearConfig, NightshadeRuntime};
+use anyhow::Context;
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_chain::types::ChainConfig;
+use near_chain::{
+    get_epoch_block_producers_view, validate_epoch_sync_finalization, validate_light_client_block,
+    validate_next_block_producers, Chain, ChainGenesis, DoomslugThresholdMode,
+};
+use near_primitives::block::BlockHeader;
+use near_primitives::hash::CryptoHash;
+use near_primitives::syncing::{EpochSyncFinalizationResponse, EpochSyncResponse};
+use near_primitives::types::EpochId;
+use near_primitives::views::validator_stake_view::ValidatorStakeView;
+use near_primitives::views::{LightClientBlockLiteView, LightClientBlockView};
+use std::path::Path;
+use std::sync::Arc;
+use tracing::info;
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct EpochCheckpoint {
+    /// Light client blocks of the last final block of every finished epoch,
+    /// in order, starting with the genesis epoch or with the trusted block
+    /// the checkpoint was created from.
+    pub light_client_blocks: Vec<LightClientBlockView>,
+    /// Headers and epoch manager data at the end of the epoch of the last
+    /// light client block, to initialize the chain with.
+    pub finalization: EpochSyncFinalizationResponse,
+}
+
+impl EpochCheckpoint {
+    /// Collects the checkpoint up to the last finished epoch known to `chain`.
+    ///
+    /// If `trusted_hash` is given, the checkpoint starts with the light client
+    /// block with this hash, which must be the last final block of an epoch,
+    /// and has to be verified with the same trusted hash.  This keeps the
+    /// checkpoint small.  Otherwise the node must have the light client blocks
+    /// of all epochs since genesis, i.e. it must not have been bootstrapped
+    /// with state sync or from another checkpoint.
+    pub fn create(chain: &Chain, trusted_hash: Option<&CryptoHash>) -> anyhow::Result<Self> {
+        let mut light_client_blocks = vec![];
+        let mut epoch_id = match trusted_hash {
+            Some(trusted_hash) => chain
+                .get_block_header(trusted_hash)
+                .with_context(|| format!("trusted block {} is unknown", trusted_hash))?
+                .epoch_id()
+                .clone(),
+            None => chain.genesis().epoch_id().clone(),
+        };
+        loop {
+            let response = chain
+                .get_epoch_sync_response(&epoch_id)
+                .with_context(|| format!("failed reading light client block of {:?}", epoch_id))?;
+            match response {
+                EpochSyncResponse::UpToDate => break,
+                EpochSyncResponse::Advance { light_client_block_view } => {
+                    epoch_id = EpochId(light_client_block_view.inner_lite.next_epoch_id);
+                    light_client_blocks.push(light_client_block_view);
+                }
+            }
+        }
+        let last_block =
+            light_client_blocks.last().context("the chain hasn't finished the epoch yet")?;
+        let final_block_hash = lite_view(last_block).hash();
+        match trusted_hash {
+            Some(trusted_hash) => {
+                if &lite_view(&light_client_blocks[0]).hash() != trusted_hash {
+                    anyhow::bail!(
+                        "trusted block {} is not the last final block of its epoch",
+                        trusted_hash
+                    );
+                }
+            }
+            None => {
+                if chain.genesis().epoch_id().0 != light_client_blocks[0].inner_lite.epoch_id {
+                    anyhow::bail!("light client blocks don't start at genesis");
+                }
+            }
+        }
+        let finalization = chain
+            .get_epoch_sync_finalization_response(&final_block_hash)
+            .context("failed collecting finalization data")?;
+        Ok(Self { light_client_blocks, finalization })
+    }
+
+    pub fn read(path: &Path) -> anyhow::Result<Self> {
+        let data = std::fs::read(path)
+            .with_context(|| format!("failed reading checkpoint {}", path.display()))?;
+        Self::try_from_slice(&data)
+            .with_context(|| format!("failed parsing checkpoint {}", path.display()))
+    }
+
+    pub fn write(&self, path: &Path) -> anyhow::Result<()> {
+        std::fs::write(path, self.try_to_vec()?)
+            .with_context(|| format!("failed writing checkpoint {}", path.display()))
+    }
+
+    /// Height of the block header sync continues from.
+    pub fn height(&self) -> u64 {
+        self.finalization.header_sync_init_header.height()
+    }
+
+    /// Verifies the checkpoint against the genesis of the chain.
+    ///
+    /// Every light client block must be signed by more than 2/3 of the
+    /// block producers announced by the previous one, starting with the
+    /// block producers of the genesis epoch.  If `trusted_hash` is given, the
+    /// light client blocks up to and including the one with this hash are
+    /// taken as is and the verification starts from there.
+    pub fn verify(
+        &self,
+        genesis_header: &BlockHeader,
+        genesis_block_producers: Vec<ValidatorStakeView>,
+        trusted_hash: Option<&CryptoHash>,
+    ) -> anyhow::Result<()> {
+        let mut head: LightClientBlockLiteView = genesis_header.clone().into();
+        let mut epoch_id = genesis_header.epoch_id().clone();
+        let mut block_producers = genesis_block_producers;
+        let mut light_client_blocks = self.light_client_blocks.iter();
+
+        match (trusted_hash, self.light_client_blocks.first()) {
+            (None, Some(first)) if first.inner_lite.epoch_id != epoch_id.0 => {
+                anyhow::bail!("checkpoint doesn't start at genesis, it needs a trusted hash")
+            }
+            _ => {}
+        }
+        if let Some(trusted_hash) = trusted_hash {
+            loop {
+                let block = light_client_blocks.next().with_context(|| {
+                    format!("trusted block {} is not in the checkpoint", trusted_hash)
+                })?;
+                let lite = lite_view(block);
+                if &lite.hash() == trusted_hash {
+                    validate_next_block_producers(block).with_context(|| {
+                        format!("invalid next block producers in trusted block {}", trusted_hash)
+                    })?;
+                    epoch_id = EpochId(block.inner_lite.next_epoch_id);
+                    block_producers = block.next_bps.clone().with_context(|| {
+                        format!("trusted block {} has no next block producers", trusted_hash)
+                    })?;
+                    head = lite;
+                    break;
+                }
+            }
+        }
+
+        for block in light_client_blocks {
+            let height = block.inner_lite.height;
+            if block.inner_lite.epoch_id != epoch_id.0 {
+                anyhow::bail!(
+                    "light client block at height {} is from an unexpected epoch",
+                    height
+                );
+            }
+            validate_light_client_block(&head, block, &block_producers)
+                .with_context(|| format!("invalid light client block at height {}", height))?;
+            block_producers = block.next_bps.clone().with_context(|| {
+                format!("light client block at height {} has no next block producers", height)
+            })?;
+            epoch_id = EpochId(block.inner_lite.next_epoch_id);
+            head = lite_view(block);
+        }
+        if &head.hash() == genesis_header.hash() {
+            anyhow::bail!("checkpoint doesn't contain any light client blocks");
+        }
+
+        validate_epoch_sync_finalization(&head.hash(), &block_producers, &self.finalization)
+            .context("invalid finalization data")?;
+        Ok(())
+    }
+}
+
+fn lite_view(block: &LightClientBlockView) -> LightClientBlockLiteView {
+    LightClientBlockLiteView {
+        prev_block_hash: block.prev_block_hash,
+        inner_rest_hash: block.inner_rest_hash,
+        inner_lite: block.inner_lite.clone(),
+    }
+}
+
+/// Verifies the checkpoint at `path` and initializes the node's storage with
+/// it, so that once started the node continues with header sync from the end
+/// of the checkpoint and then with state sync.
+///
+/// Does nothing if the node already has headers past genesis, which makes it
+/// safe to keep `--bootstrap-checkpoint` on the command line across restarts.
+pub fn bootstrap_from_checkpoint(
+    home_dir: &Path,
+    near_config: &mut NearConfig,
+    path: &Path,
+    trusted_hash: Option<&CryptoHash>,
+) -> anyhow::Result<()> {
+    if near_config.client_config.archive {
+        anyhow::bail!("archival nodes need the whole chain and cannot start from a checkpoint");
+    }
+    let checkpoint = EpochCheckpoint::read(path)?;
+
+    let storage = open_storage(home_dir, near_config)?;
+    let runtime =
+        Arc::new(NightshadeRuntime::from_config(home_dir, storage.get_hot_store(), near_config));
+    // This initializes the storage with the genesis block if it's empty.
+    let mut chain = Chain::new(
+        runtime.clone(),
+        &ChainGenesis::new(&near_config.genesis),
+        DoomslugThresholdMode::TwoThirds,
+        ChainConfig {
+            save_trie_changes: near_config.client_config.save_trie_changes,
+            background_migration_threads: 1,
+            account_activity_index: near_config.client_config.account_activity_index.is_some(),
+            event_index: near_config.client_config.event_index.is_some(),
+        },
+    )?;
+    let header_head = chain.header_head()?;
+    if header_head.height != chain.genesis().height() {
+        info!(target: "neard", height = header_head.height, "Node is past genesis, not bootstrapping from checkpoint");
+        return Ok(());
+    }
+
+    let genesis_header = chain.genesis().clone();
+    let genesis_block_producers = get_epoch_block_producers_view(
+        genesis_header.epoch_id(),
+        genesis_header.hash(),
+        &*runtime,
+    )?;
+    checkpoint
+        .verify(&genesis_header, genesis_block_producers, trusted_hash)
+        .with_context(|| format!("checkpoint {} failed verification", path.display()))?;
+
+    let height = checkpoint.height();
+    chain.init_after_epoch_sync(checkpoint.finalization)?;
+    info!(target: "neard", height, epochs = checkpoint.light_client_blocks.len(), "Bootstrapped from checkpoint");
+    Ok(())
+}
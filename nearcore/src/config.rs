@@ -205,6 +205,15 @@ fn default_trie_viewer_state_size_limit() -> Option<u64> {
     Some(50_000)
 }
 
+/// Configuration of epoch sync.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default)]
+pub struct EpochSyncConfig {
+    /// Whether a node far behind the network syncs epochs before syncing headers. Epoch sync is
+    /// experimental, so it is disabled unless explicitly enabled.
+    #[serde(default)]
+    pub enabled: bool,
+}
+
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
 pub struct Consensus {
     /// Minimum number of peers to start syncing.
@@ -323,7 +332,10 @@ pub struct Config {
     pub gc: GCConfig,
     #[serde(default = "default_view_client_threads")]
     pub view_client_threads: usize,
-    pub epoch_sync_enabled: bool,
+    /// Replaces `epoch_sync_enabled`, which is ignored as it was set to true in the configs
+    /// generated before epoch sync became opt-in.
+    #[serde(default)]
+    pub epoch_sync: EpochSyncConfig,
     #[serde(default = "default_view_client_throttle_period")]
     pub view_client_throttle_period: Duration,
     #[serde(default = "default_trie_viewer_state_size_limit")]
@@ -340,6 +352,10 @@ pub struct Config {
     /// Configuration for the
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub split_storage: Option<SplitStorageConfig>,
+    /// If set, the node periodically validates the consistency of the data
+    /// written to the storage since the previous validation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub store_validator: Option<StoreValidatorConfig>,
     // TODO(mina86): Remove those two altogether at some point.  We need to be
     // somewhat careful though and make sure that we don’t start silently
     // ignoring this option without users setting corresponding store option.
@@ -353,6 +369,22 @@ pub struct Config {
     pub db_migration_snapshot_path: Option<PathBuf>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub expected_shutdown: Option<BlockHeight>,
+    /// If set, transactions and receipts are indexed by the accounts they touch and can be
+    /// queried with `EXPERIMENTAL_account_activity`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account_activity_index: Option<near_chain_configs::AccountActivityIndexConfig>,
+    /// If set, events (NEP-297) emitted by contracts are indexed and can be queried with
+    /// `EXPERIMENTAL_events`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event_index: Option<near_chain_configs::EventIndexConfig>,
+    /// Background compilation of contracts of shards whose state the node has just synced.
+    #[serde(default)]
+    pub contract_precompilation: near_chain_configs::ContractPrecompilationConfig,
+    /// If set, accounts missing from the sandbox state are imported on demand from another
+    /// network's RPC, as they were at the given block.
+    #[cfg(feature = "sandbox")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sandbox_fork: Option<crate::sandbox_fork::SandboxForkConfig>,
 }
 
 fn is_false(value: &bool) -> bool {
@@ -378,7 +410,7 @@ impl Default for Config {
             save_trie_changes: None,
             log_summary_style: LogSummaryStyle::Colored,
             gc: GCConfig::default(),
-            epoch_sync_enabled: true,
+            epoch_sync: EpochSyncConfig::default(),
             view_client_threads: default_view_client_threads(),
             view_client_throttle_period: default_view_client_throttle_period(),
             trie_viewer_state_size_limit: default_trie_viewer_state_size_limit(),
@@ -388,7 +420,13 @@ impl Default for Config {
             store: near_store::StoreConfig::default(),
             cold_store: None,
             split_storage: None,
+            store_validator: None,
             expected_shutdown: None,
+            account_activity_index: None,
+            event_index: None,
+            contract_precompilation: Default::default(),
+            #[cfg(feature = "sandbox")]
+            sandbox_fork: None,
         }
     }
 }
@@ -436,6 +474,43 @@ impl Default for SplitStorageConfig {
     }
 }
 
+fn default_store_validator_period() -> Duration {
+    Duration::from_secs(60)
+}
+
+fn default_store_validator_max_iteration_duration() -> Duration {
+    Duration::from_secs(1)
+}
+
+fn default_store_validator_height_delay() -> Duration {
+    Duration::from_millis(10)
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct StoreValidatorConfig {
+    /// How long the store validator sleeps between iterations.
+    #[serde(default = "default_store_validator_period")]
+    pub period: Duration,
+    /// How long a single iteration may take.  Data which wasn't validated in
+    /// time is validated by the next iteration.
+    #[serde(default = "default_store_validator_max_iteration_duration")]
+    pub max_iteration_duration: Duration,
+    /// How long the store validator pauses after validating each height, to
+    /// keep its reads from competing with block processing for disk I/O.
+    #[serde(default = "default_store_validator_height_delay")]
+    pub height_delay: Duration,
+}
+
+impl Default for StoreValidatorConfig {
+    fn default() -> Self {
+        StoreValidatorConfig {
+            period: default_store_validator_period(),
+            max_iteration_duration: default_store_validator_max_iteration_duration(),
+            height_delay: default_store_validator_height_delay(),
+        }
+    }
+}
+
 impl Config {
     /// load Config from config.json without panic. Do semantic validation on field values.
     /// If config file issues occur, a ValidationError::ConfigFileError will be returned;
@@ -472,6 +547,12 @@ impl Config {
                         "{}: {field} is deprecated; please remove it from the config file",
                         path.display(),
                     );
+                } else if field == "epoch_sync_enabled" {
+                    warn!(
+                        target: "neard",
+                        "{}: {field} is ignored; epoch sync is enabled with epoch_sync.enabled",
+                        path.display(),
+                    );
                 } else {
                     unrecognised_fields.push(field);
                 }
@@ -690,13 +771,16 @@ impl NearConfig {
                 log_summary_style: config.log_summary_style,
                 gc: config.gc,
                 view_client_threads: config.view_client_threads,
-                epoch_sync_enabled: config.epoch_sync_enabled,
+                epoch_sync_enabled: config.epoch_sync.enabled,
                 view_client_throttle_period: config.view_client_throttle_period,
                 trie_viewer_state_size_limit: config.trie_viewer_state_size_limit,
                 max_gas_burnt_view: config.max_gas_burnt_view,
                 enable_statistics_export: config.store.enable_statistics_export,
                 client_background_migration_threads: config.store.background_migration_threads,
                 flat_storage_creation_period: config.store.flat_storage_creation_period,
+                account_activity_index: config.account_activity_index,
+                event_index: config.event_index,
+                contract_precompilation: config.contract_precompilation,
             },
             network_config: NetworkConfig::new(
                 config.network,
@@ -1670,6 +1754,22 @@ fn test_config_from_file_skip_validation() {
     }
 }
 
+/// Tests that `epoch_sync_enabled`, which configs generated by earlier versions set to true,
+/// doesn't enable epoch sync.
+#[test]
+fn test_epoch_sync_opt_in() {
+    for (json, enabled) in [
+        (r#"{"epoch_sync_enabled": true}"#, false),
+        (r#"{"epoch_sync": {}}"#, false),
+        (r#"{"epoch_sync_enabled": false, "epoch_sync": {"enabled": true}}"#, true),
+    ] {
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        tmp.as_file().write_all(json.as_bytes()).unwrap();
+        let config = Config::from_file_skip_validation(&tmp.into_temp_path()).unwrap();
+        assert_eq!(config.epoch_sync.enabled, enabled, "{}", json);
+    }
+}
+
 #[test]
 fn test_create_testnet_configs() {
     let num_shards = 4;
@@ -1,5 +1,6 @@
 use crate::config::Config;
 use near_chain_configs::UpdateableClientConfig;
+use near_crypto::KeyFile;
 use near_dyn_configs::{UpdateableConfigLoaderError, UpdateableConfigs};
 use near_o11y::log_config::LogConfig;
 use serde::Deserialize;
@@ -20,10 +21,20 @@ pub fn read_updateable_configs(
         }
     };
     let updateable_client_config =
-        match Config::from_file(&home_dir.join(crate::config::CONFIG_FILENAME))
-            .map(get_updateable_client_config)
-        {
-            Ok(config) => Some(config),
+        match Config::from_file(&home_dir.join(crate::config::CONFIG_FILENAME)) {
+            Ok(config) => {
+                let validator_key = match read_validator_key(home_dir, &config) {
+                    Ok(validator_key) => validator_key,
+                    Err(err) => {
+                        errs.push(err);
+                        None
+                    }
+                };
+                Some(UpdateableClientConfig {
+                    validator_key,
+                    ..get_updateable_client_config(config)
+                })
+            }
             Err(err) => {
                 errs.push(UpdateableConfigLoaderError::ConfigFileError {
                     file: PathBuf::from(crate::config::CONFIG_FILENAME),
@@ -45,7 +56,21 @@ pub fn read_updateable_configs(
 pub fn get_updateable_client_config(config: Config) -> UpdateableClientConfig {
     // All fields that can be updated while the node is running should be explicitly set here.
     // Keep this list in-sync with `core/dyn-configs/README.md`.
-    UpdateableClientConfig { expected_shutdown: config.expected_shutdown }
+    UpdateableClientConfig { expected_shutdown: config.expected_shutdown, validator_key: None }
+}
+
+/// Reads the validator key, so that the node can switch to a rotated key without a restart.
+/// Returns `None` if the node has no validator key.
+fn read_validator_key(
+    home_dir: &Path,
+    config: &Config,
+) -> Result<Option<KeyFile>, UpdateableConfigLoaderError> {
+    let path = home_dir.join(&config.validator_key_file);
+    match KeyFile::from_file(&path) {
+        Ok(key_file) => Ok(Some(key_file)),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(UpdateableConfigLoaderError::OpenAndRead { file: path, err }),
+    }
 }
 
 fn read_log_config(home_dir: &Path) -> Result<Option<LogConfig>, UpdateableConfigLoaderError> {
@@ -13,6 +13,7 @@ use near_chain::{Chain, ChainGenesis};
 use near_chunks::shards_manager_actor::start_shards_manager;
 use near_client::{start_client, start_view_client, ClientActor, ConfigUpdater, ViewClientActor};
 use near_primitives::time;
+use store_validator::{spawn_store_validator_loop, StoreValidatorLoopHandle};
 
 use near_network::PeerManagerActor;
 use near_primitives::block::GenesisId;
@@ -24,6 +25,7 @@ use std::sync::Arc;
 use tokio::sync::broadcast;
 use tracing::{info, trace};
 pub mod append_only_map;
+pub mod checkpoint;
 mod cold_storage;
 pub mod config;
 mod config_validate;
@@ -32,7 +34,10 @@ pub mod dyn_config;
 mod metrics;
 pub mod migrations;
 mod runtime;
+#[cfg(feature = "sandbox")]
+pub mod sandbox_fork;
 mod shard_tracker;
+mod store_validator;
 
 pub fn get_default_home() -> PathBuf {
     if let Ok(near_home) = std::env::var("NEAR_HOME") {
@@ -188,6 +193,9 @@ pub struct NearNode {
     /// The cold_store_loop_handle will only be set if the cold store is configured.
     /// It's a handle to a background thread that copies data from the hot store to the cold store.
     pub cold_store_loop_handle: Option<ColdStoreLoopHandle>,
+    /// Handle to a background thread that validates the data written to the
+    /// store, only set if the store validator is enabled in the config.
+    pub store_validator_loop_handle: Option<StoreValidatorLoopHandle>,
 }
 
 pub fn start_with_config(home_dir: &Path, config: NearConfig) -> anyhow::Result<NearNode> {
@@ -217,6 +225,7 @@ pub fn start_with_config_and_synchronization(
     };
 
     let cold_store_loop_handle = spawn_cold_store_loop(&config, &store, runtime.clone())?;
+    let store_validator_loop_handle = spawn_store_validator_loop(&config, &store, runtime.clone())?;
 
     let telemetry = TelemetryActor::new(config.telemetry_config.clone()).start();
     let chain_genesis = ChainGenesis::new(&config.genesis);
@@ -312,6 +321,7 @@ pub fn start_with_config_and_synchronization(
         rpc_servers,
         arbiters: vec![client_arbiter_handle, shards_manager_arbiter_handle],
         cold_store_loop_handle,
+        store_validator_loop_handle,
     })
 }
 
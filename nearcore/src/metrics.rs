@@ -44,3 +44,37 @@ pub(crate) static COLD_STORE_COPY_RESULT: Lazy<IntCounterVec> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+pub(crate) static STORE_VALIDATOR_HEIGHT: Lazy<IntGauge> = Lazy::new(|| {
+    try_create_int_gauge(
+        "near_store_validator_height",
+        "Height the store validator loop continues validating from.",
+    )
+    .unwrap()
+});
+
+pub(crate) static STORE_VALIDATOR_ERRORS: Lazy<IntCounterVec> = Lazy::new(|| {
+    try_create_int_counter_vec(
+        "near_store_validator_errors_total",
+        "Number of inconsistencies found by the store validator loop, by column.",
+        &["col"],
+    )
+    .unwrap()
+});
+
+pub(crate) static CONTRACT_PRECOMPILATION_TASKS: Lazy<IntGauge> = Lazy::new(|| {
+    try_create_int_gauge(
+        "near_contract_precompilation_tasks",
+        "Number of shards whose contracts are queued for or being compiled in the background.",
+    )
+    .unwrap()
+});
+
+pub(crate) static CONTRACT_PRECOMPILATION_CONTRACTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    try_create_int_counter_vec(
+        "near_contract_precompilation_contracts_total",
+        "Number of contracts processed by the background compilation of synced shards, by result.",
+        &["shard_id", "result"],
+    )
+    .unwrap()
+});
@@ -20,6 +20,13 @@ impl QueryError {
             node_runtime::state_viewer::errors::CallFunctionError::VMError { error_message } => {
                 Self::ContractExecutionError { error_message, block_height, block_hash }
             }
+            error @ node_runtime::state_viewer::errors::CallFunctionError::ContractTooLarge {
+                ..
+            } => Self::ContractExecutionError {
+                error_message: error.to_string(),
+                block_height,
+                block_hash,
+            },
         }
     }
 
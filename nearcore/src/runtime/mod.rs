@@ -10,8 +10,8 @@ use near_chain::types::{
 };
 use near_chain::{Error, RuntimeWithEpochManagerAdapter};
 use near_chain_configs::{
-    Genesis, GenesisConfig, ProtocolConfig, DEFAULT_GC_NUM_EPOCHS_TO_KEEP,
-    MIN_GC_NUM_EPOCHS_TO_KEEP,
+    ContractPrecompilationConfig, Genesis, GenesisConfig, ProtocolConfig,
+    DEFAULT_GC_NUM_EPOCHS_TO_KEEP, MIN_GC_NUM_EPOCHS_TO_KEEP,
 };
 use near_client_primitives::types::StateSplitApplyingStatus;
 use near_crypto::PublicKey;
@@ -34,19 +34,22 @@ use near_primitives::shard_layout::{
     account_id_to_shard_id, account_id_to_shard_uid, ShardLayout, ShardUId,
 };
 use near_primitives::state_part::PartId;
-use near_primitives::state_record::{state_record_to_account_id, StateRecord};
+use near_primitives::state_record::{
+    is_contract_code_key, state_record_to_account_id, StateRecord,
+};
 use near_primitives::syncing::{get_num_state_parts, STATE_PART_MEMORY_LIMIT};
 use near_primitives::transaction::SignedTransaction;
+use near_primitives::trie_key::trie_key_parsers;
 use near_primitives::types::validator_stake::ValidatorStakeIter;
 use near_primitives::types::{
     AccountId, Balance, BlockHeight, CompiledContractCache, EpochHeight, EpochId,
-    EpochInfoProvider, Gas, MerkleHash, NumShards, ShardId, StateChangeCause,
-    StateChangesForSplitStates, StateRoot, StateRootNode,
+    EpochInfoProvider, Gas, MerkleHash, NumShards, RawStateChangesWithTrieKey, ShardId,
+    StateChangeCause, StateChangesForSplitStates, StateRoot, StateRootNode,
 };
 use near_primitives::version::ProtocolVersion;
 use near_primitives::views::{
-    AccessKeyInfoView, CallResult, QueryRequest, QueryResponse, QueryResponseKind, ViewApplyState,
-    ViewStateResult,
+    AccessKeyInfoView, CallResult, QueryRequest, QueryResponse, QueryResponseKind, StateOverrides,
+    ViewApplyState, ViewStateResult,
 };
 use near_store::flat::{
     store_helper, ChainAccessForFlatStorage, FlatStorage, FlatStorageCreationStatus,
@@ -57,9 +60,9 @@ use near_store::split_state::get_delayed_receipts;
 use near_store::{
     get_genesis_hash, get_genesis_state_roots, set_genesis_hash, set_genesis_state_roots,
     ApplyStatePartResult, DBCol, PartialStorage, ShardTries, Store, StoreCompiledContractCache,
-    StoreUpdate, Trie, TrieConfig, WrappedTrieChanges, COLD_HEAD_KEY,
+    StoreUpdate, Trie, TrieChanges, TrieConfig, TrieUpdate, WrappedTrieChanges, COLD_HEAD_KEY,
 };
-use near_vm_runner::precompile_contract;
+use near_vm_runner::{precompile_contract, ContractPrecompilatonResult};
 use node_runtime::adapter::ViewRuntimeAdapter;
 use node_runtime::config::RuntimeConfig;
 use node_runtime::state_viewer::TrieViewer;
@@ -70,7 +73,7 @@ use node_runtime::{
 use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::Path;
-use std::sync::{Arc, RwLockReadGuard, RwLockWriteGuard};
+use std::sync::{Arc, Mutex, RwLockReadGuard, RwLockWriteGuard};
 use std::time::Instant;
 use tracing::{debug, error, info, warn};
 
@@ -95,8 +98,21 @@ pub struct NightshadeRuntime {
     genesis_state_roots: Vec<StateRoot>,
     migration_data: Arc<MigrationData>,
     gc_num_epochs_to_keep: u64,
+    contract_precompilation: ContractPrecompilationConfig,
+    /// Queue of the thread compiling contracts of synced shards one shard at a time. The thread
+    /// is started by the first `precompile_contracts_in_background` call and stops once the
+    /// runtime is dropped.
+    contract_precompilation_worker:
+        Mutex<Option<std::sync::mpsc::Sender<ContractPrecompilationTask>>>,
+    /// Source of the accounts missing from the state, see `crate::sandbox_fork`.
+    #[cfg(feature = "sandbox")]
+    sandbox_fork: Option<crate::sandbox_fork::SandboxFork>,
 }
 
+/// Changes made by importing the accounts touched by a chunk from the network the sandbox is
+/// forked from, see `NightshadeRuntime::sandbox_fork_import_chunk_accounts`.
+type SandboxForkImport = Option<(TrieChanges, Vec<RawStateChangesWithTrieKey>)>;
+
 impl NightshadeRuntime {
     pub fn from_config(home_dir: &Path, store: Store, config: &NearConfig) -> Self {
         Self::new(
@@ -109,7 +125,24 @@ impl NightshadeRuntime {
             None,
             config.config.gc.gc_num_epochs_to_keep(),
             TrieConfig::from_store_config(&config.config.store),
+            config.client_config.contract_precompilation.clone(),
         )
+        .with_sandbox_fork(config)
+    }
+
+    #[cfg(feature = "sandbox")]
+    fn with_sandbox_fork(self, config: &NearConfig) -> Self {
+        let sandbox_fork = config.config.sandbox_fork.as_ref().map(|fork_config| {
+            crate::sandbox_fork::SandboxFork::new(fork_config, self.store.clone())
+                .expect("Failed to start the sandbox fork")
+        });
+        Self { sandbox_fork, ..self }
+    }
+
+    #[cfg(not(feature = "sandbox"))]
+    #[inline(always)]
+    fn with_sandbox_fork(self, _config: &NearConfig) -> Self {
+        self
     }
 
     fn new(
@@ -122,6 +155,7 @@ impl NightshadeRuntime {
         runtime_config_store: Option<RuntimeConfigStore>,
         gc_num_epochs_to_keep: u64,
         trie_config: TrieConfig,
+        contract_precompilation: ContractPrecompilationConfig,
     ) -> Self {
         let runtime_config_store = match runtime_config_store {
             Some(store) => store,
@@ -164,6 +198,10 @@ impl NightshadeRuntime {
             genesis_state_roots: state_roots,
             migration_data: Arc::new(load_migration_data(&genesis.config.chain_id)),
             gc_num_epochs_to_keep: gc_num_epochs_to_keep.max(MIN_GC_NUM_EPOCHS_TO_KEEP),
+            contract_precompilation,
+            contract_precompilation_worker: Mutex::new(None),
+            #[cfg(feature = "sandbox")]
+            sandbox_fork: None,
         }
     }
 
@@ -184,6 +222,7 @@ impl NightshadeRuntime {
             Some(runtime_config_store),
             DEFAULT_GC_NUM_EPOCHS_TO_KEEP,
             Default::default(),
+            Default::default(),
         )
     }
 
@@ -371,6 +410,136 @@ impl NightshadeRuntime {
         Ok(ShardUId::from_shard_id_and_layout(shard_id, &shard_layout))
     }
 
+    /// Imports the given accounts from the network the sandbox is forked from if they don't exist
+    /// in `state_update` yet, see `crate::sandbox_fork`. Unless `wait` is set, only the accounts
+    /// fetched already are imported.
+    #[cfg(feature = "sandbox")]
+    fn sandbox_fork_import<'a>(
+        &self,
+        state_update: &mut TrieUpdate,
+        account_ids: impl IntoIterator<Item = &'a AccountId>,
+        wait: bool,
+    ) -> Result<(), StorageError> {
+        if let Some(sandbox_fork) = &self.sandbox_fork {
+            let patch = sandbox_fork.missing_accounts_patch(&*state_update, account_ids, wait)?;
+            self.runtime.apply_state_patch(state_update, patch);
+        }
+        Ok(())
+    }
+
+    #[cfg(not(feature = "sandbox"))]
+    #[inline(always)]
+    fn sandbox_fork_import<'a>(
+        &self,
+        _state_update: &mut TrieUpdate,
+        _account_ids: impl IntoIterator<Item = &'a AccountId>,
+        _wait: bool,
+    ) -> Result<(), StorageError> {
+        Ok(())
+    }
+
+    /// Imports the fetched accounts of the shard touched by the chunk which don't exist yet, see
+    /// `crate::sandbox_fork`. Returns the trie to apply the chunk to and the changes made by the
+    /// import, which are stored together with the ones of the chunk.
+    #[cfg(feature = "sandbox")]
+    fn sandbox_fork_import_chunk_accounts(
+        &self,
+        trie: Trie,
+        shard_id: ShardId,
+        epoch_id: &EpochId,
+        receipts: &[Receipt],
+        transactions: &[SignedTransaction],
+    ) -> Result<(Trie, SandboxForkImport), Error> {
+        let sandbox_fork = match &self.sandbox_fork {
+            Some(sandbox_fork) => sandbox_fork,
+            None => return Ok((trie, None)),
+        };
+        let shard_layout = self.epoch_manager.read().get_shard_layout(epoch_id)?;
+        let account_ids = transactions
+            .iter()
+            .flat_map(|tx| [&tx.transaction.signer_id, &tx.transaction.receiver_id])
+            .chain(receipts.iter().map(|receipt| &receipt.receiver_id))
+            .filter(|account_id| account_id_to_shard_id(account_id, &shard_layout) == shard_id);
+        let patch = sandbox_fork
+            .missing_accounts_patch(&trie, account_ids, false)
+            .map_err(Error::StorageError)?;
+        if patch.is_empty() {
+            return Ok((trie, None));
+        }
+        let trie = std::rc::Rc::new(trie);
+        let mut state_update = TrieUpdate::new(trie.clone());
+        self.runtime.apply_state_patch(&mut state_update, patch);
+        let (trie_changes, state_changes) = state_update.finalize().map_err(Error::StorageError)?;
+        let trie = std::rc::Rc::try_unwrap(trie)
+            .map_err(|_| Error::Other("Trie is still used after the import".to_string()))?;
+        Ok((trie.with_changes(&trie_changes), Some((trie_changes, state_changes))))
+    }
+
+    #[cfg(not(feature = "sandbox"))]
+    #[inline(always)]
+    fn sandbox_fork_import_chunk_accounts(
+        &self,
+        trie: Trie,
+        _shard_id: ShardId,
+        _epoch_id: &EpochId,
+        _receipts: &[Receipt],
+        _transactions: &[SignedTransaction],
+    ) -> Result<(Trie, SandboxForkImport), Error> {
+        Ok((trie, None))
+    }
+
+    /// Puts the changes of the import of the chunk accounts before the changes of the chunk,
+    /// records the accounts deleted by the chunk and requests the receivers of its outgoing
+    /// receipts, see `crate::sandbox_fork`.
+    #[cfg(feature = "sandbox")]
+    fn sandbox_fork_finish_chunk(
+        &self,
+        import: SandboxForkImport,
+        trie_changes: TrieChanges,
+        state_changes: Vec<RawStateChangesWithTrieKey>,
+        outgoing_receipts: &[Receipt],
+    ) -> Result<(TrieChanges, Vec<RawStateChangesWithTrieKey>), Error> {
+        let sandbox_fork = match &self.sandbox_fork {
+            Some(sandbox_fork) => sandbox_fork,
+            None => return Ok((trie_changes, state_changes)),
+        };
+        let (trie_changes, state_changes) = match import {
+            Some((import_trie_changes, import_state_changes)) => {
+                let mut merged: std::collections::BTreeMap<_, _> = import_state_changes
+                    .into_iter()
+                    .map(|changes| (changes.trie_key.to_vec(), changes))
+                    .collect();
+                for changes in state_changes {
+                    match merged.entry(changes.trie_key.to_vec()) {
+                        std::collections::btree_map::Entry::Occupied(mut entry) => {
+                            entry.get_mut().changes.extend(changes.changes)
+                        }
+                        std::collections::btree_map::Entry::Vacant(entry) => {
+                            entry.insert(changes);
+                        }
+                    }
+                }
+                (import_trie_changes.then(trie_changes), merged.into_values().collect())
+            }
+            None => (trie_changes, state_changes),
+        };
+        sandbox_fork.record_deleted_accounts(&state_changes).map_err(Error::StorageError)?;
+        sandbox_fork.request(outgoing_receipts.iter().map(|receipt| &receipt.receiver_id));
+        Ok((trie_changes, state_changes))
+    }
+
+    #[cfg(not(feature = "sandbox"))]
+    #[inline(always)]
+    fn sandbox_fork_finish_chunk(
+        &self,
+        _import: SandboxForkImport,
+        trie_changes: TrieChanges,
+        state_changes: Vec<RawStateChangesWithTrieKey>,
+        _outgoing_receipts: &[Receipt],
+    ) -> Result<(TrieChanges, Vec<RawStateChangesWithTrieKey>), Error> {
+        Ok((trie_changes, state_changes))
+    }
+
     /// Processes state update.
     fn process_state_update(
         &self,
@@ -393,6 +562,13 @@ impl NightshadeRuntime {
     ) -> Result<ApplyTransactionResult, Error> {
         let _span = tracing::debug_span!(target: "runtime", "process_state_update").entered();
         let epoch_id = self.get_epoch_id_from_prev_block(prev_block_hash)?;
+        let (trie, sandbox_fork_import) = self.sandbox_fork_import_chunk_accounts(
+            trie,
+            shard_id,
+            &epoch_id,
+            receipts,
+            transactions,
+        )?;
         let validator_accounts_update = {
             let epoch_manager = self.epoch_manager.read();
             let shard_layout = epoch_manager.get_shard_layout(&epoch_id)?;
@@ -549,13 +725,19 @@ impl NightshadeRuntime {
             })?;
 
         let shard_uid = self.get_shard_uid_from_prev_hash(shard_id, prev_block_hash)?;
+        let (trie_changes, state_changes) = self.sandbox_fork_finish_chunk(
+            sandbox_fork_import,
+            apply_result.trie_changes,
+            apply_result.state_changes,
+            &apply_result.outgoing_receipts,
+        )?;
 
         let result = ApplyTransactionResult {
             trie_changes: WrappedTrieChanges::new(
                 self.get_tries(),
                 shard_uid,
-                apply_result.trie_changes,
-                apply_result.state_changes,
+                trie_changes,
+                state_changes,
                 *block_hash,
             ),
             new_root: apply_result.state_root,
@@ -571,48 +753,46 @@ impl NightshadeRuntime {
         Ok(result)
     }
 
-    fn precompile_contracts(
+    fn contract_precompilation_task(
         &self,
+        shard_id: ShardId,
+        state_root: &StateRoot,
         epoch_id: &EpochId,
-        contract_codes: Vec<ContractCode>,
+        next_epoch_id: &EpochId,
+    ) -> Result<ContractPrecompilationTask, Error> {
+        let shard_uid = self.get_shard_uid_from_epoch_id(shard_id, epoch_id)?;
+        let mut protocol_versions = vec![self.get_epoch_protocol_version(epoch_id)?];
+        let next_protocol_version = self.get_epoch_protocol_version(next_epoch_id)?;
+        if next_protocol_version != protocol_versions[0] {
+            protocol_versions.push(next_protocol_version);
+        }
+        let runtime_configs = protocol_versions
+            .into_iter()
+            .map(|protocol_version| {
+                (protocol_version, self.runtime_config_store.get_config(protocol_version).clone())
+            })
+            .collect();
+        Ok(ContractPrecompilationTask {
+            shard_id,
+            shard_uid,
+            state_root: *state_root,
+            tries: self.tries.clone(),
+            store: self.store.clone(),
+            runtime_configs,
+            max_threads: self.contract_precompilation.max_threads,
+        })
+    }
+
+    /// Compiles all contracts in the state of the shard for the protocol versions of `epoch_id`
+    /// and `next_epoch_id`, returning once all of them are in the compiled contract cache.
+    pub fn precompile_shard_contracts(
+        &self,
+        shard_id: ShardId,
+        state_root: &StateRoot,
+        epoch_id: &EpochId,
+        next_epoch_id: &EpochId,
     ) -> Result<(), Error> {
-        let _span = tracing::debug_span!(
-            target: "runtime",
-            "precompile_contracts",
-            num_contracts = contract_codes.len())
-        .entered();
-        let protocol_version = self.get_epoch_protocol_version(epoch_id)?;
-        let runtime_config = self.runtime_config_store.get_config(protocol_version);
-        let compiled_contract_cache: Option<Box<dyn CompiledContractCache>> =
-            Some(Box::new(StoreCompiledContractCache::new(&self.store)));
-        // Execute precompile_contract in parallel but prevent it from using more than half of all
-        // threads so that node will still function normally.
-        rayon::scope(|scope| {
-            let (slot_sender, slot_receiver) = std::sync::mpsc::channel();
-            // Use up-to half of the threads for the compilation.
-            let max_threads = std::cmp::max(rayon::current_num_threads() / 2, 1);
-            for _ in 0..max_threads {
-                slot_sender.send(()).expect("both sender and receiver are owned here");
-            }
-            for code in contract_codes {
-                slot_receiver.recv().expect("could not receive a slot to compile contract");
-                let contract_cache = compiled_contract_cache.as_deref();
-                let slot_sender = slot_sender.clone();
-                scope.spawn(move |_| {
-                    precompile_contract(
-                        &code,
-                        &runtime_config.wasm_config,
-                        protocol_version,
-                        contract_cache,
-                    )
-                    .ok();
-                    // If this fails, it just means there won't be any more attempts to recv the
-                    // slots
-                    let _ = slot_sender.send(());
-                });
-            }
-        });
-        Ok(())
+        self.contract_precompilation_task(shard_id, state_root, epoch_id, next_epoch_id)?.run()
     }
 
     fn get_gc_stop_height_impl(&self, block_hash: &CryptoHash) -> Result<BlockHeight, Error> {
@@ -694,6 +874,89 @@ impl near_epoch_manager::HasEpochMangerHandle for NightshadeRuntime {
     }
 }
 
+/// Compilation of all contracts in the state of a shard into the compiled contract cache.
+struct ContractPrecompilationTask {
+    shard_id: ShardId,
+    shard_uid: ShardUId,
+    state_root: StateRoot,
+    tries: ShardTries,
+    store: Store,
+    /// Protocol versions to compile the contracts for, with their runtime configs.
+    runtime_configs: Vec<(ProtocolVersion, Arc<RuntimeConfig>)>,
+    /// Maximum number of contracts compiled at the same time.
+    max_threads: Option<usize>,
+}
+
+impl ContractPrecompilationTask {
+    fn run(self) -> Result<(), Error> {
+        let _span = tracing::debug_span!(
+            target: "runtime",
+            "precompile_shard_contracts",
+            shard_id = self.shard_id)
+        .entered();
+        let trie = self.tries.get_view_trie_for_shard(self.shard_uid, self.state_root);
+        let mut iter = trie.iter()?;
+        iter.seek_prefix(trie_key_parsers::get_raw_prefix_for_contract_codes())?;
+        let mut storage_error = None;
+        // The same code is usually deployed to many accounts, it only needs to be compiled once.
+        let mut code_hashes = HashSet::new();
+        let contract_codes = iter.map_while(|item| match item {
+            Ok((key, value)) if is_contract_code_key(&key) => Some(ContractCode::new(value, None)),
+            Ok(_) => None,
+            Err(err) => {
+                storage_error = Some(err);
+                None
+            }
+        });
+        let contract_codes = contract_codes.filter(|code| code_hashes.insert(*code.hash()));
+        let compiled_contract_cache = StoreCompiledContractCache::new(&self.store);
+        // Execute precompile_contract in parallel but by default prevent it from using more than
+        // half of all threads so that node will still function normally. The contracts are read
+        // from the trie on this thread, hence the in-place scope.
+        let max_threads =
+            self.max_threads.unwrap_or_else(|| rayon::current_num_threads() / 2).max(1);
+        let shard_label = self.shard_id.to_string();
+        rayon::in_place_scope(|scope| {
+            let (slot_sender, slot_receiver) = std::sync::mpsc::channel();
+            for _ in 0..max_threads {
+                slot_sender.send(()).expect("both sender and receiver are owned here");
+            }
+            for code in contract_codes {
+                slot_receiver.recv().expect("could not receive a slot to compile contract");
+                let contract_cache: &dyn CompiledContractCache = &compiled_contract_cache;
+                let runtime_configs = &self.runtime_configs;
+                let shard_label = shard_label.as_str();
+                let slot_sender = slot_sender.clone();
+                scope.spawn(move |_| {
+                    for (protocol_version, runtime_config) in runtime_configs {
+                        let result = match precompile_contract(
+                            &code,
+                            &runtime_config.wasm_config,
+                            *protocol_version,
+                            Some(contract_cache),
+                        ) {
+                            Ok(Ok(ContractPrecompilatonResult::ContractCompiled)) => "compiled",
+                            Ok(Ok(_)) => "cached",
+                            Ok(Err(_)) => "invalid",
+                            Err(_) => "failed",
+                        };
+                        metrics::CONTRACT_PRECOMPILATION_CONTRACTS
+                            .with_label_values(&[shard_label, result])
+                            .inc();
+                    }
+                    // If this fails, it just means there won't be any more attempts to recv the
+                    // slots
+                    let _ = slot_sender.send(());
+                });
+            }
+        });
+        match storage_error {
+            Some(err) => Err(err.into()),
+            None => Ok(()),
+        }
+    }
+}
+
 impl RuntimeAdapter for NightshadeRuntime {
     fn genesis_state(&self) -> (Store, Vec<StateRoot>) {
         (self.store.clone(), self.genesis_state_roots.clone())
@@ -801,6 +1064,12 @@ impl RuntimeAdapter for NightshadeRuntime {
             let shard_uid =
                 self.account_id_to_shard_uid(&transaction.transaction.signer_id, epoch_id)?;
             let mut state_update = self.tries.new_trie_update(shard_uid, state_root);
+            self.sandbox_fork_import(
+                &mut state_update,
+                [&transaction.transaction.signer_id],
+                false,
+            )
+            .map_err(Error::StorageError)?;
 
             match verify_and_charge_transaction(
                 runtime_config,
@@ -883,6 +1152,12 @@ impl RuntimeAdapter for NightshadeRuntime {
                     num_checked_transactions += 1;
                     // Verifying the transaction is on the same chain and hasn't expired yet.
                     if chain_validate(&tx) {
+                        self.sandbox_fork_import(
+                            &mut state_update,
+                            [&tx.transaction.signer_id],
+                            false,
+                        )
+                        .map_err(Error::StorageError)?;
                         // Verifying the validity of the transaction based on the current state.
                         match verify_and_charge_transaction(
                             runtime_config,
@@ -1118,7 +1393,7 @@ impl RuntimeAdapter for NightshadeRuntime {
                     block_hash: *block_hash,
                 })
             }
-            QueryRequest::CallFunction { account_id, method_name, args } => {
+            QueryRequest::CallFunction { account_id, method_name, args, state_overrides } => {
                 let mut logs = vec![];
                 let (epoch_height, current_protocol_version) = {
                     let epoch_manager = self.epoch_manager.read();
@@ -1145,6 +1420,7 @@ impl RuntimeAdapter for NightshadeRuntime {
                         account_id,
                         method_name,
                         args.as_ref(),
+                        state_overrides.as_ref(),
                         &mut logs,
                         &self.epoch_manager,
                         current_protocol_version,
@@ -1368,7 +1644,9 @@ impl RuntimeAdapter for NightshadeRuntime {
     ) -> Result<(), Error> {
         let part = BorshDeserialize::try_from_slice(data)
             .expect("Part was already validated earlier, so could never fail here");
-        let ApplyStatePartResult { trie_changes, flat_state_delta, contract_codes } =
+        // Contracts are compiled once all parts are applied, see
+        // `precompile_contracts_in_background`.
+        let ApplyStatePartResult { trie_changes, flat_state_delta } =
             Trie::apply_state_part(state_root, part_id, part);
         let tries = self.get_tries();
         let shard_uid = self.get_shard_uid_from_epoch_id(shard_id, epoch_id)?;
@@ -1378,10 +1656,50 @@ impl RuntimeAdapter for NightshadeRuntime {
             debug!(target: "chain", %shard_id, "Inserting {} values to flat storage", flat_state_delta.len());
             flat_state_delta.apply_to_flat_state(&mut store_update);
         }
-        self.precompile_contracts(epoch_id, contract_codes)?;
         Ok(store_update.commit()?)
     }
 
+    fn precompile_contracts_in_background(
+        &self,
+        shard_id: ShardId,
+        state_root: &StateRoot,
+        epoch_id: &EpochId,
+        next_epoch_id: &EpochId,
+    ) -> Result<(), Error> {
+        if !self.contract_precompilation.enabled {
+            return Ok(());
+        }
+        let task =
+            self.contract_precompilation_task(shard_id, state_root, epoch_id, next_epoch_id)?;
+        let mut worker = self.contract_precompilation_worker.lock().unwrap();
+        let sender = match worker.take() {
+            Some(sender) => sender,
+            None => {
+                let (sender, receiver) = std::sync::mpsc::channel::<ContractPrecompilationTask>();
+                std::thread::Builder::new().name("precompile_contracts".to_string()).spawn(
+                    move || {
+                        for task in receiver {
+                            let shard_id = task.shard_id;
+                            if let Err(err) = task.run() {
+                                warn!(target: "runtime", shard_id, ?err, "Failed to precompile contracts");
+                            }
+                            metrics::CONTRACT_PRECOMPILATION_TASKS.dec();
+                        }
+                    },
+                )?;
+                sender
+            }
+        };
+        metrics::CONTRACT_PRECOMPILATION_TASKS.inc();
+        if sender.send(task).is_err() {
+            // The thread only stops early if it panicked, the next call starts a new one.
+            metrics::CONTRACT_PRECOMPILATION_TASKS.dec();
+            return Err(Error::Other("Contract precompilation thread stopped".to_string()));
+        }
+        *worker = Some(sender);
+        Ok(())
+    }
+
     /// `block_hash` is a block whose `prev_state_root` is `state_root`
     fn get_state_root_node(
         &self,
@@ -1470,7 +1788,8 @@ impl node_runtime::adapter::ViewRuntimeAdapter for NightshadeRuntime {
         state_root: MerkleHash,
         account_id: &AccountId,
     ) -> Result<Account, node_runtime::state_viewer::errors::ViewAccountError> {
-        let state_update = self.tries.new_trie_update_view(*shard_uid, state_root);
+        let mut state_update = self.tries.new_trie_update_view(*shard_uid, state_root);
+        self.sandbox_fork_import(&mut state_update, [account_id], true)?;
         self.trie_viewer.view_account(&state_update, account_id)
     }
 
@@ -1480,7 +1799,8 @@ impl node_runtime::adapter::ViewRuntimeAdapter for NightshadeRuntime {
         state_root: MerkleHash,
         account_id: &AccountId,
     ) -> Result<ContractCode, node_runtime::state_viewer::errors::ViewContractCodeError> {
-        let state_update = self.tries.new_trie_update_view(*shard_uid, state_root);
+        let mut state_update = self.tries.new_trie_update_view(*shard_uid, state_root);
+        self.sandbox_fork_import(&mut state_update, [account_id], true)?;
         self.trie_viewer.view_contract_code(&state_update, account_id)
     }
 
@@ -1497,11 +1817,20 @@ impl node_runtime::adapter::ViewRuntimeAdapter for NightshadeRuntime {
         contract_id: &AccountId,
         method_name: &str,
         args: &[u8],
+        state_overrides: Option<&StateOverrides>,
         logs: &mut Vec<String>,
         epoch_info_provider: &dyn EpochInfoProvider,
         current_protocol_version: ProtocolVersion,
     ) -> Result<Vec<u8>, node_runtime::state_viewer::errors::CallFunctionError> {
-        let state_update = self.tries.new_trie_update_view(*shard_uid, state_root);
+        let mut state_update = self.tries.new_trie_update_view(*shard_uid, state_root);
+        self.sandbox_fork_import(&mut state_update, [contract_id], true)?;
+        if let Some(state_overrides) = state_overrides {
+            self.trie_viewer.apply_state_overrides(
+                &mut state_update,
+                contract_id,
+                state_overrides,
+            )?;
+        }
         let view_state = ViewApplyState {
             block_height: height,
             prev_block_hash: *prev_block_hash,
@@ -1510,7 +1839,13 @@ impl node_runtime::adapter::ViewRuntimeAdapter for NightshadeRuntime {
             epoch_height,
             block_timestamp,
             current_protocol_version,
-            cache: Some(Box::new(StoreCompiledContractCache::new(&self.tries.get_store()))),
+            // Code overrides are arbitrary and used once, so their compiled artifacts are not
+            // worth persisting.
+            cache: if state_overrides.map_or(false, |overrides| overrides.code.is_some()) {
+                None
+            } else {
+                Some(Box::new(StoreCompiledContractCache::new(&self.tries.get_store())))
+            },
         };
         self.trie_viewer.call_function(
             state_update,
@@ -1530,7 +1865,8 @@ impl node_runtime::adapter::ViewRuntimeAdapter for NightshadeRuntime {
         account_id: &AccountId,
         public_key: &PublicKey,
     ) -> Result<AccessKey, node_runtime::state_viewer::errors::ViewAccessKeyError> {
-        let state_update = self.tries.new_trie_update_view(*shard_uid, state_root);
+        let mut state_update = self.tries.new_trie_update_view(*shard_uid, state_root);
+        self.sandbox_fork_import(&mut state_update, [account_id], true)?;
         self.trie_viewer.view_access_key(&state_update, account_id, public_key)
     }
 
@@ -1541,7 +1877,8 @@ impl node_runtime::adapter::ViewRuntimeAdapter for NightshadeRuntime {
         account_id: &AccountId,
     ) -> Result<Vec<(PublicKey, AccessKey)>, node_runtime::state_viewer::errors::ViewAccessKeyError>
     {
-        let state_update = self.tries.new_trie_update_view(*shard_uid, state_root);
+        let mut state_update = self.tries.new_trie_update_view(*shard_uid, state_root);
+        self.sandbox_fork_import(&mut state_update, [account_id], true)?;
         self.trie_viewer.view_access_keys(&state_update, account_id)
     }
 
@@ -1553,7 +1890,8 @@ impl node_runtime::adapter::ViewRuntimeAdapter for NightshadeRuntime {
         prefix: &[u8],
         include_proof: bool,
     ) -> Result<ViewStateResult, node_runtime::state_viewer::errors::ViewStateError> {
-        let state_update = self.tries.new_trie_update_view(*shard_uid, state_root);
+        let mut state_update = self.tries.new_trie_update_view(*shard_uid, state_root);
+        self.sandbox_fork_import(&mut state_update, [account_id], true)?;
         self.trie_viewer.view_state(&state_update, account_id, prefix, include_proof)
     }
 }
@@ -1804,6 +2142,7 @@ mod test {
                 Some(RuntimeConfigStore::free()),
                 DEFAULT_GC_NUM_EPOCHS_TO_KEEP,
                 Default::default(),
+                Default::default(),
             );
             let (_store, state_roots) = runtime.genesis_state();
             let genesis_hash = hash(&[0]);
@@ -0,0 +1,472 @@
+//! Forking the state of another network into a sandbox node.
+//!
+//! When `sandbox_fork` is set in the config, the sandbox starts from its local genesis and
+//! accounts which don't exist locally are imported from the upstream network, as they were at the
+//! pinned block. The account, its contract code, access keys and contract data are fetched with
+//! the standard `query` calls (`view_account`, `view_code`, `view_access_key_list` and
+//! `view_state`), so any RPC node of the upstream network can serve them, and are written to the
+//! state the same way as a sandbox state patch.
+//!
+//! Accounts are fetched by a background thread, never while a chunk is applied or a transaction
+//! is validated. A view touching a missing account waits for it to be fetched, which is why the
+//! JSON-RPC server views the signer and the receiver of a submitted transaction first. Chunks
+//! import the fetched accounts their transactions and receipts touch before they are applied and
+//! request the ones which aren't fetched yet, as well as the receivers of their outgoing receipts.
+//!
+//! Accounts deleted in the sandbox are recorded in the store and never imported again.
+//!
+//! Limitations: contracts whose state is larger than the `trie_viewer_state_size_limit` of the
+//! upstream node can't be imported, and a receipt for an account which isn't fetched by the time
+//! it's executed sees the account missing. The upstream node must still have the state of the
+//! pinned block (i.e. it is recent enough or the node is an archival one).
+use near_primitives::errors::StorageError;
+use near_primitives::hash::CryptoHash;
+use near_primitives::sandbox::state_patch::SandboxStatePatch;
+use near_primitives::state_record::StateRecord;
+use near_primitives::trie_key::TrieKey;
+use near_primitives::types::{AccountId, RawStateChangesWithTrieKey};
+use near_primitives::views::{AccessKeyList, AccountView, ContractCodeView, ViewStateResult};
+use near_store::{get_account, DBCol, Store, TrieAccess, SANDBOX_FORK_DELETED_ACCOUNTS_KEY};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::{mpsc, Arc, Condvar, Mutex};
+use tracing::{info, warn};
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct SandboxForkConfig {
+    /// JSON-RPC endpoint of a node of the network to fork from.
+    pub rpc_url: String,
+    /// Hash of the block whose state the accounts are imported from.
+    pub block_hash: CryptoHash,
+}
+
+/// Error of a query sent to the upstream node.
+struct QueryError {
+    /// Name of the cause of the error if the upstream node handled the query.
+    cause: Option<String>,
+    message: String,
+}
+
+/// Fetches the records of accounts from the JSON-RPC of the upstream node. The requests are
+/// blocking, so it's only used from the fetching thread of `SandboxFork` and in tests.
+pub struct RpcStateFetcher {
+    rpc_url: String,
+    block_hash: CryptoHash,
+}
+
+impl RpcStateFetcher {
+    pub fn new(config: &SandboxForkConfig) -> Self {
+        Self { rpc_url: config.rpc_url.clone(), block_hash: config.block_hash }
+    }
+
+    /// Calls the given method of the upstream node and returns the whole response.
+    fn call(&self, method: &str, params: serde_json::Value) -> Result<serde_json::Value, String> {
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": "dontcare",
+            "method": method,
+            "params": params,
+        });
+        let response = reqwest::blocking::Client::new()
+            .post(&self.rpc_url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(request.to_string())
+            .send()
+            .and_then(|response| response.text())
+            .map_err(|err| format!("{method} request to {} failed: {err}", self.rpc_url))?;
+        serde_json::from_str(&response).map_err(|err| err.to_string())
+    }
+
+    /// Sends the `query` request with given parameters at the pinned block.
+    fn query<T: serde::de::DeserializeOwned>(
+        &self,
+        mut params: serde_json::Value,
+    ) -> Result<T, QueryError> {
+        params["block_id"] = serde_json::json!(self.block_hash);
+        let mut response =
+            self.call("query", params).map_err(|message| QueryError { cause: None, message })?;
+        if let Some(error) = response.get("error") {
+            return Err(QueryError {
+                cause: error["cause"]["name"].as_str().map(str::to_string),
+                message: format!("query failed: {error}"),
+            });
+        }
+        let message = match response.get_mut("result") {
+            Some(result) => match serde_json::from_value(result.take()) {
+                Ok(result) => return Ok(result),
+                Err(err) => format!("query response can't be parsed: {err}"),
+            },
+            None => format!("query response has no result: {response}"),
+        };
+        Err(QueryError { cause: None, message })
+    }
+
+    /// Returns the account, contract, access key and data records of the account at the pinned
+    /// block, or nothing if the account doesn't exist there.
+    pub fn fetch_account_records(
+        &self,
+        account_id: &AccountId,
+    ) -> Result<Vec<StateRecord>, String> {
+        let account: AccountView = match self.query(serde_json::json!({
+            "request_type": "view_account",
+            "account_id": account_id,
+        })) {
+            Ok(account) => account,
+            Err(err) if err.cause.as_deref() == Some("UNKNOWN_ACCOUNT") => return Ok(vec![]),
+            Err(err) => return Err(err.message),
+        };
+        let mut records = vec![StateRecord::Account {
+            account_id: account_id.clone(),
+            account: (&account).into(),
+        }];
+        if account.code_hash != CryptoHash::default() {
+            let code: ContractCodeView = self
+                .query(serde_json::json!({
+                    "request_type": "view_code",
+                    "account_id": account_id,
+                }))
+                .map_err(|err| err.message)?;
+            records.push(StateRecord::Contract { account_id: account_id.clone(), code: code.code });
+        }
+        let access_keys: AccessKeyList = self
+            .query(serde_json::json!({
+                "request_type": "view_access_key_list",
+                "account_id": account_id,
+            }))
+            .map_err(|err| err.message)?;
+        records.extend(access_keys.keys.into_iter().map(|key| StateRecord::AccessKey {
+            account_id: account_id.clone(),
+            public_key: key.public_key,
+            access_key: key.access_key.into(),
+        }));
+        let state: ViewStateResult = self
+            .query(serde_json::json!({
+                "request_type": "view_state",
+                "account_id": account_id,
+                "prefix_base64": "",
+            }))
+            .map_err(|err| err.message)?;
+        records.extend(state.values.into_iter().map(|item| StateRecord::Data {
+            account_id: account_id.clone(),
+            data_key: item.key,
+            value: item.value,
+        }));
+        Ok(records)
+    }
+}
+
+/// State of an account requested from the upstream node.
+enum Fetch {
+    InProgress,
+    /// Records of the account, empty if it doesn't exist upstream. The upstream block is pinned,
+    /// so they never change.
+    Done(Arc<Vec<StateRecord>>),
+    /// The fetch failed, it's retried when the account is requested again.
+    Failed(String),
+}
+
+#[derive(Default)]
+struct FetchedAccounts {
+    accounts: Mutex<HashMap<AccountId, Fetch>>,
+    /// Notified whenever a fetch finishes.
+    done: Condvar,
+}
+
+/// Imports the accounts missing from the sandbox state from the upstream node, see the module
+/// documentation.
+pub struct SandboxFork {
+    store: Store,
+    fetched: Arc<FetchedAccounts>,
+    /// Queue of the thread fetching the requested accounts one at a time. The thread stops once
+    /// the importer is dropped.
+    requests: Mutex<mpsc::Sender<AccountId>>,
+    /// Accounts deleted in the sandbox, also kept in the store.
+    deleted: Mutex<BTreeSet<AccountId>>,
+}
+
+impl SandboxFork {
+    pub fn new(config: &SandboxForkConfig, store: Store) -> std::io::Result<Self> {
+        let deleted =
+            store.get_ser(DBCol::BlockMisc, SANDBOX_FORK_DELETED_ACCOUNTS_KEY)?.unwrap_or_default();
+        let fetcher = RpcStateFetcher::new(config);
+        let fetched = Arc::new(FetchedAccounts::default());
+        let (sender, receiver) = mpsc::channel::<AccountId>();
+        let worker_fetched = fetched.clone();
+        std::thread::Builder::new().name("sandbox_fork".to_string()).spawn(move || {
+            for account_id in receiver {
+                let fetch = match fetcher.fetch_account_records(&account_id) {
+                    Ok(records) => {
+                        if !records.is_empty() {
+                            info!(target: "sandbox", %account_id, num_records = records.len(), "Fetched account from the forked network");
+                        }
+                        Fetch::Done(Arc::new(records))
+                    }
+                    Err(err) => {
+                        warn!(target: "sandbox", %account_id, %err, "Failed to fetch account from the forked network");
+                        Fetch::Failed(format!(
+                            "Failed to import {account_id} from {}: {err}",
+                            fetcher.rpc_url
+                        ))
+                    }
+                };
+                worker_fetched.accounts.lock().unwrap().insert(account_id, fetch);
+                worker_fetched.done.notify_all();
+            }
+        })?;
+        Ok(Self { store, fetched, requests: Mutex::new(sender), deleted: Mutex::new(deleted) })
+    }
+
+    /// Requests the given accounts to be fetched in the background, unless they are fetched
+    /// already or were deleted in the sandbox.
+    pub fn request<'a>(&self, account_ids: impl IntoIterator<Item = &'a AccountId>) {
+        let deleted = self.deleted.lock().unwrap();
+        let mut accounts = self.fetched.accounts.lock().unwrap();
+        let requests = self.requests.lock().unwrap();
+        for account_id in account_ids {
+            if deleted.contains(account_id)
+                || matches!(accounts.get(account_id), Some(Fetch::InProgress | Fetch::Done(_)))
+            {
+                continue;
+            }
+            accounts.insert(account_id.clone(), Fetch::InProgress);
+            // The thread only stops once the importer is dropped.
+            let _ = requests.send(account_id.clone());
+        }
+    }
+
+    /// Returns the records of the account if they are fetched, requesting them otherwise. If
+    /// `wait` is set, waits for the fetch to finish instead.
+    fn account_records(
+        &self,
+        account_id: &AccountId,
+        wait: bool,
+    ) -> Result<Option<Arc<Vec<StateRecord>>>, StorageError> {
+        self.request([account_id]);
+        let mut accounts = self.fetched.accounts.lock().unwrap();
+        loop {
+            match accounts.get(account_id) {
+                Some(Fetch::Done(records)) => return Ok(Some(records.clone())),
+                Some(Fetch::Failed(err)) => {
+                    return Err(StorageError::StorageInconsistentState(err.clone()))
+                }
+                _ if !wait => return Ok(None),
+                _ => accounts = self.fetched.done.wait(accounts).unwrap(),
+            }
+        }
+    }
+
+    /// Returns the patch importing the given accounts which don't exist in `state` and weren't
+    /// deleted in the sandbox. Unless `wait` is set, only the accounts fetched already are
+    /// imported and the others are requested in the background.
+    pub fn missing_accounts_patch<'a>(
+        &self,
+        state: &dyn TrieAccess,
+        account_ids: impl IntoIterator<Item = &'a AccountId>,
+        wait: bool,
+    ) -> Result<SandboxStatePatch, StorageError> {
+        let mut seen = HashSet::new();
+        let mut records = vec![];
+        for account_id in account_ids {
+            if !seen.insert(account_id)
+                || self.deleted.lock().unwrap().contains(account_id)
+                || get_account(state, account_id)?.is_some()
+            {
+                continue;
+            }
+            if let Some(account_records) = self.account_records(account_id, wait)? {
+                records.extend(account_records.iter().cloned());
+            }
+        }
+        Ok(SandboxStatePatch::new(records))
+    }
+
+    /// Records the accounts deleted by `state_changes`, so that they are never imported again.
+    pub fn record_deleted_accounts(
+        &self,
+        state_changes: &[RawStateChangesWithTrieKey],
+    ) -> Result<(), StorageError> {
+        let mut deleted = self.deleted.lock().unwrap();
+        let mut updated = false;
+        for changes in state_changes {
+            if let TrieKey::Account { account_id } = &changes.trie_key {
+                if changes.changes.last().map_or(false, |change| change.data.is_none()) {
+                    updated |= deleted.insert(account_id.clone());
+                }
+            }
+        }
+        if updated {
+            let mut store_update = self.store.store_update();
+            store_update
+                .set_ser(DBCol::BlockMisc, SANDBOX_FORK_DELETED_ACCOUNTS_KEY, &*deleted)
+                .map_err(|_| StorageError::StorageInternalError)?;
+            store_update.commit().map_err(|_| StorageError::StorageInternalError)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_crypto::{KeyType, PublicKey};
+    use near_primitives::account::{AccessKey, Account};
+    use near_primitives::hash::hash;
+    use near_primitives::shard_layout::ShardUId;
+    use near_primitives::types::{RawStateChange, StateChangeCause, StateRoot};
+    use near_primitives::views::{AccessKeyInfoView, StateItem};
+    use near_store::set_account;
+    use near_store::test_utils::{create_test_store, create_tries};
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const CODE: &[u8] = b"upstream contract";
+
+    fn upstream_account() -> Account {
+        Account::new(100, 0, hash(CODE), 200)
+    }
+
+    /// Answers queries like an upstream node which has only `alice.near`.
+    fn upstream_response(block_hash: CryptoHash, params: &serde_json::Value) -> serde_json::Value {
+        assert_eq!(params["block_id"], serde_json::json!(block_hash));
+        let account_id = params["account_id"].as_str().unwrap();
+        if account_id != "alice.near" {
+            return serde_json::json!({"error": {
+                "name": "HANDLER_ERROR",
+                "cause": {"name": "UNKNOWN_ACCOUNT", "info": {"requested_account_id": account_id}},
+            }});
+        }
+        let result = match params["request_type"].as_str().unwrap() {
+            "view_account" => serde_json::to_value(AccountView::from(upstream_account())),
+            "view_code" => {
+                serde_json::to_value(ContractCodeView { code: CODE.to_vec(), hash: hash(CODE) })
+            }
+            "view_access_key_list" => serde_json::to_value(AccessKeyList {
+                keys: vec![AccessKeyInfoView {
+                    public_key: PublicKey::empty(KeyType::ED25519),
+                    access_key: AccessKey::full_access().into(),
+                }],
+            }),
+            "view_state" => serde_json::to_value(ViewStateResult {
+                values: vec![StateItem {
+                    key: b"key".to_vec(),
+                    value: b"value".to_vec(),
+                    proof: vec![],
+                }],
+                proof: vec![],
+            }),
+            request_type => panic!("unexpected {request_type} query"),
+        };
+        serde_json::json!({ "result": result.unwrap() })
+    }
+
+    /// Starts a stand-in for the upstream RPC node, returns its config and the number of
+    /// requests it got.
+    fn start_upstream() -> (SandboxForkConfig, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let config = SandboxForkConfig {
+            rpc_url: format!("http://{}", listener.local_addr().unwrap()),
+            block_hash: hash(b"upstream block"),
+        };
+        let num_requests = Arc::new(AtomicUsize::new(0));
+        let block_hash = config.block_hash;
+        let counter = num_requests.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(&mut stream);
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line == "\r\n" {
+                        break;
+                    }
+                    if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+                let request: serde_json::Value = serde_json::from_slice(&body).unwrap();
+                assert_eq!(request["method"], "query");
+                counter.fetch_add(1, Ordering::SeqCst);
+                let mut response = upstream_response(block_hash, &request["params"]);
+                response["jsonrpc"] = "2.0".into();
+                response["id"] = request["id"].clone();
+                let response = response.to_string();
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    response.len(),
+                    response
+                )
+                .unwrap();
+            }
+        });
+        (config, num_requests)
+    }
+
+    #[test]
+    fn test_fetch_account_records() {
+        let (config, _) = start_upstream();
+        let fetcher = RpcStateFetcher::new(&config);
+        let alice: AccountId = "alice.near".parse().unwrap();
+        let records = fetcher.fetch_account_records(&alice).unwrap();
+        assert_eq!(records.len(), 4);
+        assert!(
+            matches!(&records[0], StateRecord::Account { account, .. } if account == &upstream_account())
+        );
+        assert!(matches!(&records[1], StateRecord::Contract { code, .. } if code == CODE));
+        assert!(
+            matches!(&records[2], StateRecord::AccessKey { access_key, .. } if access_key == &AccessKey::full_access())
+        );
+        assert!(
+            matches!(&records[3], StateRecord::Data { data_key, value, .. } if data_key == b"key" && value == b"value")
+        );
+        assert!(fetcher.fetch_account_records(&"bob.near".parse().unwrap()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_missing_accounts_patch() {
+        let (config, num_requests) = start_upstream();
+        let store = create_test_store();
+        let fork = SandboxFork::new(&config, store.clone()).unwrap();
+        let tries = create_tries();
+        let mut state_update =
+            tries.new_trie_update(ShardUId::single_shard(), StateRoot::default());
+        let local: AccountId = "local.near".parse().unwrap();
+        set_account(
+            &mut state_update,
+            local.clone(),
+            &Account::new(1, 0, CryptoHash::default(), 100),
+        );
+        let alice: AccountId = "alice.near".parse().unwrap();
+        let bob: AccountId = "bob.near".parse().unwrap();
+        let accounts = [&alice, &local, &alice, &bob];
+
+        let patch = fork.missing_accounts_patch(&state_update, accounts, true).unwrap();
+        let records: Vec<_> = patch.into_iter().collect();
+        assert_eq!(records.len(), 4);
+        assert!(records.iter().all(|record| {
+            near_primitives::state_record::state_record_to_account_id(record) == &alice
+        }));
+        // Four queries for alice and one for bob, the local account isn't fetched at all.
+        assert_eq!(num_requests.load(Ordering::SeqCst), 5);
+
+        // Fetched accounts, including nonexistent ones, are not requested again and are
+        // available without waiting.
+        let patch = fork.missing_accounts_patch(&state_update, accounts, false).unwrap();
+        assert_eq!(patch.into_iter().count(), 4);
+        assert_eq!(num_requests.load(Ordering::SeqCst), 5);
+
+        // Deleted accounts are not imported again, also after a restart.
+        fork.record_deleted_accounts(&[RawStateChangesWithTrieKey {
+            trie_key: TrieKey::Account { account_id: alice.clone() },
+            changes: vec![RawStateChange { cause: StateChangeCause::Migration, data: None }],
+        }])
+        .unwrap();
+        assert!(fork.missing_accounts_patch(&state_update, accounts, true).unwrap().is_empty());
+        let fork = SandboxFork::new(&config, store).unwrap();
+        assert!(fork.missing_accounts_patch(&state_update, accounts, true).unwrap().is_empty());
+    }
+}
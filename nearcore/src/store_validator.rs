@@ -0,0 +1,122 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use near_chain::store_validator::StoreValidator;
+use near_primitives::static_clock::StaticClock;
+use near_primitives::types::BlockHeight;
+use near_store::{DBCol, NodeStorage, Store, STORE_VALIDATOR_HEIGHT_KEY};
+
+use crate::{metrics, NearConfig, NightshadeRuntime};
+
+/// A handle that keeps the state of the store validator loop and can be used
+/// to stop it.
+pub struct StoreValidatorLoopHandle {
+    join_handle: std::thread::JoinHandle<()>,
+    keep_going: Arc<AtomicBool>,
+}
+
+impl StoreValidatorLoopHandle {
+    pub fn stop(self) {
+        self.keep_going.store(false, Ordering::Relaxed);
+        match self.join_handle.join() {
+            Ok(_) => {
+                tracing::debug!(target: "store_validator", "Joined the store validator loop thread");
+            }
+            Err(_) => {
+                tracing::error!(target: "store_validator", "Failed to join the store validator loop thread");
+            }
+        }
+    }
+}
+
+// Validates the data written since the previous iteration, then sleeps for
+// the configured period.  Each iteration is limited in time so that the
+// validation doesn't compete with block processing for the database for long;
+// an iteration which runs out of time is continued by the next one.  The
+// height to continue from is saved in the store so that a restarted node
+// doesn't validate everything again.
+fn store_validator_loop(
+    mut store_validator: StoreValidator,
+    store: Store,
+    keep_going: &Arc<AtomicBool>,
+    period: Duration,
+) {
+    tracing::info!(target: "store_validator", "Starting the store validator loop");
+
+    let mut height = load_height(&store).unwrap_or_else(|err| {
+        tracing::warn!(target: "store_validator", ?err, "Failed to read the store validator height, starting from genesis");
+        0
+    });
+    while keep_going.load(Ordering::Relaxed) {
+        height = store_validator.validate_incremental(height);
+        metrics::STORE_VALIDATOR_HEIGHT.set(height as i64);
+        if let Err(err) = save_height(&store, height) {
+            tracing::warn!(target: "store_validator", ?err, "Failed to save the store validator height");
+        }
+        for error in store_validator.errors.iter() {
+            tracing::error!(target: "store_validator", col = %error.col, key = %error.key, err = %error.err, "Store validation failed");
+            metrics::STORE_VALIDATOR_ERRORS.with_label_values(&[&error.col]).inc();
+        }
+
+        let sleep_until = StaticClock::instant() + period;
+        while keep_going.load(Ordering::Relaxed) && StaticClock::instant() < sleep_until {
+            std::thread::sleep(Duration::from_millis(100));
+        }
+    }
+    tracing::debug!(target: "store_validator", "Stopping the store validator loop");
+}
+
+fn load_height(store: &Store) -> std::io::Result<BlockHeight> {
+    Ok(store.get_ser(DBCol::BlockMisc, STORE_VALIDATOR_HEIGHT_KEY)?.unwrap_or(0))
+}
+
+fn save_height(store: &Store, height: BlockHeight) -> std::io::Result<()> {
+    let mut store_update = store.store_update();
+    store_update.set_ser(DBCol::BlockMisc, STORE_VALIDATOR_HEIGHT_KEY, &height)?;
+    store_update.commit()
+}
+
+/// Spawns the store validator loop in a background thread and returns
+/// StoreValidatorLoopHandle.  If the store validator is not enabled in the
+/// config it does nothing and returns None.
+pub fn spawn_store_validator_loop(
+    config: &NearConfig,
+    storage: &NodeStorage,
+    runtime: Arc<NightshadeRuntime>,
+) -> anyhow::Result<Option<StoreValidatorLoopHandle>> {
+    let store_validator_config = match &config.config.store_validator {
+        Some(store_validator_config) => store_validator_config.clone(),
+        None => {
+            tracing::debug!(target: "store_validator", "Not spawning the store validator loop because it is not enabled");
+            return Ok(None);
+        }
+    };
+
+    let mut store_validator = StoreValidator::new(
+        config.validator_signer.as_ref().map(|signer| signer.validator_id().clone()),
+        config.genesis.config.clone(),
+        runtime,
+        storage.get_hot_store(),
+        config.client_config.archive,
+    );
+    store_validator.set_timeout(store_validator_config.max_iteration_duration.as_millis() as u64);
+    store_validator.set_height_delay(store_validator_config.height_delay);
+    let store = storage.get_hot_store();
+
+    let keep_going = Arc::new(AtomicBool::new(true));
+    let keep_going_clone = keep_going.clone();
+
+    tracing::info!(target: "store_validator", "Spawning the store validator loop");
+    let join_handle =
+        std::thread::Builder::new().name("store_validator".to_string()).spawn(move || {
+            store_validator_loop(
+                store_validator,
+                store,
+                &keep_going_clone,
+                store_validator_config.period,
+            )
+        })?;
+
+    Ok(Some(StoreValidatorLoopHandle { join_handle, keep_going }))
+}
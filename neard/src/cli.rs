@@ -283,6 +283,16 @@ pub(super) struct InitCmd {
     /// from genesis configuration will be taken.
     #[clap(long)]
     max_gas_burnt_view: Option<Gas>,
+    /// Initialize the database from an epoch checkpoint created with
+    /// `neard view-state create-checkpoint`, so that the node syncs starting
+    /// from the end of the checkpoint rather than from genesis.  The
+    /// checkpoint is verified against the genesis validators first.
+    #[clap(long, parse(from_os_str))]
+    from_checkpoint: Option<PathBuf>,
+    /// Start verifying the checkpoint from the light client block with this
+    /// hash instead of from genesis.
+    #[clap(long, requires = "from-checkpoint")]
+    trusted_hash: Option<CryptoHash>,
 }
 
 /// Warns if unsupported build of the executable is used on mainnet or testnet.
@@ -346,7 +356,20 @@ impl InitCmd {
             self.boot_nodes.as_deref(),
             self.max_gas_burnt_view,
         )
-        .context("Failed to initialize configs")
+        .context("Failed to initialize configs")?;
+
+        if let Some(checkpoint) = self.from_checkpoint {
+            let mut near_config =
+                nearcore::config::load_config(home_dir, GenesisValidationMode::Full)
+                    .context("Failed to load configs")?;
+            nearcore::checkpoint::bootstrap_from_checkpoint(
+                home_dir,
+                &mut near_config,
+                &checkpoint,
+                self.trusted_hash.as_ref(),
+            )?;
+        }
+        Ok(())
     }
 }
 
@@ -396,6 +419,16 @@ pub(super) struct RunCmd {
     /// configuration will be taken.
     #[clap(long)]
     max_gas_burnt_view: Option<Gas>,
+    /// Before starting, initialize the database from an epoch checkpoint
+    /// created with `neard view-state create-checkpoint`, unless the node has
+    /// already synced past genesis.  The checkpoint is verified against the
+    /// genesis validators first.
+    #[clap(long, parse(from_os_str))]
+    bootstrap_checkpoint: Option<PathBuf>,
+    /// Start verifying the checkpoint from the light client block with this
+    /// hash instead of from genesis.
+    #[clap(long, requires = "bootstrap-checkpoint")]
+    trusted_hash: Option<CryptoHash>,
 }
 
 impl RunCmd {
@@ -463,6 +496,15 @@ impl RunCmd {
         if self.max_gas_burnt_view.is_some() {
             near_config.client_config.max_gas_burnt_view = self.max_gas_burnt_view;
         }
+        if let Some(checkpoint) = self.bootstrap_checkpoint {
+            nearcore::checkpoint::bootstrap_from_checkpoint(
+                home_dir,
+                &mut near_config,
+                &checkpoint,
+                self.trusted_hash.as_ref(),
+            )
+            .unwrap_or_else(|e| panic!("Error bootstrapping from checkpoint: {:#}", e));
+        }
 
         #[cfg(feature = "sandbox")]
         {
@@ -504,14 +546,18 @@ impl RunCmd {
                 UpdateableConfigLoader::new(updateable_configs.clone(), tx_config_update);
             let config_updater = ConfigUpdater::new(rx_config_update);
 
-            let nearcore::NearNode { rpc_servers, cold_store_loop_handle, .. } =
-                nearcore::start_with_config_and_synchronization(
-                    home_dir,
-                    near_config,
-                    Some(tx_crash),
-                    Some(config_updater),
-                )
-                .expect("start_with_config");
+            let nearcore::NearNode {
+                rpc_servers,
+                cold_store_loop_handle,
+                store_validator_loop_handle,
+                ..
+            } = nearcore::start_with_config_and_synchronization(
+                home_dir,
+                near_config,
+                Some(tx_crash),
+                Some(config_updater),
+            )
+            .expect("start_with_config");
 
             let sig = loop {
                 let sig = wait_for_interrupt_signal(home_dir, &mut rx_crash).await;
@@ -525,6 +571,7 @@ impl RunCmd {
             };
             warn!(target: "neard", "{}, stopping... this may take a few minutes.", sig);
             cold_store_loop_handle.map(|handle| handle.stop());
+            store_validator_loop_handle.map(|handle| handle.stop());
             futures::future::join_all(rpc_servers.iter().map(|(name, server)| async move {
                 server.stop(true).await;
                 debug!(target: "neard", "{} server stopped", name);
@@ -23,6 +23,50 @@ pub fn with_ext_cost_counter(f: impl FnOnce(&mut HashMap<ExtCosts, u64>)) {
     let _ = f;
 }
 
+/// A host function cost charged while tracing, see [`set_host_function_tracing`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostFunctionCharge {
+    pub cost: ExtCosts,
+    /// How many times the cost was charged at once, e.g. the number of bytes.
+    pub count: u64,
+    pub gas: Gas,
+}
+
+thread_local! {
+    static HOST_FUNCTION_TRACE: std::cell::RefCell<Option<Vec<HostFunctionCharge>>> =
+        Default::default();
+}
+
+/// Enables or disables recording of every host function cost charged on the
+/// current thread, in the order they are charged.  Every host function call
+/// starts with a charge of [`ExtCosts::base`], so the calls can be told apart.
+/// Only gas counters created while tracing is enabled record their charges.
+///
+/// This is used to trace contract execution for debugging only.
+pub fn set_host_function_tracing(enabled: bool) {
+    HOST_FUNCTION_TRACE.with(|trace| *trace.borrow_mut() = enabled.then(Vec::new));
+}
+
+/// Returns the host function costs charged on the current thread since the
+/// previous call.
+pub fn take_host_function_trace() -> Vec<HostFunctionCharge> {
+    HOST_FUNCTION_TRACE
+        .with(|trace| trace.borrow_mut().as_mut().map(std::mem::take).unwrap_or_default())
+}
+
+fn is_host_function_tracing_enabled() -> bool {
+    HOST_FUNCTION_TRACE.with(|trace| trace.borrow().is_some())
+}
+
+#[cold]
+fn trace_host_function_charge(cost: ExtCosts, count: u64, gas: Gas) {
+    HOST_FUNCTION_TRACE.with(|trace| {
+        if let Some(trace) = trace.borrow_mut().as_mut() {
+            trace.push(HostFunctionCharge { cost, count, gas });
+        }
+    });
+}
+
 type Result<T> = ::std::result::Result<T, VMLogicError>;
 
 /// Fast gas counter with very simple structure, could be exposed to compiled code in the VM.
@@ -59,6 +103,9 @@ pub struct GasCounter {
     ext_costs_config: ExtCostsConfig,
     /// Where to store profile data, if needed.
     profile: ProfileDataV3,
+    /// Whether charges are recorded, see [`set_host_function_tracing`]. Checked
+    /// once on creation to keep the thread local out of the charging path.
+    trace_host_functions: bool,
 }
 
 impl GasCounter {
@@ -84,6 +131,7 @@ impl GasCounter {
             prepaid_gas,
             is_view,
             profile: Default::default(),
+            trace_host_functions: is_host_function_tracing_enabled(),
         }
     }
 
@@ -224,6 +272,9 @@ impl GasCounter {
 
         self.inc_ext_costs_counter(cost, num);
         self.update_profile_host(cost, use_gas);
+        if self.trace_host_functions {
+            trace_host_function_charge(cost, num, use_gas);
+        }
         self.burn_gas(use_gas)
     }
 
@@ -232,6 +283,9 @@ impl GasCounter {
         let base_fee = cost.value(&self.ext_costs_config);
         self.inc_ext_costs_counter(cost, 1);
         self.update_profile_host(cost, base_fee);
+        if self.trace_host_functions {
+            trace_host_function_charge(cost, 1, base_fee);
+        }
         self.burn_gas(base_fee)
     }
 
@@ -288,6 +342,41 @@ mod tests {
         super::GasCounter::new(ExtCostsConfig::test(), max_burnt, 1, prepaid, is_view)
     }
 
+    #[test]
+    fn test_host_function_trace() {
+        use super::{set_host_function_tracing, take_host_function_trace, HostFunctionCharge};
+        use crate::ExtCosts;
+
+        let config = ExtCostsConfig::test();
+        let mut untraced_counter = make_test_counter(Gas::MAX, Gas::MAX, false);
+        untraced_counter.pay_base(ExtCosts::base).unwrap();
+        set_host_function_tracing(true);
+        // Counters created before tracing was enabled don't record their charges.
+        untraced_counter.pay_base(ExtCosts::base).unwrap();
+        let mut counter = make_test_counter(Gas::MAX, Gas::MAX, false);
+        counter.pay_base(ExtCosts::base).unwrap();
+        counter.pay_per(ExtCosts::storage_read_key_byte, 3).unwrap();
+        assert_eq!(
+            take_host_function_trace(),
+            vec![
+                HostFunctionCharge {
+                    cost: ExtCosts::base,
+                    count: 1,
+                    gas: ExtCosts::base.value(&config),
+                },
+                HostFunctionCharge {
+                    cost: ExtCosts::storage_read_key_byte,
+                    count: 3,
+                    gas: 3 * ExtCosts::storage_read_key_byte.value(&config),
+                },
+            ]
+        );
+        assert_eq!(take_host_function_trace(), vec![]);
+        set_host_function_tracing(false);
+        counter.pay_base(ExtCosts::base).unwrap();
+        assert_eq!(take_host_function_trace(), vec![]);
+    }
+
     #[test]
     fn test_deduct_gas() {
         let mut counter = make_test_counter(10, 10, false);
@@ -0,0 +1,179 @@
+use anyhow::Context;
+use near_primitives::runtime::config_store::RuntimeConfigStore;
+use near_primitives::types::Gas;
+use near_primitives::version::PROTOCOL_VERSION;
+use runtime_params_estimator::{runtime_config_gas, ComparisonReport, Cost, CostComparison};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Runs the same estimations with two builds of the estimator, or with two VM
+/// kinds, on identical state and reports which costs changed significantly.
+/// Fails if any cost was flagged, so it can gate CI.
+#[derive(clap::Parser)]
+pub(crate) struct CompareCmd {
+    /// Estimator binary built from the baseline revision.
+    #[clap(long)]
+    baseline: PathBuf,
+    /// Estimator binary built from the revision to compare against the
+    /// baseline.  Defaults to the baseline binary, which is useful to compare
+    /// VM kinds.
+    #[clap(long)]
+    candidate: Option<PathBuf>,
+    /// VM kind used by the baseline.
+    #[clap(long, possible_values = &["wasmer", "wasmer2", "wasmtime"])]
+    baseline_vm_kind: Option<String>,
+    /// VM kind used by the candidate.
+    #[clap(long, possible_values = &["wasmer", "wasmer2", "wasmtime"])]
+    candidate_vm_kind: Option<String>,
+    /// Coma-separated lists of a subset of costs to compare.
+    #[clap(long, use_value_delimiter = true)]
+    costs: Option<Vec<Cost>>,
+    /// How many times each build runs the estimations.  The confidence
+    /// intervals are computed over these repetitions.
+    #[clap(long, default_value = "5")]
+    repeats: usize,
+    /// Flag costs which moved by more than this many percent of the gas
+    /// charged for the corresponding parameter in `RuntimeConfig`, or of the
+    /// baseline estimation for costs without a parameter.
+    #[clap(long, default_value = "5")]
+    threshold: f64,
+    /// Directory with the state to run estimations on.  If not set, a
+    /// temporary directory is used to generate it.  Both builds always use the
+    /// same state.
+    #[clap(long)]
+    home: Option<PathBuf>,
+    /// How many warm up iterations per block should we run.
+    #[clap(long, default_value = "0")]
+    warmup_iters: usize,
+    /// How many iterations per block are we going to try.
+    #[clap(long, default_value = "10")]
+    iters: usize,
+    /// Number of active accounts in the state (accounts used for estimation).
+    #[clap(long, default_value = "20000")]
+    accounts_num: usize,
+    /// Number of additional accounts to add to the state, among which active accounts are selected.
+    #[clap(long, default_value = "200000")]
+    additional_accounts_num: u64,
+    /// What metric to use, see the top-level `--metric` option.
+    #[clap(long, default_value = "time", possible_values = &["icount", "time"])]
+    metric: String,
+}
+
+impl CompareCmd {
+    pub(crate) fn run(self) -> anyhow::Result<()> {
+        if self.repeats < 2 {
+            anyhow::bail!("at least two repeats are needed to compute confidence intervals");
+        }
+        let temp_dir;
+        let state_dump_path = match &self.home {
+            Some(it) => it.clone(),
+            None => {
+                temp_dir = tempfile::tempdir()?;
+                temp_dir.path().to_path_buf()
+            }
+        };
+        if state_dump_path.read_dir()?.next().is_none() {
+            crate::create_state_dump(&state_dump_path, self.additional_accounts_num)?;
+        }
+        // Estimator runs write their cost tables to the working directory,
+        // which we don't need.
+        let output_dir = tempfile::tempdir()?;
+
+        let candidate = self.candidate.as_ref().unwrap_or(&self.baseline);
+        let mut baseline_samples = BTreeMap::new();
+        let mut candidate_samples = BTreeMap::new();
+        for repeat in 0..self.repeats {
+            eprintln!("Repetition {}/{}", repeat + 1, self.repeats);
+            // Alternate the builds so that changes in machine load over time
+            // affect both of them equally.
+            self.estimate(
+                &self.baseline,
+                self.baseline_vm_kind.as_deref(),
+                &state_dump_path,
+                output_dir.path(),
+                &mut baseline_samples,
+            )?;
+            self.estimate(
+                candidate,
+                self.candidate_vm_kind.as_deref(),
+                &state_dump_path,
+                output_dir.path(),
+                &mut candidate_samples,
+            )?;
+        }
+
+        let config_store = RuntimeConfigStore::new(None);
+        let runtime_config = config_store.get_config(PROTOCOL_VERSION);
+        let comparisons = baseline_samples
+            .iter()
+            .filter_map(|(&cost, baseline)| {
+                let candidate = candidate_samples.get(&cost)?;
+                let parameter = runtime_config_gas(cost, runtime_config);
+                Some(CostComparison::new(cost, baseline, candidate, parameter))
+            })
+            .collect();
+        let report = ComparisonReport { comparisons, threshold: self.threshold / 100.0 };
+        println!("{report}");
+
+        let flagged = report.flagged().map(|comparison| comparison.cost.to_string());
+        let flagged = flagged.collect::<Vec<_>>();
+        if !flagged.is_empty() {
+            anyhow::bail!(
+                "{} costs moved by more than {}%: {}",
+                flagged.len(),
+                self.threshold,
+                flagged.join(", ")
+            );
+        }
+        Ok(())
+    }
+
+    /// Runs estimations with `binary` once and adds the resulting gas of each
+    /// cost to `samples`.
+    fn estimate(
+        &self,
+        binary: &Path,
+        vm_kind: Option<&str>,
+        state_dump_path: &Path,
+        output_dir: &Path,
+        samples: &mut BTreeMap<Cost, Vec<Gas>>,
+    ) -> anyhow::Result<()> {
+        let mut cmd = Command::new(binary);
+        cmd.current_dir(output_dir)
+            .arg("--home")
+            .arg(state_dump_path)
+            .args(&["--warmup-iters", &self.warmup_iters.to_string()])
+            .args(&["--iters", &self.iters.to_string()])
+            .args(&["--accounts-num", &self.accounts_num.to_string()])
+            .args(&["--metric", &self.metric])
+            .arg("--json-output")
+            .stderr(Stdio::inherit());
+        if let Some(vm_kind) = vm_kind {
+            cmd.args(&["--vm-kind", vm_kind]);
+        }
+        if let Some(costs) = &self.costs {
+            let costs = costs.iter().map(|cost| cost.to_string()).collect::<Vec<_>>();
+            cmd.args(&["--costs", &costs.join(",")]);
+        }
+
+        let output = cmd.output().with_context(|| format!("failed to run {}", binary.display()))?;
+        if !output.status.success() {
+            anyhow::bail!("{} failed with {}", binary.display(), output.status);
+        }
+        let stdout = String::from_utf8(output.stdout)
+            .with_context(|| format!("non UTF-8 output of {}", binary.display()))?;
+        for line in stdout.lines() {
+            // One JSON object per estimation, as printed with `--json-output`.
+            let json: serde_json::Value = match serde_json::from_str(line) {
+                Ok(json) => json,
+                Err(_) => continue,
+            };
+            match (json["name"].as_str(), json["result"]["gas"].as_u64()) {
+                (Some(name), Some(gas)) => samples.entry(name.parse()?).or_default().push(gas),
+                _ => continue,
+            }
+        }
+        Ok(())
+    }
+}
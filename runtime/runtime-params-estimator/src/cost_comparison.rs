@@ -0,0 +1,184 @@
+use near_primitives::types::Gas;
+use std::fmt;
+
+use crate::cost::Cost;
+use crate::cost_table::format_gas;
+use crate::least_squares::mean_with_confidence_interval;
+
+/// Repeated estimations of a single [`Cost`] by two builds of the estimator.
+pub struct CostComparison {
+    pub cost: Cost,
+    /// Mean estimation of the baseline and the half-width of its 95%
+    /// confidence interval.
+    pub baseline: (f64, f64),
+    /// Same for the candidate.
+    pub candidate: (f64, f64),
+    /// Gas charged for the parameter derived from this cost in the current
+    /// `RuntimeConfig`, if there is one.
+    pub parameter: Option<Gas>,
+}
+
+impl CostComparison {
+    pub fn new(
+        cost: Cost,
+        baseline_samples: &[Gas],
+        candidate_samples: &[Gas],
+        parameter: Option<Gas>,
+    ) -> Self {
+        let to_f64 = |samples: &[Gas]| samples.iter().map(|&gas| gas as f64).collect::<Vec<_>>();
+        Self {
+            cost,
+            baseline: mean_with_confidence_interval(&to_f64(baseline_samples)),
+            candidate: mean_with_confidence_interval(&to_f64(candidate_samples)),
+            parameter,
+        }
+    }
+
+    /// Difference between the candidate and the baseline estimation in gas,
+    /// as the lower and upper bound of its confidence interval.
+    ///
+    /// The bounds are taken from the ends of both intervals, which is more
+    /// conservative than the interval of the difference of the means.
+    pub fn delta_bounds(&self) -> (f64, f64) {
+        let (baseline, baseline_err) = self.baseline;
+        let (candidate, candidate_err) = self.candidate;
+        (
+            (candidate - candidate_err) - (baseline + baseline_err),
+            (candidate + candidate_err) - (baseline - baseline_err),
+        )
+    }
+
+    /// The value changes are measured against: the gas charged for the
+    /// parameter if there is one, or the baseline estimation otherwise.
+    fn reference(&self) -> f64 {
+        self.parameter.map_or(self.baseline.0, |gas| gas as f64)
+    }
+
+    /// `gas` as a fraction of the reference value.
+    ///
+    /// A zero reference (a free parameter, or a baseline which estimated
+    /// nothing) makes any non-zero change infinitely large rather than NaN.
+    fn relative(&self, gas: f64) -> f64 {
+        let reference = self.reference();
+        if reference != 0.0 {
+            gas / reference
+        } else if gas == 0.0 {
+            0.0
+        } else {
+            gas.signum() * f64::INFINITY
+        }
+    }
+
+    /// Relative change of the estimation against the reference value.
+    pub fn relative_delta(&self) -> f64 {
+        self.relative(self.candidate.0 - self.baseline.0)
+    }
+
+    /// Whether the estimation moved by more than `threshold` (a fraction) of
+    /// the reference value with 95% confidence, i.e. even the most favourable
+    /// end of the confidence interval is beyond the threshold.
+    pub fn is_flagged(&self, threshold: f64) -> bool {
+        let limit = threshold * self.reference();
+        let (low, high) = self.delta_bounds();
+        low > limit || high < -limit
+    }
+}
+
+/// Comparisons of all costs which both builds estimated.
+pub struct ComparisonReport {
+    pub comparisons: Vec<CostComparison>,
+    /// Fraction of the reference value a cost may move by before it's flagged.
+    pub threshold: f64,
+}
+
+impl ComparisonReport {
+    pub fn flagged(&self) -> impl Iterator<Item = &CostComparison> {
+        self.comparisons.iter().filter(|comparison| comparison.is_flagged(self.threshold))
+    }
+}
+
+impl fmt::Display for ComparisonReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<35} {:>25} {:>25} {:>25} {:>9} {:>19}",
+            "Cost", "Baseline", "Candidate", "Parameter", "Delta", "95% CI"
+        )?;
+
+        let mut biggest_delta_first = self.comparisons.iter().collect::<Vec<_>>();
+        biggest_delta_first
+            .sort_by(|a, b| b.relative_delta().abs().total_cmp(&a.relative_delta().abs()));
+
+        for comparison in biggest_delta_first {
+            let (low, high) = comparison.delta_bounds();
+            writeln!(
+                f,
+                "{:<35} {:>25} {:>25} {:>25} {:>+8.1}% {:>+8.1}%..{:>+8.1}%{}",
+                comparison.cost.to_string(),
+                format_gas(comparison.baseline.0 as Gas),
+                format_gas(comparison.candidate.0 as Gas),
+                comparison.parameter.map(format_gas).unwrap_or_else(|| "-".to_string()),
+                100.0 * comparison.relative_delta(),
+                100.0 * comparison.relative(low),
+                100.0 * comparison.relative(high),
+                if comparison.is_flagged(self.threshold) { "  <--" } else { "" },
+            )?
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flagged_against_parameter() {
+        // Estimation went up by 10% of itself, which is only 1% of the
+        // parameter.
+        let comparison =
+            CostComparison::new(Cost::LogBase, &[100, 100, 100], &[110, 110, 110], Some(1000));
+        assert!((comparison.relative_delta() - 0.01).abs() < 1e-9);
+        assert!(!comparison.is_flagged(0.05));
+        assert!(comparison.is_flagged(0.005));
+
+        // Without a parameter the baseline is the reference.
+        let comparison = CostComparison::new(Cost::LogBase, &[100, 100], &[110, 110], None);
+        assert!((comparison.relative_delta() - 0.1).abs() < 1e-9);
+        assert!(comparison.is_flagged(0.05));
+    }
+
+    #[test]
+    fn test_noisy_estimations_are_not_flagged() {
+        let comparison =
+            CostComparison::new(Cost::LogBase, &[80, 120, 100], &[100, 140, 120], None);
+        assert!(comparison.relative_delta() > 0.05);
+        assert!(!comparison.is_flagged(0.05));
+
+        let comparison =
+            CostComparison::new(Cost::LogBase, &[98, 102, 100], &[118, 122, 120], None);
+        assert!(comparison.is_flagged(0.05));
+    }
+
+    #[test]
+    fn test_zero_reference() {
+        let comparison = CostComparison::new(Cost::LogBase, &[0, 0], &[0, 0], Some(0));
+        assert_eq!(comparison.relative_delta(), 0.0);
+        assert!(!comparison.is_flagged(0.05));
+
+        let comparison = CostComparison::new(Cost::LogBase, &[0, 0], &[10, 10], None);
+        assert_eq!(comparison.relative_delta(), f64::INFINITY);
+        assert!(comparison.is_flagged(0.05));
+
+        // Sorting the report must not panic on such comparisons.
+        let report = ComparisonReport {
+            comparisons: vec![
+                comparison,
+                CostComparison::new(Cost::LogBase, &[0, 0], &[0, 0], Some(0)),
+                CostComparison::new(Cost::LogByte, &[100, 100], &[110, 110], None),
+            ],
+            threshold: 0.05,
+        };
+        assert_eq!(report.to_string().lines().count(), 4);
+    }
+}
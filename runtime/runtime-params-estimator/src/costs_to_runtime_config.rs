@@ -1,6 +1,7 @@
 use near_primitives::runtime::config::AccountCreationConfig;
 use near_primitives::runtime::config_store::RuntimeConfigStore;
 use near_primitives::runtime::fees::{Fee, RuntimeFeesConfig};
+use near_primitives::types::Gas;
 use near_primitives::version::PROTOCOL_VERSION;
 use near_vm_logic::{ActionCosts, ExtCosts, ExtCostsConfig, VMConfig};
 use node_runtime::config::RuntimeConfig;
@@ -53,28 +54,38 @@ fn runtime_fees_config(cost_table: &CostTable) -> anyhow::Result<RuntimeFeesConf
     let actual_fees_config = &config_store.get_config(PROTOCOL_VERSION).fees;
     let res = RuntimeFeesConfig {
         action_fees: enum_map::enum_map! {
-            ActionCosts::create_account => fee(Cost::ActionCreateAccount)?,
-            ActionCosts::delegate => fee(Cost::ActionDelegate)?,
-            ActionCosts::delete_account => fee(Cost::ActionDeleteAccount)?,
-            ActionCosts::deploy_contract_base => fee(Cost::ActionDeployContractBase)?,
-            ActionCosts::deploy_contract_byte => fee(Cost::ActionDeployContractPerByte)?,
-            ActionCosts::function_call_base => fee(Cost::ActionFunctionCallBase)?,
-            ActionCosts::function_call_byte => fee(Cost::ActionFunctionCallPerByte)?,
-            ActionCosts::transfer => fee(Cost::ActionTransfer)?,
-            ActionCosts::stake => fee(Cost::ActionStake)?,
-            ActionCosts::add_full_access_key => fee(Cost::ActionAddFullAccessKey)?,
-            ActionCosts::add_function_call_key_base => fee(Cost::ActionAddFunctionAccessKeyBase)?,
-            ActionCosts::add_function_call_key_byte => fee(Cost::ActionAddFunctionAccessKeyPerByte)?,
-            ActionCosts::delete_key => fee(Cost::ActionDeleteKey)?,
-            ActionCosts::new_action_receipt => fee(Cost::ActionReceiptCreation)?,
-            ActionCosts::new_data_receipt_base => fee(Cost::DataReceiptCreationBase)?,
-            ActionCosts::new_data_receipt_byte => fee(Cost::DataReceiptCreationPerByte)?,
+            cost => fee(action_estimation(cost))?,
         },
         ..actual_fees_config.clone()
     };
     Ok(res)
 }
 
+/// Returns the gas charged by `runtime_config` for the parameter which is
+/// derived from `cost`, or `None` if the estimation isn't used for any single
+/// parameter.  For action costs, this is the sum of the send and the execution
+/// fee, matching how `costs_to_runtime_config` splits the estimation.
+pub fn runtime_config_gas(cost: Cost, runtime_config: &RuntimeConfig) -> Option<Gas> {
+    if cost == Cost::WasmInstruction {
+        return Some(runtime_config.wasm_config.regular_op_cost.into());
+    }
+    let ext_cost = runtime_config
+        .wasm_config
+        .ext_costs
+        .costs
+        .iter()
+        .find(|(ext_cost, _)| estimation(*ext_cost) == Some(cost));
+    if let Some((_, &gas)) = ext_cost {
+        return Some(gas);
+    }
+    runtime_config
+        .fees
+        .action_fees
+        .iter()
+        .find(|(action_cost, _)| action_estimation(*action_cost) == cost)
+        .map(|(_, fee)| fee.send_sir + fee.execution)
+}
+
 fn ext_costs_config(cost_table: &CostTable) -> anyhow::Result<ExtCostsConfig> {
     Ok(ExtCostsConfig {
         costs: enum_map::enum_map! {
@@ -99,6 +110,27 @@ fn ext_costs_config(cost_table: &CostTable) -> anyhow::Result<ExtCostsConfig> {
     })
 }
 
+fn action_estimation(cost: ActionCosts) -> Cost {
+    match cost {
+        ActionCosts::create_account => Cost::ActionCreateAccount,
+        ActionCosts::delegate => Cost::ActionDelegate,
+        ActionCosts::delete_account => Cost::ActionDeleteAccount,
+        ActionCosts::deploy_contract_base => Cost::ActionDeployContractBase,
+        ActionCosts::deploy_contract_byte => Cost::ActionDeployContractPerByte,
+        ActionCosts::function_call_base => Cost::ActionFunctionCallBase,
+        ActionCosts::function_call_byte => Cost::ActionFunctionCallPerByte,
+        ActionCosts::transfer => Cost::ActionTransfer,
+        ActionCosts::stake => Cost::ActionStake,
+        ActionCosts::add_full_access_key => Cost::ActionAddFullAccessKey,
+        ActionCosts::add_function_call_key_base => Cost::ActionAddFunctionAccessKeyBase,
+        ActionCosts::add_function_call_key_byte => Cost::ActionAddFunctionAccessKeyPerByte,
+        ActionCosts::delete_key => Cost::ActionDeleteKey,
+        ActionCosts::new_action_receipt => Cost::ActionReceiptCreation,
+        ActionCosts::new_data_receipt_base => Cost::DataReceiptCreationBase,
+        ActionCosts::new_data_receipt_byte => Cost::DataReceiptCreationPerByte,
+    }
+}
+
 fn estimation(cost: ExtCosts) -> Option<Cost> {
     Some(match cost {
         ExtCosts::base => Cost::HostFunctionCall,
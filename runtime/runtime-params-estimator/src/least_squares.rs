@@ -103,6 +103,32 @@ pub(crate) fn qemu_measurement_least_squares(
     ((pos_base, pos_factor), (neg_base, neg_factor))
 }
 
+/// Two-sided 95% quantiles of Student's t-distribution, indexed by degrees of
+/// freedom minus one.
+const STUDENT_T_95: [f64; 30] = [
+    12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201, 2.179, 2.160,
+    2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086, 2.080, 2.074, 2.069, 2.064, 2.060, 2.056,
+    2.052, 2.048, 2.045, 2.042,
+];
+
+/// Returns the mean of `samples` and the half-width of its 95% confidence
+/// interval.
+///
+/// Samples are whole estimator runs, so there are usually only a handful of
+/// them and the interval is based on Student's t-distribution rather than on
+/// the normal one.  With a single sample the interval is unbounded.
+pub(crate) fn mean_with_confidence_interval(samples: &[f64]) -> (f64, f64) {
+    let n = samples.len();
+    assert!(n > 0, "no samples");
+    let mean = samples.iter().sum::<f64>() / n as f64;
+    if n == 1 {
+        return (mean, f64::INFINITY);
+    }
+    let variance = samples.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / (n - 1) as f64;
+    let t = STUDENT_T_95.get(n - 2).copied().unwrap_or(1.960);
+    (mean, t * (variance / n as f64).sqrt())
+}
+
 /// Transforms input C into two components, where A,B are non-negative and where A-B ~= input.
 /// This method intentionally rounds fractions to whole integers, rounding towards zero.
 fn split_pos_neg(num: Ratio<i128>) -> (Ratio<u64>, Ratio<u64>) {
@@ -152,6 +178,21 @@ mod tests {
         check_least_squares_method(&xs, &ys, (a, b, &error));
     }
 
+    #[test]
+    fn test_mean_with_confidence_interval() {
+        let (mean, half_width) = mean_with_confidence_interval(&[10.0, 12.0, 14.0]);
+        assert_eq!(mean, 12.0);
+        // Standard deviation 2, standard error 2/sqrt(3), t = 4.303.
+        assert!((half_width - 4.968_676).abs() < 1e-6, "{half_width}");
+
+        let (mean, half_width) = mean_with_confidence_interval(&[5.0; 4]);
+        assert_eq!((mean, half_width), (5.0, 0.0));
+
+        let (mean, half_width) = mean_with_confidence_interval(&[7.0]);
+        assert_eq!(mean, 7.0);
+        assert!(half_width.is_infinite());
+    }
+
     #[test]
     fn test_large_numbers() {
         let xs = [1, 1000000, 4000000];
@@ -59,6 +59,7 @@
 
 mod action_costs;
 mod cost;
+mod cost_comparison;
 mod cost_table;
 mod costs_to_runtime_config;
 // Encapsulates the runtime so that it can be run separately from the rest of the node.
@@ -120,8 +121,9 @@ use crate::transaction_builder::TransactionBuilder;
 use crate::vm_estimator::create_context;
 
 pub use crate::cost::Cost;
+pub use crate::cost_comparison::{ComparisonReport, CostComparison};
 pub use crate::cost_table::CostTable;
-pub use crate::costs_to_runtime_config::costs_to_runtime_config;
+pub use crate::costs_to_runtime_config::{costs_to_runtime_config, runtime_config_gas};
 pub use crate::qemu::QemuCommandBuilder;
 pub use crate::rocksdb::RocksDBTestConfig;
 
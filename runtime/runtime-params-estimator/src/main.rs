@@ -1,6 +1,7 @@
 #![doc = include_str!("../README.md")]
 
 use anyhow::Context;
+use compare::CompareCmd;
 use genesis_populate::GenesisBuilder;
 use near_chain_configs::GenesisValidationMode;
 use near_primitives::version::PROTOCOL_VERSION;
@@ -20,6 +21,7 @@ use std::process::Command;
 use std::time;
 use tracing_subscriber::Layer;
 
+mod compare;
 mod replay;
 
 #[derive(clap::Parser)]
@@ -104,6 +106,8 @@ struct CliArgs {
 #[derive(clap::Subcommand)]
 enum CliSubCmd {
     Replay(ReplayCmd),
+    /// Compare estimations of two builds of the estimator or of two VM kinds.
+    Compare(CompareCmd),
 }
 
 fn main() -> anyhow::Result<()> {
@@ -113,6 +117,7 @@ fn main() -> anyhow::Result<()> {
     if let Some(cmd) = cli_args.sub_cmd {
         return match cmd {
             CliSubCmd::Replay(inner) => inner.run(&mut std::io::stdout()),
+            CliSubCmd::Compare(inner) => inner.run(),
         };
     }
 
@@ -146,54 +151,7 @@ fn run_estimation(cli_args: CliArgs) -> anyhow::Result<Option<CostTable>> {
         }
     };
     if state_dump_path.read_dir()?.next().is_none() {
-        // Every created account gets this smart contract deployed, such that
-        // any account can be used to perform estimations that require this
-        // contract.
-        // Note: This contract no longer has a fixed size, which means that
-        // changes to the test contract might affect all kinds of estimations.
-        // (Larger code = more time spent on reading it from the database, for
-        // example.) But this is generally a sign of a badly designed
-        // estimation, therefore we make no effort to guarantee a fixed size.
-        // Also, continuous estimation should be able to pick up such changes.
-        let contract_code = near_test_contracts::estimator_contract();
-
-        nearcore::init_configs(
-            &state_dump_path,
-            None,
-            Some("test.near".parse().unwrap()),
-            Some("alice.near"),
-            1,
-            true,
-            None,
-            false,
-            None,
-            None,
-            false,
-            None,
-            None,
-            None,
-        )
-        .expect("failed to init config");
-
-        let near_config = nearcore::load_config(&state_dump_path, GenesisValidationMode::Full)
-            .context("Error loading config")?;
-        let store = near_store::NodeStorage::opener(
-            &state_dump_path,
-            near_config.config.archive,
-            &near_config.config.store,
-            None,
-        )
-        .open()
-        .unwrap()
-        .get_hot_store();
-        GenesisBuilder::from_config_and_store(&state_dump_path, near_config, store)
-            .add_additional_accounts(cli_args.additional_accounts_num)
-            .add_additional_accounts_contract(contract_code.to_vec())
-            .print_progress()
-            .build()
-            .unwrap()
-            .dump_state()
-            .unwrap();
+        create_state_dump(&state_dump_path, cli_args.additional_accounts_num)?;
     }
 
     if cli_args.docker {
@@ -303,6 +261,59 @@ fn run_estimation(cli_args: CliArgs) -> anyhow::Result<Option<CostTable>> {
     Ok(Some(cost_table))
 }
 
+/// Generates the state estimations run on in `state_dump_path`.
+fn create_state_dump(state_dump_path: &Path, additional_accounts_num: u64) -> anyhow::Result<()> {
+    // Every created account gets this smart contract deployed, such that
+    // any account can be used to perform estimations that require this
+    // contract.
+    // Note: This contract no longer has a fixed size, which means that
+    // changes to the test contract might affect all kinds of estimations.
+    // (Larger code = more time spent on reading it from the database, for
+    // example.) But this is generally a sign of a badly designed
+    // estimation, therefore we make no effort to guarantee a fixed size.
+    // Also, continuous estimation should be able to pick up such changes.
+    let contract_code = near_test_contracts::estimator_contract();
+
+    nearcore::init_configs(
+        state_dump_path,
+        None,
+        Some("test.near".parse().unwrap()),
+        Some("alice.near"),
+        1,
+        true,
+        None,
+        false,
+        None,
+        None,
+        false,
+        None,
+        None,
+        None,
+    )
+    .expect("failed to init config");
+
+    let near_config = nearcore::load_config(state_dump_path, GenesisValidationMode::Full)
+        .context("Error loading config")?;
+    let store = near_store::NodeStorage::opener(
+        state_dump_path,
+        near_config.config.archive,
+        &near_config.config.store,
+        None,
+    )
+    .open()
+    .unwrap()
+    .get_hot_store();
+    GenesisBuilder::from_config_and_store(state_dump_path, near_config, store)
+        .add_additional_accounts(additional_accounts_num)
+        .add_additional_accounts_contract(contract_code.to_vec())
+        .print_progress()
+        .build()
+        .unwrap()
+        .dump_state()
+        .unwrap();
+    Ok(())
+}
+
 /// Spawns another instance of this binary but inside docker.
 ///
 /// Most command line args are passed through but `--docker` is removed.
@@ -60,6 +60,7 @@ use crate::config::{
 };
 use crate::genesis::{GenesisStateApplier, StorageComputer};
 use crate::prefetch::TriePrefetcher;
+use crate::trace::ApplyTracer;
 use crate::verifier::{check_storage_stake, validate_receipt, StorageStakingError};
 pub use crate::verifier::{
     validate_transaction, verify_and_charge_transaction, ZERO_BALANCE_ACCOUNT_STORAGE_LIMIT,
@@ -74,6 +75,7 @@ mod genesis;
 mod metrics;
 mod prefetch;
 pub mod state_viewer;
+pub mod trace;
 mod verifier;
 
 const EXPECT_ACCOUNT_EXISTS: &str = "account exists, checked above";
@@ -1201,6 +1203,11 @@ impl Runtime {
         let trie = Rc::new(trie);
         let mut state_update = TrieUpdate::new(trie.clone());
         let mut prefetcher = TriePrefetcher::new_if_enabled(trie.clone());
+        let mut tracer = ApplyTracer::new_if_enabled(
+            apply_state.block_height,
+            apply_state.block_hash,
+            &mut state_update,
+        );
 
         if let Some(prefetcher) = &mut prefetcher {
             // Prefetcher is allowed to fail
@@ -1237,8 +1244,14 @@ impl Runtime {
             && apply_state.current_protocol_version
                 >= ProtocolFeature::FixApplyChunks.protocol_version()
         {
+            if let Some(tracer) = &mut tracer {
+                tracer.take_accesses(&state_update);
+            }
             let (trie_changes, state_changes) = state_update.finalize()?;
             let proof = trie.recorded_storage();
+            if let Some(tracer) = tracer {
+                tracer.finish(&trie, &[])?;
+            }
             return Ok(ApplyResult {
                 state_root: trie_changes.new_root,
                 trie_changes,
@@ -1307,6 +1320,9 @@ impl Runtime {
                 &mut stats,
                 epoch_info_provider,
             );
+            if let Some(tracer) = &mut tracer {
+                tracer.receipt_processed(receipt);
+            }
             tracing::debug!(target: "runtime", node_counter = ?state_update.trie().get_trie_nodes_count());
             if let Some(outcome_with_id) = result? {
                 *total_gas_burnt =
@@ -1417,6 +1433,9 @@ impl Runtime {
 
         state_update.commit(StateChangeCause::UpdatedDelayedReceipts);
         self.apply_state_patch(&mut state_update, state_patch);
+        if let Some(tracer) = &mut tracer {
+            tracer.take_accesses(&state_update);
+        }
         let (trie_changes, state_changes) = state_update.finalize()?;
 
         // Dedup proposals from the same account.
@@ -1433,6 +1452,9 @@ impl Runtime {
 
         let state_root = trie_changes.new_root;
         let proof = trie.recorded_storage();
+        if let Some(tracer) = tracer {
+            tracer.finish(&trie, &outcomes)?;
+        }
         Ok(ApplyResult {
             state_root,
             trie_changes,
@@ -1467,7 +1489,9 @@ impl Runtime {
         Ok(())
     }
 
-    fn apply_state_patch(&self, state_update: &mut TrieUpdate, state_patch: SandboxStatePatch) {
+    /// Writes the records of `state_patch` to `state_update` and commits them.  Only sandbox
+    /// builds can have a non-empty patch.
+    pub fn apply_state_patch(&self, state_update: &mut TrieUpdate, state_patch: SandboxStatePatch) {
         if state_patch.is_empty() {
             return;
         }
@@ -1547,6 +1571,7 @@ mod tests {
     };
     use near_primitives::types::MerkleHash;
     use near_primitives::version::PROTOCOL_VERSION;
+    use near_primitives::views::StateChangeCauseView;
     use near_store::set_access_key;
     use near_store::test_utils::create_tries;
     use near_store::StoreCompiledContractCache;
@@ -1762,6 +1787,56 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_apply_trace() {
+        let (runtime, tries, root, apply_state, _, epoch_info_provider) =
+            setup_runtime(to_yocto(1_000_000), 0, 10u64.pow(15));
+        let receipts = generate_refund_receipts(to_yocto(1), 2);
+
+        let (apply_result, traces) = crate::trace::with_apply_trace(|| {
+            runtime.apply(
+                tries.get_trie_for_shard(ShardUId::single_shard(), root),
+                &None,
+                &apply_state,
+                &receipts,
+                &[],
+                &epoch_info_provider,
+                Default::default(),
+            )
+        });
+        let apply_result = apply_result.unwrap();
+        assert_eq!(traces.len(), 1);
+        let trace = &traces[0];
+        assert_eq!(
+            trace.receipts.iter().map(|trace| trace.receipt.receipt_id).collect::<Vec<_>>(),
+            receipts.iter().map(|receipt| receipt.receipt_id).collect::<Vec<_>>()
+        );
+        assert_eq!(trace.outcomes.len(), apply_result.outcomes.len());
+        let account_key = TrieKey::Account { account_id: alice_account() }.to_vec();
+        let receipt_changes = trace
+            .state_changes
+            .iter()
+            .filter(|state_change| {
+                matches!(
+                    state_change.cause,
+                    StateChangeCauseView::ReceiptProcessing { receipt_hash }
+                        if receipt_hash == receipts[0].get_hash()
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(receipt_changes.len(), receipts.len());
+        for state_change in receipt_changes {
+            let account_read =
+                state_change.reads.iter().find(|read| read.key == account_key).unwrap();
+            assert_eq!(account_read.value.as_deref().map(hash), account_read.value_hash);
+            assert!(state_change.writes.iter().any(|write| write.key == account_key));
+        }
+
+        // Tracing is off outside of `with_apply_trace`.
+        let (_, traces) = crate::trace::with_apply_trace(|| ());
+        assert!(traces.is_empty());
+    }
+
     #[test]
     fn test_apply_delayed_receipts_feed_all_at_once() {
         let initial_balance = to_yocto(1_000_000);
@@ -52,6 +52,8 @@ pub enum CallFunctionError {
     InternalError { error_message: String },
     #[error("VM error occurred: #{error_message}")]
     VMError { error_message: String },
+    #[error("Contract code of {size} bytes exceeds the limit of {limit} bytes")]
+    ContractTooLarge { size: u64, limit: u64 },
 }
 
 impl From<ViewAccountError> for ViewContractCodeError {
@@ -13,11 +13,11 @@ use near_primitives::{
         migration_data::{MigrationData, MigrationFlags},
     },
     transaction::FunctionCallAction,
-    trie_key::trie_key_parsers,
+    trie_key::{trie_key_parsers, TrieKey},
     types::{AccountId, EpochInfoProvider, Gas},
-    views::{StateItem, ViewApplyState, ViewStateResult},
+    views::{StateItem, StateOverrides, ViewApplyState, ViewStateResult},
 };
-use near_store::{get_access_key, get_account, get_code, TrieUpdate};
+use near_store::{get_access_key, get_account, get_code, set_account, set_code, TrieUpdate};
 use near_vm_logic::{ReturnData, ViewConfig};
 use std::{str, sync::Arc, time::Instant};
 use tracing::debug;
@@ -158,6 +158,49 @@ impl TrieViewer {
         Ok(ViewStateResult { values, proof })
     }
 
+    /// Applies `overrides` to the state of `contract_id` in `state_update`, so that a following
+    /// `call_function` sees them. The caller must not commit the update.
+    pub fn apply_state_overrides(
+        &self,
+        state_update: &mut TrieUpdate,
+        contract_id: &AccountId,
+        overrides: &StateOverrides,
+    ) -> Result<(), errors::CallFunctionError> {
+        let mut account = get_account(state_update, contract_id)?.ok_or_else(|| {
+            errors::CallFunctionError::AccountDoesNotExist {
+                requested_account_id: contract_id.clone(),
+            }
+        })?;
+        if let Some(code) = &overrides.code {
+            let config_store = RuntimeConfigStore::new(None);
+            let limit = config_store
+                .get_config(PROTOCOL_VERSION)
+                .wasm_config
+                .limit_config
+                .max_contract_size;
+            if code.len() as u64 > limit {
+                return Err(errors::CallFunctionError::ContractTooLarge {
+                    size: code.len() as u64,
+                    limit,
+                });
+            }
+            let code = ContractCode::new(code.clone(), None);
+            account.set_code_hash(*code.hash());
+            set_code(state_update, contract_id.clone(), &code);
+        }
+        if let Some(balance) = overrides.balance {
+            account.set_amount(balance);
+        }
+        set_account(state_update, contract_id.clone(), &account);
+        for item in &overrides.storage {
+            state_update.set(
+                TrieKey::ContractData { account_id: contract_id.clone(), key: item.key.clone() },
+                item.value.clone(),
+            );
+        }
+        Ok(())
+    }
+
     pub fn call_function(
         &self,
         mut state_update: TrieUpdate,
@@ -0,0 +1,215 @@
+//! Execution traces of chunk application, used to debug nondeterminism.
+//!
+//! When tracing is enabled with [`with_apply_trace`], every call to
+//! [`crate::Runtime::apply`] on the current thread records the receipts it
+//! processed together with every host function cost charged while processing
+//! them, the gas profile of every transaction and receipt and all trie reads
+//! and writes with their values, grouped by the state change which caused
+//! them.  Two traces of the same chunk applied on different nodes or binaries
+//! can then be diffed to find the first point where they diverge.
+//!
+//! Tracing is expensive and is never enabled on a running node.
+
+use near_primitives::hash::CryptoHash;
+use near_primitives::receipt::Receipt;
+use near_primitives::serialize::{base64_format, option_base64_format};
+use near_primitives::transaction::ExecutionOutcomeWithId;
+use near_primitives::types::{AccountId, BlockHeight, Gas};
+use near_primitives::views::{ExecutionMetadataView, ReceiptView, StateChangeCauseView};
+use near_store::{StorageError, Trie, TrieAccesses, TrieRead, TrieUpdate};
+use near_vm_logic::gas_counter::{
+    set_host_function_tracing, take_host_function_trace, HostFunctionCharge,
+};
+use std::cell::RefCell;
+
+thread_local! {
+    static TRACES: RefCell<Option<Vec<ApplyTrace>>> = RefCell::new(None);
+}
+
+/// Runs `f` and returns traces of all chunks applied on the current thread
+/// during the call, in the order they were applied.
+pub fn with_apply_trace<T>(f: impl FnOnce() -> T) -> (T, Vec<ApplyTrace>) {
+    let previous = TRACES.with(|traces| traces.borrow_mut().replace(Vec::new()));
+    let result = f();
+    let traces = TRACES.with(|traces| std::mem::replace(&mut *traces.borrow_mut(), previous));
+    (result, traces.unwrap_or_default())
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ApplyTrace {
+    pub block_height: BlockHeight,
+    pub block_hash: CryptoHash,
+    /// Receipts in the order they were processed.
+    pub receipts: Vec<ReceiptTrace>,
+    /// Outcomes of transactions and receipts in the order they were produced.
+    pub outcomes: Vec<OutcomeTrace>,
+    /// Trie accesses grouped by the state change they ended with.
+    pub state_changes: Vec<StateChangeTrace>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ReceiptTrace {
+    pub receipt: ReceiptView,
+    /// Host function costs charged while processing the receipt, in order.
+    /// Every host function call starts with a `base` charge.
+    pub host_function_charges: Vec<HostFunctionChargeTrace>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct HostFunctionChargeTrace {
+    pub cost: String,
+    pub count: u64,
+    pub gas: Gas,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct OutcomeTrace {
+    /// Hash of the transaction or id of the receipt.
+    pub id: CryptoHash,
+    pub executor_id: AccountId,
+    pub gas_burnt: Gas,
+    /// Breakdown of the gas burnt by actions and host functions.
+    pub metadata: ExecutionMetadataView,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct StateChangeTrace {
+    pub cause: StateChangeCauseView,
+    pub reads: Vec<TrieReadTrace>,
+    pub writes: Vec<TrieWriteTrace>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct TrieReadTrace {
+    #[serde(with = "base64_format")]
+    pub key: Vec<u8>,
+    /// Hash of the value read, `None` if the key was missing.
+    pub value_hash: Option<CryptoHash>,
+    #[serde(with = "option_base64_format")]
+    pub value: Option<Vec<u8>>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct TrieWriteTrace {
+    #[serde(with = "base64_format")]
+    pub key: Vec<u8>,
+    /// New value, `None` if the key was removed.
+    #[serde(with = "option_base64_format")]
+    pub value: Option<Vec<u8>>,
+}
+
+/// Collects the trace of a single `Runtime::apply` call.
+pub(crate) struct ApplyTracer {
+    trace: ApplyTrace,
+    accesses: Vec<TrieAccesses>,
+    _host_function_tracing: HostFunctionTracing,
+}
+
+impl ApplyTracer {
+    /// Returns a tracer if tracing is enabled on the current thread and starts
+    /// recording accesses on `state_update`.
+    pub(crate) fn new_if_enabled(
+        block_height: BlockHeight,
+        block_hash: CryptoHash,
+        state_update: &mut TrieUpdate,
+    ) -> Option<Self> {
+        if !TRACES.with(|traces| traces.borrow().is_some()) {
+            return None;
+        }
+        state_update.start_recording_accesses();
+        Some(Self {
+            trace: ApplyTrace {
+                block_height,
+                block_hash,
+                receipts: vec![],
+                outcomes: vec![],
+                state_changes: vec![],
+            },
+            accesses: vec![],
+            _host_function_tracing: HostFunctionTracing::start(),
+        })
+    }
+
+    /// Records a processed receipt together with the host function costs
+    /// charged while processing it.
+    pub(crate) fn receipt_processed(&mut self, receipt: &Receipt) {
+        let host_function_charges = take_host_function_trace()
+            .into_iter()
+            .map(|HostFunctionCharge { cost, count, gas }| HostFunctionChargeTrace {
+                cost: cost.to_string(),
+                count,
+                gas,
+            })
+            .collect();
+        self.trace
+            .receipts
+            .push(ReceiptTrace { receipt: receipt.clone().into(), host_function_charges });
+    }
+
+    /// Takes all accesses committed on `state_update` so far.  Must be called
+    /// before the update is finalized.
+    pub(crate) fn take_accesses(&mut self, state_update: &TrieUpdate) {
+        self.accesses.extend(state_update.take_recorded_accesses());
+    }
+
+    /// Completes the trace with the outcomes and the accesses taken before.
+    /// Values read only by hash are looked up in `trie`, so this has to be
+    /// called after the storage proof is taken.
+    pub(crate) fn finish(
+        mut self,
+        trie: &Trie,
+        outcomes: &[ExecutionOutcomeWithId],
+    ) -> Result<(), StorageError> {
+        self.trace.outcomes = outcomes
+            .iter()
+            .map(|outcome_with_id| OutcomeTrace {
+                id: outcome_with_id.id,
+                executor_id: outcome_with_id.outcome.executor_id.clone(),
+                gas_burnt: outcome_with_id.outcome.gas_burnt,
+                metadata: outcome_with_id.outcome.metadata.clone().into(),
+            })
+            .collect();
+        for mut accesses in std::mem::take(&mut self.accesses) {
+            accesses.resolve_values(trie)?;
+            let TrieAccesses { cause, reads, writes } = accesses;
+            self.trace.state_changes.push(StateChangeTrace {
+                cause: cause.into(),
+                reads: reads
+                    .into_iter()
+                    .map(|TrieRead { key, value_hash, value }| TrieReadTrace {
+                        key,
+                        value_hash,
+                        value,
+                    })
+                    .collect(),
+                writes: writes
+                    .into_iter()
+                    .map(|(key, value)| TrieWriteTrace { key, value })
+                    .collect(),
+            });
+        }
+        TRACES.with(|traces| {
+            if let Some(traces) = traces.borrow_mut().as_mut() {
+                traces.push(self.trace);
+            }
+        });
+        Ok(())
+    }
+}
+
+/// Records host function charges while alive, so that tracing is turned off
+/// even if applying the chunk fails half way.
+struct HostFunctionTracing;
+
+impl HostFunctionTracing {
+    fn start() -> Self {
+        set_host_function_tracing(true);
+        Self
+    }
+}
+
+impl Drop for HostFunctionTracing {
+    fn drop(&mut self) {
+        set_host_function_tracing(false);
+    }
+}
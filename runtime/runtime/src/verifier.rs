@@ -96,6 +96,7 @@ pub fn validate_transaction(
     let signer_id = &transaction.signer_id;
 
     if verify_signature
+        && !is_sandbox_impersonation(signed_transaction)
         && !signed_transaction
             .signature
             .verify(signed_transaction.get_hash().as_ref(), &transaction.public_key)
@@ -154,16 +155,21 @@ pub fn verify_and_charge_transaction(
             return Err(InvalidTxError::SignerDoesNotExist { signer_id: signer_id.clone() }.into());
         }
     };
-    let mut access_key = match get_access_key(state_update, signer_id, &transaction.public_key)? {
-        Some(access_key) => access_key,
-        None => {
-            return Err(InvalidTxError::InvalidAccessKeyError(
-                InvalidAccessKeyError::AccessKeyNotFound {
-                    account_id: signer_id.clone(),
-                    public_key: transaction.public_key.clone(),
-                },
-            )
-            .into());
+    let impersonated = is_sandbox_impersonation(signed_transaction);
+    let mut access_key = if impersonated {
+        near_primitives::account::AccessKey::full_access()
+    } else {
+        match get_access_key(state_update, signer_id, &transaction.public_key)? {
+            Some(access_key) => access_key,
+            None => {
+                return Err(InvalidTxError::InvalidAccessKeyError(
+                    InvalidAccessKeyError::AccessKeyNotFound {
+                        account_id: signer_id.clone(),
+                        public_key: transaction.public_key.clone(),
+                    },
+                )
+                .into());
+            }
         }
     };
 
@@ -174,7 +180,7 @@ pub fn verify_and_charge_transaction(
         }
         .into());
     }
-    if checked_feature!("stable", AccessKeyNonceRange, current_protocol_version) {
+    if !impersonated && checked_feature!("stable", AccessKeyNonceRange, current_protocol_version) {
         if let Some(height) = block_height {
             let upper_bound =
                 height * near_primitives::account::AccessKey::ACCESS_KEY_NONCE_RANGE_MULTIPLIER;
@@ -271,12 +277,31 @@ pub fn verify_and_charge_transaction(
         }
     };
 
-    set_access_key(state_update, signer_id.clone(), transaction.public_key.clone(), &access_key);
+    if !impersonated {
+        set_access_key(
+            state_update,
+            signer_id.clone(),
+            transaction.public_key.clone(),
+            &access_key,
+        );
+    }
     set_account(state_update, signer_id.clone(), &signer);
 
     Ok(VerificationResult { gas_burnt, gas_remaining, receipt_gas_price, burnt_amount })
 }
 
+/// Whether the transaction was sent with `sandbox_send_tx_as`, in which case it's accepted
+/// without checking its signature and the access key of the signer.
+#[cfg(feature = "sandbox")]
+fn is_sandbox_impersonation(signed_transaction: &SignedTransaction) -> bool {
+    near_primitives::sandbox::impersonation::is_impersonated(signed_transaction)
+}
+
+#[cfg(not(feature = "sandbox"))]
+fn is_sandbox_impersonation(_signed_transaction: &SignedTransaction) -> bool {
+    false
+}
+
 /// Validates a given receipt. Checks validity of the Action or Data receipt.
 pub(crate) fn validate_receipt(
     limit_config: &VMLimitConfig,
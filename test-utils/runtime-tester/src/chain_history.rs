@@ -0,0 +1,314 @@
+//! Conversion of real chain history into self-contained [`Scenario`]s.
+//!
+//! A [`ChainHistory`] consists of transactions included in a range of blocks
+//! and of the state of the accounts touched by them as of the start of the
+//! range.  It can be read from a node's database with
+//! [`ChainHistory::from_node_db`], or assembled from the output of
+//! `neard view-state dump-tx` and the records of
+//! `neard view-state dump-state --account-ids ...` with
+//! [`ChainHistory::from_tx_dump`].
+//!
+//! We don't know the private keys of real accounts, so in the resulting
+//! scenario every account only has a single full access key derived from its
+//! account id, and all transactions are re-signed with it.  Nonces are
+//! renumbered per signer, preserving their order.  Receipts which were in
+//! flight at the start of the range are not reproduced.
+use crate::run_test::{BlockConfig, NetworkConfig, RuntimeConfig, Scenario, TransactionConfig};
+use anyhow::Context;
+use near_chain::types::RuntimeAdapter;
+use near_chain::{ChainStore, ChainStoreAccess};
+use near_chain_configs::GenesisValidationMode;
+use near_crypto::{InMemorySigner, KeyType};
+use near_primitives::account::AccessKey;
+use near_primitives::state_record::{state_record_to_account_id, StateRecord};
+use near_primitives::transaction::SignedTransaction;
+use near_primitives::trie_key::TrieKey;
+use near_primitives::types::{AccountId, BlockHeight, Nonce};
+use near_store::{Mode, NodeStorage};
+use nearcore::NightshadeRuntime;
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+
+/// Account producing the blocks of the scenario.
+const BLOCK_PRODUCER: &str = "test0";
+
+/// Transactions included at a single height.
+pub struct HistoryBlock {
+    pub height: BlockHeight,
+    pub transactions: Vec<SignedTransaction>,
+}
+
+pub struct ChainHistory {
+    pub blocks: Vec<HistoryBlock>,
+    /// State of the touched accounts before the first block was applied.
+    pub state_records: Vec<StateRecord>,
+}
+
+impl ChainHistory {
+    /// Reads the transactions included in blocks with heights in
+    /// `[start_height, end_height]` from the database of the node at
+    /// `home_dir`, together with the state of all signers and receivers of
+    /// these transactions as of the first block in the range.
+    pub fn from_node_db(
+        home_dir: &Path,
+        start_height: BlockHeight,
+        end_height: BlockHeight,
+    ) -> anyhow::Result<Self> {
+        let near_config =
+            nearcore::config::load_config(home_dir, GenesisValidationMode::UnsafeFast)
+                .context("Error loading config")?;
+        let store = NodeStorage::opener(
+            home_dir,
+            near_config.config.archive,
+            &near_config.config.store,
+            None,
+        )
+        .open_in_mode(Mode::ReadOnly)?
+        .get_hot_store();
+        let chain_store = ChainStore::new(
+            store.clone(),
+            near_config.genesis.config.genesis_height,
+            near_config.client_config.save_trie_changes,
+        );
+        let runtime = NightshadeRuntime::from_config(home_dir, store, &near_config);
+
+        let mut blocks = vec![];
+        let mut first_block = None;
+        for height in start_height..=end_height {
+            let block_hash = match chain_store.get_block_hash_by_height(height) {
+                Ok(hash) => hash,
+                Err(_) => continue,
+            };
+            let block = chain_store.get_block(&block_hash)?;
+            let mut transactions = vec![];
+            for chunk_header in block.chunks().iter() {
+                // Chunks which are missing in this block were already included earlier.
+                if chunk_header.height_included() != height {
+                    continue;
+                }
+                let chunk = chain_store.get_chunk(&chunk_header.chunk_hash())?;
+                transactions.extend(chunk.transactions().iter().cloned());
+            }
+            blocks.push(HistoryBlock { height, transactions });
+            first_block.get_or_insert(block);
+        }
+        let first_block = first_block.with_context(|| {
+            format!("No blocks found between heights {} and {}", start_height, end_height)
+        })?;
+
+        let accounts = touched_accounts(&blocks);
+        let mut state_records = vec![];
+        for (shard_id, chunk_header) in first_block.chunks().iter().enumerate() {
+            let trie = runtime.get_trie_for_shard(
+                shard_id as u64,
+                first_block.header().prev_hash(),
+                chunk_header.prev_state_root(),
+                false,
+            )?;
+            for account_id in accounts.iter() {
+                let account_key = TrieKey::Account { account_id: account_id.clone() }.to_vec();
+                let account = match trie.get(&account_key)? {
+                    Some(value) => value,
+                    None => continue,
+                };
+                state_records.extend(StateRecord::from_raw_key_value(account_key, account));
+
+                let code_key = TrieKey::ContractCode { account_id: account_id.clone() }.to_vec();
+                if let Some(code) = trie.get(&code_key)? {
+                    state_records.extend(StateRecord::from_raw_key_value(code_key, code));
+                }
+
+                let data_prefix = TrieKey::get_raw_prefix_for_contract_data(account_id, &[]);
+                let mut iter = trie.iter()?;
+                iter.seek_prefix(&data_prefix)?;
+                for item in iter {
+                    let (key, value) = item?;
+                    if !key.starts_with(&data_prefix) {
+                        break;
+                    }
+                    state_records.extend(StateRecord::from_raw_key_value(key, value));
+                }
+            }
+        }
+        Ok(Self { blocks, state_records })
+    }
+
+    /// Builds history from a list of transactions as dumped by
+    /// `neard view-state dump-tx` and state records of the touched accounts.
+    /// The dump doesn't contain the heights at which transactions were
+    /// included, so every transaction is put into a block of its own.
+    pub fn from_tx_dump(
+        transactions: Vec<SignedTransaction>,
+        state_records: Vec<StateRecord>,
+    ) -> Self {
+        let blocks = transactions
+            .into_iter()
+            .zip(1..)
+            .map(|(tx, height)| HistoryBlock { height, transactions: vec![tx] })
+            .collect();
+        Self { blocks, state_records }
+    }
+
+    /// Converts the history into a scenario which applies the same
+    /// transactions on top of the same state.  Block heights are shifted so
+    /// that the first block is at height 1, but gaps between them are kept.
+    pub fn into_scenario(self, runtime_config: RuntimeConfig) -> Scenario {
+        let block_producer: AccountId = BLOCK_PRODUCER.parse().unwrap();
+        let mut state_records = vec![];
+        for record in self.state_records {
+            match &record {
+                // The access keys are replaced below with keys we can sign with.
+                StateRecord::AccessKey { .. } => {}
+                StateRecord::Account { account_id, .. } if account_id != &block_producer => {
+                    state_records.push(StateRecord::AccessKey {
+                        account_id: account_id.clone(),
+                        public_key: signer(account_id).public_key,
+                        access_key: AccessKey::full_access(),
+                    });
+                    state_records.push(record);
+                }
+                _ => {
+                    if state_record_to_account_id(&record) != &block_producer {
+                        state_records.push(record);
+                    }
+                }
+            }
+        }
+
+        let first_height = self.blocks.first().map_or(0, |block| block.height);
+        let mut nonces: HashMap<AccountId, Nonce> = HashMap::new();
+        let blocks = self
+            .blocks
+            .into_iter()
+            .map(|block| {
+                let mut block_config = BlockConfig::at_height(block.height - first_height + 1);
+                for tx in block.transactions {
+                    let tx = tx.transaction;
+                    let nonce = nonces.entry(tx.signer_id.clone()).or_default();
+                    *nonce += 1;
+                    block_config.transactions.push(TransactionConfig {
+                        nonce: *nonce,
+                        signer: signer(&tx.signer_id),
+                        signer_id: tx.signer_id,
+                        receiver_id: tx.receiver_id,
+                        actions: tx.actions,
+                    });
+                }
+                block_config
+            })
+            .collect();
+
+        Scenario {
+            network_config: NetworkConfig { seeds: vec![BLOCK_PRODUCER.to_string()] },
+            runtime_config,
+            blocks,
+            use_in_memory_store: true,
+            state_records,
+        }
+    }
+}
+
+fn touched_accounts(blocks: &[HistoryBlock]) -> BTreeSet<AccountId> {
+    let mut accounts = BTreeSet::new();
+    for tx in blocks.iter().flat_map(|block| block.transactions.iter()) {
+        accounts.insert(tx.transaction.signer_id.clone());
+        accounts.insert(tx.transaction.receiver_id.clone());
+    }
+    accounts
+}
+
+fn signer(account_id: &AccountId) -> InMemorySigner {
+    InMemorySigner::from_seed(account_id.clone(), KeyType::ED25519, account_id.as_ref())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use near_primitives::account::Account;
+    use near_primitives::hash::CryptoHash;
+    use near_primitives::transaction::{Action, TransferAction};
+
+    #[test]
+    fn test_scenario_from_history() {
+        let alice: AccountId = "alice.near".parse().unwrap();
+        let bob: AccountId = "bob.near".parse().unwrap();
+        // Keys nobody knows the private part of.
+        let real_signer = InMemorySigner::from_random(alice.clone(), KeyType::ED25519);
+
+        let mut state_records = vec![];
+        for account_id in [&alice, &bob] {
+            state_records.push(StateRecord::Account {
+                account_id: account_id.clone(),
+                account: Account::new(10u128.pow(30), 0, CryptoHash::default(), 0),
+            });
+            state_records.push(StateRecord::AccessKey {
+                account_id: account_id.clone(),
+                public_key: real_signer.public_key.clone(),
+                access_key: AccessKey { nonce: 85_000_000_000_000, ..AccessKey::full_access() },
+            });
+        }
+        let transfer = |nonce| {
+            SignedTransaction::send_money(
+                nonce,
+                alice.clone(),
+                bob.clone(),
+                &real_signer,
+                10u128.pow(24),
+                CryptoHash::default(),
+            )
+        };
+        let history = ChainHistory {
+            blocks: vec![
+                HistoryBlock {
+                    height: 85_000_010,
+                    transactions: vec![transfer(85_000_000_000_001)],
+                },
+                HistoryBlock {
+                    height: 85_000_012,
+                    transactions: vec![transfer(85_000_000_000_005)],
+                },
+            ],
+            state_records,
+        };
+
+        let scenario = history.into_scenario(RuntimeConfig {
+            max_total_prepaid_gas: 300 * 10u64.pow(12),
+            gas_limit: 1_000_000_000_000_000,
+            epoch_length: 500,
+        });
+        assert_eq!(
+            scenario.blocks.iter().map(|block| block.height).collect::<Vec<_>>(),
+            vec![1, 3]
+        );
+        assert_eq!(
+            scenario.blocks.iter().map(|block| block.transactions[0].nonce).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        assert_eq!(
+            scenario.blocks[0].transactions[0].actions,
+            vec![Action::Transfer(TransferAction { deposit: 10u128.pow(24) })]
+        );
+
+        let mut scenario: Scenario =
+            serde_json::from_str(&serde_json::to_string(&scenario).unwrap()).unwrap();
+        // Give the receipts of the last transaction time to execute.
+        scenario.blocks.push(BlockConfig::at_height(4));
+        scenario.blocks.push(BlockConfig::at_height(5));
+        let result = scenario.run();
+        let stats = result.result.unwrap();
+        let client = &result.env.clients[0];
+        for block_stats in stats.blocks_stats {
+            for tx_hash in block_stats.tx_hashes {
+                let outcome = client.chain.get_final_transaction_result(&tx_hash).unwrap();
+                assert!(
+                    matches!(
+                        outcome.status,
+                        near_primitives::views::FinalExecutionStatus::SuccessValue(_)
+                    ),
+                    "{:?}",
+                    outcome
+                );
+            }
+        }
+    }
+}
@@ -1,12 +1,13 @@
 use crate::run_test::{BlockConfig, NetworkConfig, RuntimeConfig, Scenario, TransactionConfig};
-use near_crypto::{InMemorySigner, KeyType, PublicKey};
+use near_crypto::{InMemorySigner, KeyType, PublicKey, Signer};
 use near_primitives::{
     account::{AccessKey, AccessKeyPermission, FunctionCallPermission},
+    delegate_action::{DelegateAction, NonDelegateAction, SignedDelegateAction},
     transaction::{
         Action, AddKeyAction, CreateAccountAction, DeleteAccountAction, DeleteKeyAction,
         DeployContractAction, FunctionCallAction, TransferAction,
     },
-    types::{AccountId, Balance, BlockHeight, Nonce},
+    types::{AccountId, Balance, BlockHeight, Gas, Nonce},
 };
 use nearcore::config::{NEAR_BASE, TESTING_INIT_BALANCE};
 
@@ -22,6 +23,11 @@ pub const MAX_TXS: usize = 50;
 pub const MAX_TX_DIFF: usize = 10;
 pub const MAX_ACCOUNTS: usize = 100;
 pub const MAX_ACTIONS: usize = 100;
+pub const MAX_DELEGATE_ACTIONS: usize = 5;
+/// Number of blocks after which a generated delegate action may expire.
+pub const MAX_DELEGATE_VALIDITY: u64 = 5;
+/// How deep the promise chains created by `call_promise` can get.
+pub const MAX_PROMISE_DEPTH: usize = 4;
 
 const GAS_1: u64 = 300_000_000_000_000;
 
@@ -45,7 +51,13 @@ impl Arbitrary<'_> for Scenario {
         while blocks.len() < MAX_BLOCKS && u.len() > BlockConfig::size_hint(0).0 {
             blocks.push(BlockConfig::arbitrary(u, &mut scope)?);
         }
-        Ok(Scenario { network_config, runtime_config, blocks, use_in_memory_store: true })
+        Ok(Scenario {
+            network_config,
+            runtime_config,
+            blocks,
+            use_in_memory_store: true,
+            state_records: vec![],
+        })
     }
 
     fn size_hint(_depth: usize) -> (usize, Option<usize>) {
@@ -266,7 +278,7 @@ impl TransactionConfig {
 
             while actions.len() < actions_num && u.len() > Function::size_hint(0).1.unwrap() {
                 let function = u.choose(&receiver_functions)?;
-                actions.push(Action::FunctionCall(function.arbitrary(u)?));
+                actions.push(Action::FunctionCall(function.arbitrary(u, scope)?));
             }
 
             Ok(TransactionConfig {
@@ -278,6 +290,60 @@ impl TransactionConfig {
             })
         });
 
+        // Delegate action, i.e. a meta transaction sent by a relayer
+        options.push(|u, scope| {
+            let nonce = scope.nonce();
+
+            let relayer_account = scope.random_account(u)?;
+            let sender_account = scope.random_account(u)?;
+            let receiver_account = scope.random_account(u)?;
+
+            let relayer_signer = scope.full_access_signer(u, &relayer_account)?;
+            let sender_signer =
+                scope.function_call_signer(u, &sender_account, receiver_account.id.as_str())?;
+
+            let mut receiver_functions = vec![];
+            if let Some(contract_id) = receiver_account.deployed_contract {
+                for function in &scope.available_contracts[contract_id].functions {
+                    receiver_functions.push(function);
+                }
+            }
+
+            let mut actions = vec![];
+            let actions_num = u.int_in_range(1..=MAX_DELEGATE_ACTIONS)?;
+            while actions.len() < actions_num && u.len() > Function::size_hint(0).1.unwrap() {
+                let action = if receiver_functions.is_empty() || u.arbitrary::<bool>()? {
+                    let amount = u.int_in_range::<u128>(0..=sender_account.balance)?;
+                    Action::Transfer(TransferAction { deposit: amount })
+                } else {
+                    let function = u.choose(&receiver_functions)?;
+                    Action::FunctionCall(function.arbitrary(u, scope)?)
+                };
+                actions.push(NonDelegateAction::try_from(action).unwrap());
+            }
+
+            let delegate_action = DelegateAction {
+                sender_id: sender_account.id.clone(),
+                receiver_id: receiver_account.id.clone(),
+                actions,
+                nonce,
+                max_block_height: scope.height() + u.int_in_range(0..=MAX_DELEGATE_VALIDITY)?,
+                public_key: sender_signer.public_key(),
+            };
+            let signature = sender_signer.sign(delegate_action.get_nep461_hash().as_ref());
+
+            Ok(TransactionConfig {
+                nonce,
+                signer_id: relayer_account.id.clone(),
+                receiver_id: sender_account.id.clone(),
+                signer: relayer_signer,
+                actions: vec![Action::Delegate(SignedDelegateAction {
+                    delegate_action,
+                    signature,
+                })],
+            })
+        });
+
         // Add key
         options.push(|u, scope| {
             let nonce = scope.nonce();
@@ -388,6 +454,7 @@ pub enum Function {
     UsedGas,
     WriteKeyValue,
     WriteBlockHeight,
+    CallPromise,
     // ########################
     // # Contract for fuzzing #
     // ########################
@@ -435,6 +502,7 @@ impl Scope {
                     Function::UsedGas,
                     Function::WriteKeyValue,
                     Function::WriteBlockHeight,
+                    Function::CallPromise,
                 ],
             },
             Contract {
@@ -604,6 +672,54 @@ impl Scope {
         }
     }
 
+    /// Generates arguments for `call_promise` of the test contract.  Every
+    /// call creates a promise on a random account, optionally followed by
+    /// a callback, and both of them call `call_promise` again with arguments
+    /// generated the same way, until `depth` is exhausted.  Receivers without
+    /// the test contract make the calls fail, which produces refunds for the
+    /// attached deposits.
+    pub fn arbitrary_promises(
+        &self,
+        u: &mut Unstructured,
+        depth: usize,
+        gas: Gas,
+    ) -> Result<serde_json::Value> {
+        if depth == 0 {
+            return Ok(serde_json::json!([]));
+        }
+        // Leave some gas for the caller itself.
+        let gas = gas / 3;
+
+        let receiver_account = self.random_account(u)?;
+        let amount = *u.choose(&[0, 1, NEAR_BASE])?;
+        let mut promises = vec![serde_json::json!({
+            "create": {
+                "account_id": receiver_account.id,
+                "method_name": "call_promise",
+                "arguments": self.arbitrary_promises(u, depth - 1, gas)?,
+                "amount": amount.to_string(),
+                "gas": gas,
+            },
+            "id": 0,
+        })];
+        if u.arbitrary::<bool>()? {
+            let callback_account = self.random_account(u)?;
+            promises.push(serde_json::json!({
+                "then": {
+                    "promise_index": 0,
+                    "account_id": callback_account.id,
+                    "method_name": "call_promise",
+                    "arguments": self.arbitrary_promises(u, depth - 1, gas)?,
+                    "amount": "0",
+                    "gas": gas,
+                },
+                "id": 1,
+                "return": u.arbitrary::<bool>()?,
+            }));
+        }
+        Ok(serde_json::Value::Array(promises))
+    }
+
     pub fn delete_random_key(
         &mut self,
         u: &mut Unstructured,
@@ -671,7 +787,7 @@ impl Account {
 }
 
 impl Function {
-    pub fn arbitrary(&self, u: &mut Unstructured) -> Result<FunctionCallAction> {
+    pub fn arbitrary(&self, u: &mut Unstructured, scope: &Scope) -> Result<FunctionCallAction> {
         let method_name;
         let mut args = Vec::new();
         match self {
@@ -731,6 +847,11 @@ impl Function {
             Function::WriteBlockHeight => {
                 method_name = "write_block_height";
             }
+            Function::CallPromise => {
+                method_name = "call_promise";
+                let depth = u.int_in_range(1..=MAX_PROMISE_DEPTH)?;
+                args = serde_json::to_vec(&scope.arbitrary_promises(u, depth, GAS_1)?).unwrap();
+            }
             // ########################
             // # Contract for fuzzing #
             // ########################
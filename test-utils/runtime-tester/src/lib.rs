@@ -1,9 +1,11 @@
 #![doc = include_str!("../README.md")]
 
+pub mod chain_history;
 pub mod fuzzing;
 pub mod run_test;
 pub mod scenario_builder;
 
+pub use crate::chain_history::ChainHistory;
 pub use crate::run_test::{BlockConfig, NetworkConfig, RuntimeConfig, Scenario, TransactionConfig};
 pub use crate::scenario_builder::ScenarioBuilder;
 
@@ -28,6 +30,7 @@ fn scenario_smoke_test() {
         },
         blocks: Vec::new(),
         use_in_memory_store: true,
+        state_records: vec![],
     };
 
     for h in 1..5 {
@@ -0,0 +1,85 @@
+use anyhow::Context;
+use near_primitives::state_record::StateRecord;
+use near_primitives::transaction::SignedTransaction;
+use near_primitives::types::BlockHeight;
+use runtime_tester::{ChainHistory, ScenarioBuilder};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+/// Converts chain history into runtime-tester scenarios.
+#[derive(clap::Parser)]
+struct Cli {
+    #[clap(subcommand)]
+    subcmd: SubCommand,
+    /// Path of the JSON file to write the scenario to.
+    #[clap(long, default_value = "scenario.json")]
+    output: PathBuf,
+}
+
+#[derive(clap::Subcommand)]
+enum SubCommand {
+    /// Read transactions in a range of blocks and the state of touched
+    /// accounts from a node's database.
+    FromChain(FromChainCmd),
+    /// Combine the output of `neard view-state dump-tx` with state records of
+    /// the touched accounts, e.g. from `neard view-state dump-state --stream`.
+    FromTxDump(FromTxDumpCmd),
+}
+
+#[derive(clap::Parser)]
+struct FromChainCmd {
+    /// Home directory of the node.
+    #[clap(long)]
+    home: PathBuf,
+    /// Height of the first block to take transactions from, inclusive.
+    #[clap(long)]
+    start_height: BlockHeight,
+    /// Height of the last block to take transactions from, inclusive.
+    #[clap(long)]
+    end_height: BlockHeight,
+}
+
+#[derive(clap::Parser)]
+struct FromTxDumpCmd {
+    /// JSON file with a list of transactions.
+    #[clap(long)]
+    transactions: PathBuf,
+    /// JSON file with a list of state records.
+    #[clap(long)]
+    records: PathBuf,
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> anyhow::Result<T> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    serde_json::from_reader(BufReader::new(file))
+        .with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+fn main() -> anyhow::Result<()> {
+    let cli = <Cli as clap::Parser>::parse();
+    let history = match cli.subcmd {
+        SubCommand::FromChain(cmd) => {
+            ChainHistory::from_node_db(&cmd.home, cmd.start_height, cmd.end_height)?
+        }
+        SubCommand::FromTxDump(cmd) => {
+            let transactions: Vec<SignedTransaction> = read_json(&cmd.transactions)?;
+            let records: Vec<StateRecord> = read_json(&cmd.records)?;
+            ChainHistory::from_tx_dump(transactions, records)
+        }
+    };
+    let num_blocks = history.blocks.len();
+    let num_records = history.state_records.len();
+    // Use the same limits as scenarios created in code.
+    let runtime_config = ScenarioBuilder::new().scenario().runtime_config.clone();
+    let scenario = history.into_scenario(runtime_config);
+    std::fs::write(&cli.output, serde_json::to_string_pretty(&scenario)?)
+        .with_context(|| format!("Failed to write {}", cli.output.display()))?;
+    println!(
+        "Saved scenario with {} blocks and {} state records to {}",
+        num_blocks,
+        num_records,
+        cli.output.display()
+    );
+    Ok(())
+}
@@ -1,15 +1,18 @@
 use near_chain::{Block, ChainGenesis, Provenance};
-use near_chain_configs::Genesis;
+use near_chain_configs::{get_initial_supply, Genesis};
 use near_client::test_utils::TestEnv;
 use near_client_primitives::types::Error;
 use near_crypto::InMemorySigner;
 use near_primitives::hash::CryptoHash;
 use near_primitives::runtime::config_store::RuntimeConfigStore;
+use near_primitives::shard_layout::ShardUId;
+use near_primitives::state_record::StateRecord;
 use near_primitives::transaction::{Action, SignedTransaction};
 use near_primitives::types::{AccountId, BlockHeight, BlockHeightDelta, Gas, Nonce};
 use near_store::test_utils::create_test_store;
 use nearcore::TrackedConfig;
 use nearcore::{config::GenesisExt, NightshadeRuntime};
+use node_runtime::config::RuntimeConfig as NodeRuntimeConfig;
 use std::io;
 use std::path::Path;
 use std::sync::Arc;
@@ -39,7 +42,12 @@ impl Scenario {
             self.runtime_config.max_total_prepaid_gas;
         genesis.config.epoch_length = self.runtime_config.epoch_length;
         genesis.config.gas_limit = self.runtime_config.gas_limit;
-        let runtime_config_store = RuntimeConfigStore::with_one_config(runtime_config);
+        if !self.state_records.is_empty() {
+            let records = genesis.force_read_records();
+            records.0.extend(self.state_records.iter().cloned());
+            genesis.config.total_supply = get_initial_supply(&records.0);
+        }
+        let runtime_config_store = RuntimeConfigStore::with_one_config(runtime_config.clone());
 
         let (tempdir, store) = if self.use_in_memory_store {
             (None, create_test_store())
@@ -61,11 +69,15 @@ impl Scenario {
             ))])
             .build();
 
-        let result = self.process_blocks(&mut env);
+        let result = self.process_blocks(&mut env, &runtime_config);
         ScenarioResult { result, homedir: tempdir, env }
     }
 
-    fn process_blocks(&self, env: &mut TestEnv) -> Result<RuntimeStats, Error> {
+    fn process_blocks(
+        &self,
+        env: &mut TestEnv,
+        runtime_config: &NodeRuntimeConfig,
+    ) -> Result<RuntimeStats, Error> {
         let mut last_block = env.clients[0].chain.get_block_by_height(0).unwrap();
 
         let mut runtime_stats = RuntimeStats::default();
@@ -84,10 +96,14 @@ impl Scenario {
             last_block = env.clients[0]
                 .produce_block(block.height)?
                 .ok_or_else(|| Error::Other(String::from("No block has been produced")))?;
-            env.process_block(0, last_block.clone(), Provenance::PRODUCED);
+            // Balance conservation is checked by the runtime while applying
+            // chunks, so a violation makes processing of the block fail.
+            env.clients[0].process_block_test(last_block.clone().into(), Provenance::PRODUCED)?;
 
             block_stats.block_production_time = start_time.elapsed();
 
+            check_storage_usage(env, &last_block, runtime_config)?;
+
             runtime_stats.blocks_stats.push(block_stats);
         }
 
@@ -95,12 +111,56 @@ impl Scenario {
     }
 }
 
+/// Checks that the storage usage recorded in every account matches the size
+/// of the account's records in the state after applying the given block.
+fn check_storage_usage(
+    env: &TestEnv,
+    block: &Block,
+    runtime_config: &NodeRuntimeConfig,
+) -> Result<(), Error> {
+    let client = &env.clients[0];
+    let shard_uid = ShardUId::single_shard();
+    let state_root = *client.chain.get_chunk_extra(block.hash(), &shard_uid)?.state_root();
+    let trie = client.runtime_adapter.get_trie_for_shard(
+        shard_uid.shard_id as u64,
+        block.hash(),
+        state_root,
+        false,
+    )?;
+    let mut records = vec![];
+    for item in trie.iter().map_err(|err| Error::Other(err.to_string()))? {
+        let (key, value) = item.map_err(|err| Error::Other(err.to_string()))?;
+        records.extend(StateRecord::from_raw_key_value(key, value));
+    }
+    let storage_usage =
+        node_runtime::Runtime::new().compute_storage_usage(&records, runtime_config);
+    for record in &records {
+        if let StateRecord::Account { account_id, account } = record {
+            let expected = storage_usage.get(account_id).copied().unwrap_or_default();
+            if account.storage_usage() != expected {
+                return Err(Error::Other(format!(
+                    "Account {} at height {} has storage usage {}, but its records take {} bytes",
+                    account_id,
+                    block.header().height(),
+                    account.storage_usage(),
+                    expected
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
 #[derive(serde::Serialize, serde::Deserialize)]
 pub struct Scenario {
     pub network_config: NetworkConfig,
     pub runtime_config: RuntimeConfig,
     pub blocks: Vec<BlockConfig>,
     pub use_in_memory_store: bool,
+    /// Records added to the genesis state on top of the accounts described by
+    /// `network_config`, e.g. the state of accounts taken from a real chain.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub state_records: Vec<StateRecord>,
 }
 
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -108,7 +168,7 @@ pub struct NetworkConfig {
     pub seeds: Vec<String>,
 }
 
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
 pub struct RuntimeConfig {
     pub max_total_prepaid_gas: Gas,
     pub gas_limit: Gas,
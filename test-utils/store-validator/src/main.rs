@@ -23,6 +23,10 @@ fn main() {
                 .help("Directory for config and data (default \"~/.near\")")
                 .takes_value(true),
         )
+        .arg(Arg::new("repair").long("repair").help(
+            "Rebuild the columns which only index other data before validating, \
+             i.e. BlockRefCount, ChunkHashesByHeight and HeaderHashesByHeight",
+        ))
         .subcommand(Command::new("validate"))
         .get_matches();
 
@@ -49,6 +53,18 @@ fn main() {
         store,
         false,
     );
+    if matches.is_present("repair") {
+        let repaired = store_validator
+            .repair()
+            .unwrap_or_else(|e| panic!("Error repairing derived columns: {:#}", e));
+        for (col, num_repaired) in repaired {
+            println!(
+                "{} {}",
+                White.bold().paint(format!("Rows repaired in {}:", col)),
+                Yellow.bold().paint(num_repaired.to_string())
+            );
+        }
+    }
     store_validator.validate();
 
     if store_validator.tests_done() == 0 {
@@ -60,6 +60,11 @@ pub struct AmendGenesisCommand {
     /// optional file that should contain a JSON-serialized shard layout
     #[clap(long)]
     shard_layout_file: Option<PathBuf>,
+    /// path to a JSON file with declarative changes to apply to the records while
+    /// streaming them: accounts to remove, rename, add or change the balance, contract
+    /// code, access keys or contract storage of. See `StatePatch` for the format
+    #[clap(long)]
+    patch_file: Option<PathBuf>,
     /// runtime fees config `num_bytes_account` value. Used to initialize the `storage_usage` field
     /// on accounts in the output state
     #[clap(long)]
@@ -90,6 +95,7 @@ impl AmendGenesisCommand {
             self.extra_records.as_deref(),
             &self.validators,
             self.shard_layout_file.as_deref(),
+            self.patch_file.as_deref(),
             &genesis_changes,
             self.num_bytes_account.unwrap_or(100),
             self.num_extra_bytes_record.unwrap_or(40),
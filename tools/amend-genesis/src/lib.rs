@@ -18,8 +18,12 @@ use std::io::{BufReader, BufWriter};
 use std::path::Path;
 
 mod cli;
+mod patch;
+
+use patch::PatchApplier;
 
 pub use cli::AmendGenesisCommand;
+pub use patch::StatePatch;
 
 // while parsing the --extra-records file we will keep track of the records we see for each
 // account here, and then at the end figure out what to put in the storage_usage field
@@ -265,12 +269,22 @@ pub fn amend_genesis(
     extra_records: Option<&Path>,
     validators: &Path,
     shard_layout_file: Option<&Path>,
+    patch_file: Option<&Path>,
     genesis_changes: &GenesisChanges,
     num_bytes_account: u64,
     num_extra_bytes_record: u64,
 ) -> anyhow::Result<()> {
     let mut genesis = Genesis::from_file(genesis_file_in, GenesisValidationMode::UnsafeFast)?;
 
+    let mut patch = match patch_file {
+        Some(path) => {
+            let base_dir = path.parent().unwrap_or_else(|| Path::new(""));
+            let patch = StatePatch::from_file(path)?;
+            Some(PatchApplier::new(patch, base_dir, num_bytes_account, num_extra_bytes_record)?)
+        }
+        None => None,
+    };
+
     let shard_layout = if let Some(path) = shard_layout_file {
         let s = std::fs::read_to_string(path)
             .with_context(|| format!("failed reading shard layout file {}", path.display()))?;
@@ -295,7 +309,7 @@ pub fn amend_genesis(
     let mut wanted = wanted_records(&validators, extra_records, num_bytes_account)?;
     let mut total_supply = 0;
 
-    near_chain_configs::stream_records_from_file(reader, |mut r| {
+    let mut add_record = |mut r: StateRecord| {
         match &mut r {
             StateRecord::AccessKey { account_id, public_key, access_key } => {
                 if let Some(a) = wanted.get_mut(account_id) {
@@ -328,7 +342,31 @@ pub fn amend_genesis(
                 records_seq.serialize_element(&r).unwrap();
             }
         };
+    };
+    let mut patch_result = Ok(());
+    near_chain_configs::stream_records_from_file(reader, |r| {
+        let r = match &mut patch {
+            Some(patch) => match patch.apply(r) {
+                Ok(r) => r,
+                Err(e) => {
+                    if patch_result.is_ok() {
+                        patch_result = Err(e);
+                    }
+                    None
+                }
+            },
+            None => Some(r),
+        };
+        if let Some(r) = r {
+            add_record(r);
+        }
     })?;
+    patch_result.context("failed applying --patch-file")?;
+    if let Some(patch) = patch {
+        for r in patch.finish()? {
+            add_record(r);
+        }
+    }
 
     for (account_id, records) in wanted {
         records.write_out(
@@ -395,6 +433,7 @@ mod test {
     use near_primitives_core::types::{Balance, StorageUsage};
     use num_rational::Rational32;
     use std::collections::{HashMap, HashSet};
+    use std::path::Path;
     use std::str::FromStr;
     use tempfile::NamedTempFile;
 
@@ -630,6 +669,15 @@ mod test {
         // right now we aren't testing that other kinds of records appearing in the input records file
         // will make it into the output, but that part is pretty simple
         fn run(&self) -> anyhow::Result<()> {
+            let (got_records, wanted_records) = self.amend(None)?;
+            compare_records(got_records, wanted_records)
+        }
+
+        // returns the records amend_genesis() wrote and the parsed self.wanted_records
+        fn amend(
+            &self,
+            patch_file: Option<&Path>,
+        ) -> anyhow::Result<(Vec<StateRecord>, Vec<StateRecord>)> {
             let ParsedTestCase {
                 genesis,
                 records_file_in,
@@ -664,6 +712,7 @@ mod test {
                 Some(extra_records_file.path()),
                 validators_file.path(),
                 None,
+                patch_file,
                 &crate::GenesisChanges::default(),
                 100,
                 40,
@@ -675,7 +724,7 @@ mod test {
             let got_records: Vec<StateRecord> = serde_json::from_str(&got_records)
                 .context("failed deserializing --records-file-out")?;
 
-            compare_records(got_records, wanted_records)
+            Ok((got_records, wanted_records))
         }
     }
 
@@ -1028,4 +1077,129 @@ mod test {
             t.run().unwrap();
         }
     }
+
+    #[test]
+    fn test_amend_genesis_patch() {
+        let t = TestCase {
+            initial_validators: &[TestAccountInfo {
+                account_id: "foo0",
+                public_key: "ed25519:He7QeRuwizNEhBioYG3u4DZ8jWXyETiyNzFD3MkTjDMf",
+                amount: 1_000_000,
+            }],
+            validators_in: &[TestAccountInfo {
+                account_id: "foo0",
+                public_key: "ed25519:He7QeRuwizNEhBioYG3u4DZ8jWXyETiyNzFD3MkTjDMf",
+                amount: 1_000_000,
+            }],
+            records_in: &[
+                TestStateRecord::Account {
+                    account_id: "foo0",
+                    amount: 1_000_000,
+                    locked: 1_000_000,
+                    storage_usage: 182,
+                },
+                TestStateRecord::AccessKey {
+                    account_id: "foo0",
+                    public_key: "ed25519:He7QeRuwizNEhBioYG3u4DZ8jWXyETiyNzFD3MkTjDMf",
+                },
+                TestStateRecord::Account {
+                    account_id: "asdf.near",
+                    amount: 1_234_000,
+                    locked: 0,
+                    storage_usage: 182,
+                },
+                TestStateRecord::AccessKey {
+                    account_id: "asdf.near",
+                    public_key: "ed25519:5C66RSJgwK17Yb6VtTbgBCFHDRPzGUd6AAhFdXNvmJuo",
+                },
+                TestStateRecord::Contract { account_id: "asdf.near" },
+                TestStateRecord::Account {
+                    account_id: "old.near",
+                    amount: 2_000_000,
+                    locked: 0,
+                    storage_usage: 182,
+                },
+                TestStateRecord::AccessKey {
+                    account_id: "old.near",
+                    public_key: "ed25519:FXXrTXiKWpXj1R6r5fBvMLpstd8gPyrBq3qMByqKVzKF",
+                },
+                TestStateRecord::Account {
+                    account_id: "gone.near",
+                    amount: 3_000_000,
+                    locked: 0,
+                    storage_usage: 182,
+                },
+            ],
+            extra_records: &[],
+            wanted_records: &[],
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let code = b"\0asm".to_vec();
+        std::fs::write(dir.path().join("contract.wasm"), &code).unwrap();
+        let patch = serde_json::json!({
+            "remove_accounts": ["gone.near"],
+            "rename_accounts": { "old.near": "new.near" },
+            "accounts": {
+                "asdf.near": {
+                    "amount": "5000",
+                    "code": "contract.wasm",
+                    "access_keys": [{
+                        "public_key": "ed25519:BhnQV3oJa8iSQDKDc8gy36TsenaMFmv7qHvcnutuXj33",
+                        "access_key": { "nonce": 0, "permission": "FullAccess" }
+                    }],
+                    "storage": [{ "key": "a2V5", "value": "dmFsdWU=" }]
+                },
+                "added.near": { "amount": "7000" }
+            }
+        });
+        let patch_file = dir.path().join("patch.json");
+        std::fs::write(&patch_file, patch.to_string()).unwrap();
+
+        let (got_records, _) = t.amend(Some(&patch_file)).unwrap();
+        let mut accounts = HashMap::new();
+        let mut keys = HashSet::new();
+        let mut contracts = vec![];
+        let mut data = vec![];
+        for r in got_records {
+            match r {
+                StateRecord::Account { account_id, account } => {
+                    accounts.insert(account_id.to_string(), account);
+                }
+                StateRecord::AccessKey { account_id, public_key, .. } => {
+                    keys.insert((account_id.to_string(), public_key.to_string()));
+                }
+                StateRecord::Contract { account_id, code } => {
+                    contracts.push((account_id.to_string(), code));
+                }
+                StateRecord::Data { account_id, data_key, value } => {
+                    data.push((account_id.to_string(), data_key, value));
+                }
+                _ => panic!("got an unexpected record in the output: {}", r),
+            }
+        }
+
+        let mut account_ids = accounts.keys().map(String::as_str).collect::<Vec<_>>();
+        account_ids.sort();
+        assert_eq!(account_ids, vec!["added.near", "asdf.near", "foo0", "new.near"]);
+        assert_eq!(accounts["added.near"].amount(), 7000);
+        assert_eq!(accounts["asdf.near"].amount(), 5000);
+        assert_eq!(accounts["asdf.near"].code_hash(), near_primitives::hash::hash(&code));
+        // The account itself, the new key (40 + 33 + 9 bytes), the new code
+        // and the new storage value (40 + 3 + 5 bytes).
+        assert_eq!(accounts["asdf.near"].storage_usage(), 100 + 82 + 4 + 48);
+        assert_eq!(accounts["added.near"].storage_usage(), 100);
+        assert_eq!(accounts["new.near"].amount(), 2_000_000);
+        assert_eq!(contracts, vec![("asdf.near".to_string(), code)]);
+        assert_eq!(data, vec![("asdf.near".to_string(), b"key".to_vec(), b"value".to_vec())]);
+        let wanted_keys = [
+            ("foo0", "ed25519:He7QeRuwizNEhBioYG3u4DZ8jWXyETiyNzFD3MkTjDMf"),
+            ("asdf.near", "ed25519:BhnQV3oJa8iSQDKDc8gy36TsenaMFmv7qHvcnutuXj33"),
+            ("new.near", "ed25519:FXXrTXiKWpXj1R6r5fBvMLpstd8gPyrBq3qMByqKVzKF"),
+        ];
+        let wanted_keys = wanted_keys
+            .iter()
+            .map(|(account_id, public_key)| (account_id.to_string(), public_key.to_string()))
+            .collect::<HashSet<_>>();
+        assert_eq!(keys, wanted_keys);
+    }
 }
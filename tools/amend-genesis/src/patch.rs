@@ -0,0 +1,400 @@
+use anyhow::Context;
+use borsh::BorshSerialize;
+use near_crypto::PublicKey;
+use near_primitives::hash::{hash, CryptoHash};
+use near_primitives::receipt::{Receipt, ReceiptEnum};
+use near_primitives::serialize::{base64_format, dec_format, option_base64_format};
+use near_primitives::state_record::StateRecord;
+use near_primitives::types::AccountId;
+use near_primitives_core::account::{AccessKey, Account};
+use near_primitives_core::types::Balance;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// Declarative changes to the state records, read from the JSON file given
+/// with `--patch-file`. For example:
+/// {
+///   "remove_accounts": ["spam.near"],
+///   "rename_accounts": { "alice.near": "alice.test.near" },
+///   "accounts": {
+///     "alice.test.near": {
+///       "amount": "1000000000000000000000000",
+///       "code": "contract.wasm",
+///       "access_keys": [
+///         {
+///           "public_key": "ed25519:...",
+///           "access_key": { "nonce": 0, "permission": "FullAccess" }
+///         }
+///       ],
+///       "storage": [{ "key": "U1RBVEU=", "value": "AQ==" }]
+///     }
+///   }
+/// }
+///
+/// Renames are applied first, so accounts in `remove_accounts` and `accounts`
+/// are referred to by their new names.
+#[derive(serde::Deserialize, Default, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct StatePatch {
+    /// Accounts whose records are all dropped.
+    #[serde(default)]
+    remove_accounts: BTreeSet<AccountId>,
+    /// Map from old to new account IDs. All records of an account are moved
+    /// to the new ID, including the receipts it receives, and receipts it
+    /// sent or signed refer to the new ID.
+    #[serde(default)]
+    rename_accounts: BTreeMap<AccountId, AccountId>,
+    /// Changes to individual accounts. Accounts which don't exist in the
+    /// input records are added, in which case `amount` must be given.
+    #[serde(default)]
+    accounts: BTreeMap<AccountId, AccountPatch>,
+}
+
+#[derive(serde::Deserialize, Default, Debug)]
+#[serde(deny_unknown_fields)]
+struct AccountPatch {
+    /// Liquid balance to set on the account.
+    #[serde(default, with = "dec_format")]
+    amount: Option<Balance>,
+    /// Path to a wasm file to replace the contract with, relative to the
+    /// directory of the patch file.
+    code: Option<PathBuf>,
+    /// If given, replaces all the access keys of the account.
+    access_keys: Option<Vec<AccessKeyPatch>>,
+    /// Contract storage values to set. A `null` value removes the key.
+    #[serde(default)]
+    storage: Vec<StoragePatch>,
+}
+
+#[derive(serde::Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct AccessKeyPatch {
+    public_key: PublicKey,
+    access_key: AccessKey,
+}
+
+#[derive(serde::Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct StoragePatch {
+    #[serde(with = "base64_format")]
+    key: Vec<u8>,
+    #[serde(default, with = "option_base64_format")]
+    value: Option<Vec<u8>>,
+}
+
+/// A [`StatePatch`] being applied to a stream of records.
+pub(crate) struct PatchApplier {
+    patch: StatePatch,
+    /// Contract code read from the files given in the patch.
+    codes: BTreeMap<AccountId, Vec<u8>>,
+    /// New IDs of renamed accounts.
+    rename_targets: BTreeSet<AccountId>,
+    /// Accounts in `patch.accounts` and `patch.rename_accounts` whose
+    /// account record has been seen.
+    seen_accounts: BTreeSet<AccountId>,
+    /// Account records of accounts in `patch.accounts`. They are held back
+    /// until all records of the account are seen so that their storage usage
+    /// can be recomputed.
+    patched_accounts: BTreeMap<AccountId, Account>,
+    /// Existing contract code of accounts in `patch.accounts` whose code
+    /// isn't replaced. Held back as well because code records have to come
+    /// after the account record.
+    patched_contracts: BTreeMap<AccountId, Vec<u8>>,
+    /// Storage used by the records of accounts in `patch.accounts` which have
+    /// been passed through as they are.
+    storage_usage: BTreeMap<AccountId, u64>,
+    num_bytes_account: u64,
+    num_extra_bytes_record: u64,
+}
+
+impl StatePatch {
+    pub fn from_file(path: &Path) -> anyhow::Result<Self> {
+        let s = std::fs::read_to_string(path)
+            .with_context(|| format!("failed reading patch file {}", path.display()))?;
+        serde_json::from_str(&s)
+            .with_context(|| format!("failed deserializing patch file {}", path.display()))
+    }
+}
+
+impl PatchApplier {
+    /// Validates the patch and reads the contract code it refers to. Relative
+    /// paths are resolved against `base_dir`. The storage usage of patched
+    /// accounts is computed with `num_bytes_account` and
+    /// `num_extra_bytes_record` from the storage usage config.
+    pub(crate) fn new(
+        patch: StatePatch,
+        base_dir: &Path,
+        num_bytes_account: u64,
+        num_extra_bytes_record: u64,
+    ) -> anyhow::Result<Self> {
+        let mut rename_targets = BTreeSet::new();
+        for (from, to) in patch.rename_accounts.iter() {
+            if !rename_targets.insert(to.clone()) {
+                anyhow::bail!("more than one account renamed to {}", to);
+            }
+            if patch.rename_accounts.contains_key(to) {
+                anyhow::bail!("{} is renamed to {}, which is renamed itself", from, to);
+            }
+        }
+        for account_id in patch.accounts.keys() {
+            if patch.remove_accounts.contains(account_id) {
+                anyhow::bail!("account {} is both changed and removed", account_id);
+            }
+        }
+        let mut codes = BTreeMap::new();
+        for (account_id, account_patch) in patch.accounts.iter() {
+            if let Some(path) = &account_patch.code {
+                let path = base_dir.join(path);
+                let code = std::fs::read(&path).with_context(|| {
+                    format!("failed reading code of {} from {}", account_id, path.display())
+                })?;
+                codes.insert(account_id.clone(), code);
+            }
+        }
+        Ok(Self {
+            patch,
+            codes,
+            rename_targets,
+            seen_accounts: BTreeSet::new(),
+            patched_accounts: BTreeMap::new(),
+            patched_contracts: BTreeMap::new(),
+            storage_usage: BTreeMap::new(),
+            num_bytes_account,
+            num_extra_bytes_record,
+        })
+    }
+
+    /// Applies the patch to a record from the input, returning None if the
+    /// record should be dropped.
+    pub(crate) fn apply(&mut self, mut record: StateRecord) -> anyhow::Result<Option<StateRecord>> {
+        let account_id = record_account_id_mut(&mut record);
+        match self.patch.rename_accounts.get(account_id) {
+            Some(new_account_id) => *account_id = new_account_id.clone(),
+            None => {
+                if self.rename_targets.contains(account_id) {
+                    anyhow::bail!("an account is renamed to {}, which already exists", account_id);
+                }
+            }
+        }
+        if self.patch.remove_accounts.contains(account_id) {
+            return Ok(None);
+        }
+
+        let account_id = account_id.clone();
+        if let StateRecord::PostponedReceipt(receipt) | StateRecord::DelayedReceipt(receipt) =
+            &mut record
+        {
+            self.rename_receipt_senders(receipt);
+        }
+        if self.rename_targets.contains(&account_id) {
+            if let StateRecord::Account { .. } = &record {
+                self.seen_accounts.insert(account_id.clone());
+            }
+        }
+        let account_patch = match self.patch.accounts.get(&account_id) {
+            Some(account_patch) => account_patch,
+            None => return Ok(Some(record)),
+        };
+        match record {
+            StateRecord::Account { mut account, .. } => {
+                self.seen_accounts.insert(account_id.clone());
+                if let Some(amount) = account_patch.amount {
+                    account.set_amount(amount);
+                }
+                if let Some(code) = self.codes.get(&account_id) {
+                    account.set_code_hash(hash(code));
+                }
+                self.patched_accounts.insert(account_id, account);
+                return Ok(None);
+            }
+            StateRecord::Contract { code, .. } => {
+                if account_patch.code.is_none() {
+                    self.patched_contracts.insert(account_id, code);
+                }
+                return Ok(None);
+            }
+            StateRecord::AccessKey { .. } => {
+                if account_patch.access_keys.is_some() {
+                    return Ok(None);
+                }
+            }
+            StateRecord::Data { ref data_key, .. } => {
+                if account_patch.storage.iter().any(|storage| &storage.key == data_key) {
+                    return Ok(None);
+                }
+            }
+            _ => {}
+        }
+        let storage_usage = self.record_storage_usage(&record);
+        *self.storage_usage.entry(account_id).or_default() += storage_usage;
+        Ok(Some(record))
+    }
+
+    /// Points the predecessor, the signer and the data receivers of a receipt
+    /// at the new IDs of renamed accounts.
+    fn rename_receipt_senders(&self, receipt: &mut Receipt) {
+        let rename = |account_id: &mut AccountId| {
+            if let Some(new_account_id) = self.patch.rename_accounts.get(account_id) {
+                *account_id = new_account_id.clone();
+            }
+        };
+        rename(&mut receipt.predecessor_id);
+        if let ReceiptEnum::Action(action_receipt) = &mut receipt.receipt {
+            rename(&mut action_receipt.signer_id);
+            for data_receiver in action_receipt.output_data_receivers.iter_mut() {
+                rename(&mut data_receiver.receiver_id);
+            }
+        }
+    }
+
+    /// Storage a record adds to the usage of its account, computed the same
+    /// way as when the genesis records are loaded.
+    fn record_storage_usage(&self, record: &StateRecord) -> u64 {
+        match record {
+            StateRecord::Account { .. } => self.num_bytes_account,
+            StateRecord::Data { data_key, value, .. } => {
+                self.num_extra_bytes_record + data_key.len() as u64 + value.len() as u64
+            }
+            StateRecord::Contract { code, .. } => code.len() as u64,
+            StateRecord::AccessKey { public_key, access_key, .. } => {
+                self.num_extra_bytes_record
+                    + public_key.try_to_vec().unwrap().len() as u64
+                    + access_key.try_to_vec().unwrap().len() as u64
+            }
+            StateRecord::PostponedReceipt(_)
+            | StateRecord::ReceivedData { .. }
+            | StateRecord::DelayedReceipt(_) => 0,
+        }
+    }
+
+    /// Returns the records the patch adds and the held back records of
+    /// patched accounts, to be appended after all the input records have been
+    /// passed to [`Self::apply`].
+    pub(crate) fn finish(mut self) -> anyhow::Result<Vec<StateRecord>> {
+        for (from, to) in self.patch.rename_accounts.iter() {
+            if !self.seen_accounts.contains(to) {
+                tracing::warn!("account {} is renamed in the patch, but it doesn't exist", from);
+            }
+        }
+
+        let mut records = vec![];
+        let accounts = std::mem::take(&mut self.patch.accounts);
+        for (account_id, account_patch) in accounts {
+            let mut account = match self.patched_accounts.remove(&account_id) {
+                Some(account) => account,
+                None => {
+                    let amount = account_patch.amount.with_context(|| {
+                        format!(
+                            "account {} doesn't exist in the input records, set its amount to add it",
+                            account_id
+                        )
+                    })?;
+                    let code_hash = self
+                        .codes
+                        .get(&account_id)
+                        .map_or_else(CryptoHash::default, |code| hash(code));
+                    Account::new(amount, 0, code_hash, 0)
+                }
+            };
+
+            let mut account_records = vec![];
+            let code = self
+                .codes
+                .remove(&account_id)
+                .or_else(|| self.patched_contracts.remove(&account_id));
+            if let Some(code) = code {
+                account_records
+                    .push(StateRecord::Contract { account_id: account_id.clone(), code });
+            }
+            for AccessKeyPatch { public_key, access_key } in
+                account_patch.access_keys.into_iter().flatten()
+            {
+                account_records.push(StateRecord::AccessKey {
+                    account_id: account_id.clone(),
+                    public_key,
+                    access_key,
+                });
+            }
+            for StoragePatch { key, value } in account_patch.storage {
+                if let Some(value) = value {
+                    account_records.push(StateRecord::Data {
+                        account_id: account_id.clone(),
+                        data_key: key,
+                        value,
+                    });
+                }
+            }
+
+            let storage_usage = self.storage_usage.get(&account_id).copied().unwrap_or(0)
+                + self.num_bytes_account
+                + account_records.iter().map(|r| self.record_storage_usage(r)).sum::<u64>();
+            account.set_storage_usage(storage_usage);
+            records.push(StateRecord::Account { account_id, account });
+            records.extend(account_records);
+        }
+        Ok(records)
+    }
+}
+
+/// The account a record is stored under in the state.
+fn record_account_id_mut(record: &mut StateRecord) -> &mut AccountId {
+    match record {
+        StateRecord::Account { account_id, .. }
+        | StateRecord::Data { account_id, .. }
+        | StateRecord::Contract { account_id, .. }
+        | StateRecord::AccessKey { account_id, .. }
+        | StateRecord::ReceivedData { account_id, .. } => account_id,
+        StateRecord::PostponedReceipt(receipt) | StateRecord::DelayedReceipt(receipt) => {
+            &mut receipt.receiver_id
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_crypto::KeyType;
+    use near_primitives::receipt::{ActionReceipt, DataReceiver};
+
+    #[test]
+    fn test_rename_receipt_accounts() {
+        let patch: StatePatch = serde_json::from_value(serde_json::json!({
+            "rename_accounts": { "old.near": "new.near" }
+        }))
+        .unwrap();
+        let mut applier = PatchApplier::new(patch, Path::new(""), 100, 40).unwrap();
+        let old: AccountId = "old.near".parse().unwrap();
+        let new: AccountId = "new.near".parse().unwrap();
+        let other: AccountId = "other.near".parse().unwrap();
+        let receipt = Receipt {
+            predecessor_id: old.clone(),
+            receiver_id: other.clone(),
+            receipt_id: CryptoHash::default(),
+            receipt: ReceiptEnum::Action(ActionReceipt {
+                signer_id: old.clone(),
+                signer_public_key: PublicKey::empty(KeyType::ED25519),
+                gas_price: 0,
+                output_data_receivers: vec![DataReceiver {
+                    data_id: CryptoHash::default(),
+                    receiver_id: old,
+                }],
+                input_data_ids: vec![],
+                actions: vec![],
+            }),
+        };
+
+        let record = applier.apply(StateRecord::DelayedReceipt(Box::new(receipt))).unwrap();
+        let receipt = match record {
+            Some(StateRecord::DelayedReceipt(receipt)) => receipt,
+            _ => panic!("expected the delayed receipt to be kept, got {:?}", record),
+        };
+        assert_eq!(receipt.predecessor_id, new);
+        assert_eq!(receipt.receiver_id, other);
+        let action_receipt = match &receipt.receipt {
+            ReceiptEnum::Action(action_receipt) => action_receipt,
+            ReceiptEnum::Data(_) => unreachable!(),
+        };
+        assert_eq!(action_receipt.signer_id, new);
+        assert_eq!(action_receipt.output_data_receivers[0].receiver_id, new);
+    }
+}
@@ -16,6 +16,7 @@ use near_primitives::hash::CryptoHash;
 use near_primitives::network::{AnnounceAccount, PeerId};
 use near_primitives::sharding::ChunkHash;
 use near_primitives::sharding::ShardChunkHeader;
+use near_primitives::syncing::{EpochSyncFinalizationResponse, EpochSyncResponse};
 use near_primitives::time;
 use near_primitives::transaction::SignedTransaction;
 use near_primitives::types::{AccountId, EpochId, ShardId};
@@ -284,6 +285,26 @@ impl near_network::client::Client for Network {
 
     async fn challenge(&self, _challenge: Challenge) {}
 
+    async fn epoch_sync_request(&self, _epoch_id: EpochId) -> Option<Box<EpochSyncResponse>> {
+        None
+    }
+
+    async fn epoch_sync_response(&self, _response: Box<EpochSyncResponse>, _peer_id: PeerId) {}
+
+    async fn epoch_sync_finalization_request(
+        &self,
+        _block_hash: CryptoHash,
+    ) -> Option<Box<EpochSyncFinalizationResponse>> {
+        None
+    }
+
+    async fn epoch_sync_finalization_response(
+        &self,
+        _response: Box<EpochSyncFinalizationResponse>,
+        _peer_id: PeerId,
+    ) {
+    }
+
     async fn network_info(&self, info: NetworkInfo) {
         let mut n = self.data.lock().unwrap();
         n.info_ = Arc::new(info);
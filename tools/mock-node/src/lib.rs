@@ -11,6 +11,7 @@ use near_network::shards_manager::ShardsManagerRequestFromNetwork;
 use near_network::types::{
     BlockInfo, ConnectedPeerInfo, FullPeerInfo, NetworkInfo, NetworkRequests, NetworkResponses,
     PeerManagerMessageRequest, PeerManagerMessageResponse, PeerType, SetChainInfo,
+    SetValidatorSigner,
 };
 use near_network::types::{
     PartialEncodedChunkRequestMsg, PartialEncodedChunkResponseMsg, PeerInfo,
@@ -382,6 +383,12 @@ impl Handler<WithSpanContext<SetChainInfo>> for MockPeerManagerActor {
     fn handle(&mut self, _msg: WithSpanContext<SetChainInfo>, _ctx: &mut Self::Context) {}
 }
 
+impl Handler<WithSpanContext<SetValidatorSigner>> for MockPeerManagerActor {
+    type Result = ();
+
+    fn handle(&mut self, _msg: WithSpanContext<SetValidatorSigner>, _ctx: &mut Self::Context) {}
+}
+
 impl Handler<WithSpanContext<PeerManagerMessageRequest>> for MockPeerManagerActor {
     type Result = PeerManagerMessageResponse;
 
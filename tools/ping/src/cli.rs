@@ -27,8 +27,10 @@ pub struct PingCommand {
     protocol_version: Option<u32>,
     /// node public key and socket address in the format {pub key}@{socket addr}. e.g.:
     /// ed25519:7PGseFbWxvYVgZ89K1uTJKYoKetWs7BJtbyXDzfbAcqX@127.0.0.1:24567
-    #[clap(long)]
-    peer: String,
+    /// Can be given multiple times, in which case we keep a connection to each of these
+    /// peers, and route pings through all of them.
+    #[clap(long, required = true)]
+    peer: Vec<String>,
     /// ttl to set on our Routed messages
     #[clap(long, default_value = "100")]
     ttl: u8,
@@ -37,10 +39,18 @@ pub struct PingCommand {
     ping_frequency_millis: u64,
     /// line-separated list of accounts to filter on.
     /// We will only try to send pings to these accounts
-    // TODO: add --track-validators or something, that will constantly keep track of
-    // which accounts are validators and ping those
-    #[clap(long)]
+    #[clap(long, conflicts_with = "track-validators")]
     account_filter_file: Option<PathBuf>,
+    /// only send pings to the validators of the current and next epoch, as reported by
+    /// the node at --rpc-url. The list of validators is refreshed periodically.
+    #[clap(long)]
+    track_validators: bool,
+    /// URL of the JSON RPC endpoint used to fetch the validator set with --track-validators
+    #[clap(long, default_value = "http://localhost:3030")]
+    rpc_url: String,
+    /// number of seconds to wait between fetching the validator set with --track-validators
+    #[clap(long, default_value = "60")]
+    validators_refresh_seconds: u64,
     /// filename to append CSV data to
     #[clap(long)]
     latencies_csv_file: Option<PathBuf>,
@@ -52,7 +62,7 @@ pub struct PingCommand {
     prometheus_addr: String,
 }
 
-fn display_stats(stats: &mut [(crate::PeerIdentifier, crate::PingStats)], peer_id: &PeerId) {
+fn display_stats(stats: &mut [(crate::PeerIdentifier, crate::PingStats)], peer_ids: &[PeerId]) {
     let mut acc_width = "account".len();
     for (peer, _) in stats.iter() {
         acc_width = std::cmp::max(acc_width, format!("{}", peer).len());
@@ -84,7 +94,7 @@ fn display_stats(stats: &mut [(crate::PeerIdentifier, crate::PingStats)], peer_i
             min_latency,
             max_latency,
             average_latency,
-            if peer_id == &peer.peer_id { " <-------------- direct pings" } else { "" }
+            if peer_ids.contains(&peer.peer_id) { " <-------------- direct pings" } else { "" }
         );
     }
 }
@@ -172,13 +182,26 @@ impl PingCommand {
             }
         };
 
-        let peer = match PeerInfo::from_str(&self.peer) {
-            Ok(p) => p,
-            Err(e) => anyhow::bail!("Could not parse --peer {}: {:?}", &self.peer, e),
-        };
-        if peer.addr.is_none() {
-            anyhow::bail!("--peer should be in the form [public key]@[socket addr]");
+        let mut peers = Vec::new();
+        for peer in self.peer.iter() {
+            let peer = match PeerInfo::from_str(peer) {
+                Ok(p) => p,
+                Err(e) => anyhow::bail!("Could not parse --peer {}: {:?}", peer, e),
+            };
+            match peer.addr {
+                Some(addr) => peers.push((peer.id, addr)),
+                None => anyhow::bail!("--peer should be in the form [public key]@[socket addr]"),
+            }
         }
+        let peer_ids = peers.iter().map(|(peer_id, _)| peer_id.clone()).collect::<Vec<_>>();
+        let track_validators = if self.track_validators {
+            Some(crate::TrackValidators {
+                rpc_url: self.rpc_url.clone(),
+                refresh_period: Duration::from_secs(self.validators_refresh_seconds),
+            })
+        } else {
+            None
+        };
         let filter = if let Some(filename) = &self.account_filter_file {
             Some(parse_account_filter(filename)?)
         } else {
@@ -192,26 +215,26 @@ impl PingCommand {
             } else {
                 None
             };
-        let runtime = tokio::runtime::Runtime::new().unwrap();
-        runtime.block_on(async move {
+        // The JSON RPC client used with --track-validators needs an actix system to run.
+        actix::System::new().block_on(async move {
             let mut stats = Vec::new();
-            crate::ping_via_node(
+            crate::ping_via_nodes(
                 &self.chain_id,
                 genesis_hash,
                 self.head_height.unwrap_or(0),
                 self.protocol_version,
-                peer.id.clone(),
-                peer.addr.unwrap(),
+                peers,
                 self.ttl,
                 self.ping_frequency_millis,
                 self.recv_timeout_seconds.unwrap_or(5),
                 filter,
+                track_validators,
                 csv,
                 &mut stats,
                 &self.prometheus_addr,
             )
             .await?;
-            display_stats(&mut stats, &peer.id);
+            display_stats(&mut stats, &peer_ids);
             Ok(())
         })
     }
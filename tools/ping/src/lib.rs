@@ -13,6 +13,7 @@ use std::collections::hash_map::Entry;
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::net::SocketAddr;
 use std::pin::Pin;
+use tokio::sync::mpsc;
 
 pub mod cli;
 mod csv;
@@ -123,6 +124,12 @@ struct AppInfo {
     requests: BTreeMap<PingTarget, HashMap<Nonce, PingTimes>>,
     timeouts: BTreeSet<PingTimeout>,
     account_filter: Option<HashSet<AccountId>>,
+    // all accounts we've seen announced, regardless of the account filter, so that
+    // we can start pinging them if the filter changes later on.
+    announced_accounts: HashMap<AccountId, PeerId>,
+    // peers we're directly connected to. These are always pinged, regardless of the
+    // account filter.
+    direct_peers: HashSet<PeerId>,
     chain_id: String,
 }
 
@@ -133,6 +140,8 @@ impl AppInfo {
             requests: BTreeMap::new(),
             timeouts: BTreeSet::new(),
             account_filter,
+            announced_accounts: HashMap::new(),
+            direct_peers: HashSet::new(),
             chain_id: chain_id.to_owned(),
         }
     }
@@ -308,8 +317,51 @@ impl AppInfo {
         }
     }
 
+    fn add_direct_peer(&mut self, peer_id: &PeerId) {
+        self.direct_peers.insert(peer_id.clone());
+        self.add_peer(peer_id, None);
+    }
+
+    // Stops pinging the given peer and forgets about its stats and pending pings.
+    fn remove_peer(&mut self, peer_id: &PeerId) {
+        if let Some(state) = self.stats.remove(peer_id) {
+            self.requests
+                .remove(&PingTarget { peer_id: peer_id.clone(), last_pinged: state.last_pinged });
+            self.timeouts.retain(|t| &t.peer_id != peer_id);
+        }
+    }
+
     fn add_announce_accounts(&mut self, accounts: &[(AccountId, PeerId, EpochId)]) {
         for (account_id, peer_id, _epoch_id) in accounts.iter() {
+            self.announced_accounts.insert(account_id.clone(), peer_id.clone());
+            self.add_peer(peer_id, Some(account_id));
+        }
+    }
+
+    /// Replaces the account filter. Peers whose accounts are not in the new filter
+    /// are no longer pinged, and previously announced accounts that are now in the filter
+    /// start being pinged.
+    fn set_account_filter(&mut self, filter: HashSet<AccountId>) {
+        let removed = self
+            .stats
+            .iter()
+            .filter(|(peer_id, state)| {
+                !self.direct_peers.contains(*peer_id)
+                    && state.account_id.as_ref().map_or(false, |a| !filter.contains(a))
+            })
+            .map(|(peer_id, _)| peer_id.clone())
+            .collect::<Vec<_>>();
+        for peer_id in removed.iter() {
+            self.remove_peer(peer_id);
+        }
+        self.account_filter = Some(filter);
+
+        let announced = self
+            .announced_accounts
+            .iter()
+            .map(|(account_id, peer_id)| (account_id.clone(), peer_id.clone()))
+            .collect::<Vec<_>>();
+        for (account_id, peer_id) in announced.iter() {
             self.add_peer(peer_id, Some(account_id));
         }
     }
@@ -377,34 +429,32 @@ fn prepare_timeout(sleep: Pin<&mut tokio::time::Sleep>, app_info: &AppInfo) -> O
     }
 }
 
-async fn ping_via_node(
-    chain_id: &str,
+/// Parameters used to establish (and re-establish) connections to the peers we ping through.
+#[derive(Clone)]
+struct ConnectParams {
+    chain_id: String,
     genesis_hash: CryptoHash,
     head_height: BlockHeight,
     protocol_version: Option<ProtocolVersion>,
-    peer_id: PeerId,
-    peer_addr: SocketAddr,
-    ttl: u8,
-    ping_frequency_millis: u64,
-    recv_timeout_seconds: u32,
-    account_filter: Option<HashSet<AccountId>>,
-    mut latencies_csv: Option<crate::csv::LatenciesCsv>,
-    ping_stats: &mut Vec<(PeerIdentifier, PingStats)>,
-    prometheus_addr: &str,
-) -> anyhow::Result<()> {
-    let mut app_info = AppInfo::new(account_filter, chain_id);
+    recv_timeout: time::Duration,
+}
 
-    app_info.add_peer(&peer_id, None);
+const RECONNECT_DELAY: std::time::Duration = std::time::Duration::from_secs(10);
 
-    let mut peer = match Connection::connect(
+async fn connect(
+    params: &ConnectParams,
+    peer_id: PeerId,
+    peer_addr: SocketAddr,
+) -> anyhow::Result<Connection> {
+    match Connection::connect(
         peer_addr,
         peer_id,
-        protocol_version,
-        chain_id,
-        genesis_hash,
-        head_height,
-        time::Duration::seconds(recv_timeout_seconds.into())).await {
-        Ok(p) => p,
+        params.protocol_version,
+        &params.chain_id,
+        params.genesis_hash,
+        params.head_height,
+        params.recv_timeout).await {
+        Ok(p) => Ok(p),
         Err(ConnectError::HandshakeFailure(reason)) => {
             match reason {
                 HandshakeFailureReason::ProtocolVersionMismatch { version, oldest_supported_version } => anyhow::bail!(
@@ -424,7 +474,176 @@ async fn ping_via_node(
         Err(e) => {
             anyhow::bail!("Error connecting to {:?}: {}", peer_addr, e);
         }
+    }
+}
+
+/// Handle to a connection to one of the peers given with --peer. The connection itself
+/// is owned by a task running `run_connection()`, and pings to send through it are passed
+/// over the `pings` channel.
+struct PeerConnection {
+    peer_id: PeerId,
+    pings: mpsc::UnboundedSender<(PeerId, Nonce)>,
+}
+
+// Sends pings received over the `pings` channel through the given connection, and forwards
+// all received messages to `received`. If the connection fails, we try to reconnect until
+// the `pings` channel is closed. Pings sent while we're not connected are dropped, and will
+// be counted as timeouts.
+async fn run_connection(
+    mut conn: Connection,
+    params: ConnectParams,
+    peer_id: PeerId,
+    peer_addr: SocketAddr,
+    ttl: u8,
+    mut pings: mpsc::UnboundedReceiver<(PeerId, Nonce)>,
+    received: mpsc::UnboundedSender<(ReceivedMessage, time::Instant)>,
+) {
+    loop {
+        let err = loop {
+            tokio::select! {
+                ping = pings.recv() => {
+                    let (target, nonce) = match ping {
+                        Some(p) => p,
+                        None => return,
+                    };
+                    if let Err(e) = conn.send_ping(&target, nonce, ttl).await {
+                        break e.context(format!("Failed sending ping to {:?}", &target));
+                    }
+                }
+                res = conn.recv() => {
+                    match res {
+                        Ok(msg) => {
+                            if received.send(msg).is_err() {
+                                return;
+                            }
+                        }
+                        Err(e) => break anyhow::Error::from(e).context("Failed receiving messages"),
+                    }
+                }
+            }
+        };
+        tracing::warn!(target: "ping", "Connection to {}@{} failed: {:#}. Reconnecting", &peer_id, &peer_addr, err);
+
+        conn = loop {
+            // drop the pings that were requested while we're disconnected
+            tokio::time::sleep(RECONNECT_DELAY).await;
+            loop {
+                match pings.try_recv() {
+                    Ok(_) => {}
+                    Err(mpsc::error::TryRecvError::Empty) => break,
+                    Err(mpsc::error::TryRecvError::Disconnected) => return,
+                }
+            }
+            match connect(&params, peer_id.clone(), peer_addr).await {
+                Ok(c) => break c,
+                Err(e) => {
+                    tracing::warn!(target: "ping", "Failed reconnecting to {}@{}: {:#}", &peer_id, &peer_addr, e)
+                }
+            }
+        };
+    }
+}
+
+const DEFAULT_RPC_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+// Periodically fetches the current and next epoch validators from the node's RPC
+// and sends their account IDs to `validators`.
+async fn track_validators(
+    rpc_url: String,
+    refresh_period: std::time::Duration,
+    validators: mpsc::UnboundedSender<HashSet<AccountId>>,
+) {
+    let client = near_jsonrpc_client::new_client(&rpc_url);
+    loop {
+        match tokio::time::timeout(DEFAULT_RPC_TIMEOUT, client.validators(None)).await {
+            Ok(Ok(info)) => {
+                let accounts = info
+                    .current_validators
+                    .into_iter()
+                    .map(|v| v.account_id)
+                    .chain(info.next_validators.into_iter().map(|v| v.account_id))
+                    .collect::<HashSet<_>>();
+                if validators.send(accounts).is_err() {
+                    return;
+                }
+            }
+            Ok(Err(e)) => {
+                tracing::warn!(target: "ping", "Failed fetching validators from {}: {:?}", &rpc_url, e)
+            }
+            Err(_) => {
+                tracing::warn!(target: "ping", "Timed out fetching validators from {}", &rpc_url)
+            }
+        }
+        tokio::time::sleep(refresh_period).await;
+    }
+}
+
+/// If set, the list of pinged accounts follows the validator set, as reported by
+/// the `validators` RPC method of the node at `rpc_url`.
+struct TrackValidators {
+    rpc_url: String,
+    refresh_period: std::time::Duration,
+}
+
+async fn ping_via_nodes(
+    chain_id: &str,
+    genesis_hash: CryptoHash,
+    head_height: BlockHeight,
+    protocol_version: Option<ProtocolVersion>,
+    peers: Vec<(PeerId, SocketAddr)>,
+    ttl: u8,
+    ping_frequency_millis: u64,
+    recv_timeout_seconds: u32,
+    account_filter: Option<HashSet<AccountId>>,
+    track_validators: Option<TrackValidators>,
+    mut latencies_csv: Option<crate::csv::LatenciesCsv>,
+    ping_stats: &mut Vec<(PeerIdentifier, PingStats)>,
+    prometheus_addr: &str,
+) -> anyhow::Result<()> {
+    let mut app_info = AppInfo::new(account_filter, chain_id);
+
+    let params = ConnectParams {
+        chain_id: chain_id.to_owned(),
+        genesis_hash,
+        head_height,
+        protocol_version,
+        recv_timeout: time::Duration::seconds(recv_timeout_seconds.into()),
     };
+    let (received_sender, mut received) = mpsc::unbounded_channel();
+    let mut conns = Vec::new();
+    for (peer_id, peer_addr) in peers {
+        // One unreachable peer shouldn't stop us from pinging through the others.
+        let conn = match connect(&params, peer_id.clone(), peer_addr).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                tracing::warn!(target: "ping", "Skipping peer {}: {:#}", &peer_id, e);
+                continue;
+            }
+        };
+        app_info.add_direct_peer(&peer_id);
+        let (pings_sender, pings) = mpsc::unbounded_channel();
+        tokio::spawn(run_connection(
+            conn,
+            params.clone(),
+            peer_id.clone(),
+            peer_addr,
+            ttl,
+            pings,
+            received_sender.clone(),
+        ));
+        conns.push(PeerConnection { peer_id, pings: pings_sender });
+    }
+    drop(received_sender);
+    anyhow::ensure!(!conns.is_empty(), "No peers to connect to");
+
+    let (validators_sender, mut validators) = mpsc::unbounded_channel();
+    let tracking_validators = track_validators.is_some();
+    if let Some(TrackValidators { rpc_url, refresh_period }) = track_validators {
+        // The JSON RPC client's futures are not Send, so this has to run on the local set.
+        actix::spawn(crate::track_validators(rpc_url, refresh_period, validators_sender));
+    } else {
+        drop(validators_sender);
+    }
 
     let mut result = Ok(());
     let mut nonce = 1;
@@ -453,19 +672,25 @@ async fn ping_via_node(
         tokio::select! {
             _ = &mut next_ping, if target.is_some() => {
                 let target = target.unwrap();
-                result = peer.send_ping(&target, nonce, ttl).await.with_context(|| format!("Failed sending ping to {:?}", &target));
-                if result.is_err() {
+                // Ping the peers we're connected to directly, and route the rest of the
+                // pings through our connections in round robin fashion.
+                let conn = match conns.iter().find(|c| c.peer_id == target) {
+                    Some(c) => c,
+                    None => &conns[nonce as usize % conns.len()],
+                };
+                if conn.pings.send((target.clone(), nonce)).is_err() {
+                    result = Err(anyhow::anyhow!("Connection to {} closed", &conn.peer_id));
                     break;
                 }
                 app_info.ping_sent(&target, nonce, &chain_id);
                 nonce += 1;
                 next_ping.as_mut().reset(tokio::time::Instant::now() + std::time::Duration::from_millis(ping_frequency_millis));
             }
-            res = peer.recv() => {
-                let (msg, first_byte_time) = match res {
-                    Ok(x) => x,
-                    Err(e) => {
-                        result = Err(e).context("Failed receiving messages");
+            msg = received.recv() => {
+                let (msg, first_byte_time) = match msg {
+                    Some(x) => x,
+                    None => {
+                        result = Err(anyhow::anyhow!("All connections closed"));
                         break;
                     }
                 };
@@ -479,6 +704,14 @@ async fn ping_via_node(
                     break;
                 }
             }
+            accounts = validators.recv(), if tracking_validators => {
+                if let Some(accounts) = accounts {
+                    crate::metrics::VALIDATORS_TRACKED
+                        .with_label_values(&[&chain_id])
+                        .set(accounts.len() as i64);
+                    app_info.set_account_filter(accounts);
+                }
+            }
             _ = &mut next_timeout, if pending_timeout.is_some() => {
                 let t = pending_timeout.unwrap();
                 app_info.pop_timeout(&t);
@@ -503,3 +736,55 @@ async fn ping_via_node(
     collect_stats(app_info, ping_stats);
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_account_filter_keeps_direct_peers() {
+        let direct = PeerId::random();
+        let routed = PeerId::random();
+        let account: AccountId = "test0".parse().unwrap();
+        let mut app_info = AppInfo::new(None, "test");
+        app_info.add_direct_peer(&direct);
+        app_info.add_announce_accounts(&[(account.clone(), routed.clone(), EpochId::default())]);
+        assert!(app_info.stats.contains_key(&routed));
+
+        app_info.set_account_filter(HashSet::new());
+        assert!(app_info.stats.contains_key(&direct));
+        assert!(!app_info.stats.contains_key(&routed));
+
+        app_info.set_account_filter([account].into_iter().collect());
+        assert!(app_info.stats.contains_key(&routed));
+    }
+
+    #[tokio::test]
+    async fn test_ping_via_nodes_skips_unreachable_peers() {
+        // Bind and drop a listener to get local addresses nobody is listening on.
+        let unreachable_addr =
+            || std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        let peers =
+            vec![(PeerId::random(), unreachable_addr()), (PeerId::random(), unreachable_addr())];
+        let mut ping_stats = Vec::new();
+        let err = ping_via_nodes(
+            "test",
+            CryptoHash::default(),
+            0,
+            None,
+            peers,
+            16,
+            1000,
+            1,
+            None,
+            None,
+            None,
+            &mut ping_stats,
+            "127.0.0.1:0",
+        )
+        .await
+        .unwrap_err();
+        // Failing to connect to a peer is not fatal, we only give up once none is left.
+        assert_eq!(err.to_string(), "No peers to connect to");
+    }
+}
@@ -1,6 +1,6 @@
 use near_o11y::metrics::{
-    exponential_buckets, try_create_histogram_vec, try_create_int_counter_vec, HistogramVec,
-    IntCounterVec,
+    exponential_buckets, try_create_histogram_vec, try_create_int_counter_vec,
+    try_create_int_gauge_vec, HistogramVec, IntCounterVec, IntGaugeVec,
 };
 use once_cell::sync::Lazy;
 
@@ -31,3 +31,12 @@ pub(crate) static PING_SENT: Lazy<IntCounterVec> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+pub(crate) static VALIDATORS_TRACKED: Lazy<IntGaugeVec> = Lazy::new(|| {
+    try_create_int_gauge_vec(
+        "ping_validators_tracked",
+        "Number of validator accounts in the current and next epoch that we try to ping",
+        &["chain_id"],
+    )
+    .unwrap()
+});
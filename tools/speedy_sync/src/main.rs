@@ -236,6 +236,8 @@ fn load_snapshot(load_cmd: LoadCmd) {
         ChainConfig {
             save_trie_changes: config.client_config.save_trie_changes,
             background_migration_threads: 1,
+            account_activity_index: config.client_config.account_activity_index.is_some(),
+            event_index: config.client_config.event_index.is_some(),
         },
     )
     .unwrap();
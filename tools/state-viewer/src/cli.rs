@@ -2,12 +2,14 @@ use crate::commands::*;
 use crate::contract_accounts::ContractAccountFilter;
 use crate::rocksdb_stats::get_rocksdb_stats;
 use crate::state_parts::{apply_state_parts, dump_state_parts};
+use crate::validator_selection::EpochConfigOverrides;
 use crate::{epoch_info, state_parts};
 use near_chain_configs::{GenesisChangeConfig, GenesisValidationMode};
 use near_primitives::account::id::AccountId;
 use near_primitives::hash::CryptoHash;
+use near_primitives::num_rational::Rational32;
 use near_primitives::sharding::ChunkHash;
-use near_primitives::types::{BlockHeight, ShardId};
+use near_primitives::types::{Balance, BlockHeight, ShardId};
 use near_store::{Mode, NodeStorage, Store, Temperature};
 use nearcore::{load_config, NearConfig};
 use std::path::{Path, PathBuf};
@@ -44,6 +46,9 @@ pub enum StateViewerSubCommand {
     /// List account names with contracts deployed.
     #[clap(alias = "contract_accounts")]
     ContractAccounts(ContractAccountsCmd),
+    /// Write an epoch checkpoint to start other nodes from with
+    /// `neard init --from-checkpoint` or `neard run --bootstrap-checkpoint`.
+    CreateCheckpoint(CreateCheckpointCmd),
     /// Dump contract data in storage of given account to binary file.
     #[clap(alias = "dump_account_storage")]
     DumpAccountStorage(DumpAccountStorageCmd),
@@ -69,11 +74,17 @@ pub enum StateViewerSubCommand {
     PartialChunks(PartialChunksCmd),
     /// Looks up a certain receipt.
     Receipts(ReceiptsCmd),
+    /// Projects rewards of the current validators for the next epochs.
+    RewardProjection(RewardProjectionCmd),
     /// Replay headers from chain.
     Replay(ReplayCmd),
     /// Dump stats for the RocksDB storage.
     #[clap(name = "rocksdb-stats", alias = "rocksdb_stats")]
     RocksDBStats(RocksDBStatsCmd),
+    /// Selects validators of past epochs again from the recorded proposals
+    /// and kickouts with a modified epoch config, and prints how the result
+    /// differs from the actual validators.
+    SimulateValidatorSelection(SimulateValidatorSelectionCmd),
     /// Iterates over a trie and prints the StateRecords.
     State,
     /// View head of the storage.
@@ -120,6 +131,7 @@ impl StateViewerSubCommand {
             StateViewerSubCommand::CheckBlock => check_block_chunk_existence(near_config, store),
             StateViewerSubCommand::Chunks(cmd) => cmd.run(near_config, store),
             StateViewerSubCommand::ContractAccounts(cmd) => cmd.run(home_dir, near_config, store),
+            StateViewerSubCommand::CreateCheckpoint(cmd) => cmd.run(home_dir, near_config, store),
             StateViewerSubCommand::DumpAccountStorage(cmd) => cmd.run(home_dir, near_config, store),
             StateViewerSubCommand::DumpCode(cmd) => cmd.run(home_dir, near_config, store),
             StateViewerSubCommand::DumpState(cmd) => cmd.run(home_dir, near_config, store),
@@ -129,8 +141,10 @@ impl StateViewerSubCommand {
             StateViewerSubCommand::EpochInfo(cmd) => cmd.run(home_dir, near_config, store),
             StateViewerSubCommand::PartialChunks(cmd) => cmd.run(near_config, store),
             StateViewerSubCommand::Receipts(cmd) => cmd.run(near_config, store),
+            StateViewerSubCommand::RewardProjection(cmd) => cmd.run(near_config, store),
             StateViewerSubCommand::Replay(cmd) => cmd.run(home_dir, near_config, store),
             StateViewerSubCommand::RocksDBStats(cmd) => cmd.run(store_opener.path()),
+            StateViewerSubCommand::SimulateValidatorSelection(cmd) => cmd.run(near_config, store),
             StateViewerSubCommand::State => state(home_dir, near_config, store),
             StateViewerSubCommand::ViewChain(cmd) => cmd.run(near_config, store),
             StateViewerSubCommand::ViewTrie(cmd) => cmd.run(store),
@@ -158,12 +172,18 @@ pub struct ApplyChunkCmd {
     chunk_hash: String,
     #[clap(long)]
     target_height: Option<u64>,
+    /// Write a JSON trace of the receipts processed, the host function costs
+    /// charged, their gas profiles and all trie reads and writes with their
+    /// values to this file.
+    #[clap(long, parse(from_os_str))]
+    trace: Option<PathBuf>,
 }
 
 impl ApplyChunkCmd {
     pub fn run(self, home_dir: &Path, near_config: NearConfig, store: Store) {
         let hash = ChunkHash::from(CryptoHash::from_str(&self.chunk_hash).unwrap());
-        apply_chunk(home_dir, near_config, store, hash, self.target_height).unwrap()
+        apply_chunk(home_dir, near_config, store, hash, self.target_height, self.trace.as_deref())
+            .unwrap()
     }
 }
 
@@ -340,6 +360,25 @@ impl DumpAccountStorageCmd {
     }
 }
 
+#[derive(clap::Parser)]
+pub struct CreateCheckpointCmd {
+    /// File to write the checkpoint to.
+    #[clap(long, parse(from_os_str))]
+    output: PathBuf,
+    /// Start the checkpoint from the last final block of an epoch with this
+    /// hash instead of from genesis.  Nodes then have to pass the same
+    /// `--trusted-hash` when loading the checkpoint.
+    #[clap(long)]
+    trusted_hash: Option<CryptoHash>,
+}
+
+impl CreateCheckpointCmd {
+    pub fn run(self, home_dir: &Path, near_config: NearConfig, store: Store) {
+        create_checkpoint(&self.output, self.trusted_hash.as_ref(), home_dir, near_config, store)
+            .unwrap();
+    }
+}
+
 #[derive(clap::Parser)]
 pub struct DumpCodeCmd {
     #[clap(long)]
@@ -533,6 +572,36 @@ impl ReceiptsCmd {
     }
 }
 
+#[derive(clap::Parser)]
+pub struct RewardProjectionCmd {
+    /// Number of epochs to project rewards for.
+    #[clap(long, default_value = "10")]
+    num_epochs: u64,
+    /// Project from this block instead of the head.
+    #[clap(long)]
+    block_height: Option<BlockHeight>,
+    /// Also project rewards of this many yoctoNEAR delegated to each validator.
+    #[clap(long)]
+    delegation_amount: Option<Balance>,
+    /// Staking pool fee taken from the rewards of the delegation, e.g. `10/100`.
+    #[clap(long, default_value = "0/1")]
+    reward_fee_fraction: Rational32,
+}
+
+impl RewardProjectionCmd {
+    pub fn run(self, near_config: NearConfig, store: Store) {
+        print_reward_projection(
+            self.num_epochs,
+            self.block_height,
+            self.delegation_amount,
+            self.reward_fee_fraction,
+            near_config,
+            store,
+        )
+        .unwrap();
+    }
+}
+
 #[derive(clap::Parser)]
 pub struct ReplayCmd {
     #[clap(long)]
@@ -560,6 +629,21 @@ impl RocksDBStatsCmd {
     }
 }
 
+#[derive(clap::Parser)]
+pub struct SimulateValidatorSelectionCmd {
+    #[clap(subcommand)]
+    epoch_selection: epoch_info::EpochSelection,
+    #[clap(flatten)]
+    overrides: EpochConfigOverrides,
+}
+
+impl SimulateValidatorSelectionCmd {
+    pub fn run(self, near_config: NearConfig, store: Store) {
+        simulate_validator_selection(self.epoch_selection, &self.overrides, near_config, store)
+            .unwrap();
+    }
+}
+
 #[derive(clap::Parser)]
 pub struct ViewChainCmd {
     #[clap(long)]
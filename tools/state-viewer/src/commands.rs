@@ -5,30 +5,36 @@ use crate::contract_accounts::Summary;
 use crate::state_dump::state_dump;
 use crate::state_dump::state_dump_redis;
 use crate::tx_dump::dump_tx_from_block;
-use crate::{apply_chunk, epoch_info};
+use crate::validator_selection::EpochConfigOverrides;
+use crate::{apply_chunk, epoch_info, validator_selection};
 use ansi_term::Color::Red;
+use anyhow::Context;
 use near_chain::chain::collect_receipts_from_response;
 use near_chain::migrations::check_if_block_is_first_with_chunk_of_version;
 use near_chain::types::RuntimeAdapter;
 use near_chain::types::{ApplyTransactionResult, BlockHeaderInfo};
 use near_chain::{
-    ChainStore, ChainStoreAccess, ChainStoreUpdate, Error, RuntimeWithEpochManagerAdapter,
+    Chain, ChainGenesis, ChainStore, ChainStoreAccess, ChainStoreUpdate, DoomslugThresholdMode,
+    Error, RuntimeWithEpochManagerAdapter,
 };
 use near_chain_configs::GenesisChangeConfig;
 use near_epoch_manager::{EpochManager, EpochManagerAdapter};
 use near_primitives::account::id::AccountId;
 use near_primitives::block::{Block, BlockHeader};
 use near_primitives::hash::CryptoHash;
+use near_primitives::num_rational::Rational32;
 use near_primitives::shard_layout::ShardLayout;
 use near_primitives::shard_layout::ShardUId;
 use near_primitives::sharding::ChunkHash;
 use near_primitives::state_record::StateRecord;
 use near_primitives::trie_key::TrieKey;
-use near_primitives::types::{chunk_extra::ChunkExtra, BlockHeight, ShardId, StateRoot};
+use near_primitives::types::{chunk_extra::ChunkExtra, Balance, BlockHeight, ShardId, StateRoot};
+use near_primitives::views::StakeDelegationView;
 use near_primitives_core::types::Gas;
 use near_store::test_utils::create_test_store;
 use near_store::TrieDBStorage;
 use near_store::{Store, Trie, TrieCache, TrieCachingStorage, TrieConfig};
+use nearcore::checkpoint::EpochCheckpoint;
 use nearcore::{NearConfig, NightshadeRuntime};
 use node_runtime::adapter::ViewRuntimeAdapter;
 use serde_json::json;
@@ -151,6 +157,7 @@ pub(crate) fn apply_chunk(
     store: Store,
     chunk_hash: ChunkHash,
     target_height: Option<u64>,
+    trace: Option<&Path>,
 ) -> anyhow::Result<()> {
     let runtime = NightshadeRuntime::from_config(home_dir, store.clone(), &near_config);
     let mut chain_store = ChainStore::new(
@@ -158,9 +165,23 @@ pub(crate) fn apply_chunk(
         near_config.genesis.config.genesis_height,
         near_config.client_config.save_trie_changes,
     );
-    let (apply_result, gas_limit) =
-        apply_chunk::apply_chunk(&runtime, &mut chain_store, chunk_hash, target_height, None)?;
+    let (result, traces) = if trace.is_some() {
+        node_runtime::trace::with_apply_trace(|| {
+            apply_chunk::apply_chunk(&runtime, &mut chain_store, chunk_hash, target_height, None)
+        })
+    } else {
+        let result =
+            apply_chunk::apply_chunk(&runtime, &mut chain_store, chunk_hash, target_height, None);
+        (result, vec![])
+    };
+    let (apply_result, gas_limit) = result?;
     println!("resulting chunk extra:\n{:?}", resulting_chunk_extra(&apply_result, gas_limit));
+    if let Some(path) = trace {
+        let file = std::fs::File::create(path)
+            .with_context(|| format!("Failed creating {}", path.display()))?;
+        serde_json::to_writer_pretty(std::io::BufWriter::new(file), &traces)?;
+        println!("execution trace written to {}", path.display());
+    }
     Ok(())
 }
 
@@ -264,6 +285,31 @@ pub(crate) fn dump_account_storage(
     std::process::exit(1);
 }
 
+pub(crate) fn create_checkpoint(
+    output: &Path,
+    trusted_hash: Option<&CryptoHash>,
+    home_dir: &Path,
+    near_config: NearConfig,
+    store: Store,
+) -> anyhow::Result<()> {
+    let runtime = Arc::new(NightshadeRuntime::from_config(home_dir, store, &near_config));
+    let chain = Chain::new_for_view_client(
+        runtime,
+        &ChainGenesis::new(&near_config.genesis),
+        DoomslugThresholdMode::TwoThirds,
+        near_config.client_config.save_trie_changes,
+    )?;
+    let checkpoint = EpochCheckpoint::create(&chain, trusted_hash)?;
+    checkpoint.write(output)?;
+    println!(
+        "Wrote checkpoint of {} epochs ending at height {} to {}",
+        checkpoint.light_client_blocks.len(),
+        checkpoint.height(),
+        output.display()
+    );
+    Ok(())
+}
+
 pub(crate) fn dump_code(
     account_id: String,
     output: &Path,
@@ -766,6 +812,94 @@ pub(crate) fn print_epoch_info(
     );
 }
 
+pub(crate) fn print_reward_projection(
+    num_epochs: u64,
+    block_height: Option<BlockHeight>,
+    delegation_amount: Option<Balance>,
+    reward_fee_fraction: Rational32,
+    near_config: NearConfig,
+    store: Store,
+) -> anyhow::Result<()> {
+    let genesis_height = near_config.genesis.config.genesis_height;
+    let chain_store =
+        ChainStore::new(store.clone(), genesis_height, near_config.client_config.save_trie_changes);
+    let epoch_manager = EpochManager::new_from_genesis_config(store, &near_config.genesis.config)
+        .expect("Failed to start Epoch Manager");
+    let block_hash = match block_height {
+        Some(height) => chain_store.get_block_hash_by_height(height)?,
+        None => chain_store.head()?.last_block_hash,
+    };
+    let epoch_info = epoch_manager.get_epoch_info(&epoch_manager.get_epoch_id(&block_hash)?)?;
+    let delegations = match delegation_amount {
+        Some(amount) => epoch_info
+            .validators_iter()
+            .map(|validator| StakeDelegationView {
+                validator_id: validator.take_account_id(),
+                amount,
+                reward_fee_fraction,
+            })
+            .collect(),
+        None => vec![],
+    };
+    let projection = epoch_manager.get_reward_projection(&block_hash, num_epochs, delegations)?;
+
+    println!(
+        "Projecting from epoch {:?} at height {}, estimated epoch duration {:?}",
+        projection.epoch_id,
+        projection.epoch_height,
+        std::time::Duration::from_nanos(projection.epoch_duration_ns)
+    );
+    for epoch in &projection.epochs {
+        println!(
+            "Epoch {}: minted {}, total supply {}",
+            epoch.epoch_height, epoch.minted_amount, epoch.total_supply
+        );
+    }
+    for validator in &projection.validators {
+        let total_reward: Balance = validator.rewards.iter().map(|r| r.reward).sum();
+        println!(
+            "{}: stake {}, rewards {}, APY {:.2}%",
+            validator.account_id,
+            validator.stake,
+            total_reward,
+            validator.apy * 100.0
+        );
+    }
+    for delegation in &projection.delegations {
+        let total_reward: Balance = delegation.rewards.iter().map(|r| r.reward).sum();
+        println!(
+            "Delegation of {} to {}: rewards {}, APY {:.2}%",
+            delegation.delegation.amount,
+            delegation.delegation.validator_id,
+            total_reward,
+            delegation.apy * 100.0
+        );
+    }
+    Ok(())
+}
+
+pub(crate) fn simulate_validator_selection(
+    epoch_selection: epoch_info::EpochSelection,
+    overrides: &EpochConfigOverrides,
+    near_config: NearConfig,
+    store: Store,
+) -> anyhow::Result<()> {
+    let genesis_height = near_config.genesis.config.genesis_height;
+    let mut chain_store =
+        ChainStore::new(store.clone(), genesis_height, near_config.client_config.save_trie_changes);
+    let mut epoch_manager =
+        EpochManager::new_from_genesis_config(store.clone(), &near_config.genesis.config)
+            .expect("Failed to start Epoch Manager");
+    let epoch_ids =
+        epoch_info::get_epoch_ids(epoch_selection, store, &mut chain_store, &mut epoch_manager);
+    validator_selection::simulate_validator_selection(
+        epoch_ids,
+        overrides,
+        &chain_store,
+        &epoch_manager,
+    )
+}
+
 fn get_trie(store: Store, hash: CryptoHash, shard_id: u32, shard_version: u32) -> Trie {
     let shard_uid = ShardUId { version: shard_version, shard_id };
     let trie_config: TrieConfig = Default::default();
@@ -10,5 +10,6 @@ mod rocksdb_stats;
 mod state_dump;
 mod state_parts;
 mod tx_dump;
+mod validator_selection;
 
 pub use cli::StateViewerSubCommand;
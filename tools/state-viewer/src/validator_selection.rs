@@ -0,0 +1,228 @@
+//! Re-runs validator selection for past epochs with a modified `EpochConfig`,
+//! to see how changes to the number of seats, the minimum stake ratio or the
+//! number of shards would have affected the validator set.
+
+use anyhow::Context;
+use near_chain::{ChainStore, ChainStoreAccess};
+use near_epoch_manager::{proposals_to_epoch_info, EpochManager};
+use near_primitives::epoch_manager::epoch_info::EpochInfo;
+use near_primitives::epoch_manager::EpochConfig;
+use near_primitives::num_rational::Rational32;
+use near_primitives::shard_layout::ShardLayout;
+use near_primitives::types::{AccountId, Balance, EpochId, NumSeats, NumShards};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Changes to the `EpochConfig` used for the simulated validator selection.
+#[derive(clap::Parser, Debug)]
+pub(crate) struct EpochConfigOverrides {
+    /// Number of block producer seats.
+    #[clap(long)]
+    num_block_producer_seats: Option<NumSeats>,
+    /// Number of chunk-only producer seats.
+    #[clap(long)]
+    num_chunk_only_producer_seats: Option<NumSeats>,
+    /// Minimum number of validators assigned to each shard.
+    #[clap(long)]
+    minimum_validators_per_shard: Option<NumSeats>,
+    /// Minimum stake ratio, e.g. `160/1000000`.
+    #[clap(long)]
+    minimum_stake_ratio: Option<Rational32>,
+    /// Number of shards. The shard layout is replaced with a `ShardLayout::v0`
+    /// with this many shards.
+    #[clap(long)]
+    num_shards: Option<NumShards>,
+}
+
+impl EpochConfigOverrides {
+    fn apply(&self, config: &mut EpochConfig) {
+        if let Some(seats) = self.num_block_producer_seats {
+            config.num_block_producer_seats = seats;
+        }
+        if let Some(seats) = self.num_chunk_only_producer_seats {
+            config.validator_selection_config.num_chunk_only_producer_seats = seats;
+        }
+        if let Some(validators) = self.minimum_validators_per_shard {
+            config.validator_selection_config.minimum_validators_per_shard = validators;
+        }
+        if let Some(ratio) = self.minimum_stake_ratio {
+            config.validator_selection_config.minimum_stake_ratio = ratio;
+        }
+        if let Some(num_shards) = self.num_shards {
+            config.shard_layout = ShardLayout::v0(num_shards, 0);
+            config.num_block_producer_seats_per_shard =
+                vec![config.num_block_producer_seats; num_shards as usize];
+            config.avg_hidden_validator_seats_per_shard = vec![0; num_shards as usize];
+        }
+    }
+}
+
+/// For every epoch in `epoch_ids`, selects its validators again from the
+/// proposals and kickouts recorded two epochs earlier, using the epoch's
+/// config modified by `overrides`, and prints how the result differs from the
+/// validators which were actually selected.
+pub(crate) fn simulate_validator_selection(
+    epoch_ids: Vec<EpochId>,
+    overrides: &EpochConfigOverrides,
+    chain_store: &ChainStore,
+    epoch_manager: &EpochManager,
+) -> anyhow::Result<()> {
+    let mut epochs = epoch_ids
+        .into_iter()
+        .map(|epoch_id| Ok((epoch_manager.get_epoch_info(&epoch_id)?, epoch_id)))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    epochs.sort_by_key(|(epoch_info, _)| epoch_info.epoch_height());
+
+    for (actual, epoch_id) in epochs {
+        println!("-------------------------");
+        println!("Epoch {:?} at height {}", epoch_id, actual.epoch_height());
+        // The ID of an epoch is the hash of the last block of the epoch two
+        // epochs earlier, whose proposals were used to select its validators.
+        let last_block_hash = epoch_id.0;
+        let last_block_info = match epoch_manager.get_block_info(&last_block_hash) {
+            Ok(block_info) => block_info,
+            Err(_) => {
+                println!("Validators of this epoch were not selected from proposals, skipping.");
+                continue;
+            }
+        };
+        let proposals_epoch_id = last_block_info.epoch_id();
+        let summary = match epoch_manager.get_epoch_validator_info(proposals_epoch_id) {
+            Ok(summary) => summary,
+            Err(_) => {
+                println!("No proposals recorded for epoch {:?}, skipping.", proposals_epoch_id);
+                continue;
+            }
+        };
+        let prev_epoch_info =
+            epoch_manager.get_epoch_info(&epoch_manager.get_next_epoch_id(&last_block_hash)?)?;
+        let last_version = epoch_manager.get_epoch_info(proposals_epoch_id)?.protocol_version();
+        let rng_seed = chain_store
+            .get_block_header(&last_block_hash)
+            .with_context(|| format!("missing header of block {}", last_block_hash))?
+            .random_value()
+            .0;
+
+        let mut config = epoch_manager.get_epoch_config(&epoch_id)?;
+        overrides.apply(&mut config);
+        let simulated = match proposals_to_epoch_info(
+            &config,
+            rng_seed,
+            &prev_epoch_info,
+            summary.all_proposals,
+            summary.validator_kickout,
+            actual.validator_reward().clone(),
+            actual.minted_amount(),
+            summary.next_version,
+            last_version,
+        ) {
+            Ok(epoch_info) => epoch_info,
+            Err(err) => {
+                println!("Validator selection failed: {}", err);
+                continue;
+            }
+        };
+        display_diff(&actual, &simulated);
+    }
+    Ok(())
+}
+
+/// Role of a validator in an epoch.
+#[derive(PartialEq, Eq)]
+enum Role {
+    BlockProducer,
+    ChunkOnlyProducer,
+    Fisherman,
+}
+
+impl std::fmt::Display for Role {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Role::BlockProducer => "block producer",
+            Role::ChunkOnlyProducer => "chunk-only producer",
+            Role::Fisherman => "fisherman",
+        })
+    }
+}
+
+fn roles(epoch_info: &EpochInfo) -> BTreeMap<AccountId, (Role, Balance)> {
+    let block_producers: BTreeSet<_> =
+        epoch_info.block_producers_settlement().iter().copied().collect();
+    let mut roles = BTreeMap::new();
+    for (validator_id, validator) in epoch_info.validators_iter().enumerate() {
+        let role = if block_producers.contains(&(validator_id as u64)) {
+            Role::BlockProducer
+        } else {
+            Role::ChunkOnlyProducer
+        };
+        roles.insert(validator.account_id().clone(), (role, validator.stake()));
+    }
+    for fisherman in epoch_info.fishermen_iter() {
+        roles.insert(fisherman.account_id().clone(), (Role::Fisherman, fisherman.stake()));
+    }
+    roles
+}
+
+fn shard_assignment(epoch_info: &EpochInfo) -> Vec<BTreeSet<AccountId>> {
+    epoch_info
+        .chunk_producers_settlement()
+        .iter()
+        .map(|validator_ids| {
+            validator_ids
+                .iter()
+                .map(|validator_id| epoch_info.validator_account_id(*validator_id).clone())
+                .collect()
+        })
+        .collect()
+}
+
+fn display_diff(actual: &EpochInfo, simulated: &EpochInfo) {
+    println!("Seat price: actual {}, simulated {}", actual.seat_price(), simulated.seat_price());
+
+    let actual_roles = roles(actual);
+    let simulated_roles = roles(simulated);
+    println!(
+        "Validators: actual {}, simulated {}",
+        actual.validators_len(),
+        simulated.validators_len()
+    );
+    for (account_id, (role, stake)) in &simulated_roles {
+        match actual_roles.get(account_id) {
+            Some((actual_role, _)) if actual_role == role => {
+                println!("  {} {}: {}", account_id, stake, role)
+            }
+            Some((actual_role, _)) => {
+                println!("  {} {}: {} (actually {})", account_id, stake, role, actual_role)
+            }
+            None => println!("+ {} {}: {}", account_id, stake, role),
+        }
+    }
+    for (account_id, (role, stake)) in &actual_roles {
+        if !simulated_roles.contains_key(account_id) {
+            let reason = simulated
+                .validator_kickout()
+                .get(account_id)
+                .map_or_else(String::new, |reason| format!(", kicked out: {:?}", reason));
+            println!("- {} {}: {}{}", account_id, stake, role, reason);
+        }
+    }
+
+    let actual_shards = shard_assignment(actual);
+    let simulated_shards = shard_assignment(simulated);
+    let empty = BTreeSet::new();
+    for shard_id in 0..actual_shards.len().max(simulated_shards.len()) {
+        let actual_shard = actual_shards.get(shard_id).unwrap_or(&empty);
+        let simulated_shard = simulated_shards.get(shard_id).unwrap_or(&empty);
+        println!(
+            "Shard {}: actual {} chunk producers, simulated {}",
+            shard_id,
+            actual_shard.len(),
+            simulated_shard.len()
+        );
+        for account_id in simulated_shard.difference(actual_shard) {
+            println!("+ {}", account_id);
+        }
+        for account_id in actual_shard.difference(simulated_shard) {
+            println!("- {}", account_id);
+        }
+    }
+}